@@ -0,0 +1,207 @@
+//! Rust-friendly wrappers for [`AbortController`](https://developer.mozilla.org/en-US/docs/Web/API/AbortController)
+//! and [`AbortSignal`](https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal).
+//!
+//! An [`AbortSignal`](AbortSignal) can be passed anywhere this crate accepts a signal,
+//! such as [`PipeOptions::signal`](crate::readable::PipeOptions::signal),
+//! [`ReadableStreamDefaultReader::read_with_signal`](crate::readable::ReadableStreamDefaultReader::read_with_signal)
+//! or [`WritableStreamDefaultWriter::write_with_signal`](crate::writable::WritableStreamDefaultWriter::write_with_signal).
+//! Signals can be combined with [`AbortSignal::any`](AbortSignal::any), e.g. to abort an operation
+//! either when a component is unmounted or after a timeout.
+use std::time::Duration;
+
+use js_sys::{Array, Function, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::util::aborted;
+
+/// An [`AbortController`](https://developer.mozilla.org/en-US/docs/Web/API/AbortController),
+/// which can abort the operations that were given its [`signal`](Self::signal).
+#[derive(Clone, Debug)]
+pub struct AbortController {
+    raw: web_sys::AbortController,
+}
+
+impl AbortController {
+    /// Creates a new `AbortController`, whose signal is not yet aborted.
+    pub fn new() -> Self {
+        Self::from_raw(web_sys::AbortController::new().unwrap_throw())
+    }
+
+    /// Creates a new `AbortController` from a [JavaScript object](web_sys::AbortController).
+    #[inline]
+    pub fn from_raw(raw: web_sys::AbortController) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](web_sys::AbortController).
+    #[inline]
+    pub fn as_raw(&self) -> &web_sys::AbortController {
+        &self.raw
+    }
+
+    /// Consumes this `AbortController`, returning the underlying
+    /// [JavaScript object](web_sys::AbortController).
+    #[inline]
+    pub fn into_raw(self) -> web_sys::AbortController {
+        self.raw
+    }
+
+    /// Returns the signal of this controller.
+    ///
+    /// Every call returns a handle to the same signal.
+    #[inline]
+    pub fn signal(&self) -> AbortSignal {
+        AbortSignal::from_raw(self.as_raw().signal())
+    }
+
+    /// Aborts the signal, with a `DOMException` named `AbortError` as its reason.
+    ///
+    /// If the signal was already aborted, this does nothing.
+    #[inline]
+    pub fn abort(&self) {
+        self.as_raw().abort()
+    }
+
+    /// Aborts the signal with the given `reason`.
+    ///
+    /// If the signal was already aborted, this does nothing.
+    #[inline]
+    pub fn abort_with_reason(&self, reason: &JsValue) {
+        self.as_raw().abort_with_reason(reason)
+    }
+}
+
+impl Default for AbortController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An [`AbortSignal`](https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal),
+/// which is aborted by its [`AbortController`](AbortController).
+///
+/// Cloning an `AbortSignal` returns a new handle to the same signal.
+#[derive(Clone, Debug)]
+pub struct AbortSignal {
+    raw: web_sys::AbortSignal,
+}
+
+impl AbortSignal {
+    /// Creates a new `AbortSignal` from a [JavaScript object](web_sys::AbortSignal).
+    #[inline]
+    pub fn from_raw(raw: web_sys::AbortSignal) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](web_sys::AbortSignal).
+    #[inline]
+    pub fn as_raw(&self) -> &web_sys::AbortSignal {
+        &self.raw
+    }
+
+    /// Consumes this `AbortSignal`, returning the underlying
+    /// [JavaScript object](web_sys::AbortSignal).
+    #[inline]
+    pub fn into_raw(self) -> web_sys::AbortSignal {
+        self.raw
+    }
+
+    /// Returns a signal which is already aborted with the given `reason`.
+    pub fn aborted_with_reason(reason: &JsValue) -> Self {
+        let controller = AbortController::new();
+        controller.abort_with_reason(reason);
+        controller.signal()
+    }
+
+    /// Returns a signal which is aborted with a `DOMException` named `TimeoutError`
+    /// after the given `duration`.
+    pub fn timeout(duration: Duration) -> Self {
+        Self::from_raw(web_sys::AbortSignal::timeout_with_f64(
+            duration.as_secs_f64() * 1000.0,
+        ))
+    }
+
+    /// Returns a signal which is aborted as soon as any of the given `signals` is aborted,
+    /// with the same reason.
+    ///
+    /// If any of the signals is already aborted, the returned signal is aborted as well.
+    /// This uses [`AbortSignal.any()`](https://developer.mozilla.org/en-US/docs/Web/API/AbortSignal/any_static)
+    /// if the browser supports it.
+    pub fn any(signals: &[AbortSignal]) -> Self {
+        let raw_signals = signals
+            .iter()
+            .map(|signal| JsValue::from(signal.as_raw()))
+            .collect::<Array>();
+        if has_any() {
+            return Self::from_raw(web_sys::AbortSignal::any(&raw_signals));
+        }
+        let controller = AbortController::new();
+        if let Some(signal) = signals.iter().find(|signal| signal.is_aborted()) {
+            controller.abort_with_reason(&signal.as_raw().reason());
+            return controller.signal();
+        }
+        for signal in signals {
+            let source = signal.clone();
+            let controller = controller.clone();
+            // The listener is freed once it has been called.
+            let listener = Closure::once_into_js(move || {
+                controller.abort_with_reason(&source.as_raw().reason());
+            });
+            let _ = signal
+                .as_raw()
+                .add_event_listener_with_callback("abort", listener.unchecked_ref());
+        }
+        controller.signal()
+    }
+
+    /// Returns `true` if the signal is aborted.
+    #[inline]
+    pub fn is_aborted(&self) -> bool {
+        self.as_raw().aborted()
+    }
+
+    /// Returns the abort reason, or `None` if the signal is not yet aborted.
+    pub fn reason(&self) -> Option<JsValue> {
+        if self.is_aborted() {
+            Some(self.as_raw().reason())
+        } else {
+            None
+        }
+    }
+
+    /// Waits until the signal is aborted, and returns its abort reason.
+    ///
+    /// If the signal is already aborted, this returns immediately.
+    pub async fn wait(&self) -> JsValue {
+        aborted(self.as_raw()).await
+    }
+}
+
+impl AsRef<web_sys::AbortSignal> for AbortSignal {
+    #[inline]
+    fn as_ref(&self) -> &web_sys::AbortSignal {
+        self.as_raw()
+    }
+}
+
+impl From<web_sys::AbortSignal> for AbortSignal {
+    #[inline]
+    fn from(raw: web_sys::AbortSignal) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<AbortSignal> for web_sys::AbortSignal {
+    #[inline]
+    fn from(signal: AbortSignal) -> Self {
+        signal.into_raw()
+    }
+}
+
+/// Returns `true` if the browser supports `AbortSignal.any()`.
+fn has_any() -> bool {
+    Reflect::get(&js_sys::global(), &"AbortSignal".into())
+        .and_then(|constructor| Reflect::get(&constructor, &"any".into()))
+        .is_ok_and(|any| any.is_instance_of::<Function>())
+}
@@ -0,0 +1,347 @@
+//! Broadcasting a single [`ReadableStream`](ReadableStream) to many consumers.
+//!
+//! A [`Broadcast`](Broadcast) reads chunks from a source stream, and delivers every chunk to
+//! each of its subscribers. Unlike [`tee`](ReadableStream::tee), subscribers can be added
+//! at any time with [`subscribe`](Broadcast::subscribe), and removed by cancelling their stream.
+//!
+//! The [`BroadcastPolicy`](BroadcastPolicy) decides what happens when a subscriber
+//! cannot keep up with the source stream.
+use core::pin::Pin;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::rc::Rc;
+
+use futures::future::poll_fn;
+use futures::stream::{FusedStream, Stream, StreamExt};
+use futures::task::{Context, Poll, Waker};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+
+use crate::readable::{sys, IntoStream, ReadableStream};
+
+/// The policy of a [`Broadcast`](Broadcast) for subscribers that fall behind.
+///
+/// The default policy is [`Lossless`](BroadcastPolicy::Lossless).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BroadcastPolicy {
+    /// Every subscriber receives every chunk.
+    ///
+    /// No new chunk is read from the source stream until all subscribers have received the
+    /// previous chunk, so the slowest subscriber determines the pace of the whole broadcast.
+    /// This also couples the subscribers to each other: a subscriber that stops reading
+    /// without cancelling its stream stalls every other subscriber, and the backpressure
+    /// propagates to the source stream. Cancel a subscriber's stream once it is no longer read.
+    #[default]
+    Lossless,
+    /// Every subscriber buffers up to the given number of chunks (at least 1).
+    ///
+    /// When a subscriber's buffer is full, its oldest chunk is dropped to make room for the
+    /// new chunk. Slow subscribers never hold back the other subscribers.
+    DropOldest(usize),
+    /// Every subscriber only receives the latest chunk.
+    ///
+    /// This is equivalent to `DropOldest(1)`, and is useful for live data where only the
+    /// most recent value matters.
+    Latest,
+}
+
+impl BroadcastPolicy {
+    fn capacity(self) -> usize {
+        match self {
+            BroadcastPolicy::Lossless | BroadcastPolicy::Latest => 1,
+            BroadcastPolicy::DropOldest(capacity) => capacity.max(1),
+        }
+    }
+}
+
+/// Broadcasts the chunks of a [`ReadableStream`](ReadableStream) to any number of subscribers.
+///
+/// The source stream is only read while there is at least one subscriber.
+/// Subscribers only receive the chunks that were read after they subscribed.
+/// Note that all subscribers see the same chunk objects.
+/// If the chunks are not immutable, this could allow interference between the subscribers.
+///
+/// When the source stream closes or errors, all current and future subscribers
+/// are closed or errored as well.
+///
+/// Once the `Broadcast` is dropped and all of its subscribers have cancelled their streams,
+/// the source stream is [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream).
+#[derive(Debug)]
+pub struct Broadcast<T = JsValue> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T: JsCast + Clone + 'static> Broadcast<T> {
+    /// Creates a new `Broadcast` which reads chunks from the given `readable` stream,
+    /// using the given `policy` for subscribers that fall behind.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_new`](Self::try_new).
+    pub fn new(readable: ReadableStream<T>, policy: BroadcastPolicy) -> Self {
+        Self::try_new(readable, policy).expect_throw("already locked to a reader")
+    }
+
+    /// Tries to create a new `Broadcast` which reads chunks from the given `readable` stream,
+    /// using the given `policy` for subscribers that fall behind.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_new(
+        readable: ReadableStream<T>,
+        policy: BroadcastPolicy,
+    ) -> Result<Self, (js_sys::Error, ReadableStream<T>)> {
+        let raw = readable.as_raw().clone();
+        let stream = readable.try_into_stream()?;
+        let shared = Rc::new(RefCell::new(Shared {
+            policy,
+            subscribers: BTreeMap::new(),
+            next_id: 0,
+            state: BroadcastState::Open,
+            pump_waker: None,
+            handle_dropped: false,
+        }));
+        spawn_local(pump(stream, raw, shared.clone()));
+        Ok(Self { shared })
+    }
+
+    /// Adds a new subscriber, returning a [`ReadableStream`](ReadableStream) with all chunks
+    /// read from the source stream from now on.
+    ///
+    /// Cancelling the returned stream removes the subscriber.
+    pub fn subscribe(&self) -> ReadableStream<T> {
+        let id = {
+            let mut shared = self.shared.borrow_mut();
+            let id = shared.next_id;
+            shared.next_id += 1;
+            shared.subscribers.insert(id, SubscriberState::default());
+            shared.wake_pump();
+            id
+        };
+        let subscriber = Subscriber {
+            id,
+            shared: self.shared.clone(),
+            done: false,
+        };
+        ReadableStream::from_stream(
+            subscriber.map(|result| result.map(JsCast::unchecked_into::<JsValue>)),
+        )
+        .cast()
+    }
+
+    /// Returns the number of current subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.borrow().subscribers.len()
+    }
+
+    /// Returns the policy of this broadcast.
+    pub fn policy(&self) -> BroadcastPolicy {
+        self.shared.borrow().policy
+    }
+}
+
+impl<T> Drop for Broadcast<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.handle_dropped = true;
+        shared.wake_pump();
+    }
+}
+
+#[derive(Debug)]
+enum BroadcastState {
+    Open,
+    Closed,
+    Errored(JsValue),
+}
+
+#[derive(Debug)]
+struct SubscriberState<T> {
+    buffer: VecDeque<T>,
+    waker: Option<Waker>,
+}
+
+impl<T> Default for SubscriberState<T> {
+    fn default() -> Self {
+        Self {
+            buffer: VecDeque::new(),
+            waker: None,
+        }
+    }
+}
+
+impl<T> SubscriberState<T> {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    policy: BroadcastPolicy,
+    subscribers: BTreeMap<u64, SubscriberState<T>>,
+    next_id: u64,
+    state: BroadcastState,
+    /// Woken up whenever the pump may be able to make progress.
+    pump_waker: Option<Waker>,
+    handle_dropped: bool,
+}
+
+impl<T> Shared<T> {
+    fn wake_pump(&mut self) {
+        if let Some(waker) = self.pump_waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Returns `true` if nobody can receive any more chunks.
+    fn is_abandoned(&self) -> bool {
+        self.subscribers.is_empty() && self.handle_dropped
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// Returns `true` if the pump should read the next chunk.
+    fn wants_chunk(&self) -> bool {
+        if self.subscribers.is_empty() {
+            return false;
+        }
+        match self.policy {
+            BroadcastPolicy::Lossless => self
+                .subscribers
+                .values()
+                .all(|subscriber| subscriber.buffer.is_empty()),
+            BroadcastPolicy::DropOldest(_) | BroadcastPolicy::Latest => true,
+        }
+    }
+
+    fn push(&mut self, chunk: T) {
+        let capacity = self.policy.capacity();
+        for subscriber in self.subscribers.values_mut() {
+            while subscriber.buffer.len() >= capacity {
+                subscriber.buffer.pop_front();
+            }
+            subscriber.buffer.push_back(chunk.clone());
+            subscriber.wake();
+        }
+    }
+
+    fn finish(&mut self, state: BroadcastState) {
+        self.state = state;
+        for subscriber in self.subscribers.values_mut() {
+            subscriber.wake();
+        }
+    }
+}
+
+/// Reads chunks from the source stream, and distributes them to all subscribers.
+async fn pump<T: JsCast + Clone>(
+    mut stream: IntoStream<'static, T>,
+    raw: sys::ReadableStream,
+    shared: Rc<RefCell<Shared<T>>>,
+) {
+    loop {
+        let proceed = poll_fn(|cx| {
+            let mut shared = shared.borrow_mut();
+            if shared.wants_chunk() {
+                Poll::Ready(true)
+            } else if shared.is_abandoned() {
+                Poll::Ready(false)
+            } else {
+                shared.pump_waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await;
+        if !proceed {
+            break;
+        }
+        // While waiting for the next chunk, also watch for the last subscriber leaving,
+        // so the source stream is cancelled without waiting for its next chunk.
+        let next = poll_fn(|cx| {
+            if let Poll::Ready(item) = stream.poll_next_unpin(cx) {
+                return Poll::Ready(Some(item));
+            }
+            let mut shared = shared.borrow_mut();
+            if shared.is_abandoned() {
+                return Poll::Ready(None);
+            }
+            shared.pump_waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await;
+        match next {
+            Some(Some(Ok(chunk))) => shared.borrow_mut().push(chunk),
+            Some(Some(Err(error))) => {
+                shared.borrow_mut().finish(BroadcastState::Errored(error));
+                return;
+            }
+            Some(None) => {
+                shared.borrow_mut().finish(BroadcastState::Closed);
+                return;
+            }
+            None => break,
+        }
+    }
+    // Release the lock, so we can cancel the source stream.
+    drop(stream);
+    let _ = ReadableStream::from_raw(raw).cancel().await;
+}
+
+/// The [`Stream`](Stream) of a single subscriber.
+struct Subscriber<T: Clone> {
+    id: u64,
+    shared: Rc<RefCell<Shared<T>>>,
+    done: bool,
+}
+
+impl<T: Clone> Stream for Subscriber<T> {
+    type Item = Result<T, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let shared_rc = self.shared.clone();
+        let mut shared = shared_rc.borrow_mut();
+        let shared = &mut *shared;
+        let subscriber = shared.subscribers.get_mut(&self.id).unwrap_throw();
+        if let Some(chunk) = subscriber.buffer.pop_front() {
+            if shared.policy == BroadcastPolicy::Lossless {
+                // The pump may be waiting for this subscriber.
+                shared.wake_pump();
+            }
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+        match &shared.state {
+            BroadcastState::Open => {
+                subscriber.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            BroadcastState::Closed => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            BroadcastState::Errored(error) => {
+                self.done = true;
+                Poll::Ready(Some(Err(error.clone())))
+            }
+        }
+    }
+}
+
+impl<T: Clone> FusedStream for Subscriber<T> {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl<T: Clone> Drop for Subscriber<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.subscribers.remove(&self.id);
+        shared.wake_pump();
+    }
+}
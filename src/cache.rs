@@ -0,0 +1,79 @@
+//! Integration with the [Cache API](https://developer.mozilla.org/en-US/docs/Web/API/Cache).
+//!
+//! This module provides [`put_stream`] to store a Rust [`Stream`](Stream) of bytes as a cached
+//! response, and [`match_stream`] to read the body of a cached response as a
+//! [`ReadableStream`](ReadableStream).
+//!
+//! For write-through caching of a streamed download, use
+//! [`ReadableStream::tee_into_cache`](ReadableStream::tee_into_cache), which stores a copy of
+//! the stream in the cache while it is being read.
+//!
+//! This module requires the `cache` feature.
+use futures::stream::Stream;
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, Request, Response, ResponseInit};
+
+use crate::http::{response_from_stream, ResponseExt};
+use crate::readable::ReadableStream;
+use crate::util::{detach_promise, promise_to_void_future};
+use crate::StreamError;
+
+/// Stores a response for the given `request` in the `cache`, whose body is read from
+/// the given Rust [`Stream`](Stream) of bytes.
+///
+/// The status and headers of the response are taken from `init`.
+/// See [`response_from_stream`] for how the stream is read.
+///
+/// This waits until the whole stream has been stored. If the stream returns an error,
+/// nothing is stored and this returns that error.
+pub async fn put_stream<St, B, E>(
+    cache: &Cache,
+    request: &Request,
+    stream: St,
+    init: &ResponseInit,
+) -> Result<(), StreamError>
+where
+    St: Stream<Item = Result<B, E>> + 'static,
+    B: AsRef<[u8]> + 'static,
+    E: Into<JsValue> + 'static,
+{
+    let response = response_from_stream(stream, init)?;
+    promise_to_void_future(cache.put_with_request(request, &response))
+        .await
+        .map_err(StreamError::from)
+}
+
+/// Looks up the response for the given `request` in the `cache`, and returns its body
+/// as a [`ReadableStream`](ReadableStream).
+///
+/// This returns `None` if the cache has no matching response, or if the response has no body.
+pub async fn match_stream(
+    cache: &Cache,
+    request: &Request,
+) -> Result<Option<ReadableStream<Uint8Array>>, StreamError> {
+    let response = JsFuture::from(cache.match_with_request(request))
+        .await
+        .map_err(StreamError::from)?;
+    if response.is_undefined() {
+        return Ok(None);
+    }
+    let response = response.unchecked_into::<Response>();
+    Ok(ReadableStream::from_response_body(&response))
+}
+
+/// Tees `readable`, storing one branch in the `cache` in the background and returning
+/// the other branch.
+pub(crate) fn tee_into_cache<T: JsCast>(
+    readable: ReadableStream<T>,
+    cache: &Cache,
+    request: &Request,
+) -> Result<ReadableStream<T>, (js_sys::Error, ReadableStream<T>)> {
+    let (branch, cached) = readable.try_tee()?;
+    // The cached branch is never locked, so this cannot fail.
+    let response = Response::from_readable(cached, &ResponseInit::new()).unwrap_throw();
+    detach_promise(cache.put_with_request(request, &response));
+    Ok(branch)
+}
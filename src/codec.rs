@@ -0,0 +1,185 @@
+//! Bridging between web streams and [`tokio-util` codecs](https://docs.rs/tokio-util/latest/tokio_util/codec/).
+//!
+//! With the `tokio` feature, [`IntoAsyncRead`](crate::readable::IntoAsyncRead),
+//! [`IntoAsyncWrite`](crate::writable::IntoAsyncWrite) and
+//! [`IntoAsyncDuplex`](crate::duplex::IntoAsyncDuplex) also implement tokio's
+//! [`AsyncRead`](tokio::io::AsyncRead) and [`AsyncWrite`](tokio::io::AsyncWrite) traits.
+//! This means a byte stream can be used directly with
+//! [`FramedRead`](tokio_util::codec::FramedRead), [`FramedWrite`](tokio_util::codec::FramedWrite)
+//! and [`Framed`](tokio_util::codec::Framed), without going through `tokio_util::compat`:
+//!
+//! ```no_run
+//! # use wasm_streams::ReadableStream;
+//! use tokio_util::codec::{FramedRead, LinesCodec};
+//!
+//! # fn example(readable: ReadableStream<js_sys::Uint8Array>) {
+//! let lines = FramedRead::new(readable.into_async_read(), LinesCodec::new());
+//! # }
+//! ```
+//!
+//! This module additionally provides [`decoder_transform`](decoder_transform) and
+//! [`encoder_transform`](encoder_transform), which run a codec inside a
+//! [`TransformStream`](TransformStream) so it can be used in a JavaScript pipe chain.
+//!
+//! This module requires the `tokio` feature.
+use std::fmt;
+use std::marker::PhantomData;
+
+use bytes::BytesMut;
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::Uint8Array;
+use tokio_util::codec::{Decoder, Encoder};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::transform::{TransformStream, TransformStreamDefaultController, Transformer};
+
+fn codec_error(error: impl fmt::Display) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+/// Creates a [`TransformStream`](TransformStream) that decodes its
+/// [`Uint8Array`](Uint8Array) chunks into frames with the given `decoder`.
+///
+/// Bytes are buffered until the decoder produces a frame, so frames may span multiple chunks.
+/// When the writable side is closed, the remaining bytes are decoded with
+/// [`decode_eof`](Decoder::decode_eof). Any chunk that is not a `Uint8Array`, or any error
+/// returned by the decoder, errors the stream.
+pub fn decoder_transform<D>(decoder: D) -> TransformStream
+where
+    D: Decoder + 'static,
+    D::Item: Into<JsValue>,
+    D::Error: fmt::Display,
+{
+    TransformStream::from_transformer(DecoderTransformer {
+        decoder,
+        buffer: BytesMut::new(),
+    })
+}
+
+/// Creates a [`TransformStream`](TransformStream) that encodes its chunks of type `I`
+/// with the given `encoder`, and produces the encoded bytes as [`Uint8Array`](Uint8Array) chunks.
+///
+/// Every written chunk results in at most one output chunk. Any chunk that is not of type `I`,
+/// or any error returned by the encoder, errors the stream.
+pub fn encoder_transform<I, E>(encoder: E) -> TransformStream
+where
+    I: JsCast + 'static,
+    E: Encoder<I> + 'static,
+    E::Error: fmt::Display,
+{
+    TransformStream::from_transformer(EncoderTransformer {
+        encoder,
+        buffer: BytesMut::new(),
+        _item: PhantomData,
+    })
+}
+
+struct DecoderTransformer<D> {
+    decoder: D,
+    buffer: BytesMut,
+}
+
+impl<D> DecoderTransformer<D>
+where
+    D: Decoder,
+    D::Item: Into<JsValue>,
+    D::Error: fmt::Display,
+{
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let chunk = chunk
+            .dyn_into::<Uint8Array>()
+            .map_err(|_| js_sys::TypeError::new("chunk must be a Uint8Array"))?;
+        let start = self.buffer.len();
+        self.buffer.resize(start + chunk.length() as usize, 0);
+        chunk.copy_to(&mut self.buffer[start..]);
+        while let Some(frame) = self.decoder.decode(&mut self.buffer).map_err(codec_error)? {
+            controller.enqueue(&frame.into())?;
+        }
+        Ok(())
+    }
+
+    fn flush_sync(&mut self, controller: &TransformStreamDefaultController) -> Result<(), JsValue> {
+        while let Some(frame) = self
+            .decoder
+            .decode_eof(&mut self.buffer)
+            .map_err(codec_error)?
+        {
+            controller.enqueue(&frame.into())?;
+        }
+        Ok(())
+    }
+}
+
+impl<D> Transformer for DecoderTransformer<D>
+where
+    D: Decoder,
+    D::Item: Into<JsValue>,
+    D::Error: fmt::Display,
+{
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.flush_sync(controller)).boxed_local()
+    }
+}
+
+struct EncoderTransformer<I, E> {
+    encoder: E,
+    buffer: BytesMut,
+    _item: PhantomData<fn(I)>,
+}
+
+impl<I, E> EncoderTransformer<I, E>
+where
+    I: JsCast,
+    E: Encoder<I>,
+    E::Error: fmt::Display,
+{
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let item = chunk
+            .dyn_into::<I>()
+            .map_err(|_| js_sys::TypeError::new("chunk has the wrong type"))?;
+        self.encoder
+            .encode(item, &mut self.buffer)
+            .map_err(codec_error)?;
+        if !self.buffer.is_empty() {
+            let bytes = Uint8Array::from(&self.buffer[..]);
+            self.buffer.clear();
+            controller.enqueue(&bytes)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I, E> Transformer for EncoderTransformer<I, E>
+where
+    I: JsCast,
+    E: Encoder<I>,
+    E::Error: fmt::Display,
+{
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+}
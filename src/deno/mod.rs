@@ -0,0 +1,248 @@
+//! Helpers for the stream-based resources of the [Deno](https://deno.com/) runtime.
+//!
+//! Deno exposes the contents of files and network connections as a `readable` and a `writable`
+//! web stream. This module wraps [files](FsFile) and [connections](Conn) so those streams
+//! can be used with this crate's typed [`ReadableStream`](ReadableStream) and
+//! [`WritableStream`](WritableStream), or as a [`DuplexStream`](DuplexStream).
+//!
+//! Use [`is_deno`](is_deno) to check at runtime whether the code is running in Deno.
+//!
+//! This module requires the `deno` feature.
+use js_sys::{Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::duplex::DuplexStream;
+use crate::readable::ReadableStream;
+use crate::util::promise_to_void_future;
+use crate::writable::WritableStream;
+use crate::StreamError;
+
+pub mod sys;
+
+/// Returns `true` if the code is running in Deno.
+#[inline]
+pub fn is_deno() -> bool {
+    sys::Deno::get().is_some()
+}
+
+fn deno() -> Result<sys::Deno, StreamError> {
+    sys::Deno::get()
+        .ok_or_else(|| StreamError::TypeError(js_sys::TypeError::new("not running in Deno")))
+}
+
+/// Options for [`FsFile::open`](FsFile::open).
+///
+/// These map onto [`Deno.OpenOptions`](https://docs.deno.com/api/deno/~/Deno.OpenOptions).
+/// Options which are not set are left to Deno's defaults, which open the file for reading only.
+#[derive(Clone, Debug, Default)]
+pub struct OpenOptions {
+    read: Option<bool>,
+    write: Option<bool>,
+    append: Option<bool>,
+    truncate: Option<bool>,
+    create: Option<bool>,
+    create_new: Option<bool>,
+}
+
+impl OpenOptions {
+    /// Creates a blank set of options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert this to a raw JavaScript options object.
+    pub fn into_raw(self) -> Object {
+        let options = Object::new();
+        let entries = [
+            ("read", self.read),
+            ("write", self.write),
+            ("append", self.append),
+            ("truncate", self.truncate),
+            ("create", self.create),
+            ("createNew", self.create_new),
+        ];
+        for (name, value) in entries {
+            if let Some(value) = value {
+                Reflect::set(&options, &name.into(), &value.into()).unwrap_throw();
+            }
+        }
+        options
+    }
+
+    /// Sets whether the file can be read.
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.read = Some(read);
+        self
+    }
+
+    /// Sets whether the file can be written.
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.write = Some(write);
+        self
+    }
+
+    /// Sets whether writes append to the end of the file.
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.append = Some(append);
+        self
+    }
+
+    /// Sets whether an existing file is truncated when it is opened.
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.truncate = Some(truncate);
+        self
+    }
+
+    /// Sets whether the file is created if it does not exist yet.
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.create = Some(create);
+        self
+    }
+
+    /// Sets whether opening fails if the file already exists.
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.create_new = Some(create_new);
+        self
+    }
+}
+
+/// A [`Deno.FsFile`](https://docs.deno.com/api/deno/~/Deno.FsFile).
+#[derive(Debug)]
+pub struct FsFile {
+    raw: sys::FsFile,
+}
+
+impl FsFile {
+    /// Opens the file at the given `path` with the given `options`.
+    ///
+    /// If not running in Deno, or if the file could not be opened, then this returns an error.
+    pub async fn open(path: &str, options: &OpenOptions) -> Result<Self, StreamError> {
+        let promise = deno()?.open(path, &options.clone().into_raw());
+        let raw = JsFuture::from(promise).await.map_err(StreamError::from)?;
+        Ok(Self::from_raw(raw.unchecked_into()))
+    }
+
+    /// Creates a new `FsFile` from a [JavaScript object](sys::FsFile).
+    ///
+    /// Any object with a `readable` and a `writable` property can be used.
+    #[inline]
+    pub fn from_raw(raw: sys::FsFile) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](sys::FsFile).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::FsFile {
+        &self.raw
+    }
+
+    /// Consumes this `FsFile`, returning the underlying [JavaScript object](sys::FsFile).
+    #[inline]
+    pub fn into_raw(self) -> sys::FsFile {
+        self.raw
+    }
+
+    /// Returns the stream of bytes read from the file.
+    ///
+    /// The file is closed once the stream is read to the end or cancelled.
+    #[inline]
+    pub fn readable(&self) -> ReadableStream<Uint8Array> {
+        ReadableStream::from_raw(self.as_raw().readable()).cast()
+    }
+
+    /// Returns the stream of bytes written to the file.
+    ///
+    /// The file is closed once the stream is closed or aborted.
+    #[inline]
+    pub fn writable(&self) -> WritableStream<Uint8Array> {
+        WritableStream::from_raw(self.as_raw().writable()).cast()
+    }
+
+    /// Closes the file.
+    ///
+    /// This returns an error if the file was already closed, e.g. through one of its streams.
+    pub fn close(self) -> Result<(), StreamError> {
+        self.as_raw().close().map_err(StreamError::from)
+    }
+}
+
+/// A [`Deno.Conn`](https://docs.deno.com/api/deno/~/Deno.Conn), such as a TCP connection.
+#[derive(Debug)]
+pub struct Conn {
+    raw: sys::Conn,
+}
+
+impl Conn {
+    /// Opens a TCP connection to the given `hostname` and `port`.
+    ///
+    /// If not running in Deno, or if the connection could not be established,
+    /// then this returns an error.
+    pub async fn connect(hostname: &str, port: u16) -> Result<Self, StreamError> {
+        let options = Object::new();
+        Reflect::set(&options, &"hostname".into(), &hostname.into()).unwrap_throw();
+        Reflect::set(&options, &"port".into(), &port.into()).unwrap_throw();
+        let raw = JsFuture::from(deno()?.connect(&options))
+            .await
+            .map_err(StreamError::from)?;
+        Ok(Self::from_raw(raw.unchecked_into()))
+    }
+
+    /// Creates a new `Conn` from a [JavaScript object](sys::Conn).
+    ///
+    /// Any object with a `readable` and a `writable` property can be used.
+    #[inline]
+    pub fn from_raw(raw: sys::Conn) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](sys::Conn).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::Conn {
+        &self.raw
+    }
+
+    /// Consumes this `Conn`, returning the underlying [JavaScript object](sys::Conn).
+    #[inline]
+    pub fn into_raw(self) -> sys::Conn {
+        self.raw
+    }
+
+    /// Returns the stream of bytes received from the peer.
+    #[inline]
+    pub fn readable(&self) -> ReadableStream<Uint8Array> {
+        ReadableStream::from_raw(self.as_raw().readable()).cast()
+    }
+
+    /// Returns the stream of bytes sent to the peer.
+    #[inline]
+    pub fn writable(&self) -> WritableStream<Uint8Array> {
+        WritableStream::from_raw(self.as_raw().writable()).cast()
+    }
+
+    /// Returns both streams of this connection as a [`DuplexStream`](DuplexStream).
+    ///
+    /// This can in turn be converted into an [`AsyncRead`](futures::io::AsyncRead) +
+    /// [`AsyncWrite`](futures::io::AsyncWrite) with
+    /// [`into_async_duplex`](DuplexStream::into_async_duplex).
+    #[inline]
+    pub fn into_duplex(self) -> DuplexStream<Uint8Array, Uint8Array> {
+        DuplexStream::new(self.readable(), self.writable())
+    }
+
+    /// Closes the write side of the connection, while the read side remains open.
+    pub async fn close_write(&self) -> Result<(), StreamError> {
+        let promise = self.as_raw().close_write().map_err(StreamError::from)?;
+        promise_to_void_future(promise)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Closes the connection.
+    ///
+    /// This returns an error if the connection was already closed, e.g. through one of its streams.
+    pub fn close(self) -> Result<(), StreamError> {
+        self.as_raw().close().map_err(StreamError::from)
+    }
+}
@@ -0,0 +1,67 @@
+//! Raw bindings to the stream-based resources of the [Deno](https://deno.com/) runtime.
+use js_sys::{Object, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::readable::sys::ReadableStream;
+use crate::writable::sys::WritableStream;
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global [`Deno`](https://docs.deno.com/api/deno/) namespace object.
+    #[derive(Clone, Debug)]
+    pub type Deno;
+
+    #[wasm_bindgen(method, js_name = open)]
+    pub fn open(this: &Deno, path: &str, options: &Object) -> Promise;
+
+    #[wasm_bindgen(method, js_name = connect)]
+    pub fn connect(this: &Deno, options: &Object) -> Promise;
+}
+
+impl Deno {
+    /// Returns the global `Deno` namespace, or `None` if not running in Deno.
+    pub fn get() -> Option<Deno> {
+        let deno = js_sys::Reflect::get(&js_sys::global(), &"Deno".into()).ok()?;
+        if deno.is_object() {
+            Some(deno.unchecked_into())
+        } else {
+            None
+        }
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`Deno.FsFile`](https://docs.deno.com/api/deno/~/Deno.FsFile).
+    #[derive(Clone, Debug)]
+    pub type FsFile;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &FsFile) -> ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &FsFile) -> WritableStream;
+
+    #[wasm_bindgen(method, catch, js_name = close)]
+    pub fn close(this: &FsFile) -> Result<(), JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`Deno.Conn`](https://docs.deno.com/api/deno/~/Deno.Conn), such as a TCP connection.
+    #[derive(Clone, Debug)]
+    pub type Conn;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &Conn) -> ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &Conn) -> WritableStream;
+
+    #[wasm_bindgen(method, catch, js_name = closeWrite)]
+    pub fn close_write(this: &Conn) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = close)]
+    pub fn close(this: &Conn) -> Result<(), JsValue>;
+}
@@ -0,0 +1,384 @@
+//! A bidirectional stream, made of a [`ReadableStream`](ReadableStream) and
+//! a [`WritableStream`](WritableStream).
+//!
+//! Many Web APIs expose a connection as a `{ readable, writable }` pair, such as a
+//! [`WebTransportBidirectionalStream`](https://developer.mozilla.org/en-US/docs/Web/API/WebTransportBidirectionalStream),
+//! the connection of a [`WebSocketStream`](https://github.com/ricea/websocketstream-explainer)
+//! or a [`TransformStream`](TransformStream).
+//! A [`DuplexStream`](DuplexStream) wraps such a pair, and can be converted into a single
+//! socket-like object which implements both [`Stream`](Stream) and [`Sink`](Sink),
+//! or both [`AsyncRead`](AsyncRead) and [`AsyncWrite`](AsyncWrite).
+use core::pin::Pin;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
+
+use futures::io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use futures::sink::Sink;
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll};
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::readable::{self, IntoAsyncRead, IntoStream, ReadableStream};
+use crate::transform::TransformStream;
+use crate::writable::{self, IntoAsyncWrite, IntoSink, WritableStream};
+
+/// A pair of a [`ReadableStream`](ReadableStream) and a [`WritableStream`](WritableStream),
+/// which together form a bidirectional connection.
+///
+/// `DuplexStream`s can be created from both halves with [`new`](Self::new), from a JavaScript
+/// `{ readable, writable }` object with [`from_raw_pair`](Self::from_raw_pair), or from a
+/// [`TransformStream`](TransformStream).
+///
+/// Use [`into_duplex`](Self::into_duplex) to convert it into a Rust [`Stream`](Stream) and
+/// [`Sink`](Sink), or [`into_async_duplex`](Self::into_async_duplex) to convert it into
+/// an [`AsyncRead`](AsyncRead) and [`AsyncWrite`](AsyncWrite).
+pub struct DuplexStream<R = JsValue, W = JsValue> {
+    readable: ReadableStream<R>,
+    writable: WritableStream<W>,
+}
+
+impl<R, W> fmt::Debug for DuplexStream<R, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DuplexStream")
+            .field("readable", &self.readable)
+            .field("writable", &self.writable)
+            .finish()
+    }
+}
+
+impl<R: JsCast + 'static, W: JsCast + 'static> DuplexStream<R, W> {
+    /// Creates a new `DuplexStream` from its readable and writable half.
+    #[inline]
+    pub fn new(readable: ReadableStream<R>, writable: WritableStream<W>) -> Self {
+        Self { readable, writable }
+    }
+
+    /// Creates a new `DuplexStream` from a JavaScript object with a `readable`
+    /// and a `writable` property.
+    ///
+    /// If either property is not a stream of the right type, then this returns a `TypeError`.
+    pub fn from_raw_pair(pair: &JsValue) -> Result<Self, JsValue> {
+        let readable = Reflect::get(pair, &"readable".into())?
+            .dyn_into::<readable::sys::ReadableStream>()
+            .map_err(|_| js_sys::TypeError::new("readable is not a ReadableStream"))?;
+        let writable = Reflect::get(pair, &"writable".into())?
+            .dyn_into::<writable::sys::WritableStream>()
+            .map_err(|_| js_sys::TypeError::new("writable is not a WritableStream"))?;
+        Ok(Self::new(
+            ReadableStream::from_raw(readable).cast(),
+            WritableStream::from_raw(writable).cast(),
+        ))
+    }
+
+    /// Acquires a reference to the readable half of this stream.
+    #[inline]
+    pub fn readable(&self) -> &ReadableStream<R> {
+        &self.readable
+    }
+
+    /// Acquires a mutable reference to the readable half of this stream.
+    #[inline]
+    pub fn readable_mut(&mut self) -> &mut ReadableStream<R> {
+        &mut self.readable
+    }
+
+    /// Acquires a reference to the writable half of this stream.
+    #[inline]
+    pub fn writable(&self) -> &WritableStream<W> {
+        &self.writable
+    }
+
+    /// Acquires a mutable reference to the writable half of this stream.
+    #[inline]
+    pub fn writable_mut(&mut self) -> &mut WritableStream<W> {
+        &mut self.writable
+    }
+
+    /// Consumes this `DuplexStream`, returning its readable and writable half.
+    #[inline]
+    pub fn into_parts(self) -> (ReadableStream<R>, WritableStream<W>) {
+        (self.readable, self.writable)
+    }
+
+    /// Converts this `DuplexStream` into a Rust [`Stream`](Stream) and [`Sink`](Sink).
+    ///
+    /// See [`ReadableStream.into_stream`](ReadableStream::into_stream) and
+    /// [`WritableStream.into_sink`](WritableStream::into_sink) for more details.
+    ///
+    /// **Panics** if either half is already locked. For a non-panicking variant,
+    /// use [`try_into_duplex`](Self::try_into_duplex).
+    #[inline]
+    pub fn into_duplex(self) -> IntoDuplex<R, W> {
+        self.try_into_duplex()
+            .expect_throw("already locked to a reader or writer")
+    }
+
+    /// Try to convert this `DuplexStream` into a Rust [`Stream`](Stream) and [`Sink`](Sink).
+    ///
+    /// See [`into_duplex`](Self::into_duplex) for more details.
+    ///
+    /// If either half is already locked, then this returns an error
+    /// along with the original `DuplexStream`.
+    pub fn try_into_duplex(self) -> Result<IntoDuplex<R, W>, (js_sys::Error, Self)> {
+        if let Some(err) = self.check_unlocked() {
+            return Err((err, self));
+        }
+        Ok(IntoDuplex {
+            stream: self.readable.into_stream(),
+            sink: self.writable.into_sink(),
+        })
+    }
+
+    /// Converts this `DuplexStream` into an [`AsyncRead`](AsyncRead) and
+    /// [`AsyncWrite`](AsyncWrite).
+    ///
+    /// See [`ReadableStream.into_async_read`](ReadableStream::into_async_read) and
+    /// [`WritableStream.into_async_write`](WritableStream::into_async_write) for more details.
+    ///
+    /// **Panics** if either half is already locked. For a non-panicking variant,
+    /// use [`try_into_async_duplex`](Self::try_into_async_duplex).
+    #[inline]
+    pub fn into_async_duplex(self) -> IntoAsyncDuplex {
+        self.try_into_async_duplex()
+            .expect_throw("already locked to a reader or writer")
+    }
+
+    /// Try to convert this `DuplexStream` into an [`AsyncRead`](AsyncRead) and
+    /// [`AsyncWrite`](AsyncWrite).
+    ///
+    /// See [`into_async_duplex`](Self::into_async_duplex) for more details.
+    ///
+    /// If either half is already locked, then this returns an error
+    /// along with the original `DuplexStream`.
+    pub fn try_into_async_duplex(self) -> Result<IntoAsyncDuplex, (js_sys::Error, Self)> {
+        if let Some(err) = self.check_unlocked() {
+            return Err((err, self));
+        }
+        Ok(IntoAsyncDuplex {
+            read: self.readable.cast::<JsValue>().into_async_read(),
+            write: self.writable.cast::<JsValue>().into_async_write(),
+        })
+    }
+
+    fn check_unlocked(&self) -> Option<js_sys::Error> {
+        if self.readable.is_locked() {
+            Some(js_sys::Error::new("already locked to a reader"))
+        } else if self.writable.is_locked() {
+            Some(js_sys::Error::new("already locked to a writer"))
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<TransformStream> for DuplexStream {
+    type Error = (js_sys::Error, TransformStream);
+
+    /// Creates a `DuplexStream` which writes to the writable side of the transform stream,
+    /// and reads from its readable side.
+    ///
+    /// If the readable or writable side was already [taken](TransformStream::take_readable),
+    /// then this returns an error along with the original `TransformStream`.
+    fn try_from(transform: TransformStream) -> Result<Self, Self::Error> {
+        let (readable, writable) = transform.try_into_pair()?.into_parts();
+        Ok(Self::new(readable, writable))
+    }
+}
+
+/// A [`Stream`](Stream) and [`Sink`](Sink) for the
+/// [`into_duplex`](DuplexStream::into_duplex) method.
+///
+/// This holds both a reader and a writer, and therefore locks both halves of the
+/// [`DuplexStream`](DuplexStream). When this is dropped, it also drops its reader and writer
+/// which in turn [release their locks](https://streams.spec.whatwg.org/#release-a-lock).
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct IntoDuplex<R: 'static = JsValue, W: 'static = JsValue> {
+    stream: IntoStream<'static, R>,
+    sink: IntoSink<'static, W>,
+}
+
+impl<R: JsCast + 'static, W: JsCast + 'static> IntoDuplex<R, W> {
+    /// Acquires a reference to the readable half.
+    #[inline]
+    pub fn stream(&self) -> &IntoStream<'static, R> {
+        &self.stream
+    }
+
+    /// Acquires a mutable reference to the readable half.
+    #[inline]
+    pub fn stream_mut(&mut self) -> &mut IntoStream<'static, R> {
+        &mut self.stream
+    }
+
+    /// Acquires a reference to the writable half.
+    #[inline]
+    pub fn sink(&self) -> &IntoSink<'static, W> {
+        &self.sink
+    }
+
+    /// Acquires a mutable reference to the writable half.
+    #[inline]
+    pub fn sink_mut(&mut self) -> &mut IntoSink<'static, W> {
+        &mut self.sink
+    }
+
+    /// Splits this into its readable and writable half, so they can be used independently.
+    #[inline]
+    pub fn split(self) -> (IntoStream<'static, R>, IntoSink<'static, W>) {
+        (self.stream, self.sink)
+    }
+}
+
+impl<R: JsCast + 'static, W: JsCast + 'static> FusedStream for IntoDuplex<R, W> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl<R: JsCast + 'static, W: JsCast + 'static> Stream for IntoDuplex<R, W> {
+    type Item = Result<R, JsValue>;
+
+    #[inline]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+impl<R: JsCast + 'static, W: JsCast + 'static> Sink<W> for IntoDuplex<R, W> {
+    type Error = JsValue;
+
+    #[inline]
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.sink).poll_ready(cx)
+    }
+
+    #[inline]
+    fn start_send(mut self: Pin<&mut Self>, item: W) -> Result<(), Self::Error> {
+        Pin::new(&mut self.sink).start_send(item)
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.sink).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.sink).poll_close(cx)
+    }
+}
+
+/// An [`AsyncRead`](AsyncRead) and [`AsyncWrite`](AsyncWrite) for the
+/// [`into_async_duplex`](DuplexStream::into_async_duplex) method.
+///
+/// Closing this only closes the writable half. The readable half keeps producing data
+/// until the other side closes it, like a half-closed TCP socket.
+#[must_use = "readers and writers do nothing unless polled"]
+#[derive(Debug)]
+pub struct IntoAsyncDuplex {
+    read: IntoAsyncRead<'static>,
+    write: IntoAsyncWrite<'static>,
+}
+
+impl IntoAsyncDuplex {
+    /// Splits this into its readable and writable half, so they can be used independently.
+    #[inline]
+    pub fn split(self) -> (IntoAsyncRead<'static>, IntoAsyncWrite<'static>) {
+        (self.read, self.write)
+    }
+}
+
+impl AsyncRead for IntoAsyncDuplex {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.read).poll_read(cx, buf)
+    }
+}
+
+impl AsyncBufRead for IntoAsyncDuplex {
+    #[inline]
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        Pin::new(&mut self.get_mut().read).poll_fill_buf(cx)
+    }
+
+    #[inline]
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.read).consume(amt)
+    }
+}
+
+impl AsyncWrite for IntoAsyncDuplex {
+    #[inline]
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.write).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.write).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.write).poll_close(cx)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for IntoAsyncDuplex {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        tokio::io::AsyncRead::poll_read(Pin::new(&mut self.read), cx, buf)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncBufRead for IntoAsyncDuplex {
+    #[inline]
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        AsyncBufRead::poll_fill_buf(self, cx)
+    }
+
+    #[inline]
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        AsyncBufRead::consume(self, amt)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for IntoAsyncDuplex {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        AsyncWrite::poll_write(self, cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_flush(self, cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_close(self, cx)
+    }
+}
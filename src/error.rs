@@ -0,0 +1,321 @@
+//! Classified errors for stream operations.
+//!
+//! See [`StreamError`](StreamError) for the error type, and [`ClassifyErrors`](ClassifyErrors)
+//! for converting the errors of a [`Stream`](Stream) or [`Sink`](Sink).
+use core::pin::Pin;
+use std::fmt;
+
+use futures::sink::Sink;
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll};
+
+use js_sys::{RangeError, TypeError};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{AbortSignal, DomException};
+
+use crate::readable::ReadError;
+
+/// An error from a stream operation, classified by the type of the JavaScript error.
+///
+/// Streams can be errored with any JavaScript value, so this wraps the original value
+/// and sorts it into one of the variants below. The original value is always available
+/// through [`as_js`](Self::as_js) or [`into_js`](Self::into_js),
+/// and converting a `StreamError` back into a [`JsValue`](JsValue) returns that same value.
+///
+/// Stream operations return this error, and have a `_js` variant
+/// (e.g. [`cancel_js`](crate::ReadableStream::cancel_js)) which returns the raw `JsValue` instead.
+/// Reading chunks returns a [`ReadError`](ReadError), which can also fail for reasons
+/// that are not JavaScript errors. It can be converted into a `StreamError` with [`From`](From).
+///
+/// Some common causes for each variant:
+/// * [`Aborted`](Self::Aborted): aborting an [`AbortSignal`](web_sys::AbortSignal) passed to a pipe.
+/// * [`TypeError`](Self::TypeError): using a stream that is locked or already closed,
+///   or a network failure while reading the body of a `fetch()` response.
+/// * [`Other`](Self::Other): a stream that was errored with a non-`Error` value,
+///   such as a string.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum StreamError {
+    /// The operation was aborted, with the given abort reason.
+    ///
+    /// By default, this is a `DOMException` named `AbortError`. When the error is classified
+    /// with [`from_js_with_signal`](Self::from_js_with_signal), this can also be
+    /// a custom reason given to [`AbortController.abort_with_reason`](web_sys::AbortController::abort_with_reason).
+    Aborted(JsValue),
+    /// A `DOMException` named `TimeoutError`.
+    Timeout(DomException),
+    /// Any other `DOMException`.
+    DomException(DomException),
+    /// A `TypeError`.
+    TypeError(TypeError),
+    /// A `RangeError`.
+    RangeError(RangeError),
+    /// Any other `Error` object.
+    Error(js_sys::Error),
+    /// A value which is not an `Error` object.
+    Other(JsValue),
+}
+
+impl StreamError {
+    /// Classifies the given JavaScript error value.
+    pub fn from_js(value: JsValue) -> Self {
+        let value = match value.dyn_into::<DomException>() {
+            Ok(exception) => {
+                return match exception.name().as_str() {
+                    "AbortError" => StreamError::Aborted(exception.into()),
+                    "TimeoutError" => StreamError::Timeout(exception),
+                    _ => StreamError::DomException(exception),
+                }
+            }
+            Err(value) => value,
+        };
+        let value = match value.dyn_into::<TypeError>() {
+            Ok(error) => return StreamError::TypeError(error),
+            Err(value) => value,
+        };
+        let value = match value.dyn_into::<RangeError>() {
+            Ok(error) => return StreamError::RangeError(error),
+            Err(value) => value,
+        };
+        match value.dyn_into::<js_sys::Error>() {
+            Ok(error) => StreamError::Error(error),
+            Err(value) => StreamError::Other(value),
+        }
+    }
+
+    /// Classifies the given JavaScript error value, which was caused by an operation
+    /// that was controlled by the given `signal`.
+    ///
+    /// If the `signal` is aborted and the error is its [abort reason](AbortSignal::reason),
+    /// this returns an [`Aborted`](Self::Aborted) error, even if the reason is a custom value.
+    /// Otherwise, this is equivalent to [`from_js`](Self::from_js).
+    pub fn from_js_with_signal(value: JsValue, signal: &AbortSignal) -> Self {
+        if signal.aborted() && value == signal.reason() {
+            return StreamError::Aborted(value);
+        }
+        Self::from_js(value)
+    }
+
+    /// Acquires a reference to the original JavaScript error value.
+    pub fn as_js(&self) -> &JsValue {
+        match self {
+            StreamError::Aborted(value) | StreamError::Other(value) => value,
+            StreamError::Timeout(exception) | StreamError::DomException(exception) => exception,
+            StreamError::TypeError(error) => error,
+            StreamError::RangeError(error) => error,
+            StreamError::Error(error) => error,
+        }
+    }
+
+    /// Consumes this `StreamError`, returning the original JavaScript error value.
+    pub fn into_js(self) -> JsValue {
+        match self {
+            StreamError::Aborted(value) | StreamError::Other(value) => value,
+            StreamError::Timeout(exception) | StreamError::DomException(exception) => {
+                exception.into()
+            }
+            StreamError::TypeError(error) => error.into(),
+            StreamError::RangeError(error) => error.into(),
+            StreamError::Error(error) => error.into(),
+        }
+    }
+
+    /// Returns `true` if this is an [`Aborted`](Self::Aborted) error.
+    #[inline]
+    pub fn is_aborted(&self) -> bool {
+        matches!(self, StreamError::Aborted(_))
+    }
+
+    /// Returns the name of the error, such as `"TypeError"` or `"AbortError"`.
+    ///
+    /// Returns `None` if the error is not an `Error` object or a `DOMException`.
+    pub fn name(&self) -> Option<String> {
+        let value = self.as_js();
+        if let Some(exception) = value.dyn_ref::<DomException>() {
+            Some(exception.name())
+        } else {
+            value
+                .dyn_ref::<js_sys::Error>()
+                .map(|error| error.name().into())
+        }
+    }
+
+    /// Returns the message of the error.
+    ///
+    /// If the error is not an `Error` object or a `DOMException`, this returns
+    /// the error value itself if it is a string.
+    pub fn message(&self) -> Option<String> {
+        let value = self.as_js();
+        if let Some(exception) = value.dyn_ref::<DomException>() {
+            Some(exception.message())
+        } else if let Some(error) = value.dyn_ref::<js_sys::Error>() {
+            Some(error.message().into())
+        } else {
+            value.as_string()
+        }
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.name(), self.message()) {
+            (Some(name), Some(message)) if !message.is_empty() => {
+                write!(f, "{}: {}", name, message)
+            }
+            (Some(name), _) => f.write_str(&name),
+            (None, Some(message)) => f.write_str(&message),
+            (None, None) => write!(f, "{:?}", self.as_js()),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<JsValue> for StreamError {
+    #[inline]
+    fn from(value: JsValue) -> Self {
+        StreamError::from_js(value)
+    }
+}
+
+impl From<js_sys::Error> for StreamError {
+    #[inline]
+    fn from(error: js_sys::Error) -> Self {
+        StreamError::from_js(error.into())
+    }
+}
+
+impl From<ReadError> for StreamError {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Js(value) => StreamError::from_js(value),
+            ReadError::Aborted(reason) => StreamError::Aborted(reason),
+            error => StreamError::from_js(error.into()),
+        }
+    }
+}
+
+impl From<StreamError> for JsValue {
+    #[inline]
+    fn from(error: StreamError) -> Self {
+        error.into_js()
+    }
+}
+
+/// A [`Stream`](Stream) or [`Sink`](Sink) adapter which converts its `JsValue` errors
+/// into [`StreamError`](StreamError)s.
+///
+/// This is returned by [`IntoStream::classify_errors`](crate::readable::IntoStream::classify_errors)
+/// and [`IntoSink::classify_errors`](crate::writable::IntoSink::classify_errors).
+///
+/// If the stream is aborted through an [`AbortSignal`](AbortSignal), for example
+/// by passing the signal to [`PipeOptions::signal`](crate::readable::PipeOptions::signal),
+/// then use [`with_signal`](Self::with_signal) to report every error caused by aborting
+/// that signal as [`StreamError::Aborted`](StreamError::Aborted).
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct ClassifyErrors<S> {
+    inner: S,
+    signal: Option<AbortSignal>,
+}
+
+impl<S> ClassifyErrors<S> {
+    #[inline]
+    pub(crate) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            signal: None,
+        }
+    }
+
+    /// Classifies errors using the given `signal`.
+    ///
+    /// See [`StreamError::from_js_with_signal`](StreamError::from_js_with_signal).
+    pub fn with_signal(mut self, signal: &AbortSignal) -> Self {
+        self.signal = Some(signal.clone());
+        self
+    }
+
+    /// Acquires a reference to the underlying stream or sink.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Acquires a mutable reference to the underlying stream or sink.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consumes this adapter, returning the underlying stream or sink.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn classify(&self, error: JsValue) -> StreamError {
+        match &self.signal {
+            Some(signal) => StreamError::from_js_with_signal(error, signal),
+            None => StreamError::from_js(error),
+        }
+    }
+}
+
+impl<S, T> Stream for ClassifyErrors<S>
+where
+    S: Stream<Item = Result<T, JsValue>> + Unpin,
+{
+    type Item = Result<T, StreamError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|item| item.map(|result| result.map_err(|error| self.classify(error))))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S, T> FusedStream for ClassifyErrors<S>
+where
+    S: FusedStream<Item = Result<T, JsValue>> + Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<S, Item> Sink<Item> for ClassifyErrors<S>
+where
+    S: Sink<Item, Error = JsValue> + Unpin,
+{
+    type Error = StreamError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner)
+            .poll_ready(cx)
+            .map_err(|error| self.classify(error))
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        Pin::new(&mut self.inner)
+            .start_send(item)
+            .map_err(|error| self.classify(error))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner)
+            .poll_flush(cx)
+            .map_err(|error| self.classify(error))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.inner)
+            .poll_close(cx)
+            .map_err(|error| self.classify(error))
+    }
+}
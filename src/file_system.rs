@@ -0,0 +1,180 @@
+//! Integration with the [File System API](https://developer.mozilla.org/en-US/docs/Web/API/File_System_API).
+//!
+//! [`WritableFileStream`](WritableFileStream) wraps a
+//! [`FileSystemWritableFileStream`](web_sys::FileSystemWritableFileStream), and
+//! [`ReadableStream::pipe_to_file`](crate::ReadableStream::pipe_to_file) streams a
+//! readable stream straight into a file, e.g. to save a large download to disk.
+//!
+//! This module requires the `file-system` feature.
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AbortController, FileSystemCreateWritableOptions, FileSystemFileHandle,
+    FileSystemWritableFileStream,
+};
+
+use crate::readable::{self, PipeOptions, ReadableStream};
+use crate::util::promise_to_void_future;
+use crate::writable::WritableStream;
+use crate::StreamError;
+
+/// A writable stream to a file, created from a
+/// [`FileSystemFileHandle`](web_sys::FileSystemFileHandle).
+///
+/// Changes are written to a temporary file, and only replace the contents of the file once
+/// the stream is [closed](Self::close). If the stream is [aborted](Self::abort) instead,
+/// the changes are discarded.
+///
+/// Use [`into_writable_stream`](Self::into_writable_stream) to write to the file
+/// like any other [`WritableStream`](WritableStream).
+#[derive(Debug)]
+pub struct WritableFileStream {
+    raw: FileSystemWritableFileStream,
+}
+
+impl WritableFileStream {
+    /// Creates a new `WritableFileStream` for the file of the given `handle`.
+    ///
+    /// The stream starts out empty, so any existing contents of the file are replaced
+    /// once the stream is closed.
+    pub async fn create(handle: &FileSystemFileHandle) -> Result<Self, StreamError> {
+        let raw = JsFuture::from(handle.create_writable())
+            .await
+            .map_err(StreamError::from)?;
+        Ok(Self::from_raw(raw.unchecked_into()))
+    }
+
+    /// Creates a new `WritableFileStream` for the file of the given `handle`,
+    /// starting with the existing contents of the file.
+    ///
+    /// Use [`seek`](Self::seek) to append to the file, or to overwrite parts of it.
+    pub async fn create_keeping_existing_data(
+        handle: &FileSystemFileHandle,
+    ) -> Result<Self, StreamError> {
+        let options = FileSystemCreateWritableOptions::new();
+        options.set_keep_existing_data(true);
+        let raw = JsFuture::from(handle.create_writable_with_options(&options))
+            .await
+            .map_err(StreamError::from)?;
+        Ok(Self::from_raw(raw.unchecked_into()))
+    }
+
+    /// Creates a new `WritableFileStream` from a
+    /// [JavaScript stream](web_sys::FileSystemWritableFileStream).
+    #[inline]
+    pub fn from_raw(raw: FileSystemWritableFileStream) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying
+    /// [JavaScript stream](web_sys::FileSystemWritableFileStream).
+    #[inline]
+    pub fn as_raw(&self) -> &FileSystemWritableFileStream {
+        &self.raw
+    }
+
+    /// Consumes this `WritableFileStream`, returning the underlying
+    /// [JavaScript stream](web_sys::FileSystemWritableFileStream).
+    #[inline]
+    pub fn into_raw(self) -> FileSystemWritableFileStream {
+        self.raw
+    }
+
+    /// Moves the current write position to the given byte offset.
+    ///
+    /// If the position is past the end of the file, the gap is filled with zeroes
+    /// by the next write.
+    pub async fn seek(&mut self, position: f64) -> Result<(), StreamError> {
+        let promise = self.as_raw().seek_with_f64(position)?;
+        promise_to_void_future(promise)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Resizes the file to the given number of bytes.
+    ///
+    /// If the file grows, it is padded with zeroes. If the current write position is past
+    /// the new end of the file, it is moved to the end.
+    pub async fn truncate(&mut self, size: f64) -> Result<(), StreamError> {
+        let promise = self.as_raw().truncate_with_f64(size)?;
+        promise_to_void_future(promise)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Writes the given bytes at the current write position.
+    pub async fn write_bytes(&mut self, data: &[u8]) -> Result<(), StreamError> {
+        let promise = self.as_raw().write_with_u8_array(data)?;
+        promise_to_void_future(promise)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Closes the stream, replacing the contents of the file with everything written so far.
+    pub async fn close(self) -> Result<(), StreamError> {
+        let promise = self.as_raw().close();
+        promise_to_void_future(promise)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Aborts the stream, discarding everything written so far.
+    pub async fn abort(self) -> Result<(), StreamError> {
+        self.into_writable_stream().abort().await
+    }
+
+    /// Converts this `WritableFileStream` into a [`WritableStream`](WritableStream).
+    ///
+    /// Each chunk must be an `ArrayBuffer`, an `ArrayBufferView`, a `Blob`, a string, or a
+    /// [write command](https://developer.mozilla.org/en-US/docs/Web/API/FileSystemWritableFileStream/write#parameters).
+    #[inline]
+    pub fn into_writable_stream(self) -> WritableStream {
+        WritableStream::from_raw(self.raw.unchecked_into())
+    }
+}
+
+impl From<WritableFileStream> for WritableStream {
+    #[inline]
+    fn from(stream: WritableFileStream) -> Self {
+        stream.into_writable_stream()
+    }
+}
+
+/// Aborts the pipe when dropped, unless it has finished.
+struct AbortOnDrop {
+    controller: Option<AbortController>,
+}
+
+impl AbortOnDrop {
+    fn finish(mut self) {
+        self.controller = None;
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(controller) = self.controller.take() {
+            controller.abort();
+        }
+    }
+}
+
+pub(crate) async fn pipe_to_file(
+    readable: &readable::sys::ReadableStream,
+    handle: &FileSystemFileHandle,
+) -> Result<(), StreamError> {
+    let mut readable = ReadableStream::from_raw(readable.clone());
+    let mut writable = WritableFileStream::create(handle)
+        .await?
+        .into_writable_stream();
+    let controller = AbortController::new().unwrap_throw();
+    let mut options = PipeOptions::new();
+    options.signal(controller.signal());
+    let guard = AbortOnDrop {
+        controller: Some(controller),
+    };
+    let result = readable.pipe_to_with_options(&mut writable, &options).await;
+    guard.finish();
+    result
+}
@@ -0,0 +1,127 @@
+//! Integration with the [Fetch API](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API).
+//!
+//! This module provides extension traits to create a [`Response`](web_sys::Response) or
+//! [`Request`](web_sys::Request) with a streaming body from a [`ReadableStream`].
+//! To read the body of a response as a stream, see
+//! [`ReadableStream::from_response_body`](ReadableStream::from_response_body).
+//!
+//! It also provides [`response_from_stream`] to serve a Rust [`Stream`](Stream) of bytes
+//! as a `Response`, and [`respond_with_stream`] to do so from a service worker's
+//! [`FetchEvent`](web_sys::FetchEvent).
+//!
+//! This module requires the `http` feature.
+//! [`respond_with_stream`] additionally requires the `service-worker` feature.
+use futures::stream::{Stream, TryStreamExt};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Request, RequestInit, Response, ResponseInit};
+
+use crate::readable::ReadableStream;
+
+/// Extension methods for [`Response`](web_sys::Response).
+pub trait ResponseExt: Sized {
+    /// Creates a new `Response` whose body is read from the given `readable` stream.
+    ///
+    /// The stream should produce [`Uint8Array`](js_sys::Uint8Array) chunks.
+    ///
+    /// This returns an error if the stream is already locked to a reader.
+    fn from_readable<T: JsCast>(
+        readable: ReadableStream<T>,
+        init: &ResponseInit,
+    ) -> Result<Self, JsValue>;
+}
+
+impl ResponseExt for Response {
+    fn from_readable<T: JsCast>(
+        readable: ReadableStream<T>,
+        init: &ResponseInit,
+    ) -> Result<Self, JsValue> {
+        let body = readable
+            .into_raw()
+            .unchecked_into::<web_sys::ReadableStream>();
+        Response::new_with_opt_readable_stream_and_init(Some(&body), init)
+    }
+}
+
+/// Extension methods for [`Request`](web_sys::Request).
+pub trait RequestExt: Sized {
+    /// Creates a new `Request` for the given `url`, whose body is uploaded from
+    /// the given `readable` stream.
+    ///
+    /// The stream should produce [`Uint8Array`](js_sys::Uint8Array) chunks.
+    /// The other options are taken from `init`, which is left unchanged.
+    /// Since browsers only support streaming uploads in half-duplex mode, this also sets
+    /// the request's [`duplex`](https://fetch.spec.whatwg.org/#dom-requestinit-duplex)
+    /// option to `"half"`.
+    ///
+    /// This returns an error if the stream is already locked to a reader.
+    fn from_readable<T: JsCast>(
+        url: &str,
+        readable: ReadableStream<T>,
+        init: &RequestInit,
+    ) -> Result<Self, JsValue>;
+}
+
+impl RequestExt for Request {
+    fn from_readable<T: JsCast>(
+        url: &str,
+        readable: ReadableStream<T>,
+        init: &RequestInit,
+    ) -> Result<Self, JsValue> {
+        // Copy the options, so we don't modify the caller's init.
+        let init =
+            js_sys::Object::assign(&js_sys::Object::new(), init).unchecked_into::<RequestInit>();
+        init.set_body(readable.as_raw());
+        // web-sys doesn't have a setter for this yet.
+        js_sys::Reflect::set(
+            &init,
+            &JsValue::from_str("duplex"),
+            &JsValue::from_str("half"),
+        )?;
+        Request::new_with_str_and_init(url, &init)
+    }
+}
+
+/// Creates a new [`Response`](web_sys::Response) whose body is read from the given
+/// Rust [`Stream`](Stream) of bytes.
+///
+/// Every item of the stream is copied into a new [`Uint8Array`](js_sys::Uint8Array) chunk.
+/// If the stream returns an error, the response body is errored with that error.
+///
+/// If the consumer of the response cancels its body (e.g. because the client disconnected),
+/// the stream is dropped without being polled again.
+pub fn response_from_stream<St, B, E>(stream: St, init: &ResponseInit) -> Result<Response, JsValue>
+where
+    St: Stream<Item = Result<B, E>> + 'static,
+    B: AsRef<[u8]> + 'static,
+    E: Into<JsValue> + 'static,
+{
+    let stream = stream
+        .map_ok(|chunk| js_sys::Uint8Array::from(chunk.as_ref()).into())
+        .map_err(Into::into);
+    Response::from_readable(ReadableStream::from_stream(stream), init)
+}
+
+/// Responds to the given [`FetchEvent`](web_sys::FetchEvent) with a [`Response`](web_sys::Response)
+/// whose body is read from the given Rust [`Stream`](Stream) of bytes.
+///
+/// See [`response_from_stream`] for more details. In particular, if the client disconnects
+/// before the response is complete, the stream is dropped.
+///
+/// This must be called synchronously while handling the `fetch` event.
+///
+/// This requires the `service-worker` feature.
+#[cfg(feature = "service-worker")]
+pub fn respond_with_stream<St, B, E>(
+    event: &web_sys::FetchEvent,
+    stream: St,
+    init: &ResponseInit,
+) -> Result<(), JsValue>
+where
+    St: Stream<Item = Result<B, E>> + 'static,
+    B: AsRef<[u8]> + 'static,
+    E: Into<JsValue> + 'static,
+{
+    let response = response_from_stream(stream, init)?;
+    event.respond_with(&js_sys::Promise::resolve(&JsValue::from(response)))
+}
@@ -5,13 +5,71 @@
 //! [`WritableStream`](crate::WritableStream) and [`TransformStream`](crate::TransformStream).
 //! It also supports converting from and into [`Stream`](futures::Stream)s
 //! and [`Sink`](futures::Sink)s from the [futures crate](https://docs.rs/futures/).
+//!
+//! The raw bindings in the `sys` modules are structural: methods are looked up on the object
+//! itself rather than on the current realm's prototypes. This means that streams coming from
+//! other realms (such as iframes or workers) or from a polyfill such as
+//! [web-streams-polyfill](https://github.com/MattiasBuelens/web-streams-polyfill)
+//! can be used with [`ReadableStream::from_raw`], [`WritableStream::from_raw`]
+//! and [`TransformStream::from_raw`].
+//!
+//! The bindings only refer to the global scope (`globalThis`) and never to `window`,
+//! so this crate can be used in windows, workers and worklets. Not every global scope
+//! exposes every part of the Streams API, see the [`support`](crate::support) module
+//! for detecting what is available. Helpers which wait for some time, such as
+//! [`ReadableStream::interval`] or
+//! [`read_timeout`](readable::ReadableStreamDefaultReader::read_timeout), need `setTimeout()`.
+//! Worklets do not have it, so there these helpers return an error.
+
+#[cfg(feature = "tokio")]
+extern crate tokio1 as tokio;
+
+#[macro_use]
+mod trace;
 
+pub use error::StreamError;
+pub use pipe::pipe;
+pub use queuing_strategy::QueuingStrategy;
 pub use readable::ReadableStream;
 pub use transform::TransformStream;
 pub use writable::WritableStream;
 
-pub(crate) mod queuing_strategy;
+pub mod abort;
+pub mod broadcast;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "tokio")]
+pub mod codec;
+#[cfg(feature = "deno")]
+pub mod deno;
+pub mod duplex;
+pub mod error;
+#[cfg(feature = "file-system")]
+pub mod file_system;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "media-source")]
+pub mod media_source;
+pub mod metered;
+#[cfg(feature = "node")]
+pub mod node;
+mod pipe;
+pub mod queuing_strategy;
 pub mod readable;
+pub mod resumable;
+#[cfg(feature = "serial")]
+pub mod serial;
+pub mod support;
+#[cfg(feature = "transfer")]
+pub mod transfer;
 pub mod transform;
 pub(crate) mod util;
+#[cfg(feature = "webcodecs")]
+pub mod webcodecs;
+#[cfg(feature = "webrtc")]
+pub mod webrtc;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+#[cfg(feature = "webtransport")]
+pub mod webtransport;
 pub mod writable;
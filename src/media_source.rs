@@ -0,0 +1,253 @@
+//! Integration with [Media Source Extensions](https://developer.mozilla.org/en-US/docs/Web/API/Media_Source_Extensions_API).
+//!
+//! Use [`writable_from_source_buffer`](writable_from_source_buffer) to pipe media segments,
+//! e.g. produced by a demuxer, into a [`SourceBuffer`](SourceBuffer) for playback.
+//!
+//! This module requires the `media-source` feature.
+use core::fmt;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::{Function, Promise, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::SourceBuffer;
+
+use crate::util::sleep;
+use crate::writable::{UnderlyingSink, WritableStream, WritableStreamDefaultController};
+
+type QuotaExceededCallback = Rc<dyn Fn(&SourceBuffer)>;
+
+/// Options for [`writable_from_source_buffer`](writable_from_source_buffer).
+#[derive(Clone)]
+pub struct SourceBufferSinkOptions {
+    retry_interval: Duration,
+    max_retries: usize,
+    on_quota_exceeded: Option<QuotaExceededCallback>,
+}
+
+impl Default for SourceBufferSinkOptions {
+    fn default() -> Self {
+        Self {
+            retry_interval: Duration::from_secs(1),
+            max_retries: 5,
+            on_quota_exceeded: None,
+        }
+    }
+}
+
+impl fmt::Debug for SourceBufferSinkOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceBufferSinkOptions")
+            .field("retry_interval", &self.retry_interval)
+            .field("max_retries", &self.max_retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SourceBufferSinkOptions {
+    /// Creates a new set of sink options with the default values.
+    ///
+    /// Equivalent to [`SourceBufferSinkOptions::default`](Default::default).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets how long to wait before appending a chunk again, after the `SourceBuffer`
+    /// rejected it because it is full. Defaults to 1 second.
+    pub fn retry_interval(&mut self, interval: Duration) -> &mut Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Sets how many times a chunk is appended again after the `SourceBuffer` rejected it
+    /// because it is full. Once all retries are used up, the write fails with the
+    /// `QuotaExceededError`. Defaults to 5.
+    pub fn max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets a callback that is called when the `SourceBuffer` is full.
+    ///
+    /// The callback can free up space, typically by
+    /// [removing](https://developer.mozilla.org/en-US/docs/Web/API/SourceBuffer/remove)
+    /// media that has already been played. The sink waits for the removal to finish
+    /// before appending the chunk again.
+    pub fn on_quota_exceeded<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&SourceBuffer) + 'static,
+    {
+        self.on_quota_exceeded = Some(Rc::new(callback));
+        self
+    }
+}
+
+/// Creates a [`WritableStream`](WritableStream) which appends its chunks to the given
+/// [`SourceBuffer`](SourceBuffer).
+///
+/// Each chunk is appended with
+/// [`appendBuffer`](https://developer.mozilla.org/en-US/docs/Web/API/SourceBuffer/appendBuffer),
+/// and a write only completes once the buffer has finished processing it. This applies
+/// backpressure, since the next chunk cannot be appended before that anyway.
+///
+/// When the buffer is full, it rejects the chunk with a `QuotaExceededError`. The chunk is then
+/// kept, the [`on_quota_exceeded`](SourceBufferSinkOptions::on_quota_exceeded) callback
+/// is called, and the chunk is appended again after the
+/// [`retry_interval`](SourceBufferSinkOptions::retry_interval), until it fits or
+/// [`max_retries`](SourceBufferSinkOptions::max_retries) is reached.
+///
+/// * Closing the writable stream waits for the last chunk to be processed. Call
+///   [`endOfStream`](https://developer.mozilla.org/en-US/docs/Web/API/MediaSource/endOfStream)
+///   on the `MediaSource` afterwards to signal the end of the media.
+/// * Aborting the writable stream
+///   [aborts](https://developer.mozilla.org/en-US/docs/Web/API/SourceBuffer/abort)
+///   the current segment.
+/// * If the buffer fails to process a chunk, the writable stream errors.
+///
+/// This takes over the buffer's `onupdateend` and `onerror` handlers.
+pub fn writable_from_source_buffer(
+    buffer: &SourceBuffer,
+    options: &SourceBufferSinkOptions,
+) -> WritableStream<Uint8Array> {
+    let shared = Rc::new(Shared {
+        buffer: buffer.clone(),
+        update_end: RefCell::new(None),
+        error: RefCell::new(None),
+        handlers: RefCell::new(None),
+    });
+
+    let weak = Rc::downgrade(&shared);
+    let on_update_end = Closure::<dyn FnMut()>::new(move || {
+        if let Some(shared) = weak.upgrade() {
+            let resolve = shared.update_end.borrow_mut().take();
+            if let Some(resolve) = resolve {
+                let _ = resolve.call0(&JsValue::UNDEFINED);
+            }
+        }
+    });
+    buffer.set_onupdateend(Some(on_update_end.as_ref().unchecked_ref()));
+
+    let weak = Rc::downgrade(&shared);
+    let on_error = Closure::<dyn FnMut()>::new(move || {
+        if let Some(shared) = weak.upgrade() {
+            // The buffer fires `updateend` right after, which completes the pending write.
+            let error = js_sys::Error::new("SourceBuffer failed to append the chunk");
+            *shared.error.borrow_mut() = Some(error.into());
+        }
+    });
+    buffer.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+    *shared.handlers.borrow_mut() = Some(Handlers {
+        _on_update_end: on_update_end,
+        _on_error: on_error,
+    });
+
+    WritableStream::from_underlying_sink(SourceBufferSink {
+        shared,
+        options: options.clone(),
+    })
+    .cast()
+}
+
+/// State shared between the event handlers and the sink.
+struct Shared {
+    buffer: SourceBuffer,
+    /// Resolves the promise of a write that is waiting for the buffer to finish updating.
+    update_end: RefCell<Option<Function>>,
+    /// The error of the last append, if it failed.
+    error: RefCell<Option<JsValue>>,
+    handlers: RefCell<Option<Handlers>>,
+}
+
+/// The event handlers installed on the buffer, kept alive for as long as the stream.
+struct Handlers {
+    _on_update_end: Closure<dyn FnMut()>,
+    _on_error: Closure<dyn FnMut()>,
+}
+
+impl Shared {
+    /// Waits until the buffer is no longer updating.
+    async fn idle(&self) -> Result<(), JsValue> {
+        while self.buffer.updating() {
+            let update_end = Promise::new(&mut |resolve, _reject| {
+                *self.update_end.borrow_mut() = Some(resolve);
+            });
+            JsFuture::from(update_end).await?;
+        }
+        match self.error.borrow_mut().take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        // Remove the handlers before their closures are dropped.
+        self.buffer.set_onupdateend(None);
+        self.buffer.set_onerror(None);
+    }
+}
+
+/// Returns `true` if the given error is a `QuotaExceededError`.
+fn is_quota_exceeded(error: &JsValue) -> bool {
+    js_sys::Reflect::get(error, &"name".into())
+        .ok()
+        .and_then(|name| name.as_string())
+        .is_some_and(|name| name == "QuotaExceededError")
+}
+
+struct SourceBufferSink {
+    shared: Rc<Shared>,
+    options: SourceBufferSinkOptions,
+}
+
+impl UnderlyingSink for SourceBufferSink {
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            let chunk = chunk
+                .dyn_into::<Uint8Array>()
+                .map_err(|_| js_sys::TypeError::new("chunk must be a Uint8Array"))?;
+            let shared = &self.shared;
+            let mut retries = 0;
+            loop {
+                shared.idle().await?;
+                match shared.buffer.append_buffer_with_array_buffer_view(&chunk) {
+                    Ok(()) => return shared.idle().await,
+                    Err(error) if is_quota_exceeded(&error) => {
+                        if retries >= self.options.max_retries {
+                            return Err(error);
+                        }
+                        retries += 1;
+                        if let Some(callback) = &self.options.on_quota_exceeded {
+                            callback(&shared.buffer);
+                        }
+                        shared.idle().await?;
+                        sleep(self.options.retry_interval.as_secs_f64() * 1000.0).await?;
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move { self.shared.idle().await }.boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // This fails if the buffer was removed from its `MediaSource`, in which case
+        // there is nothing to abort.
+        let _ = self.shared.buffer.abort();
+        ready(Ok(())).boxed_local()
+    }
+}
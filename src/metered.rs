@@ -0,0 +1,244 @@
+//! Instrumentation for measuring the throughput of streams.
+//!
+//! Wrap a [`ReadableStream`](ReadableStream) or [`WritableStream`](WritableStream) with
+//! [`Metered::readable`](Metered::readable) or [`Metered::writable`](Metered::writable) to count the chunks and bytes passing through it.
+//! The counters can be queried at any time through a [`MeterHandle`](MeterHandle),
+//! e.g. to display the transfer speed while a pipe is running.
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::readable::ReadableStream;
+use crate::transform::{TransformStream, TransformStreamDefaultController, Transformer};
+use crate::util::chunk_byte_length;
+use crate::writable::WritableStream;
+
+/// A handle to the counters of a [`Metered`](Metered) stream.
+///
+/// Handles can be cloned freely, all clones share the same counters.
+#[derive(Clone, Debug, Default)]
+pub struct MeterHandle {
+    meter: Rc<Meter>,
+}
+
+#[derive(Debug, Default)]
+struct Meter {
+    chunks: Cell<u64>,
+    bytes: Cell<u64>,
+    /// The time of the first chunk, in milliseconds since the epoch.
+    started: Cell<Option<f64>>,
+    /// The time at which the stream closed, in milliseconds since the epoch.
+    finished: Cell<Option<f64>>,
+}
+
+impl MeterHandle {
+    /// Returns the number of chunks that have passed through the stream so far.
+    #[inline]
+    pub fn chunks(&self) -> u64 {
+        self.meter.chunks.get()
+    }
+
+    /// Returns the number of bytes that have passed through the stream so far.
+    ///
+    /// Only chunks that are an [`ArrayBuffer`](js_sys::ArrayBuffer) or an
+    /// [`ArrayBufferView`](https://developer.mozilla.org/en-US/docs/Web/API/ArrayBufferView)
+    /// (such as a [`Uint8Array`](js_sys::Uint8Array)) contribute to this count.
+    #[inline]
+    pub fn bytes(&self) -> u64 {
+        self.meter.bytes.get()
+    }
+
+    /// Returns `true` if the stream has closed.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.meter.finished.get().is_some()
+    }
+
+    /// Returns the time elapsed since the first chunk passed through the stream,
+    /// up until the stream closed.
+    ///
+    /// This is zero until the first chunk passes through.
+    pub fn elapsed(&self) -> Duration {
+        let started = match self.meter.started.get() {
+            Some(started) => started,
+            None => return Duration::ZERO,
+        };
+        let finished = self.meter.finished.get().unwrap_or_else(js_sys::Date::now);
+        Duration::from_secs_f64((finished - started).max(0.0) / 1000.0)
+    }
+
+    /// Returns the average throughput in bytes per second, over the [elapsed](Self::elapsed)
+    /// time.
+    ///
+    /// Returns `None` if no time has elapsed yet.
+    pub fn bytes_per_second(&self) -> Option<f64> {
+        let elapsed = self.elapsed().as_secs_f64();
+        if elapsed > 0.0 {
+            Some(self.bytes() as f64 / elapsed)
+        } else {
+            None
+        }
+    }
+
+    /// Creates an identity [`TransformStream`](TransformStream) which updates the counters
+    /// of this handle.
+    pub(crate) fn transform(&self) -> TransformStream {
+        TransformStream::from_transformer(MeteringTransformer {
+            meter: self.meter.clone(),
+        })
+    }
+}
+
+/// A stream that counts the chunks and bytes passing through it.
+///
+/// The counters can be queried through the [`handle`](Self::handle).
+/// Use [`into_inner`](Self::into_inner) to retrieve the metered stream, and then use it
+/// as usual.
+#[derive(Debug)]
+pub struct Metered<S> {
+    inner: S,
+    handle: MeterHandle,
+}
+
+impl<S> Metered<S> {
+    /// Returns a handle to the counters of this stream.
+    #[inline]
+    pub fn handle(&self) -> MeterHandle {
+        self.handle.clone()
+    }
+
+    /// Acquires a reference to the metered stream.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Acquires a mutable reference to the metered stream.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Consumes this `Metered`, returning the metered stream.
+    ///
+    /// Handles remain valid, and keep counting the chunks passing through the stream.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<T: JsCast> Metered<ReadableStream<T>> {
+    /// Creates a new `ReadableStream` that counts all chunks read from the given
+    /// `readable` stream.
+    ///
+    /// This pipes `readable` through an identity transform stream,
+    /// so `readable` becomes locked.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_readable`](Self::try_readable).
+    pub fn readable(readable: ReadableStream<T>) -> Self {
+        Self::try_readable(readable)
+            .map_err(|(err, _)| err)
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to create a new `ReadableStream` that counts all chunks read from the given
+    /// `readable` stream.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_readable(
+        mut readable: ReadableStream<T>,
+    ) -> Result<Self, (js_sys::Error, ReadableStream<T>)> {
+        let handle = MeterHandle::default();
+        let inner = match readable.try_pipe_through(&handle.transform()) {
+            Ok(inner) => inner.cast(),
+            Err(err) => return Err((err, readable)),
+        };
+        Ok(Self { inner, handle })
+    }
+}
+
+impl<T: JsCast> Metered<WritableStream<T>> {
+    /// Creates a new `WritableStream` that counts all chunks written to it,
+    /// and forwards them to the given `writable` stream.
+    ///
+    /// This pipes an identity transform stream to `writable`,
+    /// so `writable` becomes locked. Errors propagate as with
+    /// [`pipe_to`](ReadableStream::pipe_to).
+    ///
+    /// **Panics** if the stream is already locked to a writer. For a non-panicking variant,
+    /// use [`try_writable`](Self::try_writable).
+    pub fn writable(writable: WritableStream<T>) -> Self {
+        Self::try_writable(writable)
+            .map_err(|(err, _)| err)
+            .expect_throw("already locked to a writer")
+    }
+
+    /// Try to create a new `WritableStream` that counts all chunks written to it,
+    /// and forwards them to the given `writable` stream.
+    ///
+    /// If the stream is already locked to a writer, then this returns an error
+    /// along with the original `WritableStream`.
+    pub fn try_writable(
+        writable: WritableStream<T>,
+    ) -> Result<Self, (js_sys::Error, WritableStream<T>)> {
+        if writable.is_locked() {
+            return Err((
+                js_sys::TypeError::new("already locked to a writer").into(),
+                writable,
+            ));
+        }
+        let handle = MeterHandle::default();
+        let inner = writable.pipe_from_transform(handle.transform());
+        Ok(Self { inner, handle })
+    }
+}
+
+/// An identity transformer that counts the chunks it delivers downstream.
+struct MeteringTransformer {
+    meter: Rc<Meter>,
+}
+
+impl MeteringTransformer {
+    /// Enqueues a chunk downstream, and counts it once it was accepted.
+    fn enqueue(
+        &self,
+        controller: &TransformStreamDefaultController,
+        chunk: &JsValue,
+    ) -> Result<(), JsValue> {
+        // Measure the chunk before handing it off, in case enqueueing transfers its buffer.
+        let byte_length = u64::from(chunk_byte_length(chunk));
+        controller.enqueue(chunk)?;
+        let meter = &self.meter;
+        if meter.started.get().is_none() {
+            meter.started.set(Some(js_sys::Date::now()));
+        }
+        meter.chunks.set(meter.chunks.get() + 1);
+        meter.bytes.set(meter.bytes.get() + byte_length);
+        Ok(())
+    }
+}
+
+impl Transformer for MeteringTransformer {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.enqueue(controller, &chunk)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.meter.finished.set(Some(js_sys::Date::now()));
+        ready(Ok(())).boxed_local()
+    }
+}
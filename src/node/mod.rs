@@ -0,0 +1,213 @@
+//! Conversions between this crate's streams and [Node.js streams](https://nodejs.org/api/stream.html).
+//!
+//! This allows the same code to run both in the browser and in Node.js, e.g. for server-side
+//! rendering or tests. The conversions use Node's own `toWeb()` and `fromWeb()` methods when
+//! they are available (since Node.js 17), and fall back to an adapter built on
+//! [`Readable.from()`](https://nodejs.org/api/stream.html#streamreadablefromiterable-options)
+//! and the `Writable` constructor otherwise.
+//!
+//! Node.js streams created by these functions are in
+//! [object mode](https://nodejs.org/api/stream.html#object-mode), so they pass on chunks as-is.
+//!
+//! This module requires the `node` feature. Since it imports the `node:stream` module,
+//! it can only be used when the WebAssembly module is running in Node.js.
+use futures::future::{FutureExt, LocalBoxFuture};
+use js_sys::{Function, Object, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+
+use crate::duplex::DuplexStream;
+use crate::readable::ReadableStream;
+use crate::util::promise_to_void_future;
+use crate::writable::{self, UnderlyingSink, WritableStream, WritableStreamDefaultController};
+
+pub mod sys;
+
+/// Converts a Node.js [`Readable`](sys::Readable) into a [`ReadableStream`](ReadableStream).
+///
+/// Cancelling the returned stream destroys the Node.js stream.
+pub fn readable_to_web(readable: &sys::Readable) -> Result<ReadableStream, JsValue> {
+    if sys::Readable::to_web_method().is_function() {
+        return Ok(ReadableStream::from_raw(sys::Readable::to_web(readable)?));
+    }
+    // Node.js readable streams are async iterable.
+    ReadableStream::from_js_iterable(readable)
+}
+
+/// Converts a Node.js [`Writable`](sys::Writable) into a [`WritableStream`](WritableStream).
+///
+/// Aborting the returned stream destroys the Node.js stream.
+pub fn writable_to_web(writable: &sys::Writable) -> Result<WritableStream, JsValue> {
+    if sys::Writable::to_web_method().is_function() {
+        return Ok(WritableStream::from_raw(sys::Writable::to_web(writable)?));
+    }
+    Ok(WritableStream::from_underlying_sink(NodeWritableSink {
+        raw: writable.clone(),
+    }))
+}
+
+/// Converts a Node.js [`Duplex`](sys::Duplex) into a [`DuplexStream`](DuplexStream).
+pub fn duplex_to_web(duplex: &sys::Duplex) -> Result<DuplexStream, JsValue> {
+    if sys::Duplex::to_web_method().is_function() {
+        return DuplexStream::from_raw_pair(&sys::Duplex::to_web(duplex)?.into());
+    }
+    Ok(DuplexStream::new(
+        readable_to_web(duplex)?,
+        writable_to_web(duplex.as_ref())?,
+    ))
+}
+
+/// Converts a [`ReadableStream`](ReadableStream) into a Node.js [`Readable`](sys::Readable).
+///
+/// Destroying the returned Node.js stream cancels the `ReadableStream`.
+/// If the stream is already locked to a reader, then this returns an error.
+pub fn readable_from_web<T: JsCast + 'static>(
+    readable: ReadableStream<T>,
+) -> Result<sys::Readable, JsValue> {
+    if sys::Readable::from_web_method().is_function() {
+        return sys::Readable::from_web(readable.as_raw(), &object_mode_options()?);
+    }
+    let iterator = readable
+        .try_into_async_iterator(false)
+        .map_err(|(error, _)| error)?;
+    sys::Readable::from_iterable(&iterator)
+}
+
+/// Converts a [`WritableStream`](WritableStream) into a Node.js [`Writable`](sys::Writable).
+///
+/// Destroying the returned Node.js stream aborts the `WritableStream`.
+/// If the stream is already locked to a writer, then this returns an error.
+pub fn writable_from_web<T: JsCast>(writable: WritableStream<T>) -> Result<sys::Writable, JsValue> {
+    if sys::Writable::from_web_method().is_function() {
+        return sys::Writable::from_web(writable.as_raw(), &object_mode_options()?);
+    }
+    let writer = writable.as_raw().get_writer()?;
+    node_writable_from_writer(writer)
+}
+
+/// Converts a [`DuplexStream`](DuplexStream) into a Node.js [`Duplex`](sys::Duplex).
+///
+/// If either half is already locked, then this returns an error.
+pub fn duplex_from_web<R, W>(duplex: DuplexStream<R, W>) -> Result<sys::Duplex, JsValue>
+where
+    R: JsCast + 'static,
+    W: JsCast + 'static,
+{
+    let (readable, writable) = duplex.into_parts();
+    let pair = Object::new();
+    if sys::Duplex::from_web_method().is_function() {
+        Reflect::set(&pair, &"readable".into(), readable.as_raw())?;
+        Reflect::set(&pair, &"writable".into(), writable.as_raw())?;
+        return sys::Duplex::from_web(&pair, &object_mode_options()?);
+    }
+    Reflect::set(
+        &pair,
+        &"readable".into(),
+        &readable_from_web(readable)?.into(),
+    )?;
+    Reflect::set(
+        &pair,
+        &"writable".into(),
+        &writable_from_web(writable)?.into(),
+    )?;
+    sys::Duplex::from_pair(&pair)
+}
+
+fn object_mode_options() -> Result<Object, JsValue> {
+    let options = Object::new();
+    Reflect::set(&options, &"objectMode".into(), &JsValue::TRUE)?;
+    Ok(options)
+}
+
+/// Returns a promise which settles when the given Node.js-style `callback` is called.
+fn callback_promise(f: impl FnOnce(&Function)) -> Promise {
+    let mut f = Some(f);
+    Promise::new(&mut |resolve, reject| {
+        let callback = Closure::once_into_js(move |error: JsValue| {
+            if error.is_null() || error.is_undefined() {
+                let _ = resolve.call0(&JsValue::NULL);
+            } else {
+                let _ = reject.call1(&JsValue::NULL, &error);
+            }
+        });
+        if let Some(f) = f.take() {
+            f(callback.unchecked_ref());
+        }
+    })
+}
+
+/// Calls the given Node.js-style `callback` once the `promise` settles.
+fn settle_callback(promise: Promise, callback: Function) {
+    spawn_local(async move {
+        let error = JsFuture::from(promise).await.err().unwrap_or(JsValue::NULL);
+        let _ = callback.call1(&JsValue::NULL, &error);
+    });
+}
+
+/// An underlying sink which writes to a Node.js [`Writable`](sys::Writable).
+struct NodeWritableSink {
+    raw: sys::Writable,
+}
+
+impl UnderlyingSink for NodeWritableSink {
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // The callback is called once the chunk is handled, which also applies backpressure.
+        let promise = callback_promise(|callback| {
+            self.raw.write_with_callback(&chunk, callback);
+        });
+        promise_to_void_future(promise).boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let promise = callback_promise(|callback| self.raw.end_with_callback(callback));
+        promise_to_void_future(promise).boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.raw.destroy(&reason);
+        futures::future::ready(Ok(())).boxed_local()
+    }
+}
+
+/// Creates a Node.js [`Writable`](sys::Writable) which writes to the given `writer`.
+fn node_writable_from_writer(
+    writer: writable::sys::WritableStreamDefaultWriter,
+) -> Result<sys::Writable, JsValue> {
+    let write = {
+        let writer = writer.clone();
+        Closure::<dyn FnMut(JsValue, JsValue, Function)>::new(
+            move |chunk: JsValue, _encoding: JsValue, callback: Function| {
+                settle_callback(writer.write(chunk), callback);
+            },
+        )
+    };
+    let final_ = {
+        let writer = writer.clone();
+        Closure::<dyn FnMut(Function)>::new(move |callback: Function| {
+            settle_callback(writer.close(), callback);
+        })
+    };
+    let destroy =
+        Closure::<dyn FnMut(JsValue, Function)>::new(move |error: JsValue, callback: Function| {
+            let promise = if error.is_null() || error.is_undefined() {
+                writer.abort()
+            } else {
+                writer.abort_with_reason(&error)
+            };
+            spawn_local(async move {
+                let _ = JsFuture::from(promise).await;
+                let _ = callback.call1(&JsValue::NULL, &error);
+            });
+        });
+    let options = object_mode_options()?;
+    // The closures are owned by the Node.js stream from now on.
+    Reflect::set(&options, &"write".into(), &write.into_js_value())?;
+    Reflect::set(&options, &"final".into(), &final_.into_js_value())?;
+    Reflect::set(&options, &"destroy".into(), &destroy.into_js_value())?;
+    Ok(sys::Writable::new(&options))
+}
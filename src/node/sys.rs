@@ -0,0 +1,109 @@
+//! Raw bindings to the [`node:stream`](https://nodejs.org/api/stream.html) module of Node.js.
+use js_sys::Object;
+use wasm_bindgen::prelude::*;
+
+use crate::readable::sys::ReadableStream;
+use crate::writable::sys::WritableStream;
+
+#[wasm_bindgen(module = "node:stream")]
+extern "C" {
+    /// A raw Node.js [`stream.Readable`](https://nodejs.org/api/stream.html#class-streamreadable).
+    #[derive(Clone, Debug)]
+    pub type Readable;
+
+    /// Creates a new readable stream from an iterable or async iterable,
+    /// using [`Readable.from()`](https://nodejs.org/api/stream.html#streamreadablefromiterable-options).
+    #[wasm_bindgen(static_method_of = Readable, catch, js_name = from)]
+    pub fn from_iterable(iterable: &JsValue) -> Result<Readable, JsValue>;
+
+    #[wasm_bindgen(static_method_of = Readable, getter, js_name = toWeb)]
+    pub(crate) fn to_web_method() -> JsValue;
+
+    #[wasm_bindgen(static_method_of = Readable, catch, js_name = toWeb)]
+    pub(crate) fn to_web(readable: &Readable) -> Result<ReadableStream, JsValue>;
+
+    #[wasm_bindgen(static_method_of = Readable, getter, js_name = fromWeb)]
+    pub(crate) fn from_web_method() -> JsValue;
+
+    #[wasm_bindgen(static_method_of = Readable, catch, js_name = fromWeb)]
+    pub(crate) fn from_web(
+        readable: &ReadableStream,
+        options: &Object,
+    ) -> Result<Readable, JsValue>;
+
+    #[wasm_bindgen(method, js_name = destroy)]
+    pub fn destroy(this: &Readable, error: &JsValue);
+}
+
+#[wasm_bindgen(module = "node:stream")]
+extern "C" {
+    /// A raw Node.js [`stream.Writable`](https://nodejs.org/api/stream.html#class-streamwritable).
+    #[derive(Clone, Debug)]
+    pub type Writable;
+
+    #[wasm_bindgen(constructor)]
+    pub(crate) fn new(options: &Object) -> Writable;
+
+    #[wasm_bindgen(static_method_of = Writable, getter, js_name = toWeb)]
+    pub(crate) fn to_web_method() -> JsValue;
+
+    #[wasm_bindgen(static_method_of = Writable, catch, js_name = toWeb)]
+    pub(crate) fn to_web(writable: &Writable) -> Result<WritableStream, JsValue>;
+
+    #[wasm_bindgen(static_method_of = Writable, getter, js_name = fromWeb)]
+    pub(crate) fn from_web_method() -> JsValue;
+
+    #[wasm_bindgen(static_method_of = Writable, catch, js_name = fromWeb)]
+    pub(crate) fn from_web(
+        writable: &WritableStream,
+        options: &Object,
+    ) -> Result<Writable, JsValue>;
+
+    /// Writes a `chunk`, and calls `callback` with an error (or `null`) once it was handled.
+    #[wasm_bindgen(method, js_name = write)]
+    pub fn write_with_callback(
+        this: &Writable,
+        chunk: &JsValue,
+        callback: &js_sys::Function,
+    ) -> bool;
+
+    /// Ends the stream, and calls `callback` once all data has been flushed.
+    #[wasm_bindgen(method, js_name = end)]
+    pub fn end_with_callback(this: &Writable, callback: &js_sys::Function);
+
+    #[wasm_bindgen(method, js_name = destroy)]
+    pub fn destroy(this: &Writable, error: &JsValue);
+}
+
+#[wasm_bindgen(module = "node:stream")]
+extern "C" {
+    /// A raw Node.js [`stream.Duplex`](https://nodejs.org/api/stream.html#class-streamduplex),
+    /// which is both a [`Readable`](Readable) and a [`Writable`](Writable).
+    #[wasm_bindgen(extends = Readable)]
+    #[derive(Clone, Debug)]
+    pub type Duplex;
+
+    #[wasm_bindgen(static_method_of = Duplex, getter, js_name = toWeb)]
+    pub(crate) fn to_web_method() -> JsValue;
+
+    #[wasm_bindgen(static_method_of = Duplex, catch, js_name = toWeb)]
+    pub(crate) fn to_web(duplex: &Duplex) -> Result<Object, JsValue>;
+
+    #[wasm_bindgen(static_method_of = Duplex, getter, js_name = fromWeb)]
+    pub(crate) fn from_web_method() -> JsValue;
+
+    #[wasm_bindgen(static_method_of = Duplex, catch, js_name = fromWeb)]
+    pub(crate) fn from_web(pair: &Object, options: &Object) -> Result<Duplex, JsValue>;
+
+    /// Combines a readable and a writable stream into a duplex stream, using
+    /// [`Duplex.from()`](https://nodejs.org/api/stream.html#streamduplexfromsrc).
+    #[wasm_bindgen(static_method_of = Duplex, catch, js_name = from)]
+    pub fn from_pair(pair: &Object) -> Result<Duplex, JsValue>;
+}
+
+impl AsRef<Writable> for Duplex {
+    #[inline]
+    fn as_ref(&self) -> &Writable {
+        wasm_bindgen::JsCast::unchecked_ref(self)
+    }
+}
@@ -0,0 +1,168 @@
+//! An in-memory pipe, connecting a [`WritableStream`](WritableStream)
+//! to a [`ReadableStream`](ReadableStream).
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::future::{poll_fn, FutureExt, LocalBoxFuture};
+use futures::task::{Poll, Waker};
+use wasm_bindgen::prelude::*;
+
+use crate::queuing_strategy::QueuingStrategy;
+use crate::readable::{ReadableStream, ReadableStreamDefaultController, UnderlyingSource};
+use crate::writable::{UnderlyingSink, WritableStream, WritableStreamDefaultController};
+
+/// Creates a connected pair of a [`WritableStream`](WritableStream) and
+/// a [`ReadableStream`](ReadableStream), similar to `os.pipe()`.
+///
+/// Every chunk written to the writable stream is read from the readable stream,
+/// in the same order. Up to `capacity` chunks are buffered by the writable stream before
+/// it applies [backpressure](https://streams.spec.whatwg.org/#backpressure), i.e. before
+/// [`ready`](crate::writable::WritableStreamDefaultWriter::ready) stops resolving.
+///
+/// Unlike an identity [`TransformStream`](crate::TransformStream), the pipe is implemented
+/// entirely in Rust, so it also works in environments without `TransformStream` support.
+///
+/// * Closing the writable stream closes the readable stream,
+///   after all buffered chunks have been read.
+/// * Aborting the writable stream errors the readable stream with the abort reason.
+/// * Cancelling the readable stream errors the writable stream with the cancel reason.
+pub fn pipe(capacity: usize) -> (WritableStream, ReadableStream) {
+    let shared = Rc::new(RefCell::new(Shared::default()));
+    let writable = WritableStream::from_underlying_sink_with_strategy(
+        PipeSink {
+            shared: shared.clone(),
+        },
+        QueuingStrategy::new(capacity as f64),
+    );
+    let readable = ReadableStream::from_underlying_source_with_strategy(
+        PipeSource { shared },
+        QueuingStrategy::new(0.0),
+    );
+    (writable, readable)
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    /// The chunk that is being written, waiting for the reader to take it.
+    chunk: Option<JsValue>,
+    state: State,
+    read_waker: Option<Waker>,
+    write_waker: Option<Waker>,
+}
+
+#[derive(Debug, Default)]
+enum State {
+    #[default]
+    Open,
+    Closed,
+    Aborted(JsValue),
+    Cancelled(JsValue),
+}
+
+impl Shared {
+    fn wake_reader(&mut self) {
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_writer(&mut self) {
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct PipeSink {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl UnderlyingSink for PipeSink {
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        {
+            let mut shared = self.shared.borrow_mut();
+            shared.chunk = Some(chunk);
+            shared.wake_reader();
+        }
+        // Wait until the reader has taken the chunk.
+        poll_fn(move |cx| {
+            let mut shared = self.shared.borrow_mut();
+            if let State::Cancelled(reason) = &shared.state {
+                return Poll::Ready(Err(reason.clone()));
+            }
+            if shared.chunk.is_none() {
+                return Poll::Ready(Ok(()));
+            }
+            shared.write_waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let mut shared = self.shared.borrow_mut();
+        let result = match &shared.state {
+            State::Cancelled(reason) => Err(reason.clone()),
+            _ => {
+                shared.state = State::Closed;
+                Ok(())
+            }
+        };
+        shared.wake_reader();
+        futures::future::ready(result).boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let mut shared = self.shared.borrow_mut();
+        if let State::Open = shared.state {
+            shared.state = State::Aborted(reason);
+        }
+        shared.chunk = None;
+        shared.wake_reader();
+        futures::future::ready(Ok(())).boxed_local()
+    }
+}
+
+struct PipeSource {
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl UnderlyingSource for PipeSource {
+    fn pull<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        poll_fn(move |cx| {
+            let mut shared = self.shared.borrow_mut();
+            if let Some(chunk) = shared.chunk.take() {
+                shared.wake_writer();
+                return Poll::Ready(controller.enqueue(&chunk));
+            }
+            match &shared.state {
+                State::Open => {
+                    shared.read_waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+                State::Closed => Poll::Ready(controller.close()),
+                State::Aborted(reason) => Poll::Ready(Err(reason.clone())),
+                // The stream was cancelled, so pull will not be called anymore.
+                State::Cancelled(_) => Poll::Ready(Ok(())),
+            }
+        })
+        .boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let mut shared = self.shared.borrow_mut();
+        if let State::Open = shared.state {
+            shared.state = State::Cancelled(reason);
+        }
+        shared.chunk = None;
+        shared.wake_writer();
+        futures::future::ready(Ok(())).boxed_local()
+    }
+}
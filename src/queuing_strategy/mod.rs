@@ -1,21 +1,106 @@
+//! Bindings for [queuing strategies](https://developer.mozilla.org/en-US/docs/Web/API/Streams_API/Concepts#internal_queues_and_queuing_strategies).
+use js_sys::Object;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
-#[wasm_bindgen]
-#[derive(Debug)]
-pub(crate) struct QueuingStrategy {
-    high_water_mark: f64,
+pub mod sys;
+
+/// A [queuing strategy](https://developer.mozilla.org/en-US/docs/Web/API/Streams_API/Concepts#internal_queues_and_queuing_strategies),
+/// which determines when a stream applies backpressure.
+///
+/// A queuing strategy has a high water mark and a way to compute the size of each chunk.
+/// The high water mark is the total size of all chunks that can be contained
+/// in the stream's internal queue before backpressure is applied.
+///
+/// `QueuingStrategy`s can be created from a [raw JavaScript strategy](sys::QueuingStrategy) with
+/// [`from_raw`](Self::from_raw), or using one of the constructors:
+/// * [`new`](Self::new) and [`count`](Self::count) count each chunk as one.
+/// * [`byte_length`](Self::byte_length) counts the number of bytes in each chunk.
+/// * [`with_size`](Self::with_size) computes the size of each chunk with a Rust function.
+#[derive(Clone, Debug)]
+pub struct QueuingStrategy {
+    raw: sys::QueuingStrategy,
 }
 
 impl QueuingStrategy {
+    /// Creates a new `QueuingStrategy` with the given high water mark,
+    /// where each chunk counts as one.
+    ///
+    /// Unlike [`count`](Self::count), this creates a plain JavaScript object
+    /// rather than a native `CountQueuingStrategy`.
     pub fn new(high_water_mark: f64) -> Self {
-        Self { high_water_mark }
+        Self::from_raw(new_raw_strategy(high_water_mark))
     }
-}
 
-#[wasm_bindgen]
-impl QueuingStrategy {
-    #[wasm_bindgen(getter, js_name = highWaterMark)]
+    /// Creates a new `QueuingStrategy` with the given high water mark,
+    /// where the size of each chunk is computed by the given `size` function.
+    ///
+    /// For example, a strategy which counts the number of bytes in each
+    /// [`Uint8Array`](js_sys::Uint8Array) chunk can be created with:
+    /// ```no_run
+    /// # use js_sys::Uint8Array;
+    /// # use wasm_bindgen::JsCast;
+    /// # use wasm_streams::QueuingStrategy;
+    /// let strategy = QueuingStrategy::with_size(1024.0, |chunk| {
+    ///     chunk.unchecked_ref::<Uint8Array>().byte_length() as f64
+    /// });
+    /// ```
+    pub fn with_size<F>(high_water_mark: f64, size: F) -> Self
+    where
+        F: Fn(&JsValue) -> f64 + 'static,
+    {
+        let size = Closure::<dyn Fn(JsValue) -> f64>::new(move |chunk: JsValue| size(&chunk));
+        let raw = new_raw_strategy(high_water_mark);
+        // The stream holds on to the size function for as long as it lives,
+        // so hand over ownership of the closure to JavaScript.
+        raw.set_size(size.into_js_value().unchecked_ref());
+        Self::from_raw(raw)
+    }
+
+    /// Creates a native [`CountQueuingStrategy`](https://developer.mozilla.org/en-US/docs/Web/API/CountQueuingStrategy)
+    /// with the given high water mark, where each chunk counts as one.
+    pub fn count(high_water_mark: f64) -> Self {
+        let init = new_raw_strategy(high_water_mark);
+        Self::from_raw(sys::CountQueuingStrategy::new(&init).into())
+    }
+
+    /// Creates a native [`ByteLengthQueuingStrategy`](https://developer.mozilla.org/en-US/docs/Web/API/ByteLengthQueuingStrategy)
+    /// with the given high water mark, where the size of each chunk is its `byteLength`.
+    ///
+    /// This is suitable for streams of [`ArrayBuffer`](js_sys::ArrayBuffer)s
+    /// and [`ArrayBufferView`](js_sys::Uint8Array)s.
+    pub fn byte_length(high_water_mark: f64) -> Self {
+        let init = new_raw_strategy(high_water_mark);
+        Self::from_raw(sys::ByteLengthQueuingStrategy::new(&init).into())
+    }
+
+    /// Creates a new `QueuingStrategy` from a [JavaScript strategy](sys::QueuingStrategy).
+    #[inline]
+    pub fn from_raw(raw: sys::QueuingStrategy) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript strategy](sys::QueuingStrategy).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::QueuingStrategy {
+        &self.raw
+    }
+
+    /// Consumes this `QueuingStrategy`, returning the underlying [JavaScript strategy](sys::QueuingStrategy).
+    #[inline]
+    pub fn into_raw(self) -> sys::QueuingStrategy {
+        self.raw
+    }
+
+    /// Returns the high water mark of this queuing strategy.
+    #[inline]
     pub fn high_water_mark(&self) -> f64 {
-        self.high_water_mark
+        self.as_raw().high_water_mark()
     }
 }
+
+fn new_raw_strategy(high_water_mark: f64) -> sys::QueuingStrategy {
+    let raw = Object::new().unchecked_into::<sys::QueuingStrategy>();
+    raw.set_high_water_mark(high_water_mark);
+    raw
+}
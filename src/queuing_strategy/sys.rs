@@ -0,0 +1,49 @@
+//! Raw bindings to JavaScript
+//! [queuing strategies](https://developer.mozilla.org/en-US/docs/Web/API/Streams_API/Concepts#internal_queues_and_queuing_strategies).
+use js_sys::Object;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw queuing strategy.
+    ///
+    /// This can be any JavaScript object with a `highWaterMark` property,
+    /// and optionally a `size` function.
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type QueuingStrategy;
+
+    #[wasm_bindgen(method, getter, js_name = highWaterMark)]
+    pub fn high_water_mark(this: &QueuingStrategy) -> f64;
+
+    #[wasm_bindgen(method, setter, js_name = highWaterMark)]
+    pub fn set_high_water_mark(this: &QueuingStrategy, high_water_mark: f64);
+
+    #[wasm_bindgen(method, getter, js_name = size)]
+    pub fn size(this: &QueuingStrategy) -> Option<js_sys::Function>;
+
+    #[wasm_bindgen(method, setter, js_name = size)]
+    pub fn set_size(this: &QueuingStrategy, size: &js_sys::Function);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`CountQueuingStrategy`](https://developer.mozilla.org/en-US/docs/Web/API/CountQueuingStrategy).
+    #[wasm_bindgen(extends = QueuingStrategy, extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type CountQueuingStrategy;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(init: &QueuingStrategy) -> CountQueuingStrategy;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`ByteLengthQueuingStrategy`](https://developer.mozilla.org/en-US/docs/Web/API/ByteLengthQueuingStrategy).
+    #[wasm_bindgen(extends = QueuingStrategy, extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type ByteLengthQueuingStrategy;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(init: &QueuingStrategy) -> ByteLengthQueuingStrategy;
+}
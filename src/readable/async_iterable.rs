@@ -0,0 +1,148 @@
+use core::pin::Pin;
+
+use futures::future::Future;
+use futures::ready;
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll};
+use js_sys::{IteratorNext, Object, Promise, Reflect, Symbol};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::util::detach_promise;
+
+#[wasm_bindgen]
+extern "C" {
+    /// An object implementing the JavaScript iterator or async iterator protocol.
+    #[derive(Clone, Debug)]
+    type IteratorProtocol;
+
+    #[wasm_bindgen(method, catch, js_name = next)]
+    fn next(this: &IteratorProtocol) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, getter, js_name = return)]
+    fn return_fn(this: &IteratorProtocol) -> JsValue;
+}
+
+/// A [`Stream`](Stream) over the values of a JavaScript
+/// [async iterable](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Iteration_protocols#the_async_iterator_and_async_iterable_protocols).
+///
+/// This consumes the iterable the same way as a `for await (const value of iterable)` loop.
+/// Any object with a `[Symbol.asyncIterator]()` method can be used, such as
+/// a [`ReadableStream`](super::sys::ReadableStream) (in browsers that support async iteration
+/// of streams), an async generator or a Node.js readable stream.
+/// Synchronous iterables such as arrays are supported as well.
+///
+/// Items are the raw [`JsValue`](JsValue)s produced by the iterator.
+/// If the iterator throws or its promise rejects, the stream yields the error and ends.
+///
+/// When this stream is dropped before the iterator is done, it calls the iterator's `return()`
+/// method, just like breaking out of a `for await` loop. For a `ReadableStream`, this
+/// [cancels](https://streams.spec.whatwg.org/#cancel-a-readable-stream) the stream.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct AsyncIterableStream {
+    iterator: Option<IteratorProtocol>,
+    fut: Option<JsFuture>,
+}
+
+impl AsyncIterableStream {
+    /// Creates a new `AsyncIterableStream` over the values of the given `iterable`.
+    ///
+    /// This uses the iterable's `[Symbol.asyncIterator]()` method, or its `[Symbol.iterator]()`
+    /// method if it is not async iterable. If the value is not iterable at all,
+    /// then this returns a `TypeError`.
+    pub fn new(iterable: &JsValue) -> Result<Self, JsValue> {
+        let mut method = Reflect::get(iterable, &Symbol::async_iterator())?;
+        if method.is_undefined() || method.is_null() {
+            method = Reflect::get(iterable, &Symbol::iterator())?;
+        }
+        let method = method
+            .dyn_into::<js_sys::Function>()
+            .map_err(|_| js_sys::TypeError::new("value is not async iterable"))?;
+        let iterator = method.call0(iterable)?;
+        if !iterator.is_object() {
+            return Err(js_sys::TypeError::new("iterator is not an object").into());
+        }
+        Ok(Self::from_iterator(iterator.unchecked_into()))
+    }
+
+    /// Creates a new `AsyncIterableStream` which reads values from the given `iterator`.
+    ///
+    /// The iterator's `next()` method is called whenever the stream is polled for a new item.
+    #[inline]
+    pub fn from_iterator(iterator: Object) -> Self {
+        Self {
+            iterator: Some(iterator.unchecked_into()),
+            fut: None,
+        }
+    }
+}
+
+impl FusedStream for AsyncIterableStream {
+    fn is_terminated(&self) -> bool {
+        self.iterator.is_none()
+    }
+}
+
+impl Stream for AsyncIterableStream {
+    type Item = Result<JsValue, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let iterator = match &this.iterator {
+            Some(iterator) => iterator,
+            None => return Poll::Ready(None),
+        };
+        if this.fut.is_none() {
+            match iterator.next() {
+                // The result of a synchronous iterator is not a promise, so resolve it first.
+                Ok(result) => this.fut = Some(JsFuture::from(Promise::resolve(&result))),
+                Err(error) => {
+                    this.iterator = None;
+                    return Poll::Ready(Some(Err(error)));
+                }
+            }
+        }
+
+        let js_result = ready!(Pin::new(this.fut.as_mut().unwrap_throw()).poll(cx));
+        this.fut = None;
+        Poll::Ready(match js_result {
+            Ok(result) if result.is_object() => {
+                let result = result.unchecked_into::<IteratorNext>();
+                if result.done() {
+                    this.iterator = None;
+                    None
+                } else {
+                    Some(Ok(result.value()))
+                }
+            }
+            Ok(_) => {
+                this.iterator = None;
+                Some(Err(js_sys::TypeError::new(
+                    "iterator result is not an object",
+                )
+                .into()))
+            }
+            Err(error) => {
+                this.iterator = None;
+                Some(Err(error))
+            }
+        })
+    }
+}
+
+impl Drop for AsyncIterableStream {
+    fn drop(&mut self) {
+        let iterator = match self.iterator.take() {
+            Some(iterator) => iterator,
+            None => return,
+        };
+        // Close the iterator, like breaking out of a for-await loop.
+        if let Ok(return_fn) = iterator.return_fn().dyn_into::<js_sys::Function>() {
+            if let Ok(result) = return_fn.call0(&iterator) {
+                detach_promise(Promise::resolve(&result));
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use core::pin::Pin;
+use std::io::{self, SeekFrom};
+
+use futures::io::{AsyncBufRead, AsyncRead, AsyncSeek};
+use futures::ready;
+use futures::task::{Context, Poll};
+use wasm_bindgen::JsValue;
+use web_sys::Blob;
+
+use crate::util::detach_promise;
+
+use super::{sys, IntoAsyncRead, ReadableStream};
+
+/// An [`AsyncRead`](AsyncRead) and [`AsyncSeek`](AsyncSeek) over a [`Blob`](Blob)
+/// or [`File`](web_sys::File).
+///
+/// Reads are served by [streaming](ReadableStream::from_blob) a
+/// [slice](https://developer.mozilla.org/en-US/docs/Web/API/Blob/slice) of the blob,
+/// starting at the current position and ending at the end of the blob.
+/// Seeking to a different position cancels that stream, and the next read starts
+/// streaming a new slice. Reading sequentially between seeks is therefore as fast as
+/// reading the whole blob, which makes this suitable for parsing container formats
+/// (such as zip or mp4) that jump between an index and the data it points to.
+///
+/// This reader also implements [`AsyncBufRead`](AsyncBufRead).
+#[must_use = "readers do nothing unless polled"]
+#[derive(Debug)]
+pub struct BlobReader {
+    blob: Blob,
+    size: u64,
+    position: u64,
+    current: Option<Slice>,
+}
+
+/// The stream of the slice that is currently being read.
+#[derive(Debug)]
+struct Slice {
+    raw: sys::ReadableStream,
+    reader: IntoAsyncRead<'static>,
+}
+
+impl BlobReader {
+    /// Creates a new `BlobReader` for the given blob, positioned at its start.
+    pub fn new(blob: &Blob) -> Self {
+        Self {
+            blob: blob.clone(),
+            size: blob.size() as u64,
+            position: 0,
+            current: None,
+        }
+    }
+
+    /// Returns the size of the blob, in bytes.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the current position of this reader, in bytes from the start of the blob.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Acquires a reference to the underlying [`Blob`](Blob).
+    #[inline]
+    pub fn blob(&self) -> &Blob {
+        &self.blob
+    }
+
+    /// Consumes this `BlobReader`, returning the underlying [`Blob`](Blob).
+    #[inline]
+    pub fn into_blob(self) -> Blob {
+        self.blob.clone()
+    }
+
+    /// Returns the reader of the slice starting at the current position,
+    /// starting a new stream if needed.
+    fn current(&mut self) -> &mut IntoAsyncRead<'static> {
+        let (blob, position) = (&self.blob, self.position);
+        let slice = self.current.get_or_insert_with(|| {
+            let slice = blob
+                .slice_with_f64(position as f64)
+                .unwrap_or_else(|err| wasm_bindgen::throw_val(err));
+            let readable = ReadableStream::from_blob(&slice);
+            let raw = readable.as_raw().clone();
+            Slice {
+                raw,
+                reader: readable.cast::<JsValue>().into_async_read(),
+            }
+        });
+        &mut slice.reader
+    }
+
+    /// Cancels the stream of the current slice, if any.
+    fn discard(&mut self) {
+        if let Some(Slice { raw, reader }) = self.current.take() {
+            // Release the lock, so the stream can be cancelled.
+            drop(reader);
+            detach_promise(raw.cancel());
+        }
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        self.discard();
+    }
+}
+
+impl AsyncRead for BlobReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() || self.position >= self.size {
+            return Poll::Ready(Ok(0));
+        }
+        let len = ready!(Pin::new(self.current()).poll_read(cx, buf))?;
+        self.position += len as u64;
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncBufRead for BlobReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.position >= this.size {
+            return Poll::Ready(Ok(&[]));
+        }
+        Pin::new(this.current()).poll_fill_buf(cx)
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        if let Some(slice) = self.current.as_mut() {
+            Pin::new(&mut slice.reader).consume(amt);
+            self.position += amt as u64;
+        }
+    }
+}
+
+impl AsyncSeek for BlobReader {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        let position = match position {
+            Some(position) => position,
+            None => {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "invalid seek to a negative or overflowing position",
+                )))
+            }
+        };
+        if position != self.position {
+            self.discard();
+            self.position = position;
+        }
+        Poll::Ready(Ok(position))
+    }
+}
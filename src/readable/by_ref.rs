@@ -0,0 +1,80 @@
+use core::pin::Pin;
+
+use futures::future::Future;
+use futures::ready;
+use futures::stream::Stream;
+use futures::task::{Context, Poll};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::StreamError;
+
+use super::sys::{self, ReadableStreamReadResult};
+use super::{ReadError, ReadableStream};
+
+/// A read started by polling a `&mut ReadableStream`, which holds the stream's lock
+/// until the read completes.
+pub(super) struct PendingRead {
+    reader: sys::ReadableStreamDefaultReader,
+    fut: JsFuture,
+}
+
+impl Drop for PendingRead {
+    fn drop(&mut self) {
+        // Some browsers do not allow releasing the lock while the read is still pending.
+        // In that case, the stream stays locked.
+        let _ = self.reader.release_lock();
+    }
+}
+
+/// Reads chunks from a [`ReadableStream`](ReadableStream) without converting it
+/// into a [`Stream`](Stream) first.
+///
+/// Every poll acquires a reader and starts a new read, and the reader's lock is
+/// [released](https://streams.spec.whatwg.org/#release-a-lock) again as soon as the read completes.
+/// While a read is pending, the stream is [locked](ReadableStream::is_locked).
+/// If the stream is already locked to another reader, this yields a `TypeError`.
+///
+/// Dropping the stream while a read is pending may lose the chunk that was being read.
+/// Errors are reported as [`StreamError`](StreamError)s, and chunks that are not of type `T`
+/// yield a [`StreamError::TypeError`](StreamError::TypeError).
+///
+/// Note that the inherent methods of `ReadableStream` take precedence over
+/// [`StreamExt`](futures::StreamExt) methods with the same name, such as
+/// [`take`](ReadableStream::take). Use the fully qualified syntax to call those,
+/// e.g. `StreamExt::take(&mut readable, 2)`.
+impl<T: JsCast> Stream for &mut ReadableStream<T> {
+    type Item = Result<T, StreamError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = &mut **self.get_mut();
+        let pending = match &mut stream.pending_read {
+            Some(pending) => pending,
+            None => {
+                let reader = match stream.raw.get_reader() {
+                    Ok(reader) => reader,
+                    Err(error) => return Poll::Ready(Some(Err(error.into()))),
+                };
+                trace_event!("reading chunk from readable stream");
+                let fut = JsFuture::from(reader.read());
+                stream.pending_read.insert(PendingRead { reader, fut })
+            }
+        };
+
+        let js_result = ready!(Pin::new(&mut pending.fut).poll(cx));
+        // The read has completed, release the lock until the next poll.
+        stream.pending_read = None;
+
+        Poll::Ready(match js_result {
+            Ok(js_value) => {
+                let result = ReadableStreamReadResult::from(js_value);
+                if result.is_done() {
+                    None
+                } else {
+                    Some(ReadError::cast_chunk(result.value()).map_err(StreamError::from))
+                }
+            }
+            Err(js_value) => Some(Err(StreamError::from_js(js_value))),
+        })
+    }
+}
@@ -0,0 +1,285 @@
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+use futures::future::{pending, select, Either, Future};
+use js_sys::{ArrayBuffer, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{throw_val, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
+
+use crate::util::{
+    aborted, detach_promise, promise_to_void_future, promise_to_void_future_with_signal,
+};
+use crate::StreamError;
+
+use super::{sys, ReadableStream, ReaderDropPolicy};
+
+/// A [`ReadableStreamBYOBReader`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamBYOBReader)
+/// that can be used to read chunks from a [readable byte stream](https://streams.spec.whatwg.org/#readable-byte-stream)
+/// directly into a Rust buffer.
+///
+/// This is returned by the [`get_byob_reader`](ReadableStream::get_byob_reader) method.
+///
+/// When the reader is dropped, it automatically [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+/// This can be changed with [`set_drop_policy`](Self::set_drop_policy).
+#[derive(Debug)]
+pub struct ReadableStreamBYOBReader<'stream> {
+    raw: sys::ReadableStreamBYOBReader,
+    buffer: Option<ArrayBuffer>,
+    pending_read: Option<JsFuture>,
+    remainder: Option<Uint8Array>,
+    drop_policy: ReaderDropPolicy,
+    _stream: PhantomData<&'stream mut ReadableStream>,
+}
+
+impl<'stream> ReadableStreamBYOBReader<'stream> {
+    pub(super) fn new(raw: sys::ReadableStreamBYOBReader) -> Self {
+        Self {
+            raw,
+            buffer: None,
+            pending_read: None,
+            remainder: None,
+            drop_policy: ReaderDropPolicy::default(),
+            _stream: PhantomData,
+        }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript reader](sys::ReadableStreamBYOBReader).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::ReadableStreamBYOBReader {
+        &self.raw
+    }
+
+    /// Returns what this reader does with its lock when it is dropped.
+    #[inline]
+    pub fn drop_policy(&self) -> ReaderDropPolicy {
+        self.drop_policy
+    }
+
+    /// Sets what this reader does with its lock when it is dropped.
+    ///
+    /// See [`ReaderDropPolicy`](ReaderDropPolicy) for the available policies.
+    #[inline]
+    pub fn set_drop_policy(&mut self, policy: ReaderDropPolicy) -> &mut Self {
+        self.drop_policy = policy;
+        self
+    }
+
+    /// Waits for the stream to become closed.
+    ///
+    /// This returns an error if the stream ever errors, or if the reader's lock is
+    /// [released](https://streams.spec.whatwg.org/#release-a-lock) before the stream finishes
+    /// closing.
+    pub async fn closed(&self) -> Result<(), StreamError> {
+        self.closed_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`closed`](Self::closed), but returns the raw JavaScript error.
+    pub async fn closed_js(&self) -> Result<(), JsValue> {
+        promise_to_void_future(self.as_raw().closed()).await
+    }
+
+    /// Waits for the stream to become closed, or for the given `signal` to be aborted.
+    ///
+    /// This behaves like [`closed`](Self::closed), except that it returns an
+    /// [`Aborted`](StreamError::Aborted) error with the signal's abort reason
+    /// as soon as the signal is aborted. The reader is not affected by the signal.
+    pub async fn closed_with_signal(
+        &self,
+        signal: &impl AsRef<AbortSignal>,
+    ) -> Result<(), StreamError> {
+        promise_to_void_future_with_signal(self.as_raw().closed(), signal.as_ref()).await
+    }
+
+    /// [Cancels](https://streams.spec.whatwg.org/#cancel-a-readable-stream) the stream,
+    /// signaling a loss of interest in the stream by a consumer.
+    ///
+    /// Equivalent to [`ReadableStream.cancel`](ReadableStream::cancel).
+    pub async fn cancel(&mut self) -> Result<(), StreamError> {
+        self.cancel_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`cancel`](Self::cancel), but returns the raw JavaScript error.
+    pub async fn cancel_js(&mut self) -> Result<(), JsValue> {
+        promise_to_void_future(self.as_raw().cancel()).await
+    }
+
+    /// [Cancels](https://streams.spec.whatwg.org/#cancel-a-readable-stream) the stream,
+    /// signaling a loss of interest in the stream by a consumer.
+    ///
+    /// Equivalent to [`ReadableStream.cancel_with_reason`](ReadableStream::cancel_with_reason).
+    pub async fn cancel_with_reason(&mut self, reason: &JsValue) -> Result<(), StreamError> {
+        self.cancel_with_reason_js(reason)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Like [`cancel_with_reason`](Self::cancel_with_reason), but returns the raw JavaScript error.
+    pub async fn cancel_with_reason_js(&mut self, reason: &JsValue) -> Result<(), JsValue> {
+        promise_to_void_future(self.as_raw().cancel_with_reason(reason)).await
+    }
+
+    /// Reads the next chunk from the stream's internal queue into `dst`.
+    ///
+    /// * If some bytes were read, this returns `Ok(bytes_read)`.
+    /// * If the stream closes and no more bytes are available, this returns `Ok(0)`.
+    /// * If the stream encounters an `error`, this returns `Err(error)`.
+    ///
+    /// If `dst` is empty, this returns `Ok(0)` without reading from the stream.
+    ///
+    /// The reader keeps an internal JavaScript buffer which is reused across reads,
+    /// so the bytes are only copied once from the stream into `dst`.
+    pub async fn read(&mut self, dst: &mut [u8]) -> Result<usize, StreamError> {
+        self.read_js(dst).await.map_err(StreamError::from)
+    }
+
+    /// Like [`read`](Self::read), but returns the raw JavaScript error.
+    pub async fn read_js(&mut self, dst: &mut [u8]) -> Result<usize, JsValue> {
+        match self.read_or(dst, pending::<Infallible>()).await {
+            Ok(result) => result,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Reads the next chunk from the stream's internal queue into `dst`, unless the given
+    /// `signal` is aborted first.
+    ///
+    /// This behaves like [`read`](Self::read), except that it returns
+    /// [`Err(StreamError::Aborted(reason))`](StreamError::Aborted) with the signal's abort reason
+    /// as soon as the signal is aborted. The read stays pending, and its bytes are returned
+    /// by the next read on this reader.
+    pub async fn read_with_signal(
+        &mut self,
+        dst: &mut [u8],
+        signal: &impl AsRef<AbortSignal>,
+    ) -> Result<usize, StreamError> {
+        match self.read_or(dst, aborted(signal.as_ref())).await {
+            Ok(result) => result.map_err(StreamError::from),
+            Err(reason) => Err(StreamError::Aborted(reason)),
+        }
+    }
+
+    /// Reads into `dst`, unless `interrupt` completes first. In that case, the read
+    /// is kept for the next call, and this returns the output of `interrupt`.
+    async fn read_or<F>(
+        &mut self,
+        dst: &mut [u8],
+        interrupt: F,
+    ) -> Result<Result<usize, JsValue>, F::Output>
+    where
+        F: Future + Unpin,
+    {
+        if dst.is_empty() {
+            return Ok(Ok(0));
+        }
+        if let Some(remainder) = self.remainder.take() {
+            return Ok(Ok(self.copy_filled(remainder, dst)));
+        }
+        let mut fut = match self.pending_read.take() {
+            Some(fut) => fut,
+            None => {
+                let buffer = match self.buffer.take() {
+                    Some(buffer) if buffer.byte_length() as usize >= dst.len() => buffer,
+                    _ => ArrayBuffer::new(dst.len() as u32),
+                };
+                let view =
+                    Uint8Array::new_with_byte_offset_and_length(&buffer, 0, dst.len() as u32);
+                JsFuture::from(self.as_raw().read(&view))
+            }
+        };
+        let js_value = match in_span!("read", select(&mut fut, interrupt)).await {
+            Either::Left((result, _)) => result,
+            Either::Right((output, _)) => {
+                self.pending_read = Some(fut);
+                return Err(output);
+            }
+        };
+        Ok(js_value.map(|js_value| self.read_result(js_value, dst)))
+    }
+
+    fn read_result(&mut self, js_value: JsValue, dst: &mut [u8]) -> usize {
+        let result = sys::ReadableStreamReadResult::from(js_value);
+        // The stream transferred our buffer, so the returned view is our only way to get it back.
+        // This can be undefined if the stream was canceled.
+        let filled = match result.value().dyn_into::<Uint8Array>() {
+            Ok(filled) => filled,
+            Err(_) => return 0,
+        };
+        if result.is_done() {
+            self.buffer = Some(filled.buffer());
+            return 0;
+        }
+        self.copy_filled(filled, dst)
+    }
+
+    /// Copies the bytes of `filled` into `dst`.
+    ///
+    /// If a read was kept pending by [`read_with_signal`](Self::read_with_signal), its view
+    /// can be larger than `dst`, in which case the remaining bytes are kept for the next read.
+    fn copy_filled(&mut self, filled: Uint8Array, dst: &mut [u8]) -> usize {
+        let len = filled.length() as usize;
+        if len > dst.len() {
+            let split = dst.len() as u32;
+            filled.subarray(0, split).copy_to(dst);
+            self.remainder = Some(filled.subarray(split, len as u32));
+            dst.len()
+        } else {
+            filled.copy_to(&mut dst[..len]);
+            self.buffer = Some(filled.buffer());
+            len
+        }
+    }
+
+    /// Reads bytes from the stream into the given buffer.
+    ///
+    /// This is equivalent to [`read`](Self::read). It mirrors
+    /// [`ReadableStreamDefaultReader::read_into`](super::ReadableStreamDefaultReader::read_into),
+    /// but since the stream fills `dst` directly, there are never any bytes left over.
+    #[inline]
+    pub async fn read_into(&mut self, dst: &mut [u8]) -> Result<usize, StreamError> {
+        self.read(dst).await
+    }
+
+    /// [Releases](https://streams.spec.whatwg.org/#release-a-lock) this reader's lock on the
+    /// corresponding stream.
+    ///
+    /// **Panics** if the reader still has a pending read request, i.e. if a future returned
+    /// by [`read`](Self::read) is not yet ready. For a non-panicking variant,
+    /// use [`try_release_lock`](Self::try_release_lock).
+    #[inline]
+    pub fn release_lock(mut self) {
+        self.release_lock_mut()
+    }
+
+    fn release_lock_mut(&mut self) {
+        self.as_raw()
+            .release_lock()
+            .unwrap_or_else(|error| throw_val(error.into()))
+    }
+
+    /// Try to [release](https://streams.spec.whatwg.org/#release-a-lock) this reader's lock on the
+    /// corresponding stream.
+    ///
+    /// The lock cannot be released while the reader still has a pending read request, i.e.
+    /// if a future returned by [`read`](Self::read) is not yet ready. Attempting to do so will
+    /// return an error and leave the reader locked to the stream.
+    #[inline]
+    pub fn try_release_lock(self) -> Result<(), (js_sys::Error, Self)> {
+        self.as_raw().release_lock().map_err(|error| (error, self))
+    }
+}
+
+impl Drop for ReadableStreamBYOBReader<'_> {
+    fn drop(&mut self) {
+        match self.drop_policy {
+            ReaderDropPolicy::Release => self.release_lock_mut(),
+            ReaderDropPolicy::CancelThenRelease => {
+                // Cancelling completes any pending read request, so we can release the lock.
+                detach_promise(self.raw.cancel());
+                let _ = self.raw.release_lock();
+            }
+            ReaderDropPolicy::Leak => {}
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use core::pin::Pin;
+use std::time::Duration;
+
+use futures::future::Future;
+
+use futures::stream::{Stream, StreamExt};
+use futures::task::{Context, Poll};
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::util::{detach_promise, sleep, timeout_error, Sleep};
+
+use super::{sys, IntoStream, ReadableStream};
+
+/// A [`Stream`](Stream) that reads from a [`ReadableStream`](ReadableStream), and
+/// [cancels](https://streams.spec.whatwg.org/#cancel-a-readable-stream) it when dropped.
+///
+/// If the stream has already closed or errored, cancelling it does nothing.
+#[derive(Debug)]
+pub(super) struct CancelOnDrop<T: JsCast + 'static> {
+    stream: Option<IntoStream<'static, T>>,
+    raw: sys::ReadableStream,
+}
+
+impl<T: JsCast + 'static> CancelOnDrop<T> {
+    /// **Panics** if the stream is already locked to a reader.
+    pub fn new(readable: ReadableStream<T>) -> Self {
+        let raw = readable.as_raw().clone();
+        Self {
+            stream: Some(readable.into_stream()),
+            raw,
+        }
+    }
+}
+
+impl<T: JsCast + 'static> Stream for CancelOnDrop<T> {
+    type Item = Result<T, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.stream.as_mut() {
+            Some(stream) => stream.poll_next_unpin(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl<T: JsCast + 'static> Drop for CancelOnDrop<T> {
+    fn drop(&mut self) {
+        // Release the lock first, so we can cancel the stream.
+        drop(self.stream.take());
+        detach_promise(self.raw.cancel());
+    }
+}
+
+pub(super) fn merge<T: JsCast + 'static>(streams: Vec<ReadableStream<T>>) -> ReadableStream<T> {
+    let streams = streams.into_iter().map(CancelOnDrop::new);
+    let merged = futures::stream::select_all(streams)
+        .map(|result| result.map(JsCast::unchecked_into::<JsValue>));
+    ReadableStream::from_stream(merged).cast()
+}
+
+pub(super) fn concat<T: JsCast + 'static>(streams: Vec<ReadableStream<T>>) -> ReadableStream<T> {
+    // Lock all streams up front, so that the remaining streams are cancelled
+    // when the concatenated stream is dropped.
+    let streams = streams
+        .into_iter()
+        .map(CancelOnDrop::new)
+        .collect::<Vec<_>>();
+    let concatenated = futures::stream::iter(streams)
+        .flatten()
+        .map(|result| result.map(JsCast::unchecked_into::<JsValue>));
+    ReadableStream::from_stream(concatenated).cast()
+}
+
+pub(super) fn zip<T, U>(left: ReadableStream<T>, right: ReadableStream<U>) -> ReadableStream<Array>
+where
+    T: JsCast + 'static,
+    U: JsCast + 'static,
+{
+    let zipped = Zip {
+        left: CancelOnDrop::new(left),
+        right: CancelOnDrop::new(right),
+        queued_left: None,
+        queued_right: None,
+        done: false,
+    };
+    ReadableStream::from_stream(zipped).cast()
+}
+
+/// A [`Stream`](Stream) that pairs up the chunks of two streams into two-element arrays.
+///
+/// Unlike [`StreamExt::zip`], this does not hold on to an error or the end of one stream
+/// while waiting for a chunk from the other stream, but ends the zipped stream right away.
+struct Zip<T: JsCast + 'static, U: JsCast + 'static> {
+    left: CancelOnDrop<T>,
+    right: CancelOnDrop<U>,
+    queued_left: Option<T>,
+    queued_right: Option<U>,
+    done: bool,
+}
+
+// The streams are polled through `poll_next_unpin`, and the queued chunks are never pinned.
+impl<T: JsCast + 'static, U: JsCast + 'static> Unpin for Zip<T, U> {}
+
+/// Polls `stream` for its next chunk, unless a chunk is already `queued`.
+///
+/// Returns `Some(item)` if the zipped stream should yield `item` instead, because
+/// the stream has ended or errored.
+fn poll_queued<T: JsCast + 'static>(
+    stream: &mut CancelOnDrop<T>,
+    queued: &mut Option<T>,
+    cx: &mut Context<'_>,
+) -> Option<Option<Result<JsValue, JsValue>>> {
+    if queued.is_some() {
+        return None;
+    }
+    match stream.poll_next_unpin(cx) {
+        Poll::Ready(Some(Ok(chunk))) => {
+            *queued = Some(chunk);
+            None
+        }
+        Poll::Ready(Some(Err(error))) => Some(Some(Err(error))),
+        Poll::Ready(None) => Some(None),
+        Poll::Pending => None,
+    }
+}
+
+impl<T: JsCast + 'static, U: JsCast + 'static> Stream for Zip<T, U> {
+    type Item = Result<JsValue, JsValue>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let ended = poll_queued(&mut this.left, &mut this.queued_left, cx)
+            .or_else(|| poll_queued(&mut this.right, &mut this.queued_right, cx));
+        if let Some(item) = ended {
+            this.done = true;
+            return Poll::Ready(item);
+        }
+        match (this.queued_left.take(), this.queued_right.take()) {
+            (Some(left), Some(right)) => {
+                Poll::Ready(Some(Ok(Array::of2(left.as_ref(), right.as_ref()).into())))
+            }
+            (left, right) => {
+                this.queued_left = left;
+                this.queued_right = right;
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A [`Stream`](Stream) that errors with a `TimeoutError` if its inner stream does not
+/// produce an item within the given time, and cancels the inner stream with that error.
+struct TimeoutBetweenChunks<T: JsCast + 'static> {
+    stream: CancelOnDrop<T>,
+    millis: f64,
+    timer: Option<Sleep>,
+}
+
+impl<T: JsCast + 'static> Stream for TimeoutBetweenChunks<T> {
+    type Item = Result<T, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(item) = self.stream.poll_next_unpin(cx) {
+            self.timer = None;
+            return Poll::Ready(item);
+        }
+        let millis = self.millis;
+        let timer = self.timer.get_or_insert_with(|| sleep(millis));
+        let error = match Pin::new(timer).poll(cx) {
+            Poll::Ready(Ok(())) => JsValue::from(timeout_error(&format!(
+                "no chunk was received within {}ms",
+                millis
+            ))),
+            Poll::Ready(Err(error)) => error,
+            Poll::Pending => return Poll::Pending,
+        };
+        // Cancel the inner stream with the timeout error, rather than when it is dropped.
+        if let Some(stream) = self.stream.stream.take() {
+            drop(stream);
+            detach_promise(self.stream.raw.cancel_with_reason(&error));
+        }
+        Poll::Ready(Some(Err(error)))
+    }
+}
+
+pub(super) fn timeout_between_chunks<T: JsCast + 'static>(
+    readable: ReadableStream<T>,
+    duration: Duration,
+) -> ReadableStream<T> {
+    let stream = TimeoutBetweenChunks {
+        stream: CancelOnDrop::new(readable),
+        millis: duration.as_secs_f64() * 1000.0,
+        timer: None,
+    };
+    let stream = stream.map(|result| result.map(JsCast::unchecked_into::<JsValue>));
+    ReadableStream::from_stream(stream).cast()
+}
@@ -0,0 +1,28 @@
+/// What a reader does with its lock on the stream when it is dropped.
+///
+/// See [`ReadableStreamDefaultReader::set_drop_policy`](super::ReadableStreamDefaultReader::set_drop_policy)
+/// and [`ReadableStreamBYOBReader::set_drop_policy`](super::ReadableStreamBYOBReader::set_drop_policy).
+///
+/// The default policy is [`Release`](ReaderDropPolicy::Release).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReaderDropPolicy {
+    /// [Release](https://streams.spec.whatwg.org/#release-a-lock) the reader's lock.
+    ///
+    /// Some browsers do not allow releasing the lock while the reader still has a pending read
+    /// request, for example when a future returned by `read` was dropped before it was ready.
+    /// In that case, dropping the reader throws a JavaScript exception.
+    #[default]
+    Release,
+    /// [Cancel](https://streams.spec.whatwg.org/#cancel-a-readable-stream) the stream,
+    /// and then release the reader's lock.
+    ///
+    /// Cancelling the stream completes any pending read request, so the lock can always
+    /// be released. If releasing the lock still fails, the stream is left locked instead
+    /// of throwing.
+    CancelThenRelease,
+    /// Do not release the reader's lock.
+    ///
+    /// The stream stays locked forever, and can no longer be read by anyone else.
+    /// This never throws.
+    Leak,
+}
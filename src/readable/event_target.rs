@@ -0,0 +1,175 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::AbortController;
+
+use super::underlying_source::{ReadableStreamDefaultController, UnderlyingSource};
+
+/// Enqueues `chunk` if the stream's internal queue is not full, otherwise drops it.
+fn enqueue_or_drop(controller: &ReadableStreamDefaultController, chunk: &JsValue) {
+    if matches!(controller.desired_size(), Some(size) if size > 0.0) {
+        let _ = controller.enqueue(chunk);
+    }
+}
+
+/// An underlying source which enqueues the events of the given type that are dispatched
+/// on an [`EventTarget`](web_sys::EventTarget).
+#[cfg(feature = "event-target")]
+pub(crate) struct EventTargetSource {
+    target: web_sys::EventTarget,
+    event_name: String,
+    listener: Option<Closure<dyn FnMut(web_sys::Event)>>,
+}
+
+#[cfg(feature = "event-target")]
+impl EventTargetSource {
+    pub fn new(target: &web_sys::EventTarget, event_name: &str) -> Self {
+        Self {
+            target: target.clone(),
+            event_name: event_name.to_owned(),
+            listener: None,
+        }
+    }
+
+    fn remove_listener(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            let _ = self.target.remove_event_listener_with_callback(
+                &self.event_name,
+                listener.as_ref().unchecked_ref(),
+            );
+        }
+    }
+}
+
+#[cfg(feature = "event-target")]
+impl UnderlyingSource for EventTargetSource {
+    fn start<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let controller = controller.clone();
+        let listener = Closure::<dyn FnMut(web_sys::Event)>::new(move |event: web_sys::Event| {
+            enqueue_or_drop(&controller, &event);
+        });
+        let result = self
+            .target
+            .add_event_listener_with_callback(&self.event_name, listener.as_ref().unchecked_ref());
+        self.listener = Some(listener);
+        ready(result).boxed_local()
+    }
+
+    fn pull<'a>(
+        &'a mut self,
+        _controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Events are enqueued by the listener as they are dispatched.
+        ready(Ok(())).boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.remove_listener();
+        ready(Ok(())).boxed_local()
+    }
+}
+
+#[cfg(feature = "event-target")]
+impl Drop for EventTargetSource {
+    fn drop(&mut self) {
+        // Remove the listener before its closure is dropped.
+        self.remove_listener();
+    }
+}
+
+/// The callbacks of the observer passed to `subscribe()`, kept alive for as long as the stream.
+struct Observer {
+    _next: Closure<dyn FnMut(JsValue)>,
+    _error: Closure<dyn FnMut(JsValue)>,
+    _complete: Closure<dyn FnMut()>,
+}
+
+/// An underlying source which subscribes to an
+/// [`Observable`](https://wicg.github.io/observable/#observable), and enqueues its values.
+pub(crate) struct ObservableSource {
+    subscribe: Function,
+    observable: JsValue,
+    abort_controller: AbortController,
+    observer: Option<Observer>,
+}
+
+impl ObservableSource {
+    /// Creates a new source for the given observable.
+    ///
+    /// If the value does not have a `subscribe()` method, then this returns a `TypeError`.
+    pub fn new(observable: &JsValue) -> Result<Self, JsValue> {
+        let subscribe = Reflect::get(observable, &"subscribe".into())?
+            .dyn_into::<Function>()
+            .map_err(|_| js_sys::TypeError::new("value is not an Observable"))?;
+        Ok(Self {
+            subscribe,
+            observable: observable.clone(),
+            abort_controller: AbortController::new()?,
+            observer: None,
+        })
+    }
+}
+
+impl UnderlyingSource for ObservableSource {
+    fn start<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let next = {
+            let controller = controller.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |value: JsValue| {
+                enqueue_or_drop(&controller, &value);
+            })
+        };
+        let error = {
+            let controller = controller.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |error: JsValue| {
+                controller.error(&error);
+            })
+        };
+        let complete = {
+            let controller = controller.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                let _ = controller.close();
+            })
+        };
+
+        let observer = Object::new();
+        let options = Object::new();
+        let result = Reflect::set(&observer, &"next".into(), next.as_ref())
+            .and_then(|_| Reflect::set(&observer, &"error".into(), error.as_ref()))
+            .and_then(|_| Reflect::set(&observer, &"complete".into(), complete.as_ref()))
+            .and_then(|_| Reflect::set(&options, &"signal".into(), &self.abort_controller.signal()))
+            .and_then(|_| self.subscribe.call2(&self.observable, &observer, &options));
+        self.observer = Some(Observer {
+            _next: next,
+            _error: error,
+            _complete: complete,
+        });
+        ready(result.map(|_| ())).boxed_local()
+    }
+
+    fn pull<'a>(
+        &'a mut self,
+        _controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Values are enqueued by the observer as they are emitted.
+        ready(Ok(())).boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.abort_controller.abort_with_reason(&reason);
+        ready(Ok(())).boxed_local()
+    }
+}
+
+impl Drop for ObservableSource {
+    fn drop(&mut self) {
+        // Unsubscribe before the observer's closures are dropped.
+        self.abort_controller.abort();
+    }
+}
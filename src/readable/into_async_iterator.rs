@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::channel::oneshot;
+use futures::future::{select, Either, FutureExt};
+use futures::lock::Mutex;
+use futures::stream::{Stream, StreamExt};
+use js_sys::{Function, Object, Promise, Reflect, Symbol};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::future_to_promise;
+
+type BoxedStream = std::pin::Pin<Box<dyn Stream<Item = Result<JsValue, JsValue>>>>;
+
+/// Converts a [`Stream`](Stream) into a JavaScript [async iterator](js_sys::AsyncIterator)
+/// over its items.
+///
+/// The returned object implements both the async iterator and the async iterable protocol,
+/// so it can be consumed with a `for await (const value of iterator)` loop or passed
+/// to JavaScript libraries that consume async iterables rather than streams.
+///
+/// Items must be `Result<JsValue, JsValue>`. `Ok` items are yielded by the iterator,
+/// while an `Err` item rejects the pending `next()` promise and ends the iteration.
+///
+/// When the iterator is ended early (e.g. by a `break` in a `for await` loop), its `return()`
+/// method drops the Rust stream.
+pub fn async_iterator_from_stream<St>(stream: St) -> js_sys::AsyncIterator
+where
+    St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+{
+    let state: Rc<Mutex<Option<BoxedStream>>> = Rc::new(Mutex::new(Some(Box::pin(stream))));
+    // Completed by return(), so a pending next() stops waiting for the stream and releases
+    // the lock, instead of keeping return() waiting forever.
+    let (close_sender, closed) = oneshot::channel::<()>();
+    let closed = closed.shared();
+    let close_sender = RefCell::new(Some(close_sender));
+
+    let next_state = state.clone();
+    let next = Closure::<dyn FnMut() -> Promise>::new(move || {
+        let state = next_state.clone();
+        let closed = closed.clone();
+        future_to_promise(async move {
+            // Calls to next() are queued, so they resolve in the order they were made.
+            let mut guard = state.lock().await;
+            let item = match guard.as_mut() {
+                Some(stream) => match select(stream.next(), closed).await {
+                    Either::Left((item, _)) => item,
+                    // The iterator was ended by return(), so end the iteration.
+                    Either::Right(_) => None,
+                },
+                None => None,
+            };
+            match item {
+                Some(Ok(value)) => Ok(iterator_result(value, false)),
+                Some(Err(error)) => {
+                    *guard = None;
+                    Err(error)
+                }
+                None => {
+                    *guard = None;
+                    Ok(iterator_result(JsValue::undefined(), true))
+                }
+            }
+        })
+    });
+
+    let return_ = Closure::<dyn FnMut(JsValue) -> Promise>::new(move |value: JsValue| {
+        let state = state.clone();
+        // Dropping the sender completes `closed`.
+        close_sender.borrow_mut().take();
+        future_to_promise(async move {
+            // Drop the stream, like dropping it after breaking out of a loop in Rust.
+            *state.lock().await = None;
+            Ok(iterator_result(value, true))
+        })
+    });
+
+    new_iterator(
+        next.into_js_value().unchecked_into(),
+        return_.into_js_value().unchecked_into(),
+    )
+}
+
+fn new_iterator(next: Function, return_: Function) -> js_sys::AsyncIterator {
+    let iterator = Object::new();
+    Reflect::set(&iterator, &"next".into(), &next).unwrap_throw();
+    Reflect::set(&iterator, &"return".into(), &return_).unwrap_throw();
+    // `[Symbol.asyncIterator]()` returns an iterator sharing the same state, without
+    // referencing the iterator object itself from the closure.
+    let async_iterator = Closure::<dyn FnMut() -> JsValue>::new(move || {
+        new_iterator(next.clone(), return_.clone()).into()
+    });
+    Reflect::set(
+        &iterator,
+        &Symbol::async_iterator(),
+        &async_iterator.into_js_value(),
+    )
+    .unwrap_throw();
+    iterator.unchecked_into()
+}
+
+fn iterator_result(value: JsValue, done: bool) -> JsValue {
+    let result = Object::new();
+    Reflect::set(&result, &"value".into(), &value).unwrap_throw();
+    Reflect::set(&result, &"done".into(), &JsValue::from_bool(done)).unwrap_throw();
+    result.into()
+}
@@ -0,0 +1,154 @@
+use core::pin::Pin;
+use std::io;
+
+use futures::io::{AsyncBufRead, AsyncRead};
+use futures::ready;
+use futures::stream::Stream;
+use futures::task::{Context, Poll};
+use js_sys::Uint8Array;
+use wasm_bindgen::JsCast;
+
+use crate::util::js_to_io_error;
+
+use super::IntoStream;
+
+/// An [`AsyncRead`](AsyncRead) for the [`into_async_read`](super::ReadableStream::into_async_read)
+/// method.
+///
+/// This reader holds a reader, and therefore locks the [`ReadableStream`](super::ReadableStream).
+/// When this reader is dropped, it also drops its reader which in turn
+/// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+///
+/// All chunks read from the stream must be [`Uint8Array`](Uint8Array)s.
+/// Any other chunk results in an error of kind [`InvalidData`](io::ErrorKind::InvalidData).
+///
+/// This reader also implements [`AsyncBufRead`](AsyncBufRead), so it can be passed directly
+/// to parsers which require buffered reads. [`poll_fill_buf`](AsyncBufRead::poll_fill_buf)
+/// returns the unread bytes of the current chunk, and only reads the next chunk once all of them
+/// have been [consumed](AsyncBufRead::consume). [`poll_read`](AsyncRead::poll_read) copies
+/// a chunk straight into the caller's buffer when it fits, and only keeps the remaining bytes
+/// of a larger chunk in the internal buffer for the next read.
+///
+/// This reader is always [`Unpin`](Unpin), so it can be used with
+/// [`AsyncReadExt`](futures::io::AsyncReadExt) and [`AsyncBufReadExt`](futures::io::AsyncBufReadExt)
+/// without pinning it first.
+#[must_use = "readers do nothing unless polled"]
+#[derive(Debug)]
+pub struct IntoAsyncRead<'reader> {
+    stream: IntoStream<'reader>,
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<'reader> IntoAsyncRead<'reader> {
+    #[inline]
+    pub(super) fn new(stream: IntoStream<'reader>) -> Self {
+        Self {
+            stream,
+            buffer: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+fn into_chunk(js_value: wasm_bindgen::JsValue) -> io::Result<Uint8Array> {
+    js_value
+        .dyn_into::<Uint8Array>()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "chunk must be a Uint8Array"))
+}
+
+impl AsyncBufRead for IntoAsyncRead<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        while this.pos >= this.buffer.len() {
+            // Buffer is empty, read the next chunk
+            match ready!(Pin::new(&mut this.stream).poll_next(cx)) {
+                Some(Ok(js_value)) => {
+                    let chunk = into_chunk(js_value)?;
+                    this.buffer.resize(chunk.length() as usize, 0);
+                    chunk.copy_to(&mut this.buffer);
+                    this.pos = 0;
+                }
+                Some(Err(js_value)) => return Poll::Ready(Err(js_to_io_error(js_value))),
+                None => return Poll::Ready(Ok(&[])),
+            }
+        }
+        Poll::Ready(Ok(&this.buffer[this.pos..]))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buffer.len());
+    }
+}
+
+impl AsyncRead for IntoAsyncRead<'_> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_into(cx, buf)
+    }
+}
+
+impl IntoAsyncRead<'_> {
+    fn poll_read_into(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        while self.pos >= self.buffer.len() {
+            // Buffer is empty, copy the next chunk directly if it fits
+            match ready!(Pin::new(&mut self.stream).poll_next(cx)) {
+                Some(Ok(js_value)) => {
+                    let chunk = into_chunk(js_value)?;
+                    let len = chunk.length() as usize;
+                    if len == 0 {
+                        // Skip empty chunks, since returning 0 bytes would signal the end
+                        continue;
+                    }
+                    if len <= buf.len() {
+                        chunk.copy_to(&mut buf[..len]);
+                        return Poll::Ready(Ok(len));
+                    }
+                    self.buffer.resize(len, 0);
+                    chunk.copy_to(&mut self.buffer);
+                    self.pos = 0;
+                }
+                Some(Err(js_value)) => return Poll::Ready(Err(js_to_io_error(js_value))),
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+        let available = &self.buffer[self.pos..];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.pos += len;
+        Poll::Ready(Ok(len))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncBufRead for IntoAsyncRead<'_> {
+    #[inline]
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        AsyncBufRead::poll_fill_buf(self, cx)
+    }
+
+    #[inline]
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        AsyncBufRead::consume(self, amt)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for IntoAsyncRead<'_> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let len = ready!(self.get_mut().poll_read_into(cx, buf.initialize_unfilled()))?;
+        buf.advance(len);
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,112 @@
+use core::pin::Pin;
+use std::collections::VecDeque;
+
+use futures::future::Future;
+use futures::ready;
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use super::sys::ReadableStreamReadResult;
+use super::{ReadError, ReadableStreamDefaultReader};
+
+/// A [`Stream`](Stream) for the [`into_stream_buffered`](super::ReadableStream::into_stream_buffered)
+/// method.
+///
+/// This stream keeps up to a fixed number of read requests pending on its reader,
+/// and yields their results in order.
+///
+/// This stream holds a reader, and therefore locks the [`ReadableStream`](super::ReadableStream).
+/// When this stream is dropped, it also drops its reader which in turn
+/// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+/// Any chunks that were already requested but not yet yielded are lost.
+///
+/// Chunks which are not of type `T` result in a [`TypeError`](js_sys::TypeError),
+/// but do not end the stream.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct IntoBufferedStream<'reader, T = JsValue> {
+    reader: Option<ReadableStreamDefaultReader<'reader, T>>,
+    reads: VecDeque<JsFuture>,
+    max_reads: usize,
+}
+
+impl<'reader, T: JsCast> IntoBufferedStream<'reader, T> {
+    #[inline]
+    pub(super) fn new(
+        mut reader: ReadableStreamDefaultReader<'reader, T>,
+        max_reads: usize,
+    ) -> Self {
+        let max_reads = max_reads.max(1);
+        let mut reads = VecDeque::with_capacity(max_reads);
+        // Continue any read that was handed over by an IntoStream
+        reads.extend(reader.take_pending_read());
+        Self {
+            reader: Some(reader),
+            reads,
+            max_reads,
+        }
+    }
+}
+
+impl<T: JsCast> FusedStream for IntoBufferedStream<'_, T> {
+    fn is_terminated(&self) -> bool {
+        self.reader.is_none() && self.reads.is_empty()
+    }
+}
+
+impl<'reader, T: JsCast> Stream for IntoBufferedStream<'reader, T> {
+    type Item = Result<T, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let reader = match this.reader.as_mut() {
+            Some(reader) => reader,
+            None => {
+                // Reader was already dropped
+                return Poll::Ready(None);
+            }
+        };
+
+        // Return any bytes left over from a previous read_into first
+        if let Some(remainder) = reader.take_remainder() {
+            return Poll::Ready(Some(
+                ReadError::cast_chunk(remainder.into()).map_err(JsValue::from),
+            ));
+        }
+
+        // Keep the configured number of reads in flight
+        while this.reads.len() < this.max_reads {
+            this.reads.push_back(JsFuture::from(reader.as_raw().read()));
+        }
+
+        // Poll the oldest pending read
+        let js_result = ready!(Pin::new(this.reads.front_mut().unwrap_throw()).poll(cx));
+        this.reads.pop_front();
+
+        // Read completed
+        Poll::Ready(match js_result {
+            Ok(js_value) => {
+                let result = ReadableStreamReadResult::from(js_value);
+                if result.is_done() {
+                    // End of stream, all other pending reads are done as well.
+                    // Drop them along with the reader.
+                    this.reads.clear();
+                    this.reader = None;
+                    None
+                } else {
+                    Some(ReadError::cast_chunk(result.value()).map_err(JsValue::from))
+                }
+            }
+            Err(js_value) => {
+                // Error, all other pending reads are rejected as well.
+                // Drop them along with the reader.
+                this.reads.clear();
+                this.reader = None;
+                Some(Err(js_value))
+            }
+        })
+    }
+}
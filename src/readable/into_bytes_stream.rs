@@ -0,0 +1,49 @@
+use core::pin::Pin;
+
+use futures::ready;
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use super::IntoStream;
+
+/// A [`Stream`](Stream) of bytes for the
+/// [`into_bytes_stream`](super::ReadableStream::into_bytes_stream) method.
+///
+/// This stream holds a reader, and therefore locks the [`ReadableStream`](super::ReadableStream).
+/// When this stream is dropped, it also drops its reader which in turn
+/// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+///
+/// Every [`Uint8Array`](Uint8Array) chunk is copied into a new `Vec<u8>`.
+/// Any other chunk results in a [`TypeError`](js_sys::TypeError).
+///
+/// If the `bytes` feature is enabled, use [`map_ok(Bytes::from)`](futures::TryStreamExt::map_ok)
+/// to get a stream of [`Bytes`](bytes::Bytes) instead. This does not copy the chunks again.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct IntoBytesStream<'reader> {
+    stream: IntoStream<'reader, Uint8Array>,
+}
+
+impl<'reader> IntoBytesStream<'reader> {
+    #[inline]
+    pub(super) fn new(stream: IntoStream<'reader, Uint8Array>) -> Self {
+        Self { stream }
+    }
+}
+
+impl FusedStream for IntoBytesStream<'_> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+impl Stream for IntoBytesStream<'_> {
+    type Item = Result<Vec<u8>, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let item = ready!(Pin::new(&mut self.stream).poll_next(cx));
+        Poll::Ready(item.map(|result| result.map(|chunk| chunk.to_vec())))
+    }
+}
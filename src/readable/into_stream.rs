@@ -2,51 +2,141 @@ use core::pin::Pin;
 
 use futures::future::Future;
 use futures::ready;
-use futures::stream::{FusedStream, Stream};
+use futures::stream::{FusedStream, Stream, StreamExt};
 use futures::task::{Context, Poll};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
 
-use super::sys::ReadableStreamReadResult;
-use super::ReadableStreamDefaultReader;
+use crate::error::ClassifyErrors;
+
+use super::sys::{self, ReadableStreamReadResult};
+use super::{ReadError, ReadableStream, ReadableStreamDefaultReader};
 
 /// A [`Stream`](Stream) for the [`into_stream`](super::ReadableStream::into_stream) method.
 ///
 /// This stream holds a reader, and therefore locks the [`ReadableStream`](super::ReadableStream).
 /// When this stream is dropped, it also drops its reader which in turn
 /// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+///
+/// Chunks which are not of type `T` result in a [`TypeError`](js_sys::TypeError),
+/// but do not end the stream.
 #[must_use = "streams do nothing unless polled"]
 #[derive(Debug)]
-pub struct IntoStream<'reader> {
-    reader: Option<ReadableStreamDefaultReader<'reader>>,
+pub struct IntoStream<'reader, T = JsValue> {
+    reader: Option<ReadableStreamDefaultReader<'reader, T>>,
     fut: Option<JsFuture>,
+    /// The stream that is locked by the reader, if it is owned by this `IntoStream`.
+    stream: Option<sys::ReadableStream>,
 }
 
-impl<'reader> IntoStream<'reader> {
+impl<'reader, T: JsCast> IntoStream<'reader, T> {
     #[inline]
-    pub(super) fn new(reader: ReadableStreamDefaultReader) -> IntoStream {
-        IntoStream {
+    pub(super) fn new(mut reader: ReadableStreamDefaultReader<'reader, T>) -> Self {
+        // Continue any read that was handed over by a previous IntoStream
+        let fut = reader.take_pending_read();
+        Self {
             reader: Some(reader),
-            fut: None,
+            fut,
+            stream: None,
+        }
+    }
+
+    #[inline]
+    pub(super) fn with_stream(mut self, stream: sys::ReadableStream) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Converts this `IntoStream` back into its [reader](ReadableStreamDefaultReader),
+    /// so the remaining chunks can be read with the reader's methods.
+    ///
+    /// If a read is still in flight, it is handed over to the reader, such that its chunk
+    /// is returned by the reader's next read. No chunks are lost.
+    ///
+    /// This returns `None` if the stream has already closed or encountered an error,
+    /// in which case the reader has already released its lock.
+    pub fn into_reader(mut self) -> Option<ReadableStreamDefaultReader<'reader, T>> {
+        let mut reader = self.reader.take()?;
+        reader.pending_read = self.fut.take();
+        Some(reader)
+    }
+
+    /// Converts the errors of this stream into [`StreamError`](crate::StreamError)s.
+    ///
+    /// This allows distinguishing a stream which was [aborted](crate::StreamError::Aborted)
+    /// from one which failed for another reason. See [`ClassifyErrors`](ClassifyErrors)
+    /// for more details.
+    #[inline]
+    pub fn classify_errors(self) -> ClassifyErrors<Self> {
+        ClassifyErrors::new(self)
+    }
+}
+
+impl<T: JsCast + 'static> IntoStream<'static, T> {
+    /// Converts this `IntoStream` back into a [`ReadableStream`](ReadableStream)
+    /// with the remaining chunks.
+    ///
+    /// If nothing was read ahead, the reader's lock is released and this returns the original
+    /// stream, for example to [pipe](ReadableStream::pipe_to) the remainder somewhere else.
+    /// Otherwise, if a read is still in flight or some bytes were left over from a
+    /// [`read_into`](ReadableStreamDefaultReader::read_into), this returns a new stream
+    /// which first yields that chunk, followed by the remaining chunks of the original stream.
+    /// In both cases, no chunks are lost.
+    pub fn into_inner(mut self) -> ReadableStream<T> {
+        let has_read_ahead = self.fut.is_some()
+            || matches!(&self.reader, Some(reader) if reader.remainder.is_some());
+        match self.stream.take() {
+            Some(stream) if !has_read_ahead => {
+                // Release the lock, if the stream hasn't already finished
+                drop(self.reader.take());
+                ReadableStream::from_raw(stream).cast()
+            }
+            stream => {
+                self.stream = stream;
+                ReadableStream::from_stream(
+                    self.map(|result| result.map(JsCast::unchecked_into::<JsValue>)),
+                )
+                .cast()
+            }
         }
     }
+
+    /// Converts this `IntoStream` into a JavaScript [async iterator](js_sys::AsyncIterator)
+    /// over the remaining chunks.
+    ///
+    /// See [`async_iterator_from_stream`](super::async_iterator_from_stream) for more details.
+    /// When the iterator is ended early, this stream is dropped and the reader's lock
+    /// is released.
+    pub fn into_async_iterator(self) -> js_sys::AsyncIterator {
+        super::async_iterator_from_stream(
+            self.map(|result| result.map(JsCast::unchecked_into::<JsValue>)),
+        )
+    }
 }
 
-impl FusedStream for IntoStream<'_> {
+impl<T: JsCast> FusedStream for IntoStream<'_, T> {
     fn is_terminated(&self) -> bool {
         self.reader.is_none() && self.fut.is_none()
     }
 }
 
-impl<'reader> Stream for IntoStream<'reader> {
-    type Item = Result<JsValue, JsValue>;
+impl<'reader, T: JsCast> Stream for IntoStream<'reader, T> {
+    type Item = Result<T, JsValue>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         if self.fut.is_none() {
             // No pending read, start reading the next chunk
-            match self.reader.as_ref() {
+            match self.reader.as_mut() {
                 Some(reader) => {
+                    // Return any bytes left over from a previous read_into first
+                    if let Some(remainder) = reader.take_remainder() {
+                        return Poll::Ready(Some(
+                            ReadError::cast_chunk(remainder.into()).map_err(JsValue::from),
+                        ));
+                    }
                     // Read a chunk and store its future
+                    trace_event!("reading chunk from readable stream");
                     let fut = JsFuture::from(reader.as_raw().read());
                     self.as_mut().fut = Some(fut);
                 }
@@ -67,14 +157,16 @@ impl<'reader> Stream for IntoStream<'reader> {
                 let result = ReadableStreamReadResult::from(js_value);
                 if result.is_done() {
                     // End of stream, drop reader
+                    trace_event!("readable stream closed");
                     self.as_mut().reader = None;
                     None
                 } else {
-                    Some(Ok(result.value()))
+                    Some(ReadError::cast_chunk(result.value()).map_err(JsValue::from))
                 }
             }
             Err(js_value) => {
                 // Error, drop reader
+                debug_event!(error = ?js_value, "reading from readable stream failed");
                 self.as_mut().reader = None;
                 Some(Err(js_value))
             }
@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use futures::future::{abortable, AbortHandle, TryFutureExt};
+use futures::io::{AsyncRead, AsyncReadExt};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+
+use crate::util::io_error_to_js;
+
+use super::sys;
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[wasm_bindgen]
+pub(crate) struct IntoUnderlyingByteSource {
+    inner: Rc<RefCell<Inner>>,
+    default_buffer_len: usize,
+    pull_handle: Option<AbortHandle>,
+}
+
+impl IntoUnderlyingByteSource {
+    pub fn new(async_read: Box<dyn AsyncRead>, default_buffer_len: usize) -> Self {
+        IntoUnderlyingByteSource {
+            inner: Rc::new(RefCell::new(Inner::new(async_read))),
+            default_buffer_len,
+            pull_handle: None,
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[wasm_bindgen]
+impl IntoUnderlyingByteSource {
+    #[wasm_bindgen(getter, js_name = type)]
+    pub fn type_(&self) -> String {
+        "bytes".to_string()
+    }
+
+    #[wasm_bindgen(getter, js_name = autoAllocateChunkSize)]
+    pub fn auto_allocate_chunk_size(&self) -> usize {
+        self.default_buffer_len
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn pull(&mut self, controller: sys::ReadableByteStreamController) {
+        let inner = self.inner.clone();
+        let fut = async move {
+            // This mutable borrow can never panic, since the ReadableStream always queues
+            // each operation on the underlying source.
+            let mut inner = inner.try_borrow_mut().unwrap_throw();
+            inner.pull(controller).await;
+        };
+
+        // Do not return a promise from pull(), for the same reasons as IntoUnderlyingSource.
+        let (fut, handle) = abortable(fut);
+        // Ignore errors from aborting the future.
+        let fut = fut.unwrap_or_else(|_| ());
+
+        self.pull_handle = Some(handle);
+        spawn_local(fut);
+    }
+
+    pub fn cancel(self) {
+        // The stream has been canceled, drop everything.
+        drop(self);
+    }
+}
+
+impl Drop for IntoUnderlyingByteSource {
+    fn drop(&mut self) {
+        // Abort the pending pull, if any.
+        if let Some(handle) = &mut self.pull_handle {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+struct Inner {
+    async_read: Option<Pin<Box<dyn AsyncRead>>>,
+    buffer: Vec<u8>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+impl Inner {
+    fn new(async_read: Box<dyn AsyncRead>) -> Self {
+        Inner {
+            async_read: Some(async_read.into()),
+            buffer: Vec::new(),
+        }
+    }
+
+    async fn pull(&mut self, controller: sys::ReadableByteStreamController) {
+        // The AsyncRead should still exist, since pull() will not be called again
+        // after the stream has closed or encountered an error.
+        let async_read = self.async_read.as_mut().unwrap_throw();
+        // We set autoAllocateChunkSize, so there should always be a BYOB request.
+        let request = controller.byob_request().unwrap_throw();
+        let view: Uint8Array = request.view().unwrap_throw().unchecked_into();
+        let len = view.byte_length() as usize;
+        self.buffer.resize(len, 0);
+        match async_read.read(&mut self.buffer[..len]).await {
+            Ok(0) => {
+                // The AsyncRead has reached its end, drop it.
+                self.async_read = None;
+                self.buffer = Vec::new();
+                controller.close();
+                request.respond(0);
+            }
+            Ok(bytes_read) => {
+                view.subarray(0, bytes_read as u32)
+                    .copy_from(&self.buffer[..bytes_read]);
+                request.respond(bytes_read as u32);
+            }
+            Err(err) => {
+                // The AsyncRead encountered an error, drop it.
+                self.async_read = None;
+                self.buffer = Vec::new();
+                controller.error(&io_error_to_js(err));
+            }
+        }
+    }
+}
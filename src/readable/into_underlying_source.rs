@@ -1,40 +1,139 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::pin::Pin;
 use std::rc::Rc;
+use std::task::Poll;
 
-use futures::future::{abortable, AbortHandle, TryFutureExt};
+use futures::future::{poll_fn, ready, FutureExt, LocalBoxFuture};
 use futures::stream::{Stream, TryStreamExt};
+use js_sys::Promise;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::spawn_local;
+use wasm_bindgen_futures::{future_to_promise, spawn_local};
+
+use crate::queuing_strategy::{self, QueuingStrategy};
+use crate::util::future_to_promise_eager;
 
 use super::sys;
+use super::underlying_source::{ReadableStreamDefaultController, UnderlyingSource};
 
-type JsValueStream = dyn Stream<Item = Result<JsValue, JsValue>>;
+/// Converts a [`Stream`](Stream) into the raw parts of a
+/// [`ReadableStream`](super::ReadableStream): a JavaScript
+/// [underlying source](https://streams.spec.whatwg.org/#underlying-source-api) object,
+/// and the raw [queuing strategy](QueuingStrategy).
+///
+/// These can be passed to the `ReadableStream` constructor of a different realm or of a polyfill,
+/// or to the constructor of a JavaScript subclass. In all other cases, prefer
+/// [`from_stream_with_strategy`](super::ReadableStream::from_stream_with_strategy),
+/// which constructs the stream directly.
+///
+/// The underlying source must be used to construct at most one stream.
+pub fn into_raw_parts<St>(
+    stream: St,
+    strategy: QueuingStrategy,
+) -> (JsValue, queuing_strategy::sys::QueuingStrategy)
+where
+    St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+{
+    let source = IntoUnderlyingSource::new(StreamUnderlyingSource::new(stream));
+    (source.into(), strategy.into_raw())
+}
 
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 #[wasm_bindgen]
 pub(crate) struct IntoUnderlyingSource {
-    inner: Rc<RefCell<Inner>>,
-    pull_handle: Option<AbortHandle>,
+    // The underlying source is only boxed once, as the erased type at the JavaScript boundary.
+    source: Rc<RefCell<dyn UnderlyingSource>>,
+    pull_state: Rc<PullState>,
+    // The running pull, if any. This is shared with the spawned task which polls it,
+    // so it can be dropped synchronously when the stream is canceled.
+    pull_task: Rc<RefCell<Option<LocalBoxFuture<'static, ()>>>>,
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[derive(Default)]
+struct PullState {
+    pulling: Cell<bool>,
+    pull_again: Cell<bool>,
+    canceled: Cell<bool>,
 }
 
 impl IntoUnderlyingSource {
-    pub fn new(stream: Box<JsValueStream>) -> Self {
+    pub fn new<S: UnderlyingSource + 'static>(source: S) -> Self {
         IntoUnderlyingSource {
-            inner: Rc::new(RefCell::new(Inner::new(stream))),
-            pull_handle: None,
+            source: Rc::new(RefCell::new(source)),
+            pull_state: Rc::new(PullState::default()),
+            pull_task: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Drops the running pull, if any, which releases its borrow of the source.
+    ///
+    /// Returns `false` if the pull is currently being polled, e.g. when the stream is
+    /// canceled from within the source. In that case, the pull is dropped by its task
+    /// as soon as that poll returns.
+    fn drop_pull(&self) -> bool {
+        self.pull_state.canceled.set(true);
+        match self.pull_task.try_borrow_mut() {
+            Ok(mut pull_task) => {
+                pull_task.take();
+                true
+            }
+            Err(_) => false,
         }
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 #[wasm_bindgen]
 impl IntoUnderlyingSource {
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn start(&mut self, controller: sys::ReadableStreamDefaultController) -> Promise {
+        let source = self.source.clone();
+        future_to_promise(async move {
+            // This mutable borrow can never panic, since the ReadableStream always waits
+            // for start() to finish before calling pull().
+            let mut source = source.try_borrow_mut().unwrap_throw();
+            let controller = ReadableStreamDefaultController::from_raw(controller);
+            source
+                .start(&controller)
+                .await
+                .map(|_| JsValue::undefined())
+        })
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
     pub fn pull(&mut self, controller: sys::ReadableStreamDefaultController) {
-        let inner = self.inner.clone();
+        // Since we don't return a promise (see below), the ReadableStream may call pull() again
+        // while the previous pull is still running, e.g. when the source enqueues a chunk.
+        // In that case, remember to pull again once the current pull has finished.
+        if self.pull_state.pulling.get() {
+            self.pull_state.pull_again.set(true);
+            return;
+        }
+        self.pull_state.pulling.set(true);
+
+        let source = self.source.clone();
+        let pull_state = self.pull_state.clone();
         let fut = async move {
-            // This mutable borrow can never panic, since the ReadableStream always queues
-            // each operation on the underlying source.
-            let mut inner = inner.try_borrow_mut().unwrap_throw();
-            inner.pull(controller).await;
+            let controller = ReadableStreamDefaultController::from_raw(controller);
+            loop {
+                pull_state.pull_again.set(false);
+                // This mutable borrow can never panic, since only one pull runs at a time.
+                let mut source = source.try_borrow_mut().unwrap_throw();
+                trace_event!("pulling from underlying source");
+                if let Err(err) = source.pull(&controller).await {
+                    debug_event!(error = ?err, "pulling from underlying source failed");
+                    controller.error(&err);
+                    break;
+                }
+                // Only pull again if the stream still wants more chunks. Once the source has
+                // closed or errored the stream, the desired size is no longer positive.
+                if !pull_state.pull_again.get()
+                    || !matches!(controller.desired_size(), Some(size) if size > 0.0)
+                {
+                    break;
+                }
+            }
+            pull_state.pulling.set(false);
         };
 
         // If pull() returns a promise, and the ReadableStream is canceled while the promise
@@ -46,58 +145,135 @@ impl IntoUnderlyingSource {
         // wait until the next enqueue() call before it attempts to call pull() again.
         // See also: https://github.com/whatwg/streams/issues/1014
 
-        // Since we run the future separately, we need to abort it manually when the stream
-        // is dropped.
-        let (fut, handle) = abortable(fut);
-        // Ignore errors from aborting the future.
-        let fut = fut.unwrap_or_else(|_| ());
-
-        self.pull_handle = Some(handle);
-        spawn_local(fut);
+        // Since we run the future separately, we need to drop it manually when the stream
+        // is canceled or dropped. Therefore, the spawned task only polls the future through
+        // a shared slot, which is emptied when the future completes or is dropped.
+        *self.pull_task.borrow_mut() = Some(in_span!("pull", fut).boxed_local());
+        let pull_task = self.pull_task.clone();
+        let pull_state = self.pull_state.clone();
+        spawn_local(poll_fn(move |cx| {
+            let mut slot = pull_task.borrow_mut();
+            let poll = match slot.as_mut() {
+                Some(fut) => fut.as_mut().poll(cx),
+                None => Poll::Ready(()),
+            };
+            // The stream may have been canceled while the pull was being polled.
+            if poll.is_ready() || pull_state.canceled.get() {
+                slot.take();
+                return Poll::Ready(());
+            }
+            poll
+        }));
     }
 
-    pub fn cancel(self) {
-        // The stream has been canceled, drop everything.
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn cancel(self, reason: JsValue) -> Promise {
+        debug_event!(reason = ?reason, "underlying source cancelled");
+        let source = self.source.clone();
+        // Drop the pending pull, if any.
+        let dropped = self.drop_pull();
         drop(self);
+        let fut = async move {
+            // If the pull could not be dropped right away, it is dropped by its task before
+            // this future runs, so the borrow is released by then.
+            let mut source = source.try_borrow_mut().unwrap_throw();
+            source.cancel(reason).await.map(|_| JsValue::undefined())
+        };
+        if dropped {
+            // No pull holds a borrow, so cancel right away. This lets the source drop
+            // its resources (e.g. the Rust stream) before returning to JavaScript.
+            future_to_promise_eager(fut)
+        } else {
+            future_to_promise(fut)
+        }
     }
 }
 
 impl Drop for IntoUnderlyingSource {
     fn drop(&mut self) {
-        // Abort the pending pull, if any.
-        if let Some(handle) = &mut self.pull_handle {
-            handle.abort();
-        }
+        // Drop the pending pull, if any.
+        self.drop_pull();
     }
 }
 
-struct Inner {
-    stream: Option<Pin<Box<JsValueStream>>>,
+/// An [`UnderlyingSource`](UnderlyingSource) that enqueues the items of a [`Stream`](Stream).
+///
+/// This is generic over the stream type, so polling the stream does not go through
+/// dynamic dispatch. The stream is only boxed to pin it.
+pub(crate) struct StreamUnderlyingSource<St> {
+    stream: Option<Pin<Box<St>>>,
+    cancel_handler: Option<Box<dyn FnOnce(JsValue)>>,
 }
 
-impl Inner {
-    fn new(stream: Box<JsValueStream>) -> Self {
-        Inner {
-            stream: Some(stream.into()),
+impl<St> StreamUnderlyingSource<St> {
+    pub fn new(stream: St) -> Self {
+        StreamUnderlyingSource {
+            stream: Some(Box::pin(stream)),
+            cancel_handler: None,
         }
     }
 
-    async fn pull(&mut self, controller: sys::ReadableStreamDefaultController) {
-        // The stream should still exist, since pull() will not be called again
-        // after the stream has closed or encountered an error.
-        let stream = self.stream.as_mut().unwrap_throw();
-        match stream.try_next().await {
-            Ok(Some(chunk)) => controller.enqueue(&chunk),
-            Ok(None) => {
-                // The stream has closed, drop it.
-                self.stream = None;
-                controller.close();
-            }
-            Err(err) => {
-                // The stream encountered an error, drop it.
-                self.stream = None;
-                controller.error(&err);
+    pub fn with_cancel_handler(mut self, cancel_handler: Box<dyn FnOnce(JsValue)>) -> Self {
+        self.cancel_handler = Some(cancel_handler);
+        self
+    }
+}
+
+impl<St> UnderlyingSource for StreamUnderlyingSource<St>
+where
+    St: Stream<Item = Result<JsValue, JsValue>>,
+{
+    fn pull<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            // The stream is gone after it has closed or encountered an error.
+            // In that case, there is nothing left to pull.
+            let stream = match self.stream.as_mut() {
+                Some(stream) => stream,
+                None => return Ok(()),
+            };
+            let mut next = stream.try_next().await;
+            loop {
+                match next {
+                    Ok(Some(chunk)) => controller.enqueue(&chunk)?,
+                    Ok(None) => {
+                        // The stream has closed, drop it.
+                        self.stream = None;
+                        return controller.close();
+                    }
+                    Err(err) => {
+                        // The stream encountered an error, drop it.
+                        self.stream = None;
+                        return Err(err);
+                    }
+                }
+                // Keep enqueuing chunks that are immediately available, as long as the queue
+                // still has room. This avoids a round-trip through pull() for every chunk.
+                if !matches!(controller.desired_size(), Some(size) if size > 0.0) {
+                    return Ok(());
+                }
+                let stream = match self.stream.as_mut() {
+                    Some(stream) => stream,
+                    None => return Ok(()),
+                };
+                next = match stream.try_next().now_or_never() {
+                    Some(next) => next,
+                    None => return Ok(()),
+                };
             }
         }
+        .boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // The stream has been canceled, drop it before notifying the handler, so the handler
+        // can clean up any state that was shared with the stream.
+        self.stream = None;
+        if let Some(cancel_handler) = self.cancel_handler.take() {
+            cancel_handler(reason);
+        }
+        ready(Ok(())).boxed_local()
     }
 }
@@ -1,44 +1,101 @@
 //! Bindings and conversions for
 //! [readable streams](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream).
+use std::fmt;
 use std::marker::PhantomData;
+use std::time::Duration;
 
-use futures::stream::Stream;
+use futures::future::{select, Either, Future};
+use futures::io::AsyncRead;
+use futures::stream::{Stream, TryStreamExt};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::{throw_val, JsCast};
 use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
 
+pub use async_iterable::AsyncIterableStream;
+#[cfg(feature = "blob")]
+pub use blob_reader::BlobReader;
+pub use byob_reader::ReadableStreamBYOBReader;
+pub use drop_policy::ReaderDropPolicy;
+pub use into_async_iterator::async_iterator_from_stream;
+pub use into_async_read::IntoAsyncRead;
+pub use into_buffered_stream::IntoBufferedStream;
+pub use into_bytes_stream::IntoBytesStream;
 pub use into_stream::IntoStream;
-use into_underlying_source::IntoUnderlyingSource;
+use into_underlying_byte_source::IntoUnderlyingByteSource;
+pub use into_underlying_source::into_raw_parts;
+use into_underlying_source::{IntoUnderlyingSource, StreamUnderlyingSource};
+use pausable::Pausable;
+pub use pausable::PauseHandle;
+pub use pipe_handle::PipeHandle;
 pub use pipe_options::PipeOptions;
+pub use read_error::ReadError;
+pub use underlying_source::{ReadableStreamDefaultController, UnderlyingSource};
 
 use crate::queuing_strategy::QueuingStrategy;
-use crate::util::promise_to_void_future;
+use crate::transform::PipeThrough;
+use crate::util::{
+    aborted, detach_promise, promise_to_void_future, promise_to_void_future_with_signal, sleep,
+};
 use crate::writable::WritableStream;
+use crate::StreamError;
 
+mod async_iterable;
+#[cfg(feature = "blob")]
+mod blob_reader;
+mod by_ref;
+mod byob_reader;
+mod combinators;
+mod drop_policy;
+mod event_target;
+mod into_async_iterator;
+mod into_async_read;
+mod into_buffered_stream;
+mod into_bytes_stream;
 mod into_stream;
+mod into_underlying_byte_source;
 mod into_underlying_source;
+mod pausable;
+mod pipe_handle;
 mod pipe_options;
+mod read_error;
 pub mod sys;
+mod underlying_source;
 
 /// A [`ReadableStream`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream).
 ///
 /// `ReadableStream`s can be created from a [raw JavaScript stream](sys::ReadableStream) with
-/// [`from_raw`](Self::from_raw), or from a Rust [`Stream`](Stream)
-/// with [`from_stream`](Self::from_stream).
+/// [`from_raw`](Self::from_raw), from a Rust [`Stream`](Stream)
+/// with [`from_stream`](Self::from_stream), or from a Rust [`UnderlyingSource`](UnderlyingSource)
+/// with [`from_underlying_source`](Self::from_underlying_source).
 ///
 /// They can be converted into a [raw JavaScript stream](sys::ReadableStream) with
 /// [`into_raw`](Self::into_raw), or into a Rust [`Stream`](Stream)
 /// with [`into_stream`](Self::into_stream).
-#[derive(Debug)]
-pub struct ReadableStream {
+///
+/// By default, chunks are represented by their raw [`JsValue`](JsValue).
+/// Use [`cast`](Self::cast) to get a `ReadableStream<T>` whose chunks are
+/// [checked](wasm_bindgen::JsCast::dyn_into) to be of type `T` when they are read,
+/// e.g. a `ReadableStream<Uint8Array>` for a stream of bytes.
+pub struct ReadableStream<T = JsValue> {
     raw: sys::ReadableStream,
+    pending_read: Option<by_ref::PendingRead>,
+    _chunk: PhantomData<T>,
+}
+
+impl<T> fmt::Debug for ReadableStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadableStream")
+            .field("raw", &self.raw)
+            .finish()
+    }
 }
 
 impl ReadableStream {
     /// Creates a new `ReadableStream` from a [JavaScript stream](sys::ReadableStream).
     #[inline]
     pub fn from_raw(raw: sys::ReadableStream) -> Self {
-        Self { raw }
+        Self::from_raw_unchecked(raw)
     }
 
     /// Creates a new `ReadableStream` from a [`Stream`](Stream).
@@ -51,12 +108,283 @@ impl ReadableStream {
     where
         St: Stream<Item = Result<JsValue, JsValue>> + 'static,
     {
-        let source = IntoUnderlyingSource::new(Box::new(stream));
         // Set HWM to 0 to prevent the JS ReadableStream from buffering chunks in its queue,
         // since the original Rust stream is better suited to handle that.
-        let strategy = QueuingStrategy::new(0.0);
-        let raw = sys::ReadableStream::new_with_source(source, strategy);
-        Self { raw }
+        Self::from_stream_with_strategy(stream, QueuingStrategy::new(0.0))
+    }
+
+    /// Creates a new `ReadableStream` from a [`Stream`](Stream) with the given
+    /// [queuing strategy](QueuingStrategy).
+    ///
+    /// Unlike [`from_stream`](Self::from_stream), this lets the JavaScript stream
+    /// pull chunks from the Rust stream ahead of time, until its internal queue
+    /// reaches the strategy's high water mark. Use [`QueuingStrategy::with_size`]
+    /// to measure chunks in e.g. bytes rather than chunks.
+    ///
+    /// See [`from_stream`](Self::from_stream) for more details.
+    pub fn from_stream_with_strategy<St>(stream: St, strategy: QueuingStrategy) -> Self
+    where
+        St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+    {
+        let source = IntoUnderlyingSource::new(StreamUnderlyingSource::new(stream));
+        Self::from_raw(sys::ReadableStream::new_with_source(
+            source,
+            strategy.as_raw(),
+        ))
+    }
+
+    /// Creates a new `ReadableStream` from a [`Stream`](Stream), calling `on_cancel`
+    /// with the cancel reason when the stream is [cancelled](Self::cancel_with_reason).
+    ///
+    /// When the stream is cancelled, the Rust stream is dropped right away, before the
+    /// JavaScript `cancel()` call returns, and then `on_cancel` is called. This can be used
+    /// to release any resources that were shared with the stream.
+    ///
+    /// See [`from_stream`](Self::from_stream) for more details.
+    pub fn from_stream_with_cancel_handler<St, F>(stream: St, on_cancel: F) -> Self
+    where
+        St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+        F: FnOnce(JsValue) + 'static,
+    {
+        let source = StreamUnderlyingSource::new(stream).with_cancel_handler(Box::new(on_cancel));
+        let source = IntoUnderlyingSource::new(source);
+        Self::from_raw(sys::ReadableStream::new_with_source(
+            source,
+            QueuingStrategy::new(0.0).as_raw(),
+        ))
+    }
+
+    /// Creates a new `ReadableStream` from a JavaScript iterable or async iterable object,
+    /// such as an array, a generator or an async generator.
+    ///
+    /// This uses the static [`ReadableStream.from()`](sys::ReadableStream::from_iterable) method
+    /// if the browser supports it. Otherwise, the values are read with an
+    /// [`AsyncIterableStream`](AsyncIterableStream), which behaves the same way.
+    /// Cancelling the returned stream calls the iterator's `return()` method.
+    ///
+    /// If the value is not iterable, then this returns a `TypeError`.
+    pub fn from_js_iterable(iterable: &JsValue) -> Result<Self, JsValue> {
+        if sys::ReadableStream::has_from() {
+            return Ok(Self::from_raw(sys::ReadableStream::from_iterable(
+                iterable,
+            )?));
+        }
+        let stream = AsyncIterableStream::new(iterable)?;
+        Ok(Self::from_stream(stream))
+    }
+
+    /// Creates a new `ReadableStream` of the events with the given `event_name` that are
+    /// dispatched on the given [`EventTarget`](web_sys::EventTarget).
+    ///
+    /// Events are buffered until they are read, up to 16 events. An `EventTarget` cannot
+    /// stop dispatching events, so any events that arrive while the buffer is full are dropped.
+    /// Cancelling the stream removes the event listener.
+    ///
+    /// Use [`from_event_target_with_strategy`](Self::from_event_target_with_strategy)
+    /// to use a different buffer size.
+    #[cfg(feature = "event-target")]
+    pub fn from_event_target(
+        target: &web_sys::EventTarget,
+        event_name: &str,
+    ) -> ReadableStream<web_sys::Event> {
+        Self::from_event_target_with_strategy(target, event_name, QueuingStrategy::new(16.0))
+    }
+
+    /// Creates a new `ReadableStream` of the events with the given `event_name` that are
+    /// dispatched on the given [`EventTarget`](web_sys::EventTarget), with the given
+    /// [queuing strategy](QueuingStrategy).
+    ///
+    /// Events are dropped whenever the stream's internal queue has reached its high water mark.
+    /// See [`from_event_target`](Self::from_event_target) for more details.
+    #[cfg(feature = "event-target")]
+    pub fn from_event_target_with_strategy(
+        target: &web_sys::EventTarget,
+        event_name: &str,
+        strategy: QueuingStrategy,
+    ) -> ReadableStream<web_sys::Event> {
+        let source = event_target::EventTargetSource::new(target, event_name);
+        Self::from_underlying_source_with_strategy(source, strategy).cast()
+    }
+
+    /// Creates a new `ReadableStream` of the values emitted by the given
+    /// [`Observable`](https://wicg.github.io/observable/), such as the one returned by
+    /// [`EventTarget.when()`](https://wicg.github.io/observable/#dom-eventtarget-when)
+    /// in browsers that support it.
+    ///
+    /// The stream subscribes to the observable when it is created. Values are buffered until
+    /// they are read, up to 16 values, and any values emitted while the buffer is full
+    /// are dropped. The stream closes when the observable completes, and errors when
+    /// the observable errors. Cancelling the stream unsubscribes from the observable.
+    ///
+    /// If the value is not an observable, then this returns a `TypeError`.
+    pub fn from_observable(observable: &JsValue) -> Result<Self, JsValue> {
+        Self::from_observable_with_strategy(observable, QueuingStrategy::new(16.0))
+    }
+
+    /// Creates a new `ReadableStream` of the values emitted by the given
+    /// [`Observable`](https://wicg.github.io/observable/), with the given
+    /// [queuing strategy](QueuingStrategy).
+    ///
+    /// Values are dropped whenever the stream's internal queue has reached its high water mark.
+    /// See [`from_observable`](Self::from_observable) for more details.
+    pub fn from_observable_with_strategy(
+        observable: &JsValue,
+        strategy: QueuingStrategy,
+    ) -> Result<Self, JsValue> {
+        let source = event_target::ObservableSource::new(observable)?;
+        Ok(Self::from_underlying_source_with_strategy(source, strategy))
+    }
+
+    /// Creates a new `ReadableStream` which produces a tick every `period`.
+    ///
+    /// Each tick is the [current time](js_sys::Date::now) in milliseconds since the Unix epoch
+    /// at which it was produced. The first tick is produced after one `period`.
+    ///
+    /// Ticks are only produced while the stream is being read. If the consumer falls behind
+    /// by more than one `period`, the missed ticks are skipped, and the next tick is produced
+    /// at the next multiple of `period` since the stream was created.
+    /// Cancelling the stream clears the pending timer.
+    ///
+    /// **Panics** if `period` is zero.
+    pub fn interval(period: Duration) -> ReadableStream<js_sys::Number> {
+        assert!(!period.is_zero(), "period must be positive");
+        let period = period.as_secs_f64() * 1000.0;
+        let first_deadline = js_sys::Date::now() + period;
+        let stream = futures::stream::unfold(first_deadline, move |deadline| async move {
+            if let Err(error) = sleep((deadline - js_sys::Date::now()).max(0.0)).await {
+                return Some((Err(error), deadline));
+            }
+            let now = js_sys::Date::now();
+            let missed = ((now - deadline) / period).floor();
+            let next_deadline = deadline + (missed + 1.0) * period;
+            Some((Ok(JsValue::from(now)), next_deadline))
+        });
+        Self::from_stream(stream).cast()
+    }
+
+    /// Creates a new `ReadableStream` from an [`UnderlyingSource`](UnderlyingSource).
+    ///
+    /// Compared to [`from_stream`](Self::from_stream), this gives the source access to the
+    /// stream's [controller](ReadableStreamDefaultController), so it can enqueue multiple chunks
+    /// per pull and observe the desired size of the stream's internal queue.
+    ///
+    /// This uses the default queuing strategy, with a high water mark of 1 chunk.
+    /// Use [`from_underlying_source_with_strategy`](Self::from_underlying_source_with_strategy)
+    /// to use a different strategy.
+    pub fn from_underlying_source<S>(source: S) -> Self
+    where
+        S: UnderlyingSource + 'static,
+    {
+        Self::from_underlying_source_with_strategy(source, QueuingStrategy::new(1.0))
+    }
+
+    /// Creates a new `ReadableStream` from an [`UnderlyingSource`](UnderlyingSource) with the
+    /// given [queuing strategy](QueuingStrategy).
+    ///
+    /// See [`from_underlying_source`](Self::from_underlying_source) for more details.
+    pub fn from_underlying_source_with_strategy<S>(source: S, strategy: QueuingStrategy) -> Self
+    where
+        S: UnderlyingSource + 'static,
+    {
+        let source = IntoUnderlyingSource::new(source);
+        Self::from_raw(sys::ReadableStream::new_with_source(
+            source,
+            strategy.as_raw(),
+        ))
+    }
+
+    /// Creates a new [readable byte stream](https://streams.spec.whatwg.org/#readable-byte-stream)
+    /// from an [`AsyncRead`](AsyncRead).
+    ///
+    /// This creates a readable byte stream whose
+    /// [`autoAllocateChunkSize`](https://streams.spec.whatwg.org/#dom-underlyingsource-autoallocatechunksize)
+    /// is `default_buffer_len`. Therefore, if a default reader is used to consume the stream,
+    /// the given `async_read` will be [polled](AsyncRead::poll_read) with a buffer of this size.
+    /// If a [BYOB reader](ReadableStreamBYOBReader) is used, then it will be polled with
+    /// a buffer of the same size as the BYOB read request instead.
+    ///
+    /// Errors from the `AsyncRead` are converted to a JavaScript
+    /// [`Error`](js_sys::Error) with the same message.
+    ///
+    /// **Panics** if `default_buffer_len` is zero.
+    pub fn from_async_read<R>(async_read: R, default_buffer_len: usize) -> Self
+    where
+        R: AsyncRead + 'static,
+    {
+        assert!(
+            default_buffer_len > 0,
+            "default_buffer_len must be positive"
+        );
+        let source = IntoUnderlyingByteSource::new(Box::new(async_read), default_buffer_len);
+        Self::from_raw(sys::ReadableStream::new_with_byte_source(source))
+    }
+
+    /// Creates a new `ReadableStream` which reads the contents of the given
+    /// [`Blob`](web_sys::Blob).
+    ///
+    /// This wraps the stream returned by
+    /// [`blob.stream()`](https://developer.mozilla.org/en-US/docs/Web/API/Blob/stream),
+    /// which produces [`Uint8Array`](js_sys::Uint8Array) chunks.
+    #[cfg(feature = "blob")]
+    pub fn from_blob(blob: &web_sys::Blob) -> ReadableStream<js_sys::Uint8Array> {
+        Self::from_raw(blob.stream().unchecked_into()).cast()
+    }
+
+    /// Creates a new `ReadableStream` which reads the contents of the given
+    /// [`File`](web_sys::File).
+    ///
+    /// This is equivalent to [`from_blob`](Self::from_blob), since every `File` is a `Blob`.
+    #[cfg(feature = "blob")]
+    #[inline]
+    pub fn from_file(file: &web_sys::File) -> ReadableStream<js_sys::Uint8Array> {
+        Self::from_blob(file)
+    }
+
+    /// Creates a new `ReadableStream` which reads the body of the given
+    /// [`Response`](web_sys::Response).
+    ///
+    /// This wraps the response's [`body`](https://developer.mozilla.org/en-US/docs/Web/API/Response/body),
+    /// which produces [`Uint8Array`](js_sys::Uint8Array) chunks.
+    /// This returns `None` if the response has no body, e.g. for a `204 No Content` response.
+    #[cfg(feature = "http")]
+    pub fn from_response_body(
+        response: &web_sys::Response,
+    ) -> Option<ReadableStream<js_sys::Uint8Array>> {
+        let body = response.body()?;
+        Some(Self::from_raw(body.unchecked_into()).cast())
+    }
+
+    /// Creates a new `ReadableStream` which receives the stream that is sent
+    /// with [`post_to`](Self::post_to) on the other end of the given `port`.
+    ///
+    /// This takes over the port's `onmessage` handler. See the [`transfer`](crate::transfer)
+    /// module for more details.
+    #[cfg(feature = "transfer")]
+    pub fn receive_from(port: &web_sys::MessagePort) -> Self {
+        Self::from_stream(crate::transfer::ReceiveStream::new(port))
+    }
+}
+
+impl<T: JsCast> ReadableStream<T> {
+    #[inline]
+    fn from_raw_unchecked(raw: sys::ReadableStream) -> Self {
+        Self {
+            raw,
+            pending_read: None,
+            _chunk: PhantomData,
+        }
+    }
+
+    /// Casts this stream into a `ReadableStream` with chunks of type `U`.
+    ///
+    /// This does not check anything up front. Instead, every chunk is
+    /// [checked](wasm_bindgen::JsCast::dyn_into) to be of type `U` when it is read,
+    /// and a chunk of a different type results in a [`ReadError::InvalidChunk`] error.
+    ///
+    /// Use `cast::<JsValue>()` to go back to an untyped stream.
+    #[inline]
+    pub fn cast<U: JsCast>(self) -> ReadableStream<U> {
+        ReadableStream::from_raw_unchecked(self.raw)
     }
 
     /// Acquires a reference to the underlying [JavaScript stream](sys::ReadableStream).
@@ -81,7 +409,12 @@ impl ReadableStream {
     /// signaling a loss of interest in the stream by a consumer.
     ///
     /// If the stream is currently locked to a reader, then this returns an error.
-    pub async fn cancel(&mut self) -> Result<(), JsValue> {
+    pub async fn cancel(&mut self) -> Result<(), StreamError> {
+        self.cancel_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`cancel`](Self::cancel), but returns the raw JavaScript error.
+    pub async fn cancel_js(&mut self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().cancel()).await
     }
 
@@ -91,7 +424,14 @@ impl ReadableStream {
     /// The supplied `reason` will be given to the underlying source, which may or may not use it.
     ///
     /// If the stream is currently locked to a reader, then this returns an error.
-    pub async fn cancel_with_reason(&mut self, reason: &JsValue) -> Result<(), JsValue> {
+    pub async fn cancel_with_reason(&mut self, reason: &JsValue) -> Result<(), StreamError> {
+        self.cancel_with_reason_js(reason)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Like [`cancel_with_reason`](Self::cancel_with_reason), but returns the raw JavaScript error.
+    pub async fn cancel_with_reason_js(&mut self, reason: &JsValue) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().cancel_with_reason(reason)).await
     }
 
@@ -103,7 +443,7 @@ impl ReadableStream {
     /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
     /// use [`try_get_reader`](Self::try_get_reader).
     #[inline]
-    pub fn get_reader(&mut self) -> ReadableStreamDefaultReader {
+    pub fn get_reader(&mut self) -> ReadableStreamDefaultReader<'_, T> {
         self.try_get_reader()
             .expect_throw("already locked to a reader")
     }
@@ -114,11 +454,43 @@ impl ReadableStream {
     /// While the stream is locked, no other reader can be acquired until this one is released.
     ///
     /// If the stream is already locked to a reader, then this returns an error.
-    pub fn try_get_reader(&mut self) -> Result<ReadableStreamDefaultReader, js_sys::Error> {
-        Ok(ReadableStreamDefaultReader {
-            raw: self.as_raw().get_reader()?,
-            _stream: PhantomData,
-        })
+    pub fn try_get_reader(&mut self) -> Result<ReadableStreamDefaultReader<'_, T>, js_sys::Error> {
+        Ok(ReadableStreamDefaultReader::new(
+            self.as_raw().get_reader()?,
+        ))
+    }
+
+    /// Creates a [BYOB reader](ReadableStreamBYOBReader) and
+    /// [locks](https://streams.spec.whatwg.org/#lock) the stream to the new reader.
+    ///
+    /// This reader can only be used with [readable byte streams](https://streams.spec.whatwg.org/#readable-byte-stream),
+    /// and allows reading directly into a Rust buffer.
+    ///
+    /// While the stream is locked, no other reader can be acquired until this one is released.
+    ///
+    /// **Panics** if the stream is already locked to a reader, or if this stream is not a readable
+    /// byte stream. For a non-panicking variant, use [`try_get_byob_reader`](Self::try_get_byob_reader).
+    #[inline]
+    pub fn get_byob_reader(&mut self) -> ReadableStreamBYOBReader<'_> {
+        self.try_get_byob_reader()
+            .expect_throw("already locked to a reader, or not a readable byte stream")
+    }
+
+    /// Try to create a [BYOB reader](ReadableStreamBYOBReader) and
+    /// [lock](https://streams.spec.whatwg.org/#lock) the stream to the new reader.
+    ///
+    /// This reader can only be used with [readable byte streams](https://streams.spec.whatwg.org/#readable-byte-stream),
+    /// and allows reading directly into a Rust buffer.
+    ///
+    /// While the stream is locked, no other reader can be acquired until this one is released.
+    ///
+    /// If the stream is already locked to a reader, or if this stream is not a readable
+    /// byte stream, then this returns an error.
+    pub fn try_get_byob_reader(&mut self) -> Result<ReadableStreamBYOBReader<'_>, js_sys::Error> {
+        let options = sys::GetReaderOptions::new(sys::ReaderMode::Byob);
+        Ok(ReadableStreamBYOBReader::new(
+            self.as_raw().get_reader_with_options(options)?,
+        ))
     }
 
     /// [Pipes](https://streams.spec.whatwg.org/#piping) this readable stream to a given
@@ -129,11 +501,23 @@ impl ReadableStream {
     ///
     /// This returns `()` if the pipe completes successfully, or `Err(error)` if any `error`
     /// was encountered during the process.
-    pub async fn pipe_to<'a>(&'a mut self, dest: &'a mut WritableStream) -> Result<(), JsValue> {
+    pub async fn pipe_to<'a>(
+        &'a mut self,
+        dest: &'a mut WritableStream<T>,
+    ) -> Result<(), StreamError> {
         self.pipe_to_with_options(dest, &PipeOptions::default())
             .await
     }
 
+    /// Like [`pipe_to`](Self::pipe_to), but returns the raw JavaScript error.
+    pub async fn pipe_to_js<'a>(
+        &'a mut self,
+        dest: &'a mut WritableStream<T>,
+    ) -> Result<(), JsValue> {
+        self.pipe_to_with_options_js(dest, &PipeOptions::default())
+            .await
+    }
+
     /// [Pipes](https://streams.spec.whatwg.org/#piping) this readable stream to a given
     /// writable stream.
     ///
@@ -155,15 +539,254 @@ impl ReadableStream {
     ///
     /// This returns `()` if the pipe completes successfully, or `Err(error)` if any `error`
     /// was encountered during the process.
+    ///
+    /// If the pipe is aborted through [`options.signal`](PipeOptions::signal), this returns
+    /// [`StreamError::Aborted`](StreamError::Aborted) with the signal's abort reason.
     pub async fn pipe_to_with_options<'a>(
         &'a mut self,
-        dest: &'a mut WritableStream,
+        dest: &'a mut WritableStream<T>,
+        options: &PipeOptions,
+    ) -> Result<(), StreamError> {
+        // An error caused by aborting the signal is reported as aborted,
+        // even if the signal was aborted with a custom reason.
+        self.pipe_to_with_options_js(dest, options)
+            .await
+            .map_err(|error| match options.abort_signal() {
+                Some(signal) => StreamError::from_js_with_signal(error, signal),
+                None => StreamError::from_js(error),
+            })
+    }
+
+    /// Like [`pipe_to_with_options`](Self::pipe_to_with_options), but returns the raw
+    /// JavaScript error.
+    pub async fn pipe_to_with_options_js<'a>(
+        &'a mut self,
+        dest: &'a mut WritableStream<T>,
         options: &PipeOptions,
     ) -> Result<(), JsValue> {
         let promise = self
             .as_raw()
             .pipe_to(dest.as_raw(), options.clone().into_raw());
-        promise_to_void_future(promise).await
+        in_span!("pipe", async move {
+            debug_event!("pipe started");
+            let result = promise_to_void_future(promise).await;
+            match &result {
+                Ok(()) => debug_event!("pipe finished"),
+                Err(err) => debug_event!(error = ?err, "pipe failed"),
+            }
+            result
+        })
+        .await
+    }
+
+    /// [Pipes](https://streams.spec.whatwg.org/#piping) this readable stream to a given
+    /// writable stream in the background, returning a [`PipeHandle`](PipeHandle) to monitor
+    /// and control the pipe.
+    ///
+    /// The pipe is started immediately, and continues even if the handle is dropped.
+    /// The handle can be used to query the number of chunks and bytes piped so far,
+    /// to [abort](PipeHandle::abort) the pipe, or to wait for it to complete.
+    /// Errors and closures propagate as described in
+    /// [`pipe_to_with_options`](Self::pipe_to_with_options).
+    ///
+    /// The pipe is aborted through the handle's own abort signal, so any
+    /// [`signal`](PipeOptions::signal) set in `options` is ignored.
+    ///
+    /// If this stream is already locked to a reader or `dest` is already locked to a writer,
+    /// then the returned handle completes with an error.
+    pub fn pipe_to_spawned(self, dest: WritableStream<T>, options: &PipeOptions) -> PipeHandle {
+        PipeHandle::spawn(self.cast(), dest.cast(), options)
+    }
+
+    /// [Pipes](https://streams.spec.whatwg.org/#piping) this readable stream into the file
+    /// of the given `handle`, replacing its contents.
+    ///
+    /// The chunks are written to a [`WritableFileStream`](crate::file_system::WritableFileStream),
+    /// so they must be `ArrayBuffer`s, `ArrayBufferView`s, `Blob`s or strings.
+    /// The file is only replaced once this stream closes. If this stream errors, the file stream
+    /// is aborted and the file is left untouched.
+    ///
+    /// If the returned future is dropped before the pipe completes, the pipe is aborted:
+    /// the file stream is aborted and this stream is [canceled](https://streams.spec.whatwg.org/#cancel-a-readable-stream).
+    ///
+    /// Piping a stream will [lock](https://streams.spec.whatwg.org/#lock) it for the duration
+    /// of the pipe, preventing any other consumer from acquiring a reader.
+    #[cfg(feature = "file-system")]
+    pub async fn pipe_to_file(
+        &mut self,
+        handle: &web_sys::FileSystemFileHandle,
+    ) -> Result<(), StreamError> {
+        crate::file_system::pipe_to_file(self.as_raw(), handle).await
+    }
+
+    /// [Pipes](https://streams.spec.whatwg.org/#piping) this readable stream through a given
+    /// transform stream, returning the readable side of the transform stream.
+    ///
+    /// This pipes this stream to the writable side of `transform`, and returns its readable side.
+    /// Errors and closures propagate as described in
+    /// [`pipe_to_with_options`](Self::pipe_to_with_options).
+    ///
+    /// Piping a stream will [lock](https://streams.spec.whatwg.org/#lock) it for the duration
+    /// of the pipe, preventing any other consumer from acquiring a reader.
+    ///
+    /// The `transform` can be a [`&TransformStream`](crate::transform::TransformStream), or a
+    /// [`ReadableWritablePair`](crate::transform::ReadableWritablePair) which is consumed by the pipe and keeps
+    /// the chunk type of its readable side, see [`PipeThrough`](PipeThrough).
+    ///
+    /// **Panics** if this stream is already locked to a reader, or if the writable side
+    /// of `transform` is already locked to a writer. For a non-panicking variant,
+    /// use [`try_pipe_through`](Self::try_pipe_through).
+    #[inline]
+    pub fn pipe_through<P: PipeThrough<T>>(&mut self, transform: P) -> ReadableStream<P::Output> {
+        self.pipe_through_with_options(transform, &PipeOptions::default())
+    }
+
+    /// Pipes this readable stream through a transform that runs in the given `worker`,
+    /// returning the resulting stream.
+    ///
+    /// The stream is [sent](Self::post_to) to the worker, piped through the transform that the
+    /// worker [registered](crate::transfer::register_worker_transform) with `transform_id`,
+    /// and the result is sent back. This keeps CPU-heavy transforms, such as decompression,
+    /// off the main thread. The worker must forward its messages to
+    /// [`handle_worker_message`](crate::transfer::handle_worker_message).
+    ///
+    /// Piping a stream will [lock](https://streams.spec.whatwg.org/#lock) it, preventing any
+    /// other consumer from acquiring a reader.
+    ///
+    /// **Panics** if this stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_pipe_through_worker`](Self::try_pipe_through_worker).
+    #[cfg(feature = "worker")]
+    pub fn pipe_through_worker(
+        &mut self,
+        worker: &web_sys::Worker,
+        transform_id: &str,
+    ) -> ReadableStream {
+        self.try_pipe_through_worker(worker, transform_id)
+            .unwrap_or_else(|err| throw_val(err))
+    }
+
+    /// Try to pipe this readable stream through a transform that runs in the given `worker`,
+    /// returning the resulting stream.
+    ///
+    /// See [`pipe_through_worker`](Self::pipe_through_worker) for more details.
+    ///
+    /// If this stream is already locked to a reader, or if the stream could not be sent
+    /// to the worker, then this returns an error.
+    #[cfg(feature = "worker")]
+    pub fn try_pipe_through_worker(
+        &mut self,
+        worker: &web_sys::Worker,
+        transform_id: &str,
+    ) -> Result<ReadableStream, JsValue> {
+        if self.is_locked() {
+            return Err(js_sys::Error::new("already locked to a reader").into());
+        }
+        let readable = ReadableStream::from_raw(self.as_raw().clone());
+        crate::transfer::pipe_through_worker(readable, worker, transform_id)
+    }
+
+    /// Splits this stream into one chunk per line.
+    ///
+    /// This is equivalent to [piping](Self::pipe_through) this stream through
+    /// [`transform::lines`](crate::transform::lines). See there for more details.
+    ///
+    /// **Panics** if this stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_lines`](Self::try_lines).
+    pub fn lines(&mut self) -> ReadableStream {
+        self.try_lines().expect_throw("already locked to a reader")
+    }
+
+    /// Try to split this stream into one chunk per line.
+    ///
+    /// See [`lines`](Self::lines) for more details.
+    ///
+    /// If this stream is already locked to a reader, then this returns an error.
+    pub fn try_lines(&mut self) -> Result<ReadableStream, js_sys::Error> {
+        self.try_pipe_through(&crate::transform::lines())
+    }
+
+    /// Returns a new `ReadableStream` with the same chunks as this stream,
+    /// calling `f` with a reference to every chunk as it is read.
+    ///
+    /// This is equivalent to [piping](Self::pipe_through) this stream through
+    /// [`transform::inspect`](crate::transform::inspect). It is useful for debugging a pipeline,
+    /// without disturbing it. Chunks which are not of type `T` are passed through,
+    /// but are not passed to `f`.
+    ///
+    /// **Panics** if this stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_inspect`](Self::try_inspect).
+    pub fn inspect<F>(&mut self, f: F) -> ReadableStream<T>
+    where
+        F: FnMut(&T) + 'static,
+    {
+        self.try_inspect(f)
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to create a new `ReadableStream` with the same chunks as this stream,
+    /// calling `f` with a reference to every chunk as it is read.
+    ///
+    /// See [`inspect`](Self::inspect) for more details.
+    ///
+    /// If this stream is already locked to a reader, then this returns an error.
+    pub fn try_inspect<F>(&mut self, mut f: F) -> Result<ReadableStream<T>, js_sys::Error>
+    where
+        F: FnMut(&T) + 'static,
+    {
+        let transform = crate::transform::inspect(move |chunk| {
+            if let Some(chunk) = chunk.dyn_ref::<T>() {
+                f(chunk)
+            }
+        });
+        Ok(self.try_pipe_through(&transform)?.cast())
+    }
+
+    /// [Pipes](https://streams.spec.whatwg.org/#piping) this readable stream through a given
+    /// transform stream, returning the readable side of the transform stream.
+    ///
+    /// This is like [`pipe_through`](Self::pipe_through), but allows configuring how errors
+    /// and closures propagate using the given `options`.
+    /// See [`pipe_to_with_options`](Self::pipe_to_with_options) for more details.
+    ///
+    /// **Panics** if this stream is already locked to a reader, or if the writable side
+    /// of `transform` is already locked to a writer. For a non-panicking variant,
+    /// use [`try_pipe_through_with_options`](Self::try_pipe_through_with_options).
+    pub fn pipe_through_with_options<P: PipeThrough<T>>(
+        &mut self,
+        transform: P,
+        options: &PipeOptions,
+    ) -> ReadableStream<P::Output> {
+        self.try_pipe_through_with_options(transform, options)
+            .expect_throw("already locked to a reader or writer")
+    }
+
+    /// Try to [pipe](https://streams.spec.whatwg.org/#piping) this readable stream through a given
+    /// transform stream, returning the readable side of the transform stream.
+    ///
+    /// If this stream is already locked to a reader, or if the writable side of `transform` is
+    /// already locked to a writer, then this returns an error. For a
+    /// [`ReadableWritablePair`](crate::transform::ReadableWritablePair), the error also returns the original pair.
+    #[inline]
+    pub fn try_pipe_through<P: PipeThrough<T>>(
+        &mut self,
+        transform: P,
+    ) -> Result<ReadableStream<P::Output>, P::Error> {
+        self.try_pipe_through_with_options(transform, &PipeOptions::default())
+    }
+
+    /// Try to [pipe](https://streams.spec.whatwg.org/#piping) this readable stream through a given
+    /// transform stream, returning the readable side of the transform stream.
+    ///
+    /// If this stream is already locked to a reader, or if the writable side of `transform` is
+    /// already locked to a writer, then this returns an error. For a
+    /// [`ReadableWritablePair`](crate::transform::ReadableWritablePair), the error also returns the original pair.
+    #[inline]
+    pub fn try_pipe_through_with_options<P: PipeThrough<T>>(
+        &mut self,
+        transform: P,
+        options: &PipeOptions,
+    ) -> Result<ReadableStream<P::Output>, P::Error> {
+        transform.pipe_from(self.as_raw(), options)
     }
 
     /// [Tees](https://streams.spec.whatwg.org/#tee-a-readable-stream) this readable stream,
@@ -180,7 +803,7 @@ impl ReadableStream {
     ///
     /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
     /// use [`try_tee`](Self::try_tee).
-    pub fn tee(self) -> (ReadableStream, ReadableStream) {
+    pub fn tee(self) -> (ReadableStream<T>, ReadableStream<T>) {
         self.try_tee().expect_throw("already locked to a reader")
     }
 
@@ -198,7 +821,8 @@ impl ReadableStream {
     ///
     /// If the stream is already locked to a reader, then this returns an error
     /// along with the original `ReadableStream`.
-    pub fn try_tee(self) -> Result<(ReadableStream, ReadableStream), (js_sys::Error, Self)> {
+    #[allow(clippy::type_complexity)]
+    pub fn try_tee(self) -> Result<(ReadableStream<T>, ReadableStream<T>), (js_sys::Error, Self)> {
         let branches = match self.as_raw().tee() {
             Ok(branches) => branches,
             Err(err) => return Err((err, self)),
@@ -206,92 +830,765 @@ impl ReadableStream {
         debug_assert_eq!(branches.length(), 2);
         let (left, right) = (branches.get(0), branches.get(1));
         Ok((
-            Self::from_raw(left.unchecked_into()),
-            Self::from_raw(right.unchecked_into()),
+            Self::from_raw_unchecked(left.unchecked_into()),
+            Self::from_raw_unchecked(right.unchecked_into()),
         ))
     }
 
-    /// Converts this `ReadableStream` into a [`Stream`](Stream).
+    /// [Tees](https://streams.spec.whatwg.org/#tee-a-readable-stream) this readable stream
+    /// into `n` branches, returning them as new [`ReadableStream`](ReadableStream) instances.
     ///
-    /// Items and errors are represented by their raw [`JsValue`](JsValue).
-    /// Use [`map`](futures::StreamExt::map), [`map_ok`](futures::TryStreamExt::map_ok) and/or
-    /// [`map_err`](futures::TryStreamExt::map_err) on the returned stream to convert them to a more
-    /// appropriate type.
+    /// This repeatedly [tees](Self::tee) the stream and its branches, such that every chunk
+    /// passes through at most `log2(n)` tees. If `n` is 1, this returns the stream itself.
     ///
-    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
-    /// use [`try_into_stream`](Self::try_into_stream).
-    #[inline]
-    pub fn into_stream(self) -> IntoStream<'static> {
-        self.try_into_stream()
-            .expect_throw("already locked to a reader")
+    /// As with `tee`, the stream is only [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream)
+    /// once all of the resulting branches are cancelled. The cancellation reason propagated
+    /// to the stream's underlying source is then a nested composite of the branches' reasons.
+    ///
+    /// Note that the chunks seen in each branch will be the same object.
+    /// If the chunks are not immutable, this could allow interference between the branches.
+    ///
+    /// **Panics** if `n` is 0, or if the stream is already locked to a reader.
+    /// For a non-panicking variant, use [`try_tee_n`](Self::try_tee_n).
+    pub fn tee_n(self, n: usize) -> Vec<ReadableStream<T>> {
+        self.try_tee_n(n).expect_throw("already locked to a reader")
     }
 
-    /// Try to convert this `ReadableStream` into a [`Stream`](Stream).
+    /// Tries to [tee](https://streams.spec.whatwg.org/#tee-a-readable-stream) this readable
+    /// stream into `n` branches, returning them as new [`ReadableStream`](ReadableStream)
+    /// instances.
     ///
-    /// Items and errors are represented by their raw [`JsValue`](JsValue).
-    /// Use [`map`](futures::StreamExt::map), [`map_ok`](futures::TryStreamExt::map_ok) and/or
-    /// [`map_err`](futures::TryStreamExt::map_err) on the returned stream to convert them to a more
-    /// appropriate type.
+    /// See [`tee_n`](Self::tee_n) for more details.
     ///
     /// If the stream is already locked to a reader, then this returns an error
     /// along with the original `ReadableStream`.
-    pub fn try_into_stream(self) -> Result<IntoStream<'static>, (js_sys::Error, Self)> {
-        let raw_reader = match self.as_raw().get_reader() {
-            Ok(raw_reader) => raw_reader,
-            Err(err) => return Err((err, self)),
-        };
-        let reader = ReadableStreamDefaultReader {
-            raw: raw_reader,
-            _stream: PhantomData,
-        };
-        Ok(reader.into_stream())
-    }
-}
-
-impl<St> From<St> for ReadableStream
-where
-    St: Stream<Item = Result<JsValue, JsValue>> + 'static,
-{
-    /// Equivalent to [`from_stream`](Self::from_stream).
-    #[inline]
-    fn from(stream: St) -> Self {
-        Self::from_stream(stream)
+    ///
+    /// **Panics** if `n` is 0.
+    pub fn try_tee_n(self, n: usize) -> Result<Vec<ReadableStream<T>>, (js_sys::Error, Self)> {
+        assert!(n > 0, "cannot tee into zero branches");
+        if n == 1 {
+            if self.is_locked() {
+                let err = js_sys::TypeError::new("ReadableStream is locked to a reader");
+                return Err((err.into(), self));
+            }
+            return Ok(vec![self]);
+        }
+        let (left, right) = self.try_tee()?;
+        let mut branches = Vec::with_capacity(n);
+        // The new branches are never locked, so these cannot fail.
+        branches.extend(left.tee_n(n / 2));
+        branches.extend(right.tee_n(n - n / 2));
+        Ok(branches)
     }
-}
-
-/// A [`ReadableStreamDefaultReader`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamDefaultReader)
-/// that can be used to read chunks from a [`ReadableStream`](ReadableStream).
-///
-/// This is returned by the [`get_reader`](ReadableStream::get_reader) method.
-///
-/// When the reader is dropped, it automatically [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
-#[derive(Debug)]
-pub struct ReadableStreamDefaultReader<'stream> {
-    raw: sys::ReadableStreamDefaultReader,
-    _stream: PhantomData<&'stream mut ReadableStream>,
-}
 
-impl<'stream> ReadableStreamDefaultReader<'stream> {
-    /// Acquires a reference to the underlying [JavaScript reader](sys::ReadableStreamDefaultReader).
-    #[inline]
-    pub fn as_raw(&self) -> &sys::ReadableStreamDefaultReader {
-        &self.raw
+    /// [Tees](https://streams.spec.whatwg.org/#tee-a-readable-stream) this readable stream into
+    /// the given `cache`, storing a copy of it as the body of the response for `request`.
+    ///
+    /// This returns a new stream with the same chunks, which should be read as usual.
+    /// The copy is stored in the background with a `200 OK` response, and only becomes
+    /// visible in the cache once the whole stream has been read. If the stream errors,
+    /// nothing is stored. The stream should produce [`Uint8Array`](js_sys::Uint8Array) chunks.
+    ///
+    /// Note that the copy is read as fast as the cache can store it, so chunks are buffered
+    /// in memory if the returned stream is read more slowly.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_tee_into_cache`](Self::try_tee_into_cache).
+    #[cfg(feature = "cache")]
+    pub fn tee_into_cache(
+        self,
+        cache: &web_sys::Cache,
+        request: &web_sys::Request,
+    ) -> ReadableStream<T> {
+        self.try_tee_into_cache(cache, request)
+            .expect_throw("already locked to a reader")
     }
 
-    /// Waits for the stream to become closed.
+    /// Tries to [tee](https://streams.spec.whatwg.org/#tee-a-readable-stream) this readable
+    /// stream into the given `cache`.
     ///
-    /// This returns an error if the stream ever errors, or if the reader's lock is
-    /// [released](https://streams.spec.whatwg.org/#release-a-lock) before the stream finishes
-    /// closing.
-    pub async fn closed(&self) -> Result<(), JsValue> {
-        promise_to_void_future(self.as_raw().closed()).await
+    /// See [`tee_into_cache`](Self::tee_into_cache) for more details.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    #[cfg(feature = "cache")]
+    pub fn try_tee_into_cache(
+        self,
+        cache: &web_sys::Cache,
+        request: &web_sys::Request,
+    ) -> Result<ReadableStream<T>, (js_sys::Error, Self)> {
+        crate::cache::tee_into_cache(self, cache, request)
     }
 
-    /// [Cancels](https://streams.spec.whatwg.org/#cancel-a-readable-stream) the stream,
-    /// signaling a loss of interest in the stream by a consumer.
+    /// Merges the given readable streams into a single [`ReadableStream`](ReadableStream),
+    /// which yields the chunks of all streams as they become available.
+    ///
+    /// The chunks of each individual stream are yielded in order, but chunks from different
+    /// streams may be interleaved arbitrarily. The merged stream closes once all streams have
+    /// closed, and errors as soon as any stream errors.
+    ///
+    /// When the merged stream is [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream)
+    /// or errors, all remaining streams are cancelled.
+    ///
+    /// **Panics** if any of the streams is already locked to a reader. For a non-panicking
+    /// variant, use [`try_merge`](Self::try_merge).
+    pub fn merge(streams: Vec<ReadableStream<T>>) -> ReadableStream<T>
+    where
+        T: 'static,
+    {
+        Self::try_merge(streams).expect_throw("already locked to a reader")
+    }
+
+    /// Tries to merge the given readable streams into a single [`ReadableStream`](ReadableStream).
+    ///
+    /// See [`merge`](Self::merge) for more details.
+    ///
+    /// If any of the streams is already locked to a reader, then this returns an error
+    /// along with the original streams, none of which are locked by this call.
+    #[allow(clippy::type_complexity)]
+    pub fn try_merge(
+        streams: Vec<ReadableStream<T>>,
+    ) -> Result<ReadableStream<T>, (js_sys::Error, Vec<ReadableStream<T>>)>
+    where
+        T: 'static,
+    {
+        if streams.iter().any(ReadableStream::is_locked) {
+            let err = js_sys::TypeError::new("ReadableStream is locked to a reader");
+            return Err((err.into(), streams));
+        }
+        Ok(combinators::merge(streams))
+    }
+
+    /// Concatenates this readable stream with `other`, returning a new
+    /// [`ReadableStream`](ReadableStream) which yields all chunks of this stream,
+    /// followed by all chunks of `other`.
+    ///
+    /// This is a shorthand for [`concat`](Self::concat) with two streams.
+    ///
+    /// **Panics** if either stream is already locked to a reader. For a non-panicking
+    /// variant, use [`try_chain`](Self::try_chain).
+    pub fn chain(self, other: ReadableStream<T>) -> ReadableStream<T>
+    where
+        T: 'static,
+    {
+        Self::concat(vec![self, other])
+    }
+
+    /// Tries to concatenate this readable stream with `other`.
+    ///
+    /// See [`chain`](Self::chain) for more details.
+    ///
+    /// If either stream is already locked to a reader, then this returns an error
+    /// along with the original streams, neither of which is locked by this call.
+    #[allow(clippy::type_complexity)]
+    pub fn try_chain(
+        self,
+        other: ReadableStream<T>,
+    ) -> Result<ReadableStream<T>, (js_sys::Error, Self, ReadableStream<T>)>
+    where
+        T: 'static,
+    {
+        Self::try_concat(vec![self, other]).map_err(|(err, mut streams)| {
+            let other = streams.pop().unwrap_throw();
+            let this = streams.pop().unwrap_throw();
+            (err, this, other)
+        })
+    }
+
+    /// Concatenates the given readable streams into a single [`ReadableStream`](ReadableStream),
+    /// which reads the streams one after another.
+    ///
+    /// A stream is only read once all preceding streams have closed.
+    /// If any stream errors, the concatenated stream errors as well.
+    ///
+    /// All streams are locked immediately. When the concatenated stream errors or is
+    /// [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream),
+    /// the current stream and all streams that were not yet started are cancelled.
+    ///
+    /// **Panics** if any of the streams is already locked to a reader. For a non-panicking
+    /// variant, use [`try_concat`](Self::try_concat).
+    pub fn concat(streams: Vec<ReadableStream<T>>) -> ReadableStream<T>
+    where
+        T: 'static,
+    {
+        Self::try_concat(streams).expect_throw("already locked to a reader")
+    }
+
+    /// Tries to concatenate the given readable streams into a single
+    /// [`ReadableStream`](ReadableStream).
+    ///
+    /// See [`concat`](Self::concat) for more details.
+    ///
+    /// If any of the streams is already locked to a reader, then this returns an error
+    /// along with the original streams, none of which are locked by this call.
+    #[allow(clippy::type_complexity)]
+    pub fn try_concat(
+        streams: Vec<ReadableStream<T>>,
+    ) -> Result<ReadableStream<T>, (js_sys::Error, Vec<ReadableStream<T>>)>
+    where
+        T: 'static,
+    {
+        if streams.iter().any(ReadableStream::is_locked) {
+            let err = js_sys::TypeError::new("ReadableStream is locked to a reader");
+            return Err((err.into(), streams));
+        }
+        Ok(combinators::concat(streams))
+    }
+
+    /// Zips two readable streams into a single [`ReadableStream`](ReadableStream),
+    /// which yields a two-element [`Array`](js_sys::Array) for every pair of chunks
+    /// from `left` and `right`.
+    ///
+    /// The zipped stream closes as soon as either stream closes, and errors as soon as either
+    /// stream errors. When the zipped stream closes, errors or is
+    /// [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream),
+    /// both streams are cancelled.
+    ///
+    /// **Panics** if either stream is already locked to a reader. For a non-panicking
+    /// variant, use [`try_zip`](Self::try_zip).
+    pub fn zip<U: JsCast + 'static>(
+        left: ReadableStream<T>,
+        right: ReadableStream<U>,
+    ) -> ReadableStream<js_sys::Array>
+    where
+        T: 'static,
+    {
+        Self::try_zip(left, right).expect_throw("already locked to a reader")
+    }
+
+    /// Tries to zip two readable streams into a single [`ReadableStream`](ReadableStream).
+    ///
+    /// See [`zip`](Self::zip) for more details.
+    ///
+    /// If either stream is already locked to a reader, then this returns an error
+    /// along with the original streams, neither of which is locked by this call.
+    #[allow(clippy::type_complexity)]
+    pub fn try_zip<U: JsCast + 'static>(
+        left: ReadableStream<T>,
+        right: ReadableStream<U>,
+    ) -> Result<ReadableStream<js_sys::Array>, (js_sys::Error, ReadableStream<T>, ReadableStream<U>)>
+    where
+        T: 'static,
+    {
+        if left.is_locked() || right.is_locked() {
+            let err = js_sys::TypeError::new("ReadableStream is locked to a reader");
+            return Err((err.into(), left, right));
+        }
+        Ok(combinators::zip(left, right))
+    }
+
+    /// Wraps this readable stream in a new `ReadableStream` that can be paused and resumed
+    /// through the returned [`PauseHandle`](PauseHandle).
+    ///
+    /// While paused, the returned stream stops reading from this stream, without canceling it.
+    /// This is useful to let users pause a long transfer, e.g. while it is being
+    /// [piped](Self::pipe_to) to a destination.
+    ///
+    /// The returned stream is [locked](https://streams.spec.whatwg.org/#lock) to this stream.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_pausable`](Self::try_pausable).
+    pub fn pausable(self) -> (ReadableStream<T>, PauseHandle)
+    where
+        T: 'static,
+    {
+        self.try_pausable()
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to wrap this readable stream in a new `ReadableStream` that can be paused and resumed
+    /// through the returned [`PauseHandle`](PauseHandle).
+    ///
+    /// While paused, the returned stream stops reading from this stream, without canceling it.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    #[allow(clippy::type_complexity)]
+    pub fn try_pausable(self) -> Result<(ReadableStream<T>, PauseHandle), (js_sys::Error, Self)>
+    where
+        T: 'static,
+    {
+        let stream = self.try_into_stream()?.map_ok(Into::into);
+        let handle = PauseHandle::default();
+        let stream = Pausable::new(stream, handle.clone());
+        Ok((ReadableStream::from_stream(stream).cast(), handle))
+    }
+
+    /// Wraps this readable stream in a new `ReadableStream` which errors if no chunk arrives
+    /// within the given `duration`.
+    ///
+    /// The timer only runs while the returned stream is waiting for a chunk from this stream,
+    /// so a slow consumer does not cause a timeout. When the timer expires, the returned
+    /// stream errors with a `DOMException` named `TimeoutError`, which is classified as
+    /// [`StreamError::Timeout`](StreamError::Timeout), and this stream is cancelled
+    /// with that same error. This is useful to detect a dead connection while streaming
+    /// the body of a `fetch()` response.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_timeout_between_chunks`](Self::try_timeout_between_chunks).
+    pub fn timeout_between_chunks(self, duration: Duration) -> ReadableStream<T>
+    where
+        T: 'static,
+    {
+        self.try_timeout_between_chunks(duration)
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Tries to wrap this readable stream in a new `ReadableStream` which errors if no chunk
+    /// arrives within the given `duration`.
+    ///
+    /// See [`timeout_between_chunks`](Self::timeout_between_chunks) for more details.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_timeout_between_chunks(
+        self,
+        duration: Duration,
+    ) -> Result<ReadableStream<T>, (js_sys::Error, Self)>
+    where
+        T: 'static,
+    {
+        if self.is_locked() {
+            let err = js_sys::TypeError::new("ReadableStream is locked to a reader");
+            return Err((err.into(), self));
+        }
+        Ok(combinators::timeout_between_chunks(self, duration))
+    }
+
+    /// Converts this `ReadableStream` into a [`Stream`](Stream).
+    ///
+    /// Items are chunks of type `T`, and errors are represented by their raw [`JsValue`](JsValue).
+    /// A chunk which is not of type `T` results in a [`TypeError`](js_sys::TypeError).
+    /// Use [`map`](futures::StreamExt::map), [`map_ok`](futures::TryStreamExt::map_ok) and/or
+    /// [`map_err`](futures::TryStreamExt::map_err) on the returned stream to convert them to a more
+    /// appropriate type.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_into_stream`](Self::try_into_stream).
+    #[inline]
+    pub fn into_stream(self) -> IntoStream<'static, T> {
+        self.try_into_stream()
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to convert this `ReadableStream` into a [`Stream`](Stream).
+    ///
+    /// Items are chunks of type `T`, and errors are represented by their raw [`JsValue`](JsValue).
+    /// A chunk which is not of type `T` results in a [`TypeError`](js_sys::TypeError).
+    /// Use [`map`](futures::StreamExt::map), [`map_ok`](futures::TryStreamExt::map_ok) and/or
+    /// [`map_err`](futures::TryStreamExt::map_err) on the returned stream to convert them to a more
+    /// appropriate type.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_into_stream(self) -> Result<IntoStream<'static, T>, (js_sys::Error, Self)> {
+        let raw_reader = match self.as_raw().get_reader() {
+            Ok(raw_reader) => raw_reader,
+            Err(err) => return Err((err, self)),
+        };
+        let reader = ReadableStreamDefaultReader::new(raw_reader);
+        Ok(reader.into_stream().with_stream(self.raw))
+    }
+
+    /// Converts this `ReadableStream` into a [`Stream`](Stream) which keeps up to `n` reads
+    /// in flight.
+    ///
+    /// This is similar to [`into_stream`](Self::into_stream), except that the next `n` chunks
+    /// are requested from the stream ahead of time. Results are still yielded in order.
+    /// This can improve throughput for sources with a high latency per chunk.
+    /// If `n` is zero, it is treated as one.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_into_stream_buffered`](Self::try_into_stream_buffered).
+    #[inline]
+    pub fn into_stream_buffered(self, n: usize) -> IntoBufferedStream<'static, T> {
+        self.try_into_stream_buffered(n)
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to convert this `ReadableStream` into a [`Stream`](Stream) which keeps up to `n` reads
+    /// in flight.
+    ///
+    /// See [`into_stream_buffered`](Self::into_stream_buffered) for more details.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_into_stream_buffered(
+        self,
+        n: usize,
+    ) -> Result<IntoBufferedStream<'static, T>, (js_sys::Error, Self)> {
+        let raw_reader = match self.as_raw().get_reader() {
+            Ok(raw_reader) => raw_reader,
+            Err(err) => return Err((err, self)),
+        };
+        let reader = ReadableStreamDefaultReader::new(raw_reader);
+        Ok(reader.into_stream_buffered(n))
+    }
+
+    /// Converts this `ReadableStream` into a JavaScript [async iterator](js_sys::AsyncIterator)
+    /// over its chunks.
+    ///
+    /// This is the same iterator that a `for await (const chunk of stream)` loop uses.
+    /// It can be passed to JavaScript code that consumes async iterators rather than streams.
+    /// When the iterator is ended early (e.g. by a `break` in a `for await` loop), the stream
+    /// is [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream), unless
+    /// `prevent_cancel` is `true`.
+    ///
+    /// If the browser does not support async iteration of readable streams, this falls back to
+    /// an equivalent iterator built with [`async_iterator_from_stream`](async_iterator_from_stream).
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_into_async_iterator`](Self::try_into_async_iterator).
+    #[inline]
+    pub fn into_async_iterator(self, prevent_cancel: bool) -> js_sys::AsyncIterator
+    where
+        T: 'static,
+    {
+        self.try_into_async_iterator(prevent_cancel)
+            .unwrap_or_else(|(err, _)| throw_val(err.into()))
+    }
+
+    /// Try to convert this `ReadableStream` into a JavaScript [async iterator](js_sys::AsyncIterator)
+    /// over its chunks.
+    ///
+    /// See [`into_async_iterator`](Self::into_async_iterator) for more details.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error along with
+    /// the original `ReadableStream`.
+    pub fn try_into_async_iterator(
+        self,
+        prevent_cancel: bool,
+    ) -> Result<js_sys::AsyncIterator, (js_sys::Error, Self)>
+    where
+        T: 'static,
+    {
+        if self.is_locked() {
+            let err = js_sys::TypeError::new("already locked to a reader");
+            return Err((err.into(), self));
+        }
+        match self
+            .as_raw()
+            .values_with_options(sys::ValuesOptions::new(prevent_cancel))
+        {
+            Ok(iterator) => Ok(iterator),
+            // The browser does not support async iteration of streams, fall back to
+            // an async iterator implemented in Rust.
+            Err(_) if prevent_cancel => Ok(self.into_stream().into_async_iterator()),
+            Err(_) => Ok(async_iterator_from_stream(
+                combinators::CancelOnDrop::new(self).map_ok(JsCast::unchecked_into::<JsValue>),
+            )),
+        }
+    }
+
+    /// Sends this `ReadableStream` to the other end of the given `port`,
+    /// where it can be received with [`receive_from`](Self::receive_from).
+    ///
+    /// The stream is transferred if the browser supports transferable streams.
+    /// Otherwise, its chunks are sent over the port one by one, and the port's `onmessage`
+    /// handler is taken over until the stream is done.
+    /// See the [`transfer`](crate::transfer) module for more details.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_post_to`](Self::try_post_to).
+    #[cfg(feature = "transfer")]
+    #[inline]
+    pub fn post_to(self, port: &web_sys::MessagePort) {
+        self.try_post_to(port)
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to send this `ReadableStream` to the other end of the given `port`.
+    ///
+    /// See [`post_to`](Self::post_to) for more details.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    #[cfg(feature = "transfer")]
+    pub fn try_post_to(self, port: &web_sys::MessagePort) -> Result<(), (js_sys::Error, Self)> {
+        if self.is_locked() {
+            return Err((js_sys::Error::new("already locked to a reader"), self));
+        }
+        crate::transfer::post_stream(self.into_raw(), port);
+        Ok(())
+    }
+
+    /// Reads all chunks from this `ReadableStream` into a `Vec<u8>`.
+    ///
+    /// The stream must only produce [`Uint8Array`](js_sys::Uint8Array) chunks.
+    /// See [`ReadableStreamDefaultReader.read_to_end`](ReadableStreamDefaultReader::read_to_end)
+    /// for more details.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error.
+    pub async fn collect_bytes(self) -> Result<Vec<u8>, ReadError> {
+        let raw_reader = self
+            .as_raw()
+            .get_reader()
+            .map_err(|err| ReadError::Js(err.into()))?;
+        let mut reader = ReadableStreamDefaultReader::<T>::new(raw_reader);
+        reader.read_to_end().await
+    }
+
+    /// Reads up to `n` chunks from the start of this `ReadableStream`, and then
+    /// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+    ///
+    /// Fewer than `n` chunks are returned if the stream closes early.
+    /// Afterwards, the stream can still be used to read the remaining chunks,
+    /// e.g. to [`pipe_to`](Self::pipe_to) a writable stream.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error.
+    /// If reading a chunk fails, the error is returned and the chunks read so far are lost.
+    pub async fn take(&mut self, n: usize) -> Result<Vec<T>, ReadError> {
+        let mut reader = self
+            .try_get_reader()
+            .map_err(|err| ReadError::Js(err.into()))?;
+        let mut chunks = Vec::with_capacity(n.min(16));
+        while chunks.len() < n {
+            match reader.read().await? {
+                Some(chunk) => chunks.push(chunk),
+                None => break,
+            }
+        }
+        Ok(chunks)
+    }
+
+    /// Discards up to `n` chunks from the start of this `ReadableStream`, and then
+    /// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+    ///
+    /// Returns the number of chunks that were discarded,
+    /// which is less than `n` if the stream closes early.
+    /// Afterwards, the stream can still be used to read the remaining chunks.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error.
+    pub async fn skip(&mut self, n: usize) -> Result<usize, ReadError> {
+        let mut reader = self
+            .try_get_reader()
+            .map_err(|err| ReadError::Js(err.into()))?;
+        let mut skipped = 0;
+        while skipped < n {
+            match reader.read().await? {
+                Some(_) => skipped += 1,
+                None => break,
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// Reads all chunks from this `ReadableStream` into a [`Blob`](web_sys::Blob)
+    /// with the given MIME type.
+    ///
+    /// The stream must only produce [`Uint8Array`](js_sys::Uint8Array) chunks.
+    /// The chunks are passed to the `Blob` constructor as-is, without copying them
+    /// into WebAssembly memory.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error.
+    #[cfg(feature = "blob")]
+    pub async fn collect_blob(self, mime: &str) -> Result<web_sys::Blob, ReadError> {
+        let raw_reader = self
+            .as_raw()
+            .get_reader()
+            .map_err(|err| ReadError::Js(err.into()))?;
+        let mut reader = ReadableStreamDefaultReader::<js_sys::Uint8Array>::new(raw_reader);
+        let parts = js_sys::Array::new();
+        while let Some(chunk) = reader.read().await? {
+            parts.push(&chunk);
+        }
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime);
+        web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+            .map_err(ReadError::Js)
+    }
+
+    /// Converts this `ReadableStream` into a [`Stream`](Stream) of bytes.
+    ///
+    /// The stream must only produce [`Uint8Array`](js_sys::Uint8Array) chunks,
+    /// which are copied into a new `Vec<u8>`. Any other chunk results in a
+    /// [`TypeError`](js_sys::TypeError).
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_into_bytes_stream`](Self::try_into_bytes_stream).
+    #[inline]
+    pub fn into_bytes_stream(self) -> IntoBytesStream<'static> {
+        self.try_into_bytes_stream()
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to convert this `ReadableStream` into a [`Stream`](Stream) of bytes.
+    ///
+    /// The stream must only produce [`Uint8Array`](js_sys::Uint8Array) chunks,
+    /// which are copied into a new `Vec<u8>`. Any other chunk results in a
+    /// [`TypeError`](js_sys::TypeError).
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_into_bytes_stream(self) -> Result<IntoBytesStream<'static>, (js_sys::Error, Self)> {
+        let raw_reader = match self.as_raw().get_reader() {
+            Ok(raw_reader) => raw_reader,
+            Err(err) => return Err((err, self)),
+        };
+        let reader = ReadableStreamDefaultReader::<JsValue>::new(raw_reader);
+        Ok(reader.into_bytes_stream())
+    }
+
+    /// Converts this `ReadableStream` into an [`AsyncRead`](AsyncRead).
+    ///
+    /// The stream must only produce [`Uint8Array`](js_sys::Uint8Array) chunks,
+    /// which are copied into the buffers passed to [`poll_read`](AsyncRead::poll_read).
+    /// The returned reader also implements [`AsyncBufRead`](futures::io::AsyncBufRead).
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_into_async_read`](Self::try_into_async_read).
+    #[inline]
+    pub fn into_async_read(self) -> IntoAsyncRead<'static> {
+        self.try_into_async_read()
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to convert this `ReadableStream` into an [`AsyncRead`](AsyncRead).
+    ///
+    /// The stream must only produce [`Uint8Array`](js_sys::Uint8Array) chunks,
+    /// which are copied into the buffers passed to [`poll_read`](AsyncRead::poll_read).
+    /// The returned reader also implements [`AsyncBufRead`](futures::io::AsyncBufRead).
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_into_async_read(self) -> Result<IntoAsyncRead<'static>, (js_sys::Error, Self)> {
+        let raw_reader = match self.as_raw().get_reader() {
+            Ok(raw_reader) => raw_reader,
+            Err(err) => return Err((err, self)),
+        };
+        let reader = ReadableStreamDefaultReader::<JsValue>::new(raw_reader);
+        Ok(reader.into_async_read())
+    }
+}
+
+impl<St> From<St> for ReadableStream
+where
+    St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+{
+    /// Equivalent to [`from_stream`](Self::from_stream).
+    #[inline]
+    fn from(stream: St) -> Self {
+        Self::from_stream(stream)
+    }
+}
+
+/// A [`ReadableStreamDefaultReader`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamDefaultReader)
+/// that can be used to read chunks from a [`ReadableStream`](ReadableStream).
+///
+/// This is returned by the [`get_reader`](ReadableStream::get_reader) method.
+///
+/// When the reader is dropped, it automatically [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+/// This can be changed with [`set_drop_policy`](Self::set_drop_policy).
+///
+/// Chunks read by this reader are [checked](wasm_bindgen::JsCast::dyn_into) to be of type `T`.
+#[derive(Debug)]
+pub struct ReadableStreamDefaultReader<'stream, T = JsValue> {
+    raw: sys::ReadableStreamDefaultReader,
+    remainder: Option<js_sys::Uint8Array>,
+    /// A read that was started by an [`IntoStream`](IntoStream), but not yet completed.
+    pending_read: Option<JsFuture>,
+    drop_policy: ReaderDropPolicy,
+    _stream: PhantomData<&'stream mut ReadableStream<T>>,
+}
+
+impl<'stream, T: JsCast> ReadableStreamDefaultReader<'stream, T> {
+    #[inline]
+    fn new(raw: sys::ReadableStreamDefaultReader) -> Self {
+        Self {
+            raw,
+            remainder: None,
+            pending_read: None,
+            drop_policy: ReaderDropPolicy::default(),
+            _stream: PhantomData,
+        }
+    }
+
+    fn cast<U: JsCast>(mut self) -> ReadableStreamDefaultReader<'stream, U> {
+        // Move the raw reader out, and leave behind a value that doesn't need to be dropped.
+        let raw = std::mem::replace(&mut self.raw, JsValue::UNDEFINED.unchecked_into());
+        let remainder = self.remainder.take();
+        let pending_read = self.pending_read.take();
+        let drop_policy = self.drop_policy;
+        // Skip our Drop implementation, since the lock is transferred to the new reader.
+        std::mem::forget(self);
+        let mut reader = ReadableStreamDefaultReader::new(raw);
+        reader.remainder = remainder;
+        reader.pending_read = pending_read;
+        reader.drop_policy = drop_policy;
+        reader
+    }
+
+    /// Takes the bytes left over from a previous [`read_into`](Self::read_into), if any.
+    #[inline]
+    pub(super) fn take_remainder(&mut self) -> Option<js_sys::Uint8Array> {
+        self.remainder.take()
+    }
+
+    /// Takes the read that was handed over by an [`IntoStream`](IntoStream), if any.
+    #[inline]
+    pub(super) fn take_pending_read(&mut self) -> Option<JsFuture> {
+        self.pending_read.take()
+    }
+
+    /// Acquires a reference to the underlying [JavaScript reader](sys::ReadableStreamDefaultReader).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::ReadableStreamDefaultReader {
+        &self.raw
+    }
+
+    /// Returns what this reader does with its lock when it is dropped.
+    #[inline]
+    pub fn drop_policy(&self) -> ReaderDropPolicy {
+        self.drop_policy
+    }
+
+    /// Sets what this reader does with its lock when it is dropped.
+    ///
+    /// The policy also applies when a [`Stream`](Stream) or [`AsyncRead`](futures::io::AsyncRead)
+    /// created from this reader is dropped. See [`ReaderDropPolicy`](ReaderDropPolicy)
+    /// for the available policies.
+    #[inline]
+    pub fn set_drop_policy(&mut self, policy: ReaderDropPolicy) -> &mut Self {
+        self.drop_policy = policy;
+        self
+    }
+
+    /// Waits for the stream to become closed.
+    ///
+    /// This returns an error if the stream ever errors, or if the reader's lock is
+    /// [released](https://streams.spec.whatwg.org/#release-a-lock) before the stream finishes
+    /// closing.
+    pub async fn closed(&self) -> Result<(), StreamError> {
+        self.closed_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`closed`](Self::closed), but returns the raw JavaScript error.
+    pub async fn closed_js(&self) -> Result<(), JsValue> {
+        promise_to_void_future(self.as_raw().closed()).await
+    }
+
+    /// Waits for the stream to become closed, or for the given `signal` to be aborted.
+    ///
+    /// This behaves like [`closed`](Self::closed), except that it returns an
+    /// [`Aborted`](StreamError::Aborted) error with the signal's abort reason
+    /// as soon as the signal is aborted. The reader is not affected by the signal.
+    pub async fn closed_with_signal(
+        &self,
+        signal: &impl AsRef<AbortSignal>,
+    ) -> Result<(), StreamError> {
+        promise_to_void_future_with_signal(self.as_raw().closed(), signal.as_ref()).await
+    }
+
+    /// [Cancels](https://streams.spec.whatwg.org/#cancel-a-readable-stream) the stream,
+    /// signaling a loss of interest in the stream by a consumer.
     ///
     /// Equivalent to [`ReadableStream.cancel`](ReadableStream::cancel).
-    pub async fn cancel(&mut self) -> Result<(), JsValue> {
+    pub async fn cancel(&mut self) -> Result<(), StreamError> {
+        self.cancel_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`cancel`](Self::cancel), but returns the raw JavaScript error.
+    pub async fn cancel_js(&mut self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().cancel()).await
     }
 
@@ -299,7 +1596,14 @@ impl<'stream> ReadableStreamDefaultReader<'stream> {
     /// signaling a loss of interest in the stream by a consumer.
     ///
     /// Equivalent to [`ReadableStream.cancel_with_reason`](ReadableStream::cancel_with_reason).
-    pub async fn cancel_with_reason(&mut self, reason: &JsValue) -> Result<(), JsValue> {
+    pub async fn cancel_with_reason(&mut self, reason: &JsValue) -> Result<(), StreamError> {
+        self.cancel_with_reason_js(reason)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Like [`cancel_with_reason`](Self::cancel_with_reason), but returns the raw JavaScript error.
+    pub async fn cancel_with_reason_js(&mut self, reason: &JsValue) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().cancel_with_reason(reason)).await
     }
 
@@ -307,18 +1611,206 @@ impl<'stream> ReadableStreamDefaultReader<'stream> {
     ///
     /// * If a next `chunk` becomes available, this returns `Ok(Some(chunk))`.
     /// * If the stream closes and no more chunks are available, this returns `Ok(None)`.
-    /// * If the stream encounters an `error`, this returns `Err(error)`.
-    pub async fn read(&mut self) -> Result<Option<JsValue>, JsValue> {
-        let promise = self.as_raw().read();
-        let js_value = JsFuture::from(promise).await?;
+    /// * If the stream encounters an `error`, this returns `Err(ReadError::Js(error))`.
+    /// * If the next chunk is not of type `T`, this returns
+    ///   [`Err(ReadError::InvalidChunk { .. })`](ReadError::InvalidChunk).
+    ///   The chunk is consumed, but the stream is left intact.
+    ///
+    /// If a previous [`read_into`](Self::read_into) left some bytes of a chunk unread,
+    /// those bytes are returned first as a [`Uint8Array`](js_sys::Uint8Array).
+    pub async fn read(&mut self) -> Result<Option<T>, ReadError> {
+        if let Some(remainder) = self.remainder.take() {
+            return ReadError::cast_chunk(remainder.into()).map(Some);
+        }
+        match self.read_raw().await? {
+            Some(chunk) => ReadError::cast_chunk(chunk).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the next chunk from the stream's internal queue, waiting at most `duration`.
+    ///
+    /// This behaves like [`read`](Self::read), except that it returns
+    /// [`Err(ReadError::Timeout)`](ReadError::Timeout) if no chunk becomes available in time.
+    /// A timeout does not affect the reader: the read stays pending, and its chunk is returned
+    /// by the next call to [`read`](Self::read) or `read_timeout`, so no chunks are lost.
+    /// This is useful for protocols which interleave reads with other periodic work.
+    pub async fn read_timeout(&mut self, duration: Duration) -> Result<Option<T>, ReadError> {
+        let timer = sleep(duration.as_secs_f64() * 1000.0);
+        match self.read_or(timer).await {
+            Ok(result) => result,
+            Err(Ok(())) => Err(ReadError::Timeout),
+            Err(Err(error)) => Err(ReadError::Js(error)),
+        }
+    }
+
+    /// Reads the next chunk from the stream's internal queue, unless the given `signal`
+    /// is aborted first.
+    ///
+    /// This behaves like [`read`](Self::read), except that it returns
+    /// [`Err(ReadError::Aborted(reason))`](ReadError::Aborted) with the signal's abort reason
+    /// as soon as the signal is aborted. Like with [`read_timeout`](Self::read_timeout), the read stays
+    /// pending, and its chunk is returned by the next read on this reader.
+    pub async fn read_with_signal(
+        &mut self,
+        signal: &impl AsRef<AbortSignal>,
+    ) -> Result<Option<T>, ReadError> {
+        match self.read_or(aborted(signal.as_ref())).await {
+            Ok(result) => result,
+            Err(reason) => Err(ReadError::Aborted(reason)),
+        }
+    }
+
+    /// Reads the next chunk, unless `interrupt` completes first. In that case, the read
+    /// is kept for the next call, and this returns the output of `interrupt`.
+    async fn read_or<F>(&mut self, interrupt: F) -> Result<Result<Option<T>, ReadError>, F::Output>
+    where
+        F: Future + Unpin,
+    {
+        if let Some(remainder) = self.remainder.take() {
+            return Ok(ReadError::cast_chunk(remainder.into()).map(Some));
+        }
+        let mut fut = match self.pending_read.take() {
+            Some(fut) => fut,
+            None => JsFuture::from(self.as_raw().read()),
+        };
+        let result = match in_span!("read", select(&mut fut, interrupt)).await {
+            Either::Left((result, _)) => Ok(result),
+            Either::Right((output, _)) => Err(output),
+        };
+        let result = match result {
+            Ok(result) => result,
+            Err(output) => {
+                self.pending_read = Some(fut);
+                return Err(output);
+            }
+        };
+        Ok(match result.map(Self::read_result) {
+            Ok(Some(chunk)) => ReadError::cast_chunk(chunk).map(Some),
+            Ok(None) => Ok(None),
+            Err(error) => Err(ReadError::Js(error)),
+        })
+    }
+
+    async fn read_raw(&mut self) -> Result<Option<JsValue>, ReadError> {
+        let fut = match self.pending_read.take() {
+            Some(fut) => fut,
+            None => JsFuture::from(self.as_raw().read()),
+        };
+        let js_value = in_span!("read", fut).await.map_err(ReadError::Js)?;
+        Ok(Self::read_result(js_value))
+    }
+
+    /// Returns the chunk of a read result, or `None` if the stream is done.
+    fn read_result(js_value: JsValue) -> Option<JsValue> {
         let result = sys::ReadableStreamReadResult::from(js_value);
         if result.is_done() {
-            Ok(None)
+            None
         } else {
-            Ok(Some(result.value()))
+            Some(result.value())
         }
     }
 
+    /// Reads bytes from the stream into the given buffer.
+    ///
+    /// Each chunk must be a [`Uint8Array`](js_sys::Uint8Array), which is copied directly into
+    /// `dst`. If the chunk is larger than `dst`, the remaining bytes are kept by this reader
+    /// and returned by the next read.
+    ///
+    /// * If some bytes were read, this returns `Ok(n)` with the number of bytes read.
+    /// * If the stream closes and no more chunks are available, or if `dst` is empty,
+    ///   this returns `Ok(0)`.
+    /// * If the stream encounters an `error`, this returns `Err(ReadError::Js(error))`.
+    /// * If the next chunk is not a `Uint8Array`, this returns
+    ///   [`Err(ReadError::InvalidChunk { .. })`](ReadError::InvalidChunk).
+    pub async fn read_into(&mut self, dst: &mut [u8]) -> Result<usize, ReadError> {
+        if dst.is_empty() {
+            return Ok(0);
+        }
+        let chunk = match self.remainder.take() {
+            Some(chunk) => chunk,
+            None => loop {
+                match self.read_raw().await? {
+                    Some(chunk) => {
+                        let chunk = ReadError::cast_chunk::<js_sys::Uint8Array>(chunk)?;
+                        // Skip empty chunks, since returning 0 would signal the end of the stream.
+                        if chunk.length() > 0 {
+                            break chunk;
+                        }
+                    }
+                    None => return Ok(0),
+                }
+            },
+        };
+        let len = chunk.length() as usize;
+        let n = len.min(dst.len());
+        chunk.subarray(0, n as u32).copy_to(&mut dst[..n]);
+        if n < len {
+            self.remainder = Some(chunk.subarray(n as u32, len as u32));
+        }
+        Ok(n)
+    }
+
+    /// Reads all remaining bytes from the stream into a `Vec<u8>`.
+    ///
+    /// Each chunk must be a [`Uint8Array`](js_sys::Uint8Array). This returns once the stream
+    /// closes, or returns an error as soon as the stream errors or a chunk of another type
+    /// is read. The bytes read so far are lost in case of an error.
+    pub async fn read_to_end(&mut self) -> Result<Vec<u8>, ReadError> {
+        let mut data = Vec::new();
+        if let Some(remainder) = self.remainder.take() {
+            append_chunk(&mut data, &remainder);
+        }
+        while let Some(chunk) = self.read_raw().await? {
+            let chunk = ReadError::cast_chunk::<js_sys::Uint8Array>(chunk)?;
+            append_chunk(&mut data, &chunk);
+        }
+        Ok(data)
+    }
+
+    /// Reads exactly `n` bytes from the stream.
+    ///
+    /// Each chunk must be a [`Uint8Array`](js_sys::Uint8Array). Bytes are buffered across chunk
+    /// boundaries, and any bytes of the last chunk that were not needed are kept by this reader
+    /// for the next read.
+    ///
+    /// If the stream closes before `n` bytes could be read, this returns
+    /// [`Err(ReadError::UnexpectedEof)`](ReadError::UnexpectedEof). In that case, the bytes
+    /// that were read are kept by this reader, and are returned by the next read.
+    pub async fn read_exact(&mut self, n: usize) -> Result<Vec<u8>, ReadError> {
+        let mut data = vec![0; n];
+        let mut filled = 0;
+        while filled < n {
+            let bytes_read = self.read_into(&mut data[filled..]).await?;
+            if bytes_read == 0 {
+                if filled > 0 {
+                    self.remainder = Some(js_sys::Uint8Array::from(&data[..filled]));
+                }
+                return Err(ReadError::UnexpectedEof);
+            }
+            filled += bytes_read;
+        }
+        Ok(data)
+    }
+
+    /// Reads the next chunk from the stream's internal queue as [`Bytes`](bytes::Bytes).
+    ///
+    /// The chunk must be a [`Uint8Array`](js_sys::Uint8Array), which is copied out of
+    /// JavaScript memory exactly once. Any other chunk results in a
+    /// [`ReadError::InvalidChunk`] error.
+    /// See [`read`](Self::read) for more details.
+    #[cfg(feature = "bytes")]
+    pub async fn read_bytes(&mut self) -> Result<Option<bytes::Bytes>, ReadError> {
+        let chunk = match self.remainder.take() {
+            Some(chunk) => chunk,
+            None => match self.read_raw().await? {
+                Some(chunk) => ReadError::cast_chunk::<js_sys::Uint8Array>(chunk)?,
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(chunk.to_vec().into()))
+    }
+
     /// [Releases](https://streams.spec.whatwg.org/#release-a-lock) this reader's lock on the
     /// corresponding stream.
     ///
@@ -330,12 +1822,6 @@ impl<'stream> ReadableStreamDefaultReader<'stream> {
         self.release_lock_mut()
     }
 
-    fn release_lock_mut(&mut self) {
-        self.as_raw()
-            .release_lock()
-            .unwrap_or_else(|error| throw_val(error.into()))
-    }
-
     /// Try to [release](https://streams.spec.whatwg.org/#release-a-lock) this reader's lock on the
     /// corresponding stream.
     ///
@@ -354,13 +1840,68 @@ impl<'stream> ReadableStreamDefaultReader<'stream> {
     /// usable. This allows reading only a few chunks from the `Stream`, while still allowing
     /// another reader to read the remaining chunks later on.
     #[inline]
-    pub fn into_stream(self) -> IntoStream<'stream> {
+    pub fn into_stream(self) -> IntoStream<'stream, T> {
         IntoStream::new(self)
     }
+
+    /// Converts this `ReadableStreamDefaultReader` into a [`Stream`](Stream) which keeps up to
+    /// `n` reads in flight.
+    ///
+    /// This is similar to [`ReadableStream.into_stream_buffered`](ReadableStream::into_stream_buffered),
+    /// except that after the returned `Stream` is dropped, the original `ReadableStream` is still
+    /// usable. Note that any chunks that were requested but not yet yielded from the `Stream`
+    /// are lost when it is dropped.
+    #[inline]
+    pub fn into_stream_buffered(self, n: usize) -> IntoBufferedStream<'stream, T> {
+        IntoBufferedStream::new(self, n)
+    }
+
+    /// Converts this `ReadableStreamDefaultReader` into a [`Stream`](Stream) of bytes.
+    ///
+    /// This is similar to [`ReadableStream.into_bytes_stream`](ReadableStream::into_bytes_stream),
+    /// except that after the returned `Stream` is dropped, the original `ReadableStream` is still
+    /// usable.
+    #[inline]
+    pub fn into_bytes_stream(self) -> IntoBytesStream<'stream> {
+        IntoBytesStream::new(self.cast().into_stream())
+    }
+
+    /// Converts this `ReadableStreamDefaultReader` into an [`AsyncRead`](AsyncRead).
+    ///
+    /// This is similar to [`ReadableStream.into_async_read`](ReadableStream::into_async_read),
+    /// except that after the returned `AsyncRead` is dropped, the original `ReadableStream` is
+    /// still usable. Note that any bytes that were read from the stream but not yet consumed
+    /// from the `AsyncRead` are lost when it is dropped.
+    #[inline]
+    pub fn into_async_read(self) -> IntoAsyncRead<'stream> {
+        IntoAsyncRead::new(self.cast().into_stream())
+    }
+}
+
+fn append_chunk(data: &mut Vec<u8>, chunk: &js_sys::Uint8Array) {
+    let start = data.len();
+    data.resize(start + chunk.length() as usize, 0);
+    chunk.copy_to(&mut data[start..]);
 }
 
-impl Drop for ReadableStreamDefaultReader<'_> {
+impl<T> ReadableStreamDefaultReader<'_, T> {
+    fn release_lock_mut(&mut self) {
+        self.raw
+            .release_lock()
+            .unwrap_or_else(|error| throw_val(error.into()))
+    }
+}
+
+impl<T> Drop for ReadableStreamDefaultReader<'_, T> {
     fn drop(&mut self) {
-        self.release_lock_mut();
+        match self.drop_policy {
+            ReaderDropPolicy::Release => self.release_lock_mut(),
+            ReaderDropPolicy::CancelThenRelease => {
+                // Cancelling completes any pending read request, so we can release the lock.
+                detach_promise(self.raw.cancel());
+                let _ = self.raw.release_lock();
+            }
+            ReaderDropPolicy::Leak => {}
+        }
     }
 }
@@ -0,0 +1,87 @@
+use core::pin::Pin;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll, Waker};
+
+/// A handle to pause and resume a [`ReadableStream`](super::ReadableStream) returned by
+/// [`pausable`](super::ReadableStream::pausable).
+///
+/// While paused, no more chunks are read from the original stream, so any backpressure
+/// propagates back to its underlying source. Chunks that were already read before pausing
+/// are still delivered.
+///
+/// Handles can be cloned freely, all clones control the same stream.
+#[derive(Clone, Debug, Default)]
+pub struct PauseHandle {
+    state: Rc<RefCell<PauseState>>,
+}
+
+#[derive(Debug, Default)]
+struct PauseState {
+    paused: bool,
+    waker: Option<Waker>,
+}
+
+impl PauseHandle {
+    /// Pauses the stream.
+    ///
+    /// Reads from the paused stream will remain pending until the stream is
+    /// [resumed](Self::resume).
+    pub fn pause(&self) {
+        self.state.borrow_mut().paused = true;
+    }
+
+    /// Resumes the stream, waking up any pending read.
+    pub fn resume(&self) {
+        let waker = {
+            let mut state = self.state.borrow_mut();
+            state.paused = false;
+            state.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Returns `true` if the stream is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().paused
+    }
+}
+
+/// A [`Stream`](Stream) that stops polling its inner stream while paused.
+#[derive(Debug)]
+pub(super) struct Pausable<St> {
+    stream: St,
+    handle: PauseHandle,
+}
+
+impl<St> Pausable<St> {
+    pub fn new(stream: St, handle: PauseHandle) -> Self {
+        Self { stream, handle }
+    }
+}
+
+impl<St: Stream + Unpin> Stream for Pausable<St> {
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        {
+            let mut state = self.handle.state.borrow_mut();
+            if state.paused {
+                // Wait until resumed
+                state.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+impl<St: FusedStream + Unpin> FusedStream for Pausable<St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
@@ -0,0 +1,121 @@
+use core::pin::Pin;
+
+use futures::channel::oneshot;
+use futures::future::{Future, FutureExt};
+use futures::task::{Context, Poll};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::AbortController;
+
+use crate::metered::MeterHandle;
+use crate::util::{detach_promise, promise_to_void_future};
+use crate::writable::WritableStream;
+use crate::StreamError;
+
+use super::{PipeOptions, ReadableStream};
+
+/// A handle to a pipe started by [`pipe_to_spawned`](ReadableStream::pipe_to_spawned).
+///
+/// The handle reports how many chunks and bytes have been piped so far,
+/// and can be used to [abort](Self::abort) the pipe.
+/// It is also a [`Future`](Future) which resolves once the pipe completes,
+/// with the same result as [`pipe_to_with_options`](ReadableStream::pipe_to_with_options).
+///
+/// Dropping the handle does not abort the pipe.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct PipeHandle {
+    controller: AbortController,
+    meter: MeterHandle,
+    result: oneshot::Receiver<Result<(), StreamError>>,
+}
+
+impl PipeHandle {
+    pub(super) fn spawn(
+        mut readable: ReadableStream,
+        dest: WritableStream,
+        options: &PipeOptions,
+    ) -> Self {
+        let controller = AbortController::new().unwrap_throw();
+        let meter = MeterHandle::default();
+        let (sender, result) = oneshot::channel();
+
+        let mut options = options.clone();
+        options.signal(controller.signal());
+        if dest.is_locked() {
+            let error = js_sys::TypeError::new("already locked to a writer");
+            let _ = sender.send(Err(StreamError::from(JsValue::from(error))));
+        } else {
+            // Meter the chunks inside the pipe: the caller's options apply to the pipe into
+            // the meter, which then forwards everything to `dest`.
+            let (metered, mut meter_writable) = meter.transform().into_pair().into_parts();
+            let forward = metered
+                .as_raw()
+                .pipe_to(dest.as_raw(), PipeOptions::default().into_raw());
+            spawn_local(async move {
+                let mut result = readable
+                    .pipe_to_with_options(&mut meter_writable, &options)
+                    .await;
+                if result.is_ok() && !options.prevents_close() {
+                    // Wait until `dest` is closed as well.
+                    result = promise_to_void_future(forward)
+                        .await
+                        .map_err(StreamError::from);
+                } else {
+                    detach_promise(forward);
+                }
+                let _ = sender.send(result);
+            });
+        }
+
+        Self {
+            controller,
+            meter,
+            result,
+        }
+    }
+
+    /// Returns the number of chunks that have been piped so far.
+    #[inline]
+    pub fn chunks_piped(&self) -> u64 {
+        self.meter.chunks()
+    }
+
+    /// Returns the number of bytes that have been piped so far.
+    ///
+    /// Only chunks that are an [`ArrayBuffer`](js_sys::ArrayBuffer) or an
+    /// [`ArrayBufferView`](https://developer.mozilla.org/en-US/docs/Web/API/ArrayBufferView)
+    /// (such as a [`Uint8Array`](js_sys::Uint8Array)) contribute to this count.
+    #[inline]
+    pub fn bytes_piped(&self) -> u64 {
+        self.meter.bytes()
+    }
+
+    /// Returns a handle to the counters of this pipe, which also measures its throughput.
+    #[inline]
+    pub fn meter(&self) -> MeterHandle {
+        self.meter.clone()
+    }
+
+    /// Aborts the pipe.
+    ///
+    /// The source readable stream is [canceled](https://streams.spec.whatwg.org/#cancel-a-readable-stream)
+    /// and the destination writable stream is [aborted](https://streams.spec.whatwg.org/#abort-a-writable-stream),
+    /// unless the respective options [`prevent_cancel`](PipeOptions::prevent_cancel)
+    /// or [`prevent_abort`](PipeOptions::prevent_abort) were set.
+    /// The pipe then completes with an `AbortError`.
+    pub fn abort(&self) {
+        self.controller.abort();
+    }
+}
+
+impl Future for PipeHandle {
+    type Output = Result<(), StreamError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.result.poll_unpin(cx).map(|result| {
+            // The spawned task always sends a result before finishing.
+            result.unwrap_throw()
+        })
+    }
+}
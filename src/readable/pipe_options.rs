@@ -1,7 +1,21 @@
 use super::sys;
 use web_sys::AbortSignal;
 
-/// Options for [`pipe_to_with_options`](super::ReadableStream::pipe_to_with_options).
+/// Options for [`pipe_to_with_options`](super::ReadableStream::pipe_to_with_options)
+/// and [`pipe_through_with_options`](super::ReadableStream::pipe_through_with_options).
+///
+/// All options can be combined:
+/// ```no_run
+/// # use wasm_streams::readable::PipeOptions;
+/// # fn example(signal: web_sys::AbortSignal) {
+/// let mut options = PipeOptions::new();
+/// options
+///     .prevent_close(true)
+///     .prevent_cancel(true)
+///     .prevent_abort(false)
+///     .signal(signal);
+/// # }
+/// ```
 #[derive(Clone, Debug, Default)]
 pub struct PipeOptions {
     prevent_close: bool,
@@ -64,8 +78,35 @@ impl PipeOptions {
     /// and the destination writable stream will be aborted
     /// unless the respective options [`prevent_cancel`](Self::prevent_cancel)
     /// or [`prevent_abort`](Self::prevent_abort) are set.
-    pub fn signal(&mut self, signal: AbortSignal) -> &mut Self {
-        self.signal = Some(signal);
+    ///
+    /// This accepts both a raw [`web_sys::AbortSignal`](AbortSignal) and an
+    /// [`abort::AbortSignal`](crate::abort::AbortSignal).
+    pub fn signal(&mut self, signal: impl Into<AbortSignal>) -> &mut Self {
+        self.signal = Some(signal.into());
         self
     }
+
+    #[inline]
+    pub(super) fn prevents_close(&self) -> bool {
+        self.prevent_close
+    }
+
+    #[inline]
+    pub(super) fn abort_signal(&self) -> Option<&AbortSignal> {
+        self.signal.as_ref()
+    }
+}
+
+impl From<sys::PipeOptions> for PipeOptions {
+    #[inline]
+    fn from(raw: sys::PipeOptions) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<PipeOptions> for sys::PipeOptions {
+    #[inline]
+    fn from(options: PipeOptions) -> Self {
+        options.into_raw()
+    }
 }
@@ -0,0 +1,89 @@
+use std::any::type_name;
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::util::timeout_error;
+
+/// An error returned when reading chunks from a [`ReadableStream`](super::ReadableStream).
+///
+/// Reading chunks can fail for reasons that are not JavaScript errors, such as a chunk
+/// of the wrong type or a timeout, so reads return this error instead of a
+/// [`StreamError`](crate::StreamError). A JavaScript error is kept as the raw
+/// [`Js`](Self::Js) value. Convert a `ReadError` into a `StreamError` to classify it
+/// like the errors of all other stream operations.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReadError {
+    /// The read failed with the given JavaScript error,
+    /// e.g. because the stream errored or the reader's lock was released.
+    Js(JsValue),
+    /// A chunk was read from the stream, but it is not of the stream's chunk type.
+    InvalidChunk {
+        /// The chunk that was read.
+        chunk: JsValue,
+        /// The name of the expected chunk type.
+        expected: &'static str,
+    },
+    /// The stream closed before the requested number of bytes could be read.
+    UnexpectedEof,
+    /// No chunk became available within the timeout of a
+    /// [`read_timeout`](super::ReadableStreamDefaultReader::read_timeout).
+    ///
+    /// The read is still pending, and its result is returned by the next read on the same reader.
+    Timeout,
+    /// The [`AbortSignal`](web_sys::AbortSignal) of a
+    /// [`read_with_signal`](super::ReadableStreamDefaultReader::read_with_signal) was aborted,
+    /// with the given abort reason.
+    ///
+    /// The read is still pending, and its result is returned by the next read on the same reader.
+    Aborted(JsValue),
+}
+
+impl ReadError {
+    pub(crate) fn cast_chunk<T: JsCast>(chunk: JsValue) -> Result<T, Self> {
+        chunk
+            .dyn_into::<T>()
+            .map_err(|chunk| ReadError::InvalidChunk {
+                chunk,
+                expected: type_name::<T>(),
+            })
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Js(error) => write!(f, "read failed: {:?}", error),
+            ReadError::InvalidChunk { chunk, expected } => {
+                write!(f, "expected chunk of type {}, got {:?}", expected, chunk)
+            }
+            ReadError::UnexpectedEof => write!(f, "stream closed before enough bytes were read"),
+            ReadError::Timeout => write!(f, "read timed out"),
+            ReadError::Aborted(reason) => write!(f, "read aborted: {:?}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+impl From<ReadError> for JsValue {
+    /// Converts the error back into a JavaScript value.
+    ///
+    /// An [`InvalidChunk`](ReadError::InvalidChunk) error is converted to a
+    /// [`TypeError`](js_sys::TypeError) describing the expected chunk type.
+    /// An [`UnexpectedEof`](ReadError::UnexpectedEof) error is converted to an
+    /// [`Error`](js_sys::Error), and a [`Timeout`](ReadError::Timeout) error is converted to
+    /// a `DOMException` named `TimeoutError`. An [`Aborted`](ReadError::Aborted) error is
+    /// converted to its abort reason.
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Js(error) | ReadError::Aborted(error) => error,
+            error @ ReadError::InvalidChunk { .. } => {
+                js_sys::TypeError::new(&error.to_string()).into()
+            }
+            error @ ReadError::UnexpectedEof => js_sys::Error::new(&error.to_string()).into(),
+            error @ ReadError::Timeout => timeout_error(&error.to_string()).into(),
+        }
+    }
+}
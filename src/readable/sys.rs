@@ -1,12 +1,16 @@
 //! Raw bindings to JavaScript objects used
 //! by a [`ReadableStream`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream).
-use js_sys::{Array, Error, Promise};
+use js_sys::{Array, AsyncIterator, Error, Function, Object, Promise, Reflect};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use web_sys::AbortSignal;
 
-use crate::queuing_strategy::QueuingStrategy;
+use crate::queuing_strategy::sys::QueuingStrategy;
+use crate::transform::sys::{ReadableWritablePair, TransformStream};
+use crate::util::has_methods;
 use crate::writable::sys::WritableStream;
 
+use super::into_underlying_byte_source::IntoUnderlyingByteSource;
 use super::into_underlying_source::IntoUnderlyingSource;
 
 #[wasm_bindgen]
@@ -17,7 +21,12 @@ extern "C" {
     /// If you're using an API that returns such an object, you can cast it to this type using
     /// [`unchecked_into`][wasm_bindgen::JsCast::unchecked_into].
     ///
+    /// Type checks such as [`dyn_into`][wasm_bindgen::JsCast::dyn_into] only check that
+    /// the object has a `getReader()` and a `cancel()` method, so they also accept streams
+    /// from other realms and from polyfills.
+    ///
     /// [web-sys]: https://docs.rs/web-sys/latest/web_sys/struct.ReadableStream.html
+    #[wasm_bindgen(is_type_of = is_readable_stream)]
     #[derive(Clone, Debug)]
     pub type ReadableStream;
 
@@ -27,9 +36,30 @@ extern "C" {
     #[wasm_bindgen(constructor)]
     pub(crate) fn new_with_source(
         source: IntoUnderlyingSource,
-        strategy: QueuingStrategy,
+        strategy: &QueuingStrategy,
     ) -> ReadableStream;
 
+    #[wasm_bindgen(constructor)]
+    pub(crate) fn new_with_byte_source(source: IntoUnderlyingByteSource) -> ReadableStream;
+
+    /// Creates a new stream from a JavaScript [underlying source](UnderlyingSource)
+    /// and [queuing strategy](QueuingStrategy).
+    ///
+    /// If the underlying source is invalid, e.g. because its `start()` method throws,
+    /// then this returns the error.
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new_with_underlying_source(
+        source: &UnderlyingSource,
+        strategy: &QueuingStrategy,
+    ) -> Result<ReadableStream, Error>;
+
+    /// Creates a new stream from an [iterable or async iterable](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/from_static)
+    /// object, using the static `ReadableStream.from()` method.
+    ///
+    /// Not all browsers support this method yet, see [`has_from`](Self::has_from).
+    #[wasm_bindgen(static_method_of = ReadableStream, catch, js_name = from)]
+    pub fn from_iterable(iterable: &JsValue) -> Result<ReadableStream, Error>;
+
     #[wasm_bindgen(method, getter, js_name = locked)]
     pub fn is_locked(this: &ReadableStream) -> bool;
 
@@ -42,11 +72,56 @@ extern "C" {
     #[wasm_bindgen(method, catch, js_name = getReader)]
     pub fn get_reader(this: &ReadableStream) -> Result<ReadableStreamDefaultReader, Error>;
 
+    #[wasm_bindgen(method, catch, js_name = getReader)]
+    pub fn get_reader_with_options(
+        this: &ReadableStream,
+        opts: GetReaderOptions,
+    ) -> Result<ReadableStreamBYOBReader, Error>;
+
+    /// Pipes this stream to `dest`, using [`pipeTo()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/pipeTo).
+    ///
+    /// All [options](PipeOptions) are passed on, including the abort signal if it is set.
     #[wasm_bindgen(method, js_name = pipeTo)]
     pub fn pipe_to(this: &ReadableStream, dest: &WritableStream, opts: PipeOptions) -> Promise;
 
+    /// Pipes this stream through `transform`, using
+    /// [`pipeThrough()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/pipeThrough).
+    ///
+    /// All [options](PipeOptions) are passed on, including the abort signal if it is set.
+    #[wasm_bindgen(method, catch, js_name = pipeThrough)]
+    pub fn pipe_through(
+        this: &ReadableStream,
+        transform: &TransformStream,
+        opts: PipeOptions,
+    ) -> Result<ReadableStream, Error>;
+
+    /// Pipes this stream through the writable side of `transform`, returning its readable side.
+    ///
+    /// This is the same as [`pipe_through`](Self::pipe_through), but accepts any
+    /// [pair](ReadableWritablePair) instead of a transform stream.
+    #[wasm_bindgen(method, catch, js_name = pipeThrough)]
+    pub fn pipe_through_pair(
+        this: &ReadableStream,
+        transform: &ReadableWritablePair,
+        opts: PipeOptions,
+    ) -> Result<ReadableStream, Error>;
+
     #[wasm_bindgen(method, catch, js_name = tee)]
     pub fn tee(this: &ReadableStream) -> Result<Array, Error>;
+
+    /// Returns an [async iterator](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream#async_iteration)
+    /// over the chunks of the stream, which locks the stream.
+    ///
+    /// This is the same iterator as returned by `stream[Symbol.asyncIterator]()`.
+    /// Not all browsers support async iteration of readable streams yet.
+    #[wasm_bindgen(method, catch, js_name = values)]
+    pub fn values(this: &ReadableStream) -> Result<AsyncIterator, Error>;
+
+    #[wasm_bindgen(method, catch, js_name = values)]
+    pub fn values_with_options(
+        this: &ReadableStream,
+        opts: ValuesOptions,
+    ) -> Result<AsyncIterator, Error>;
 }
 
 #[wasm_bindgen]
@@ -61,16 +136,131 @@ extern "C" {
     #[wasm_bindgen(method, js_name = close)]
     pub fn close(this: &ReadableStreamDefaultController);
 
+    #[wasm_bindgen(method, catch, js_name = close)]
+    pub fn try_close(this: &ReadableStreamDefaultController) -> Result<(), JsValue>;
+
     #[wasm_bindgen(method, js_name = enqueue)]
     pub fn enqueue(this: &ReadableStreamDefaultController, chunk: &JsValue);
 
+    #[wasm_bindgen(method, catch, js_name = enqueue)]
+    pub fn try_enqueue(
+        this: &ReadableStreamDefaultController,
+        chunk: &JsValue,
+    ) -> Result<(), JsValue>;
+
     #[wasm_bindgen(method, js_name = error)]
     pub fn error(this: &ReadableStreamDefaultController, error: &JsValue);
 }
 
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`ReadableByteStreamController`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableByteStreamController).
+    #[derive(Clone, Debug)]
+    pub type ReadableByteStreamController;
+
+    #[wasm_bindgen(method, getter, js_name = byobRequest)]
+    pub fn byob_request(this: &ReadableByteStreamController) -> Option<ReadableStreamBYOBRequest>;
+
+    #[wasm_bindgen(method, getter, js_name = desiredSize)]
+    pub fn desired_size(this: &ReadableByteStreamController) -> Option<f64>;
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close(this: &ReadableByteStreamController);
+
+    #[wasm_bindgen(method, catch, js_name = close)]
+    pub fn try_close(this: &ReadableByteStreamController) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = enqueue)]
+    pub fn enqueue(this: &ReadableByteStreamController, chunk: &Object);
+
+    #[wasm_bindgen(method, catch, js_name = enqueue)]
+    pub fn try_enqueue(this: &ReadableByteStreamController, chunk: &Object) -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = error)]
+    pub fn error(this: &ReadableByteStreamController, error: &JsValue);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`ReadableStreamBYOBRequest`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamBYOBRequest).
+    #[derive(Clone, Debug)]
+    pub type ReadableStreamBYOBRequest;
+
+    #[wasm_bindgen(method, getter, js_name = view)]
+    pub fn view(this: &ReadableStreamBYOBRequest) -> Option<Object>;
+
+    #[wasm_bindgen(method, js_name = respond)]
+    pub fn respond(this: &ReadableStreamBYOBRequest, bytes_written: u32);
+
+    #[wasm_bindgen(method, catch, js_name = respond)]
+    pub fn try_respond(this: &ReadableStreamBYOBRequest, bytes_written: u32)
+        -> Result<(), JsValue>;
+
+    /// Responds with a new view onto the same buffer as [`view`](Self::view),
+    /// e.g. after its buffer was transferred.
+    #[wasm_bindgen(method, js_name = respondWithNewView)]
+    pub fn respond_with_new_view(this: &ReadableStreamBYOBRequest, view: &Object);
+
+    #[wasm_bindgen(method, catch, js_name = respondWithNewView)]
+    pub fn try_respond_with_new_view(
+        this: &ReadableStreamBYOBRequest,
+        view: &Object,
+    ) -> Result<(), JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [underlying source](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/ReadableStream#underlyingsource)
+    /// object, passed to [`ReadableStream::new_with_underlying_source`].
+    ///
+    /// Create an empty one with [`UnderlyingSource::new`](UnderlyingSource::new), then set its
+    /// `start`, `pull` and `cancel` methods with [`Reflect::set`](js_sys::Reflect::set).
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type UnderlyingSource;
+
+    #[wasm_bindgen(method, getter, js_name = type)]
+    pub fn type_(this: &UnderlyingSource) -> Option<ReadableStreamType>;
+
+    /// Sets the type of the stream. Use [`ReadableStreamType::Bytes`] for a readable byte stream,
+    /// whose controller is a [`ReadableByteStreamController`].
+    #[wasm_bindgen(method, setter, js_name = type)]
+    pub fn set_type(this: &UnderlyingSource, type_: ReadableStreamType);
+
+    #[wasm_bindgen(method, getter, js_name = autoAllocateChunkSize)]
+    pub fn auto_allocate_chunk_size(this: &UnderlyingSource) -> Option<u32>;
+
+    /// Sets the size of the buffer which is allocated for a BYOB request when the stream
+    /// is read by a default reader. Only applies to readable byte streams.
+    #[wasm_bindgen(method, setter, js_name = autoAllocateChunkSize)]
+    pub fn set_auto_allocate_chunk_size(this: &UnderlyingSource, size: u32);
+}
+
+impl UnderlyingSource {
+    /// Creates a new, empty underlying source object.
+    pub fn new() -> Self {
+        wasm_bindgen::JsCast::unchecked_into(Object::new())
+    }
+}
+
+impl Default for UnderlyingSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The [`type`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/ReadableStream#type)
+/// of an [underlying source](UnderlyingSource).
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadableStreamType {
+    Bytes = "bytes",
+}
+
 #[wasm_bindgen]
 extern "C" {
     /// A raw [`ReadableStreamDefaultReader`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamDefaultReader).
+    #[wasm_bindgen(is_type_of = is_default_reader)]
     #[derive(Clone, Debug)]
     pub type ReadableStreamDefaultReader;
 
@@ -90,6 +280,32 @@ extern "C" {
     pub fn release_lock(this: &ReadableStreamDefaultReader) -> Result<(), Error>;
 }
 
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`ReadableStreamBYOBReader`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamBYOBReader).
+    #[wasm_bindgen(is_type_of = is_byob_reader)]
+    #[derive(Clone, Debug)]
+    pub type ReadableStreamBYOBReader;
+
+    #[wasm_bindgen(method, getter, js_name = closed)]
+    pub fn closed(this: &ReadableStreamBYOBReader) -> Promise;
+
+    #[wasm_bindgen(method, js_name = cancel)]
+    pub fn cancel(this: &ReadableStreamBYOBReader) -> Promise;
+
+    #[wasm_bindgen(method, js_name = cancel)]
+    pub fn cancel_with_reason(this: &ReadableStreamBYOBReader, reason: &JsValue) -> Promise;
+
+    /// Reads into the given `view`, which must be an
+    /// [`ArrayBufferView`](https://developer.mozilla.org/en-US/docs/Web/API/ArrayBufferView)
+    /// such as a [`Uint8Array`](js_sys::Uint8Array).
+    #[wasm_bindgen(method, js_name = read)]
+    pub fn read(this: &ReadableStreamBYOBReader, view: &Object) -> Promise;
+
+    #[wasm_bindgen(method, catch, js_name = releaseLock)]
+    pub fn release_lock(this: &ReadableStreamBYOBReader) -> Result<(), Error>;
+}
+
 #[wasm_bindgen]
 extern "C" {
     /// A result returned by [`ReadableStreamDefaultReader.read`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamDefaultReader/read).
@@ -103,7 +319,85 @@ extern "C" {
     pub fn value(this: &ReadableStreamReadResult) -> JsValue;
 }
 
-/// Raw options for [`pipeTo()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/pipeTo).
+fn is_readable_stream(value: &JsValue) -> bool {
+    has_methods(value, &["getReader", "cancel"])
+}
+
+fn is_reader(value: &JsValue) -> bool {
+    has_methods(value, &["read", "cancel", "releaseLock"])
+}
+
+/// Returns the number of declared parameters of the reader's `read()` method.
+///
+/// Default and BYOB readers have the same methods, but a default reader's `read()` takes
+/// no arguments, while a BYOB reader's `read(view)` requires a view.
+fn read_arity(value: &JsValue) -> u32 {
+    Reflect::get(value, &JsValue::from_str("read"))
+        .ok()
+        .and_then(|read| read.dyn_into::<Function>().ok())
+        .map_or(0, |read| read.length())
+}
+
+fn is_default_reader(value: &JsValue) -> bool {
+    is_reader(value) && read_arity(value) == 0
+}
+
+fn is_byob_reader(value: &JsValue) -> bool {
+    is_reader(value) && read_arity(value) > 0
+}
+
+/// Raw options for [`getReader()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/getReader).
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct GetReaderOptions {
+    mode: ReaderMode,
+}
+
+/// The [`mode`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/getReader#mode)
+/// of a reader requested by [`GetReaderOptions`](GetReaderOptions).
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReaderMode {
+    Byob = "byob",
+}
+
+impl GetReaderOptions {
+    pub fn new(mode: ReaderMode) -> Self {
+        Self { mode }
+    }
+}
+
+#[wasm_bindgen]
+impl GetReaderOptions {
+    #[wasm_bindgen(getter, js_name = mode)]
+    pub fn mode(&self) -> ReaderMode {
+        self.mode
+    }
+}
+
+/// Raw options for [`values()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream#async_iteration).
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct ValuesOptions {
+    prevent_cancel: bool,
+}
+
+impl ValuesOptions {
+    pub fn new(prevent_cancel: bool) -> Self {
+        Self { prevent_cancel }
+    }
+}
+
+#[wasm_bindgen]
+impl ValuesOptions {
+    #[wasm_bindgen(getter, js_name = preventCancel)]
+    pub fn prevent_cancel(&self) -> bool {
+        self.prevent_cancel
+    }
+}
+
+/// Raw options for [`pipeTo()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/pipeTo)
+/// and [`pipeThrough()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/pipeThrough).
 #[wasm_bindgen]
 #[derive(Clone, Debug, Default)]
 pub struct PipeOptions {
@@ -151,3 +445,11 @@ impl PipeOptions {
         self.signal.clone()
     }
 }
+
+impl ReadableStream {
+    /// Returns `true` if the browser supports the static
+    /// [`ReadableStream.from()`](Self::from_iterable) method.
+    pub fn has_from() -> bool {
+        crate::support::supports_readable_stream_from()
+    }
+}
@@ -0,0 +1,101 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+
+use super::sys;
+
+/// A Rust implementation of a JavaScript [underlying source](https://streams.spec.whatwg.org/#underlying-source-api),
+/// which defines the behavior of a [`ReadableStream`](super::ReadableStream).
+///
+/// Use [`ReadableStream::from_underlying_source`](super::ReadableStream::from_underlying_source)
+/// to create a readable stream from an `UnderlyingSource`.
+///
+/// Unlike a [`Stream`](futures::Stream), an `UnderlyingSource` has access to the stream's
+/// [controller](ReadableStreamDefaultController). This allows it to enqueue multiple chunks
+/// in a single [`pull`](Self::pull), or to react to the [desired size](ReadableStreamDefaultController::desired_size)
+/// of the stream's internal queue.
+///
+/// All methods return a boxed future, which allows them to perform asynchronous work.
+/// If any of these futures returns an error, the readable stream becomes errored.
+pub trait UnderlyingSource {
+    /// Called once when the readable stream is created, before the first [`pull`](Self::pull).
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+
+    /// Called whenever the stream's internal queue is not full, to pull more chunks
+    /// from the source.
+    ///
+    /// The source should [enqueue](ReadableStreamDefaultController::enqueue) one or more chunks,
+    /// or [close](ReadableStreamDefaultController::close) the stream when it has no more chunks.
+    /// While the returned future is pending, `pull` will not be called again.
+    /// If the stream requested more chunks in the meantime, `pull` is called again
+    /// as soon as the returned future completes.
+    fn pull<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+
+    /// Called when the consumer cancels the stream with the given `reason`.
+    ///
+    /// Any pending [`pull`](Self::pull) is aborted before this is called.
+    fn cancel<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+}
+
+/// A [`ReadableStreamDefaultController`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamDefaultController)
+/// that is passed to the methods of an [`UnderlyingSource`](UnderlyingSource).
+#[derive(Clone, Debug)]
+pub struct ReadableStreamDefaultController {
+    raw: sys::ReadableStreamDefaultController,
+}
+
+impl ReadableStreamDefaultController {
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn from_raw(raw: sys::ReadableStreamDefaultController) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript controller](sys::ReadableStreamDefaultController).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::ReadableStreamDefaultController {
+        &self.raw
+    }
+
+    /// Returns the desired size to fill the stream's internal queue.
+    ///
+    /// It can be negative, if the queue is over-full.
+    /// It will be `None` if the stream is errored, and `Some(0.0)` if the stream is closed.
+    #[inline]
+    pub fn desired_size(&self) -> Option<f64> {
+        self.as_raw().desired_size()
+    }
+
+    /// Enqueues the given `chunk` in the stream.
+    ///
+    /// This returns an error if the stream can no longer accept chunks,
+    /// e.g. because it was closed or canceled.
+    #[inline]
+    pub fn enqueue(&self, chunk: &JsValue) -> Result<(), JsValue> {
+        self.as_raw().try_enqueue(chunk)
+    }
+
+    /// Closes the stream.
+    ///
+    /// Consumers can still read any chunks that were previously enqueued.
+    /// This returns an error if the stream was already closed or canceled.
+    #[inline]
+    pub fn close(&self) -> Result<(), JsValue> {
+        self.as_raw().try_close()
+    }
+
+    /// Errors the stream with the given `error`.
+    #[inline]
+    pub fn error(&self, error: &JsValue) {
+        self.as_raw().error(error)
+    }
+}
@@ -0,0 +1,297 @@
+//! Resuming byte streams after transient errors.
+//!
+//! A [`ResumableStream`](ResumableStream) reads a byte stream that is (re-)opened by a factory,
+//! such as a `fetch` with a [`Range`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Range)
+//! header. When the stream errors, it waits according to its [`RetryPolicy`](RetryPolicy),
+//! and then asks the factory for a new stream starting at the first byte that was not yet
+//! delivered.
+use core::fmt;
+use core::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{Future, FutureExt, LocalBoxFuture};
+use futures::ready;
+use futures::stream::{FusedStream, Stream, TryStreamExt};
+use futures::task::{Context, Poll};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::readable::{IntoStream, ReadableStream, ReaderDropPolicy};
+use crate::util::sleep;
+use crate::StreamError;
+
+/// The retry policy of a [`ResumableStream`](ResumableStream).
+///
+/// After the `n`-th consecutive failure, the stream waits for
+/// `initial_delay * multiplier^(n - 1)`, capped at `max_delay`, before trying again.
+/// The count of consecutive failures is reset whenever a chunk is delivered.
+///
+/// By default, the stream retries up to 5 times, starting with a delay of 500 milliseconds
+/// which doubles up to 30 seconds, and retries every error except
+/// [`StreamError::Aborted`](StreamError::Aborted).
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    retry_if: Rc<dyn Fn(&StreamError) -> bool>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            retry_if: Rc::new(|error| !matches!(error, StreamError::Aborted(_))),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_retries", &self.max_retries)
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a new retry policy with the default values.
+    ///
+    /// Equivalent to [`RetryPolicy::default`](Default::default).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of consecutive retries. Defaults to 5.
+    pub fn max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry. Defaults to 500 milliseconds.
+    pub fn initial_delay(&mut self, delay: Duration) -> &mut Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the maximum delay between two retries. Defaults to 30 seconds.
+    pub fn max_delay(&mut self, delay: Duration) -> &mut Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets the factor by which the delay grows after every failed retry. Defaults to 2.
+    pub fn multiplier(&mut self, multiplier: f64) -> &mut Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets which errors are retried. Any other error ends the stream right away.
+    ///
+    /// By default, every error except [`StreamError::Aborted`](StreamError::Aborted) is retried.
+    pub fn retry_if<F>(&mut self, retry_if: F) -> &mut Self
+    where
+        F: Fn(&StreamError) -> bool + 'static,
+    {
+        self.retry_if = Rc::new(retry_if);
+        self
+    }
+
+    /// Returns the delay before the given retry, counting from 1.
+    fn delay(&self, retry: usize) -> Duration {
+        let factor = self.multiplier.powi(retry.saturating_sub(1) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+enum State {
+    Idle,
+    Opening(LocalBoxFuture<'static, Result<ReadableStream<Uint8Array>, JsValue>>),
+    Reading(IntoStream<'static, Uint8Array>),
+    Waiting(LocalBoxFuture<'static, Result<(), JsValue>>),
+    Done,
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Idle => f.write_str("Idle"),
+            State::Opening(_) => f.write_str("Opening"),
+            State::Reading(stream) => f.debug_tuple("Reading").field(stream).finish(),
+            State::Waiting(_) => f.write_str("Waiting"),
+            State::Done => f.write_str("Done"),
+        }
+    }
+}
+
+/// A [`Stream`](Stream) of bytes which resumes after transient errors.
+///
+/// The stream is opened by calling the factory with the offset of the first byte to read,
+/// starting at 0. When the stream errors or fails to open, the error is passed to the
+/// [`RetryPolicy`](RetryPolicy). If it should be retried, the factory is called again with
+/// the number of bytes delivered so far, after a delay. Otherwise, the error is yielded and
+/// the stream ends.
+///
+/// The factory must return a stream of the remaining bytes starting at the given offset,
+/// for example by fetching the resource with a `Range: bytes={offset}-` header.
+///
+/// When this stream is dropped, the current underlying stream is
+/// [cancelled](https://streams.spec.whatwg.org/#cancel-a-readable-stream).
+#[must_use = "streams do nothing unless polled"]
+pub struct ResumableStream<F> {
+    factory: F,
+    policy: RetryPolicy,
+    offset: u64,
+    retries: usize,
+    state: State,
+}
+
+impl<F> fmt::Debug for ResumableStream<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResumableStream")
+            .field("policy", &self.policy)
+            .field("offset", &self.offset)
+            .field("retries", &self.retries)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+// The factory is never pinned.
+impl<F> Unpin for ResumableStream<F> {}
+
+impl<F, Fut> ResumableStream<F>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<ReadableStream<Uint8Array>, JsValue>> + 'static,
+{
+    /// Creates a new `ResumableStream` which opens its streams with the given `factory`,
+    /// and retries according to the given `policy`.
+    ///
+    /// The first stream is only opened when this stream is first polled.
+    pub fn new(factory: F, policy: RetryPolicy) -> Self {
+        Self {
+            factory,
+            policy,
+            offset: 0,
+            retries: 0,
+            state: State::Idle,
+        }
+    }
+
+    /// Returns the number of bytes delivered so far.
+    ///
+    /// This is the offset at which the next stream would be opened.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Converts this `ResumableStream` into a [`ReadableStream`](ReadableStream).
+    pub fn into_readable_stream(self) -> ReadableStream<Uint8Array>
+    where
+        F: 'static,
+    {
+        ReadableStream::from_stream(self.map_ok(JsValue::from)).cast()
+    }
+
+    /// Handles an error of the current stream, returning it if it should not be retried.
+    fn fail(&mut self, error: JsValue) -> Option<JsValue> {
+        let classified = StreamError::from_js(error.clone());
+        if self.retries < self.policy.max_retries && (self.policy.retry_if)(&classified) {
+            self.retries += 1;
+            let delay = self.policy.delay(self.retries);
+            self.state = State::Waiting(sleep(delay.as_secs_f64() * 1000.0).boxed_local());
+            None
+        } else {
+            self.state = State::Done;
+            Some(error)
+        }
+    }
+}
+
+impl<F> Drop for ResumableStream<F> {
+    fn drop(&mut self) {
+        if let State::Reading(stream) = std::mem::replace(&mut self.state, State::Done) {
+            if let Some(mut reader) = stream.into_reader() {
+                reader.set_drop_policy(ReaderDropPolicy::CancelThenRelease);
+            }
+        }
+    }
+}
+
+impl<F, Fut> FusedStream for ResumableStream<F>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<ReadableStream<Uint8Array>, JsValue>> + 'static,
+{
+    fn is_terminated(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+}
+
+impl<F, Fut> Stream for ResumableStream<F>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<ReadableStream<Uint8Array>, JsValue>> + 'static,
+{
+    type Item = Result<Uint8Array, JsValue>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match &mut this.state {
+                State::Idle => {
+                    let fut = (this.factory)(this.offset);
+                    this.state = State::Opening(fut.boxed_local());
+                }
+                State::Opening(fut) => match ready!(fut.as_mut().poll(cx)) {
+                    Ok(readable) => match readable.try_into_stream() {
+                        Ok(stream) => this.state = State::Reading(stream),
+                        Err((error, _)) => {
+                            this.state = State::Done;
+                            return Poll::Ready(Some(Err(error.into())));
+                        }
+                    },
+                    Err(error) => {
+                        if let Some(error) = this.fail(error) {
+                            return Poll::Ready(Some(Err(error)));
+                        }
+                    }
+                },
+                State::Reading(stream) => match ready!(Pin::new(stream).poll_next(cx)) {
+                    Some(Ok(chunk)) => {
+                        this.offset += u64::from(chunk.length());
+                        this.retries = 0;
+                        return Poll::Ready(Some(Ok(chunk)));
+                    }
+                    Some(Err(error)) => {
+                        if let Some(error) = this.fail(error) {
+                            return Poll::Ready(Some(Err(error)));
+                        }
+                    }
+                    None => {
+                        this.state = State::Done;
+                        return Poll::Ready(None);
+                    }
+                },
+                State::Waiting(fut) => {
+                    if let Err(error) = ready!(fut.as_mut().poll(cx)) {
+                        this.state = State::Done;
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                    this.state = State::Idle;
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Bindings and conversions for the [Web Serial API](https://developer.mozilla.org/en-US/docs/Web/API/Web_Serial_API).
+//!
+//! A [`SerialPort`](SerialPort) exposes its input and output as this crate's
+//! [`ReadableStream`](ReadableStream) and [`WritableStream`](WritableStream) types,
+//! typed with [`Uint8Array`](Uint8Array) chunks. Use [`open_duplex`](SerialPort::open_duplex)
+//! to open a port as a [`DuplexStream`](DuplexStream), which can in turn be converted into
+//! an [`AsyncRead`](futures::io::AsyncRead) + [`AsyncWrite`](futures::io::AsyncWrite) with
+//! [`into_async_duplex`](DuplexStream::into_async_duplex). This allows running serial
+//! protocols written against the `futures` IO traits in the browser.
+//!
+//! Not all browsers support the Web Serial API yet. Use
+//! [`SerialPort::is_supported`](SerialPort::is_supported) to check for support at runtime.
+//!
+//! This module requires the `serial` feature.
+use js_sys::Uint8Array;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::duplex::DuplexStream;
+use crate::readable::ReadableStream;
+use crate::util::promise_to_void_future;
+use crate::writable::WritableStream;
+use crate::StreamError;
+
+pub use options::{FlowControl, Parity, SerialOptions};
+
+mod options;
+pub mod sys;
+
+/// A [`SerialPort`](https://developer.mozilla.org/en-US/docs/Web/API/SerialPort).
+///
+/// `SerialPort`s can be requested from the user with [`request`](Self::request), or created
+/// from a [raw JavaScript object](sys::SerialPort) with [`from_raw`](Self::from_raw).
+#[derive(Debug)]
+pub struct SerialPort {
+    raw: sys::SerialPort,
+}
+
+impl SerialPort {
+    /// Returns `true` if the browser supports the Web Serial API.
+    #[inline]
+    pub fn is_supported() -> bool {
+        sys::Serial::get().is_some()
+    }
+
+    /// Prompts the user to select a serial port.
+    ///
+    /// This must be called in response to a user gesture, such as a click.
+    /// If the browser does not support the Web Serial API, or if the user did not select
+    /// a port, then this returns an error.
+    pub async fn request() -> Result<Self, StreamError> {
+        let serial = sys::Serial::get().ok_or_else(|| {
+            StreamError::TypeError(js_sys::TypeError::new("Web Serial is not supported"))
+        })?;
+        let raw = JsFuture::from(serial.request_port())
+            .await
+            .map_err(StreamError::from)?;
+        Ok(Self::from_raw(raw.unchecked_into()))
+    }
+
+    /// Creates a new `SerialPort` from a [JavaScript object](sys::SerialPort).
+    #[inline]
+    pub fn from_raw(raw: sys::SerialPort) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](sys::SerialPort).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::SerialPort {
+        &self.raw
+    }
+
+    /// Consumes this `SerialPort`, returning the underlying [JavaScript object](sys::SerialPort).
+    #[inline]
+    pub fn into_raw(self) -> sys::SerialPort {
+        self.raw
+    }
+
+    /// Opens the port with the given `options`.
+    ///
+    /// This returns an error if the port is already open, or if it could not be opened.
+    pub async fn open(&self, options: &SerialOptions) -> Result<(), StreamError> {
+        promise_to_void_future(self.as_raw().open(options.clone().into_raw()))
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Opens the port with the given `options`, and returns its input and output
+    /// as a [`DuplexStream`](DuplexStream).
+    ///
+    /// See [`open`](Self::open) for more details.
+    pub async fn open_duplex(
+        &self,
+        options: &SerialOptions,
+    ) -> Result<DuplexStream<Uint8Array, Uint8Array>, StreamError> {
+        self.open(options).await?;
+        match (self.readable(), self.writable()) {
+            (Some(readable), Some(writable)) => Ok(DuplexStream::new(readable, writable)),
+            _ => Err(StreamError::Error(js_sys::Error::new(
+                "serial port closed while opening",
+            ))),
+        }
+    }
+
+    /// Closes the port.
+    ///
+    /// The [`readable`](Self::readable) and [`writable`](Self::writable) streams must be
+    /// unlocked first, otherwise this returns an error.
+    pub async fn close(&self) -> Result<(), StreamError> {
+        promise_to_void_future(self.as_raw().close())
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Returns the stream of bytes received from the device.
+    ///
+    /// This returns `None` if the port is not open. After a
+    /// [non-fatal error](https://developer.mozilla.org/en-US/docs/Web/API/SerialPort/readable)
+    /// (such as a parity error), the stream errors and a new stream becomes available here.
+    #[inline]
+    pub fn readable(&self) -> Option<ReadableStream<Uint8Array>> {
+        let raw = self.as_raw().readable()?;
+        Some(ReadableStream::from_raw(raw).cast())
+    }
+
+    /// Returns the stream of bytes sent to the device.
+    ///
+    /// This returns `None` if the port is not open.
+    #[inline]
+    pub fn writable(&self) -> Option<WritableStream<Uint8Array>> {
+        let raw = self.as_raw().writable()?;
+        Some(WritableStream::from_raw(raw).cast())
+    }
+}
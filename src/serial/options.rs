@@ -0,0 +1,112 @@
+use super::sys;
+
+/// The parity mode of a serial port.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit is sent.
+    #[default]
+    None,
+    /// The parity bit makes the number of set bits even.
+    Even,
+    /// The parity bit makes the number of set bits odd.
+    Odd,
+}
+
+impl Parity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Parity::None => "none",
+            Parity::Even => "even",
+            Parity::Odd => "odd",
+        }
+    }
+}
+
+/// The flow control mode of a serial port.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlowControl {
+    /// No flow control.
+    #[default]
+    None,
+    /// Hardware flow control using the RTS and CTS signals.
+    Hardware,
+}
+
+impl FlowControl {
+    fn as_str(self) -> &'static str {
+        match self {
+            FlowControl::None => "none",
+            FlowControl::Hardware => "hardware",
+        }
+    }
+}
+
+/// Options for [`SerialPort::open`](super::SerialPort::open).
+#[derive(Clone, Debug)]
+pub struct SerialOptions {
+    baud_rate: u32,
+    data_bits: Option<u8>,
+    stop_bits: Option<u8>,
+    parity: Option<Parity>,
+    buffer_size: Option<u32>,
+    flow_control: Option<FlowControl>,
+}
+
+impl SerialOptions {
+    /// Creates a new set of serial options with the given baud rate.
+    ///
+    /// All other options are left to the browser's defaults: 8 data bits, 1 stop bit,
+    /// no parity, a buffer of 255 bytes and no flow control.
+    pub fn new(baud_rate: u32) -> Self {
+        Self {
+            baud_rate,
+            data_bits: None,
+            stop_bits: None,
+            parity: None,
+            buffer_size: None,
+            flow_control: None,
+        }
+    }
+
+    /// Convert this to a raw [`SerialOptions`](sys::SerialOptions) object.
+    pub fn into_raw(self) -> sys::SerialOptions {
+        sys::SerialOptions::new(
+            self.baud_rate,
+            self.data_bits,
+            self.stop_bits,
+            self.parity.map(|parity| parity.as_str().to_owned()),
+            self.buffer_size,
+            self.flow_control.map(|flow| flow.as_str().to_owned()),
+        )
+    }
+
+    /// Sets the number of data bits per frame, either 7 or 8.
+    pub fn data_bits(&mut self, data_bits: u8) -> &mut Self {
+        self.data_bits = Some(data_bits);
+        self
+    }
+
+    /// Sets the number of stop bits at the end of a frame, either 1 or 2.
+    pub fn stop_bits(&mut self, stop_bits: u8) -> &mut Self {
+        self.stop_bits = Some(stop_bits);
+        self
+    }
+
+    /// Sets the parity mode.
+    pub fn parity(&mut self, parity: Parity) -> &mut Self {
+        self.parity = Some(parity);
+        self
+    }
+
+    /// Sets the size of the read and write buffers, in bytes.
+    pub fn buffer_size(&mut self, buffer_size: u32) -> &mut Self {
+        self.buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// Sets the flow control mode.
+    pub fn flow_control(&mut self, flow_control: FlowControl) -> &mut Self {
+        self.flow_control = Some(flow_control);
+        self
+    }
+}
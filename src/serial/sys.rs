@@ -0,0 +1,125 @@
+//! Raw bindings to JavaScript objects used
+//! by a [`SerialPort`](https://developer.mozilla.org/en-US/docs/Web/API/SerialPort).
+use js_sys::{Object, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::readable::sys::ReadableStream;
+use crate::writable::sys::WritableStream;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`SerialPort`](https://developer.mozilla.org/en-US/docs/Web/API/SerialPort).
+    ///
+    /// This represents the same JavaScript objects as `web_sys::SerialPort`, which is
+    /// only available with `--cfg=web_sys_unstable_apis`.
+    #[derive(Clone, Debug)]
+    pub type SerialPort;
+
+    #[wasm_bindgen(method, js_name = open)]
+    pub fn open(this: &SerialPort, options: SerialOptions) -> Promise;
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close(this: &SerialPort) -> Promise;
+
+    #[wasm_bindgen(method, js_name = getInfo)]
+    pub fn get_info(this: &SerialPort) -> Object;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &SerialPort) -> Option<ReadableStream>;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &SerialPort) -> Option<WritableStream>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`Serial`](https://developer.mozilla.org/en-US/docs/Web/API/Serial) object,
+    /// available as `navigator.serial`.
+    #[derive(Clone, Debug)]
+    pub type Serial;
+
+    #[wasm_bindgen(method, js_name = requestPort)]
+    pub fn request_port(this: &Serial) -> Promise;
+
+    #[wasm_bindgen(method, js_name = getPorts)]
+    pub fn get_ports(this: &Serial) -> Promise;
+}
+
+impl Serial {
+    /// Returns `navigator.serial`, or `None` if the browser does not support the Web Serial API.
+    pub fn get() -> Option<Serial> {
+        let navigator = js_sys::Reflect::get(&js_sys::global(), &"navigator".into()).ok()?;
+        let serial = js_sys::Reflect::get(&navigator, &"serial".into()).ok()?;
+        if serial.is_object() {
+            Some(serial.unchecked_into())
+        } else {
+            None
+        }
+    }
+}
+
+/// Raw options for [`open()`](SerialPort::open).
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct SerialOptions {
+    baud_rate: u32,
+    data_bits: Option<u8>,
+    stop_bits: Option<u8>,
+    parity: Option<String>,
+    buffer_size: Option<u32>,
+    flow_control: Option<String>,
+}
+
+impl SerialOptions {
+    pub fn new(
+        baud_rate: u32,
+        data_bits: Option<u8>,
+        stop_bits: Option<u8>,
+        parity: Option<String>,
+        buffer_size: Option<u32>,
+        flow_control: Option<String>,
+    ) -> Self {
+        Self {
+            baud_rate,
+            data_bits,
+            stop_bits,
+            parity,
+            buffer_size,
+            flow_control,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SerialOptions {
+    #[wasm_bindgen(getter, js_name = baudRate)]
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate
+    }
+
+    #[wasm_bindgen(getter, js_name = dataBits)]
+    pub fn data_bits(&self) -> Option<u8> {
+        self.data_bits
+    }
+
+    #[wasm_bindgen(getter, js_name = stopBits)]
+    pub fn stop_bits(&self) -> Option<u8> {
+        self.stop_bits
+    }
+
+    #[wasm_bindgen(getter, js_name = parity)]
+    pub fn parity(&self) -> Option<String> {
+        self.parity.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = bufferSize)]
+    pub fn buffer_size(&self) -> Option<u32> {
+        self.buffer_size
+    }
+
+    #[wasm_bindgen(getter, js_name = flowControl)]
+    pub fn flow_control(&self) -> Option<String> {
+        self.flow_control.clone()
+    }
+}
@@ -0,0 +1,176 @@
+//! Runtime detection of the parts of the Streams API supported by the current realm.
+//!
+//! Browsers differ in which stream features they support, for example older versions of Safari
+//! lack readable byte streams. The functions in this module probe for a single feature each,
+//! so libraries can pick a code path that works everywhere.
+//!
+//! Each result is detected once per thread and then cached. Install a
+//! [polyfill](https://github.com/MattiasBuelens/web-streams-polyfill) before calling any of
+//! these functions, otherwise they keep reporting the native features.
+use std::cell::Cell;
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+export function detect_readable_byte_streams() {
+    try {
+        new ReadableStream({ type: "bytes" });
+        return true;
+    } catch (e) {
+        return false;
+    }
+}
+
+export function detect_byob_reader() {
+    try {
+        new ReadableStream({ type: "bytes" }).getReader({ mode: "byob" }).releaseLock();
+        return true;
+    } catch (e) {
+        return false;
+    }
+}
+
+export function detect_readable_stream_from() {
+    return typeof ReadableStream.from === "function";
+}
+
+export function detect_async_iteration() {
+    return typeof ReadableStream.prototype[Symbol.asyncIterator] === "function";
+}
+
+export function detect_transferable_streams() {
+    if (typeof MessageChannel !== "function") {
+        return false;
+    }
+    const { port1, port2 } = new MessageChannel();
+    try {
+        const stream = new ReadableStream();
+        port1.postMessage(stream, [stream]);
+        return true;
+    } catch (e) {
+        return false;
+    } finally {
+        port1.close();
+        port2.close();
+    }
+}
+"#)]
+extern "C" {
+    fn detect_readable_byte_streams() -> bool;
+    fn detect_byob_reader() -> bool;
+    fn detect_readable_stream_from() -> bool;
+    fn detect_async_iteration() -> bool;
+    fn detect_transferable_streams() -> bool;
+}
+
+#[derive(Clone, Copy)]
+enum Feature {
+    ReadableStream,
+    WritableStream,
+    TransformStream,
+    ReadableByteStreams,
+    ByobReader,
+    ReadableStreamFrom,
+    AsyncIteration,
+    TransferableStreams,
+    CompressionStreams,
+    TextDecoderStream,
+}
+
+const FEATURE_COUNT: usize = Feature::TextDecoderStream as usize + 1;
+
+thread_local! {
+    static CACHE: [Cell<Option<bool>>; FEATURE_COUNT] = Default::default();
+}
+
+/// Returns the cached result for the given `feature`, or runs `detect` and caches its result.
+fn cached(feature: Feature, detect: impl FnOnce() -> bool) -> bool {
+    CACHE.with(|cache| {
+        let cell = &cache[feature as usize];
+        cell.get().unwrap_or_else(|| {
+            let supported = detect();
+            cell.set(Some(supported));
+            supported
+        })
+    })
+}
+
+pub(crate) fn is_global_defined(name: &str) -> bool {
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str(name))
+        .is_ok_and(|value| value.is_function())
+}
+
+/// Returns `true` if the `ReadableStream` class is available.
+pub fn supports_readable_stream() -> bool {
+    cached(Feature::ReadableStream, || {
+        is_global_defined("ReadableStream")
+    })
+}
+
+/// Returns `true` if the `WritableStream` class is available.
+pub fn supports_writable_stream() -> bool {
+    cached(Feature::WritableStream, || {
+        is_global_defined("WritableStream")
+    })
+}
+
+/// Returns `true` if the `TransformStream` class is available.
+pub fn supports_transform_stream() -> bool {
+    cached(Feature::TransformStream, || {
+        is_global_defined("TransformStream")
+    })
+}
+
+/// Returns `true` if [readable byte streams](https://developer.mozilla.org/en-US/docs/Web/API/ReadableByteStreamController)
+/// can be constructed, as done by e.g. [`ReadableStream::from_async_read`](crate::ReadableStream::from_async_read).
+pub fn supports_readable_byte_streams() -> bool {
+    cached(Feature::ReadableByteStreams, || {
+        supports_readable_stream() && detect_readable_byte_streams()
+    })
+}
+
+/// Returns `true` if [BYOB readers](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStreamBYOBReader)
+/// can be acquired from readable byte streams.
+pub fn supports_byob_reader() -> bool {
+    cached(Feature::ByobReader, || {
+        supports_readable_stream() && detect_byob_reader()
+    })
+}
+
+/// Returns `true` if the static [`ReadableStream.from()`](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream/from_static)
+/// method is available.
+pub fn supports_readable_stream_from() -> bool {
+    cached(Feature::ReadableStreamFrom, || {
+        supports_readable_stream() && detect_readable_stream_from()
+    })
+}
+
+/// Returns `true` if readable streams support
+/// [async iteration](https://developer.mozilla.org/en-US/docs/Web/API/ReadableStream#async_iteration).
+pub fn supports_async_iteration() -> bool {
+    cached(Feature::AsyncIteration, || {
+        supports_readable_stream() && detect_async_iteration()
+    })
+}
+
+/// Returns `true` if streams can be [transferred](https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Transferable_objects)
+/// with `postMessage()`.
+pub fn supports_transferable_streams() -> bool {
+    cached(Feature::TransferableStreams, || {
+        supports_readable_stream() && detect_transferable_streams()
+    })
+}
+
+/// Returns `true` if both `CompressionStream` and `DecompressionStream` are available.
+pub fn supports_compression_streams() -> bool {
+    cached(Feature::CompressionStreams, || {
+        is_global_defined("CompressionStream") && is_global_defined("DecompressionStream")
+    })
+}
+
+/// Returns `true` if `TextDecoderStream` is available.
+pub fn supports_text_decoder_stream() -> bool {
+    cached(Feature::TextDecoderStream, || {
+        is_global_defined("TextDecoderStream")
+    })
+}
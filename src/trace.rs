@@ -0,0 +1,48 @@
+//! Internal macros for the optional [`tracing`](https://docs.rs/tracing/) integration.
+//!
+//! These forward to the corresponding `tracing` macros when the `tracing` feature is enabled.
+//! Otherwise, the event macros only borrow the values of their fields, so that variables
+//! which are only used for tracing do not trigger unused warnings.
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        tracing::trace!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($field:ident = ?$value:expr,)* $message:literal) => {{
+        $(let _ = &$value;)*
+    }};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        tracing::debug!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug_event {
+    ($($field:ident = ?$value:expr,)* $message:literal) => {{
+        $(let _ = &$value;)*
+    }};
+}
+
+/// Instruments a future with a debug-level span of the given name.
+#[cfg(feature = "tracing")]
+macro_rules! in_span {
+    ($name:literal, $fut:expr) => {
+        tracing::Instrument::instrument($fut, tracing::debug_span!($name))
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! in_span {
+    ($name:literal, $fut:expr) => {
+        $fut
+    };
+}
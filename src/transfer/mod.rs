@@ -0,0 +1,256 @@
+//! Sending [`ReadableStream`](ReadableStream)s across a [`MessagePort`](MessagePort),
+//! for example to or from a worker.
+//!
+//! Use [`ReadableStream::post_to`](ReadableStream::post_to) on one end of a
+//! [`MessageChannel`](https://developer.mozilla.org/en-US/docs/Web/API/MessageChannel),
+//! and [`ReadableStream::receive_from`](ReadableStream::receive_from) on the other end.
+//!
+//! When the browser supports [transferable streams](https://streams.spec.whatwg.org/#rs-transfer),
+//! the stream itself is transferred to the other side. Otherwise, the chunks are sent one by one
+//! using the message protocol below, which is implemented on both ends by this module:
+//!
+//! * The receiver sends `{ type: "pull" }` whenever it wants a new chunk,
+//!   and `{ type: "cancel", reason }` when the stream is cancelled.
+//! * The sender answers each pull with either `{ type: "chunk", chunk }`,
+//!   `{ type: "close" }` or `{ type: "error", reason }`.
+//!
+//! With this fallback, chunks and reasons are [structured cloned](https://developer.mozilla.org/en-US/docs/Web/API/Web_Workers_API/Structured_clone_algorithm)
+//! rather than transferred.
+//!
+//! With the `worker` feature, this module can also offload a transform to a
+//! [`Worker`](web_sys::Worker) with
+//! [`ReadableStream::pipe_through_worker`](ReadableStream::pipe_through_worker).
+//! In the worker, [register the transform](register_worker_transform) and forward all
+//! messages to [`handle_worker_message`](handle_worker_message).
+//!
+//! This module requires the `transfer` feature.
+use core::pin::Pin;
+
+use futures::channel::mpsc;
+use futures::stream::{FusedStream, Stream, StreamExt};
+use futures::task::{Context, Poll};
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{MessageEvent, MessagePort};
+
+use crate::readable::{sys, IntoStream, ReadableStream};
+use crate::util::detach_promise;
+
+#[cfg(feature = "worker")]
+pub(crate) use worker::pipe_through_worker;
+#[cfg(feature = "worker")]
+pub use worker::{handle_worker_message, register_worker_transform};
+
+#[cfg(feature = "worker")]
+mod worker;
+
+type MessageHandler = Closure<dyn FnMut(MessageEvent)>;
+
+/// Listens to all messages on the given `port`, until the returned handler is dropped
+/// and the port's `onmessage` handler is removed.
+fn listen(port: &MessagePort) -> (MessageHandler, mpsc::UnboundedReceiver<JsValue>) {
+    let (sender, receiver) = mpsc::unbounded();
+    let handler = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let _ = sender.unbounded_send(event.data());
+    });
+    // Setting onmessage also starts the port.
+    port.set_onmessage(Some(handler.as_ref().unchecked_ref()));
+    (handler, receiver)
+}
+
+fn message(type_: &str) -> Object {
+    let message = Object::new();
+    Reflect::set(&message, &"type".into(), &type_.into()).unwrap_throw();
+    message
+}
+
+fn message_with(type_: &str, key: &str, value: &JsValue) -> Object {
+    let message = message(type_);
+    Reflect::set(&message, &key.into(), value).unwrap_throw();
+    message
+}
+
+fn message_type(message: &JsValue) -> Option<String> {
+    Reflect::get(message, &"type".into()).ok()?.as_string()
+}
+
+fn message_field(message: &JsValue, key: &str) -> JsValue {
+    Reflect::get(message, &key.into()).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Sends the given unlocked `raw` stream to the other end of the `port`.
+pub(crate) fn post_stream(raw: sys::ReadableStream, port: &MessagePort) {
+    let transfer = Array::of1(&raw);
+    if port
+        .post_message_with_transferable(&message_with("stream", "stream", &raw), &transfer)
+        .is_ok()
+    {
+        return;
+    }
+
+    // Transferable streams are not supported, fall back to sending individual chunks.
+    let (handler, mut requests) = listen(port);
+    let port = port.clone();
+    let mut stream = ReadableStream::from_raw(raw.clone()).into_stream();
+    spawn_local(async move {
+        while let Some(request) = requests.next().await {
+            match message_type(&request).as_deref() {
+                Some("pull") => {
+                    let (response, done) = match stream.next().await {
+                        Some(Ok(chunk)) => (message_with("chunk", "chunk", &chunk), false),
+                        Some(Err(reason)) => (message_with("error", "reason", &reason), true),
+                        None => (message("close"), true),
+                    };
+                    // If the chunk can't be cloned, error the receiving stream instead,
+                    // and cancel the source since its remaining chunks can no longer be sent.
+                    if let Err(err) = port.post_message(&response) {
+                        let _ = port.post_message(&message_with("error", "reason", &err));
+                        if !done {
+                            drop(stream);
+                            detach_promise(raw.cancel_with_reason(&err));
+                        }
+                        break;
+                    }
+                    if done {
+                        break;
+                    }
+                }
+                Some("cancel") => {
+                    // Release the lock, so we can cancel the stream.
+                    drop(stream);
+                    detach_promise(raw.cancel_with_reason(&message_field(&request, "reason")));
+                    break;
+                }
+                _ => {}
+            }
+        }
+        port.set_onmessage(None);
+        drop(handler);
+    });
+}
+
+/// A [`Stream`](Stream) of the chunks sent by [`post_stream`](post_stream)
+/// on the other end of a port.
+pub(crate) struct ReceiveStream {
+    port: MessagePort,
+    handler: Option<MessageHandler>,
+    messages: mpsc::UnboundedReceiver<JsValue>,
+    state: ReceiveState,
+}
+
+enum ReceiveState {
+    /// No chunk has been requested yet.
+    Idle,
+    /// A chunk was requested, waiting for the response.
+    Pulling,
+    /// The stream was transferred, read directly from it.
+    Transferred(sys::ReadableStream, IntoStream<'static>),
+    Done,
+}
+
+impl ReceiveStream {
+    pub(crate) fn new(port: &MessagePort) -> Self {
+        let (handler, messages) = listen(port);
+        Self {
+            port: port.clone(),
+            handler: Some(handler),
+            messages,
+            state: ReceiveState::Idle,
+        }
+    }
+
+    fn finish(&mut self) {
+        self.state = ReceiveState::Done;
+        if self.handler.take().is_some() {
+            self.port.set_onmessage(None);
+        }
+    }
+}
+
+impl FusedStream for ReceiveStream {
+    fn is_terminated(&self) -> bool {
+        matches!(self.state, ReceiveState::Done)
+    }
+}
+
+impl Stream for ReceiveStream {
+    type Item = Result<JsValue, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            match &mut this.state {
+                ReceiveState::Done => return Poll::Ready(None),
+                ReceiveState::Transferred(_, stream) => {
+                    let item = futures::ready!(stream.poll_next_unpin(cx));
+                    if !matches!(item, Some(Ok(_))) {
+                        this.finish();
+                    }
+                    return Poll::Ready(item);
+                }
+                ReceiveState::Idle => {
+                    // A sender using a transferable stream simply ignores this request.
+                    if let Err(err) = this.port.post_message(&message("pull")) {
+                        this.finish();
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                    this.state = ReceiveState::Pulling;
+                }
+                ReceiveState::Pulling => {
+                    let message = match futures::ready!(this.messages.poll_next_unpin(cx)) {
+                        Some(message) => message,
+                        None => {
+                            this.finish();
+                            return Poll::Ready(None);
+                        }
+                    };
+                    match message_type(&message).as_deref() {
+                        Some("stream") => {
+                            let raw: sys::ReadableStream =
+                                message_field(&message, "stream").unchecked_into();
+                            let stream = ReadableStream::from_raw(raw.clone()).into_stream();
+                            // The port is no longer needed.
+                            this.finish();
+                            this.state = ReceiveState::Transferred(raw, stream);
+                        }
+                        Some("chunk") => {
+                            this.state = ReceiveState::Idle;
+                            return Poll::Ready(Some(Ok(message_field(&message, "chunk"))));
+                        }
+                        Some("close") => {
+                            this.finish();
+                            return Poll::Ready(None);
+                        }
+                        Some("error") => {
+                            this.finish();
+                            return Poll::Ready(Some(Err(message_field(&message, "reason"))));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ReceiveStream {
+    fn drop(&mut self) {
+        // Cancel the stream on the other end, if it hasn't finished yet.
+        match std::mem::replace(&mut self.state, ReceiveState::Done) {
+            ReceiveState::Done => {}
+            ReceiveState::Transferred(raw, stream) => {
+                // Release the lock, so we can cancel the stream.
+                drop(stream);
+                detach_promise(raw.cancel());
+            }
+            ReceiveState::Idle | ReceiveState::Pulling => {
+                let _ =
+                    self.port
+                        .post_message(&message_with("cancel", "reason", &JsValue::UNDEFINED));
+            }
+        }
+        self.finish();
+    }
+}
@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageChannel, MessagePort, Worker};
+
+use crate::readable::ReadableStream;
+use crate::transform::TransformStream;
+use crate::util::detach_promise;
+
+use super::{message_field, message_type, post_stream};
+
+const PIPE_THROUGH: &str = "wasm-streams:pipe-through";
+
+type TransformFactory = Rc<dyn Fn() -> TransformStream>;
+
+thread_local! {
+    static TRANSFORMS: RefCell<HashMap<String, TransformFactory>> = RefCell::new(HashMap::new());
+}
+
+/// Registers a transform in the current worker under the given `id`, so it can be used
+/// by [`ReadableStream::pipe_through_worker`](ReadableStream::pipe_through_worker).
+///
+/// The `factory` is called to create a new [`TransformStream`](TransformStream)
+/// for every stream that is piped through the worker.
+/// Registering a transform with an existing `id` replaces the previous one.
+pub fn register_worker_transform<F>(id: &str, factory: F)
+where
+    F: Fn() -> TransformStream + 'static,
+{
+    TRANSFORMS.with(|transforms| {
+        transforms
+            .borrow_mut()
+            .insert(id.to_owned(), Rc::new(factory));
+    });
+}
+
+/// Handles a message sent by [`ReadableStream::pipe_through_worker`](ReadableStream::pipe_through_worker).
+///
+/// Call this from the worker's `onmessage` handler with the message's
+/// [`data`](web_sys::MessageEvent::data). It pipes the received stream through the
+/// [registered](register_worker_transform) transform, and sends the result back.
+/// If no transform is registered with the requested id, the resulting stream errors
+/// with a `TypeError`.
+///
+/// Returns `false` if the message was not sent by `pipe_through_worker`,
+/// so it can be handled by other code.
+pub fn handle_worker_message(data: &JsValue) -> bool {
+    if message_type(data).as_deref() != Some(PIPE_THROUGH) {
+        return false;
+    }
+    let id = message_field(data, "transform")
+        .as_string()
+        .unwrap_or_default();
+    let input: MessagePort = message_field(data, "input").unchecked_into();
+    let output: MessagePort = message_field(data, "output").unchecked_into();
+
+    let mut readable = ReadableStream::receive_from(&input);
+    let factory = TRANSFORMS.with(|transforms| transforms.borrow().get(&id).cloned());
+    let result = match factory {
+        Some(factory) => readable.pipe_through(&factory()),
+        None => {
+            let error = js_sys::TypeError::new(&format!("unknown worker transform: {}", id));
+            detach_promise(readable.as_raw().cancel_with_reason(&error));
+            ReadableStream::from_stream(futures::stream::once(async { Err(error.into()) }))
+        }
+    };
+    post_stream(result.into_raw(), &output);
+    true
+}
+
+/// Sends the given unlocked `readable` stream to the `worker`, where it is piped through
+/// the transform registered with `transform_id`, and returns the resulting stream.
+pub(crate) fn pipe_through_worker(
+    readable: ReadableStream,
+    worker: &Worker,
+    transform_id: &str,
+) -> Result<ReadableStream, JsValue> {
+    let input = MessageChannel::new()?;
+    let output = MessageChannel::new()?;
+
+    let message = Object::new();
+    Reflect::set(&message, &"type".into(), &PIPE_THROUGH.into())?;
+    Reflect::set(&message, &"transform".into(), &transform_id.into())?;
+    Reflect::set(&message, &"input".into(), &input.port2())?;
+    Reflect::set(&message, &"output".into(), &output.port2())?;
+    worker.post_message_with_transfer(&message, &Array::of2(&input.port2(), &output.port2()))?;
+
+    post_stream(readable.into_raw(), &input.port1());
+    Ok(ReadableStream::receive_from(&output.port1()))
+}
@@ -0,0 +1,172 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{abortable, ready, AbortHandle, Aborted, FutureExt, LocalBoxFuture};
+use js_sys::{Array, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+
+use crate::util::sleep;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Creates a [`TransformStream`](TransformStream) that groups its chunks into
+/// [`Array`](Array)s of up to `max_items` chunks.
+///
+/// A batch is flushed as soon as it contains `max_items` chunks, or when `max_delay` has passed
+/// since its first chunk was written, whichever comes first. Any remaining chunks are flushed
+/// when the writable side is closed. Empty batches are never emitted.
+///
+/// This is useful to reduce the per-message overhead when forwarding high-frequency events,
+/// e.g. over a network connection.
+///
+/// **Panics** if `max_items` is 0.
+pub fn batch(max_items: usize, max_delay: Duration) -> TransformStream {
+    TransformStream::from_transformer(Batch::new(max_items, max_delay, BatchOutput::Array))
+}
+
+/// Creates a [`TransformStream`](TransformStream) that concatenates up to `max_items`
+/// [`Uint8Array`](Uint8Array) chunks into a single `Uint8Array`.
+///
+/// Batches are flushed in the same way as with [`batch`](batch).
+/// Any chunk that is not a `Uint8Array` errors the stream.
+///
+/// **Panics** if `max_items` is 0.
+pub fn batch_bytes(max_items: usize, max_delay: Duration) -> TransformStream {
+    TransformStream::from_transformer(Batch::new(max_items, max_delay, BatchOutput::Bytes))
+}
+
+#[derive(Clone, Copy, Debug)]
+enum BatchOutput {
+    Array,
+    Bytes,
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    chunks: Vec<JsValue>,
+    /// Aborts the timer which flushes the current batch after the maximum delay.
+    timer: Option<AbortHandle>,
+}
+
+impl Pending {
+    /// Enqueues the current batch, if it is not empty.
+    fn flush(
+        &mut self,
+        output: BatchOutput,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        if let Some(timer) = self.timer.take() {
+            timer.abort();
+        }
+        if self.chunks.is_empty() {
+            return Ok(());
+        }
+        let chunks = std::mem::take(&mut self.chunks);
+        let batch: JsValue = match output {
+            BatchOutput::Array => chunks.into_iter().collect::<Array>().into(),
+            BatchOutput::Bytes => {
+                let chunks = chunks
+                    .into_iter()
+                    .map(JsCast::unchecked_into::<Uint8Array>)
+                    .collect::<Vec<_>>();
+                let length = chunks.iter().map(|chunk| chunk.length()).sum();
+                let bytes = Uint8Array::new_with_length(length);
+                let mut offset = 0;
+                for chunk in chunks {
+                    bytes.set(&chunk, offset);
+                    offset += chunk.length();
+                }
+                bytes.into()
+            }
+        };
+        controller.enqueue(&batch)
+    }
+}
+
+#[derive(Debug)]
+struct Batch {
+    max_items: usize,
+    max_delay: f64,
+    output: BatchOutput,
+    pending: Rc<RefCell<Pending>>,
+}
+
+impl Batch {
+    fn new(max_items: usize, max_delay: Duration, output: BatchOutput) -> Self {
+        assert!(max_items > 0, "max_items must be greater than zero");
+        Self {
+            max_items,
+            max_delay: max_delay.as_secs_f64() * 1000.0,
+            output,
+            pending: Default::default(),
+        }
+    }
+
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        if let BatchOutput::Bytes = self.output {
+            if !chunk.is_instance_of::<Uint8Array>() {
+                return Err(js_sys::TypeError::new("chunk must be a Uint8Array").into());
+            }
+        }
+        let mut pending = self.pending.borrow_mut();
+        pending.chunks.push(chunk);
+        if pending.chunks.len() >= self.max_items {
+            return pending.flush(self.output, controller);
+        }
+        if pending.timer.is_none() {
+            let weak = Rc::downgrade(&self.pending);
+            let output = self.output;
+            let controller = controller.clone();
+            let (timer, handle) = abortable(sleep(self.max_delay));
+            pending.timer = Some(handle);
+            spawn_local(async move {
+                let result = match timer.await {
+                    Ok(result) => result,
+                    Err(Aborted) => return,
+                };
+                if let Some(pending) = weak.upgrade() {
+                    let mut pending = pending.borrow_mut();
+                    // The timer has fired, so it no longer needs to be aborted.
+                    pending.timer = None;
+                    if let Err(error) = result.and_then(|()| pending.flush(output, &controller)) {
+                        controller.error(&error);
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Transformer for Batch {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let result = self.pending.borrow_mut().flush(self.output, controller);
+        ready(result).boxed_local()
+    }
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        if let Some(timer) = self.pending.borrow_mut().timer.take() {
+            timer.abort();
+        }
+    }
+}
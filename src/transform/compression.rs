@@ -0,0 +1,145 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::support::is_global_defined;
+
+use super::{sys, TransformStream};
+
+/// A compression format supported by [`CompressionStream`](CompressionStream)
+/// and [`DecompressionStream`](DecompressionStream).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompressionFormat {
+    /// The [gzip](https://www.rfc-editor.org/rfc/rfc1952) format.
+    Gzip,
+    /// The [zlib](https://www.rfc-editor.org/rfc/rfc1950) format.
+    Deflate,
+    /// The raw [DEFLATE](https://www.rfc-editor.org/rfc/rfc1951) format, without a header.
+    DeflateRaw,
+}
+
+impl CompressionFormat {
+    /// Returns the name of this format, as accepted by the JavaScript constructors.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompressionFormat::Gzip => "gzip",
+            CompressionFormat::Deflate => "deflate",
+            CompressionFormat::DeflateRaw => "deflate-raw",
+        }
+    }
+}
+
+/// A [`CompressionStream`](https://developer.mozilla.org/en-US/docs/Web/API/CompressionStream).
+///
+/// This compresses a stream of [`Uint8Array`](js_sys::Uint8Array) chunks using the given
+/// [format](CompressionFormat). Use [`as_transform_stream`](Self::as_transform_stream)
+/// to [pipe](crate::ReadableStream::pipe_through) a readable stream through it.
+///
+/// Not all browsers support `CompressionStream`. Use [`is_supported`](Self::is_supported)
+/// to check for support first.
+#[derive(Debug)]
+pub struct CompressionStream {
+    inner: TransformStream,
+}
+
+impl CompressionStream {
+    /// Returns whether the current environment supports `CompressionStream`.
+    pub fn is_supported() -> bool {
+        is_global_defined("CompressionStream")
+    }
+
+    /// Creates a new `CompressionStream` for the given format.
+    ///
+    /// **Panics** if `CompressionStream` is not supported, or if the format is not supported.
+    /// For a non-panicking variant, use [`try_new`](Self::try_new).
+    pub fn new(format: CompressionFormat) -> Self {
+        Self::try_new(format).expect_throw("CompressionStream is not supported")
+    }
+
+    /// Try to create a new `CompressionStream` for the given format.
+    ///
+    /// If `CompressionStream` is not supported, or if the format is not supported,
+    /// then this returns an error.
+    pub fn try_new(format: CompressionFormat) -> Result<Self, JsValue> {
+        let raw = sys::CompressionStream::new(format.as_str())?;
+        Ok(Self {
+            inner: TransformStream::from_raw(raw.unchecked_into()),
+        })
+    }
+
+    /// Acquires a reference to this stream as a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn as_transform_stream(&self) -> &TransformStream {
+        &self.inner
+    }
+
+    /// Converts this stream into a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn into_transform_stream(self) -> TransformStream {
+        self.inner
+    }
+}
+
+impl From<CompressionStream> for TransformStream {
+    #[inline]
+    fn from(stream: CompressionStream) -> Self {
+        stream.into_transform_stream()
+    }
+}
+
+/// A [`DecompressionStream`](https://developer.mozilla.org/en-US/docs/Web/API/DecompressionStream).
+///
+/// This decompresses a stream of [`Uint8Array`](js_sys::Uint8Array) chunks using the given
+/// [format](CompressionFormat). Use [`as_transform_stream`](Self::as_transform_stream)
+/// to [pipe](crate::ReadableStream::pipe_through) a readable stream through it.
+///
+/// Not all browsers support `DecompressionStream`. Use [`is_supported`](Self::is_supported)
+/// to check for support first.
+#[derive(Debug)]
+pub struct DecompressionStream {
+    inner: TransformStream,
+}
+
+impl DecompressionStream {
+    /// Returns whether the current environment supports `DecompressionStream`.
+    pub fn is_supported() -> bool {
+        is_global_defined("DecompressionStream")
+    }
+
+    /// Creates a new `DecompressionStream` for the given format.
+    ///
+    /// **Panics** if `DecompressionStream` is not supported, or if the format is not supported.
+    /// For a non-panicking variant, use [`try_new`](Self::try_new).
+    pub fn new(format: CompressionFormat) -> Self {
+        Self::try_new(format).expect_throw("DecompressionStream is not supported")
+    }
+
+    /// Try to create a new `DecompressionStream` for the given format.
+    ///
+    /// If `DecompressionStream` is not supported, or if the format is not supported,
+    /// then this returns an error.
+    pub fn try_new(format: CompressionFormat) -> Result<Self, JsValue> {
+        let raw = sys::DecompressionStream::new(format.as_str())?;
+        Ok(Self {
+            inner: TransformStream::from_raw(raw.unchecked_into()),
+        })
+    }
+
+    /// Acquires a reference to this stream as a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn as_transform_stream(&self) -> &TransformStream {
+        &self.inner
+    }
+
+    /// Converts this stream into a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn into_transform_stream(self) -> TransformStream {
+        self.inner
+    }
+}
+
+impl From<DecompressionStream> for TransformStream {
+    #[inline]
+    fn from(stream: DecompressionStream) -> Self {
+        stream.into_transform_stream()
+    }
+}
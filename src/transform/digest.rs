@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ::digest::{Digest, Output};
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Creates an identity [`TransformStream`](TransformStream) that computes a digest
+/// of all bytes passing through it, using the hash function `D`.
+///
+/// All [`Uint8Array`](Uint8Array) chunks are passed through unchanged, while they are
+/// fed to the hasher. Any other chunk errors the stream.
+/// The digest can be retrieved from the returned [`DigestHandle`](DigestHandle)
+/// once the stream is closed.
+///
+/// Any hash function implementing the [`Digest`](Digest) trait from the `digest` crate
+/// can be used, such as `sha2::Sha256`. (The browser's `SubtleCrypto.digest()` cannot
+/// hash a stream incrementally.)
+///
+/// This requires the `digest` feature.
+pub fn digest<D>() -> (TransformStream, DigestHandle<D>)
+where
+    D: Digest + 'static,
+{
+    let handle = DigestHandle {
+        output: Rc::new(RefCell::new(None)),
+    };
+    let transformer = DigestTransformer {
+        hasher: D::new(),
+        output: handle.output.clone(),
+    };
+    (TransformStream::from_transformer(transformer), handle)
+}
+
+/// A handle to the digest computed by a [`digest`](digest()) transform stream.
+#[derive(Debug)]
+pub struct DigestHandle<D: Digest> {
+    output: Rc<RefCell<Option<Output<D>>>>,
+}
+
+impl<D: Digest> DigestHandle<D> {
+    /// Returns the digest of all bytes that passed through the transform stream.
+    ///
+    /// This returns `None` until the writable side of the stream is closed and all chunks
+    /// have been transformed, e.g. after piping through the stream completes.
+    /// If the stream errored instead, this always returns `None`.
+    pub fn digest(&self) -> Option<Output<D>> {
+        self.output.borrow().clone()
+    }
+}
+
+struct DigestTransformer<D: Digest> {
+    hasher: D,
+    output: Rc<RefCell<Option<Output<D>>>>,
+}
+
+impl<D: Digest> DigestTransformer<D> {
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let bytes = chunk
+            .dyn_ref::<Uint8Array>()
+            .ok_or_else(|| js_sys::TypeError::new("chunk must be a Uint8Array"))?;
+        self.hasher.update(bytes.to_vec());
+        controller.enqueue(&chunk)
+    }
+
+    fn flush_sync(&mut self) -> Result<(), JsValue> {
+        let hasher = std::mem::replace(&mut self.hasher, D::new());
+        *self.output.borrow_mut() = Some(hasher.finalize());
+        Ok(())
+    }
+}
+
+impl<D: Digest> Transformer for DigestTransformer<D> {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.flush_sync()).boxed_local()
+    }
+}
@@ -0,0 +1,34 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Creates an identity [`TransformStream`](TransformStream) that calls `f`
+/// with a reference to every chunk passing through it.
+///
+/// This is useful for debugging or monitoring a pipeline, e.g. to log every chunk.
+/// The chunks themselves are passed through unchanged.
+///
+/// See also [`ReadableStream::inspect`](crate::ReadableStream::inspect) and
+/// [`WritableStream::inspect_writes`](crate::WritableStream::inspect_writes).
+pub fn inspect<F>(f: F) -> TransformStream
+where
+    F: FnMut(&JsValue) + 'static,
+{
+    TransformStream::from_transformer(Inspect { f })
+}
+
+struct Inspect<F> {
+    f: F,
+}
+
+impl<F: FnMut(&JsValue)> Transformer for Inspect<F> {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        (self.f)(&chunk);
+        ready(controller.enqueue(&chunk)).boxed_local()
+    }
+}
@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+use super::sys;
+use super::transformer::{TransformStreamDefaultController, Transformer};
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[wasm_bindgen]
+pub(crate) struct IntoUnderlyingTransformer {
+    inner: Rc<RefCell<Box<dyn Transformer>>>,
+}
+
+impl IntoUnderlyingTransformer {
+    pub fn new(transformer: Box<dyn Transformer>) -> Self {
+        IntoUnderlyingTransformer {
+            inner: Rc::new(RefCell::new(transformer)),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+#[wasm_bindgen]
+impl IntoUnderlyingTransformer {
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn start(&mut self, controller: sys::TransformStreamDefaultController) -> Promise {
+        let inner = self.inner.clone();
+        future_to_promise(async move {
+            // This mutable borrow can never panic, since the TransformStream always waits
+            // for start() to finish before calling transform() or flush().
+            let mut inner = inner.try_borrow_mut().unwrap_throw();
+            let controller = TransformStreamDefaultController::from_raw(controller);
+            inner.start(&controller).await.map(|_| JsValue::undefined())
+        })
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn transform(
+        &mut self,
+        chunk: JsValue,
+        controller: sys::TransformStreamDefaultController,
+    ) -> Promise {
+        let inner = self.inner.clone();
+        future_to_promise(async move {
+            // This mutable borrow can never panic, since the TransformStream always waits
+            // for the previous transform() to finish before calling transform() again.
+            let mut inner = inner.try_borrow_mut().unwrap_throw();
+            let controller = TransformStreamDefaultController::from_raw(controller);
+            inner
+                .transform(chunk, &controller)
+                .await
+                .map(|_| JsValue::undefined())
+        })
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn flush(self, controller: sys::TransformStreamDefaultController) -> Promise {
+        future_to_promise(async move {
+            let mut inner = self.inner.try_borrow_mut().unwrap_throw();
+            let controller = TransformStreamDefaultController::from_raw(controller);
+            inner.flush(&controller).await.map(|_| JsValue::undefined())
+        })
+    }
+}
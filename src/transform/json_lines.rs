@@ -0,0 +1,96 @@
+use futures::stream::{LocalBoxStream, Stream, StreamExt, TryStreamExt};
+use js_sys::Uint8Array;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::readable::ReadableStream;
+
+fn json_error_to_js(error: serde_json::Error) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+/// Decodes a stream of [JSON lines](https://jsonlines.org/) into values of type `T`.
+///
+/// The `readable` stream can either be a byte stream or a text stream, and is split into lines
+/// using [`lines`](super::lines). Every non-empty line is then deserialized as a `T`.
+///
+/// Errors from the `readable` stream are passed through as-is. A line that fails
+/// to deserialize results in an [`Error`](js_sys::Error) with the same message,
+/// but does not end the stream.
+///
+/// **Panics** if `readable` is already locked to a reader. For a non-panicking variant,
+/// use [`try_json_lines_decoder`](try_json_lines_decoder).
+///
+/// This requires the `serde` feature.
+pub fn json_lines_decoder<T>(
+    readable: ReadableStream,
+) -> LocalBoxStream<'static, Result<T, JsValue>>
+where
+    T: DeserializeOwned + 'static,
+{
+    try_json_lines_decoder(readable)
+        .map_err(|(err, _)| err)
+        .expect_throw("already locked to a reader")
+}
+
+/// Try to decode a stream of [JSON lines](https://jsonlines.org/) into values of type `T`.
+///
+/// See [`json_lines_decoder`](json_lines_decoder) for more details.
+///
+/// If `readable` is already locked to a reader, then this returns an error
+/// along with the original `ReadableStream`.
+///
+/// This requires the `serde` feature.
+#[allow(clippy::type_complexity)]
+pub fn try_json_lines_decoder<T>(
+    mut readable: ReadableStream,
+) -> Result<LocalBoxStream<'static, Result<T, JsValue>>, (js_sys::Error, ReadableStream)>
+where
+    T: DeserializeOwned + 'static,
+{
+    let lines = match readable.try_lines() {
+        Ok(lines) => lines,
+        Err(err) => return Err((err, readable)),
+    };
+    Ok(lines
+        .into_stream()
+        .try_filter_map(|line| async move {
+            // Lines are either strings or Uint8Arrays, depending on the input stream.
+            let line = match line.as_string() {
+                Some(line) => line.into_bytes(),
+                None => line.unchecked_into::<Uint8Array>().to_vec(),
+            };
+            if line.iter().all(u8::is_ascii_whitespace) {
+                return Ok(None);
+            }
+            serde_json::from_slice(&line)
+                .map(Some)
+                .map_err(json_error_to_js)
+        })
+        .boxed_local())
+}
+
+/// Encodes a stream of values of type `T` as [JSON lines](https://jsonlines.org/).
+///
+/// This returns a [`ReadableStream`](ReadableStream) of [`Uint8Array`](Uint8Array) chunks,
+/// with one chunk per line (including its trailing newline). This can be used directly
+/// as the body of a streaming upload.
+///
+/// Errors from the `stream` are passed through as-is. A value that fails to serialize
+/// errors the returned stream with an [`Error`](js_sys::Error) with the same message.
+///
+/// This requires the `serde` feature.
+pub fn json_lines_encoder<T, St>(stream: St) -> ReadableStream<Uint8Array>
+where
+    T: Serialize + 'static,
+    St: Stream<Item = Result<T, JsValue>> + 'static,
+{
+    let stream = stream.and_then(|value| async move {
+        let mut line = serde_json::to_vec(&value).map_err(json_error_to_js)?;
+        line.push(b'\n');
+        Ok(Uint8Array::from(&line[..]).into())
+    });
+    ReadableStream::from_stream(stream).cast()
+}
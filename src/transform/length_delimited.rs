@@ -0,0 +1,248 @@
+//! Transform streams for length-delimited framing.
+//!
+//! The [`encoder`](encoder) prefixes every [`Uint8Array`](Uint8Array) chunk with its length,
+//! and the [`decoder`](decoder) splits a byte stream back into the original chunks,
+//! reassembling frames that are split across multiple chunks.
+//! This is useful for sending binary messages over a byte stream,
+//! similar to [`LengthDelimitedCodec`](https://docs.rs/tokio-util/latest/tokio_util/codec/length_delimited/)
+//! from `tokio-util`.
+//!
+//! The format of the length prefix is configured with [`FramingOptions`](FramingOptions).
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Options for the length-delimited [`encoder`](encoder) and [`decoder`](decoder).
+///
+/// By default, the length prefix is a 4 byte big-endian integer,
+/// and frames can be at most 8 MiB long.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FramingOptions {
+    length_field_length: usize,
+    big_endian: bool,
+    max_frame_length: usize,
+}
+
+impl Default for FramingOptions {
+    fn default() -> Self {
+        Self {
+            length_field_length: 4,
+            big_endian: true,
+            max_frame_length: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl FramingOptions {
+    /// Creates the default set of framing options.
+    ///
+    /// Equivalent to [`FramingOptions::default`](Default::default).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the number of bytes of the length prefix.
+    ///
+    /// **Panics** if `length` is not between 1 and 8.
+    pub fn length_field_length(&mut self, length: usize) -> &mut Self {
+        assert!(
+            (1..=8).contains(&length),
+            "length field length must be between 1 and 8"
+        );
+        self.length_field_length = length;
+        self
+    }
+
+    /// Sets the length prefix to be encoded in big-endian (network) byte order.
+    pub fn big_endian(&mut self) -> &mut Self {
+        self.big_endian = true;
+        self
+    }
+
+    /// Sets the length prefix to be encoded in little-endian byte order.
+    pub fn little_endian(&mut self) -> &mut Self {
+        self.big_endian = false;
+        self
+    }
+
+    /// Sets the maximum length of a frame, excluding the length prefix.
+    ///
+    /// Larger frames error the stream, rather than being buffered in memory.
+    pub fn max_frame_length(&mut self, length: usize) -> &mut Self {
+        self.max_frame_length = length;
+        self
+    }
+
+    /// Returns the largest frame length that fits in the length prefix,
+    /// and is allowed by the maximum frame length.
+    fn effective_max_frame_length(&self) -> u64 {
+        let max_length_field = match self.length_field_length {
+            8 => u64::MAX,
+            n => (1 << (8 * n)) - 1,
+        };
+        max_length_field.min(self.max_frame_length as u64)
+    }
+
+    fn encode_length(&self, length: u64, dst: &mut Vec<u8>) {
+        let n = self.length_field_length;
+        if self.big_endian {
+            dst.extend_from_slice(&length.to_be_bytes()[8 - n..]);
+        } else {
+            dst.extend_from_slice(&length.to_le_bytes()[..n]);
+        }
+    }
+
+    fn decode_length(&self, src: &[u8]) -> u64 {
+        let n = self.length_field_length;
+        let mut bytes = [0u8; 8];
+        if self.big_endian {
+            bytes[8 - n..].copy_from_slice(&src[..n]);
+            u64::from_be_bytes(bytes)
+        } else {
+            bytes[..n].copy_from_slice(&src[..n]);
+            u64::from_le_bytes(bytes)
+        }
+    }
+}
+
+fn frame_too_large(length: u64, max_length: u64) -> JsValue {
+    js_sys::RangeError::new(&format!(
+        "frame of {} bytes exceeds maximum frame length of {} bytes",
+        length, max_length
+    ))
+    .into()
+}
+
+fn expect_bytes(chunk: JsValue) -> Result<Uint8Array, JsValue> {
+    chunk
+        .dyn_into::<Uint8Array>()
+        .map_err(|_| js_sys::TypeError::new("chunk must be a Uint8Array").into())
+}
+
+/// Creates a [`TransformStream`](TransformStream) that prefixes every
+/// [`Uint8Array`](Uint8Array) chunk with its length.
+///
+/// Every chunk results in a single output chunk containing both the length prefix
+/// and the original bytes. A chunk that is too large for the given `options`,
+/// or a chunk that is not a `Uint8Array`, errors the stream.
+pub fn encoder(options: &FramingOptions) -> TransformStream {
+    TransformStream::from_transformer(Encoder {
+        options: options.clone(),
+    })
+}
+
+/// Creates a [`TransformStream`](TransformStream) that splits a byte stream into
+/// length-prefixed frames, as produced by the [`encoder`](encoder) with the same `options`.
+///
+/// Every frame is output as a single [`Uint8Array`](Uint8Array) chunk, without its length
+/// prefix. A frame that is larger than the maximum frame length errors the stream,
+/// as does a stream that ends in the middle of a frame.
+pub fn decoder(options: &FramingOptions) -> TransformStream {
+    TransformStream::from_transformer(Decoder {
+        options: options.clone(),
+        buffer: Vec::new(),
+    })
+}
+
+#[derive(Debug)]
+struct Encoder {
+    options: FramingOptions,
+}
+
+impl Encoder {
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let chunk = expect_bytes(chunk)?;
+        let length = chunk.length() as u64;
+        let max_length = self.options.effective_max_frame_length();
+        if length > max_length {
+            return Err(frame_too_large(length, max_length));
+        }
+        let header_length = self.options.length_field_length;
+        let mut frame = Vec::with_capacity(header_length + chunk.length() as usize);
+        self.options.encode_length(length, &mut frame);
+        frame.resize(header_length + chunk.length() as usize, 0);
+        chunk.copy_to(&mut frame[header_length..]);
+        controller.enqueue(&Uint8Array::from(&frame[..]))
+    }
+}
+
+impl Transformer for Encoder {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+}
+
+#[derive(Debug)]
+struct Decoder {
+    options: FramingOptions,
+    /// The bytes of the current incomplete frame, including its length prefix.
+    buffer: Vec<u8>,
+}
+
+impl Decoder {
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let chunk = expect_bytes(chunk)?;
+        let start = self.buffer.len();
+        self.buffer.resize(start + chunk.length() as usize, 0);
+        chunk.copy_to(&mut self.buffer[start..]);
+
+        let header_length = self.options.length_field_length;
+        let max_length = self.options.effective_max_frame_length();
+        let mut offset = 0;
+        while self.buffer.len() - offset >= header_length {
+            let length = self.options.decode_length(&self.buffer[offset..]);
+            if length > max_length {
+                return Err(frame_too_large(length, max_length));
+            }
+            let frame_start = offset + header_length;
+            let frame_end = frame_start + length as usize;
+            if self.buffer.len() < frame_end {
+                // Wait for the rest of the frame.
+                break;
+            }
+            controller.enqueue(&Uint8Array::from(&self.buffer[frame_start..frame_end]))?;
+            offset = frame_end;
+        }
+        self.buffer.drain(..offset);
+        Ok(())
+    }
+
+    fn flush_sync(&mut self) -> Result<(), JsValue> {
+        if !self.buffer.is_empty() {
+            return Err(js_sys::Error::new("stream ended in the middle of a frame").into());
+        }
+        Ok(())
+    }
+}
+
+impl Transformer for Decoder {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.flush_sync()).boxed_local()
+    }
+}
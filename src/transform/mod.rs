@@ -1,53 +1,302 @@
 //! Bindings and conversions for
 //! [transform streams](https://developer.mozilla.org/en-US/docs/Web/API/TransformStream).
+//!
+//! # Time-based transforms
+//!
+//! [`debounce`], [`throttle`] and [`sample`] apply the [`Debounce`], [`Throttle`] and
+//! [`Sample`] stream adapters to the chunks written to the writable side, using
+//! [`TransformStream::from_sink_and_stream`].
+//!
+//! All of them wait with `setTimeout()`, which is missing in some global scopes such as
+//! worklets. There, the transforms error both of their sides, and creating one of the
+//! stream adapters returns an error.
+use futures::{Sink, Stream};
+use wasm_bindgen::prelude::*;
+
+use crate::queuing_strategy::QueuingStrategy;
 use crate::readable::ReadableStream;
 use crate::writable::WritableStream;
 
+#[cfg(feature = "digest")]
+pub use self::digest::{digest, DigestHandle};
+pub use batch::{batch, batch_bytes};
+pub use compression::{CompressionFormat, CompressionStream, DecompressionStream};
+pub use inspect::inspect;
+use into_underlying_transformer::IntoUnderlyingTransformer;
+#[cfg(feature = "serde")]
+pub use json_lines::{json_lines_decoder, json_lines_encoder, try_json_lines_decoder};
+pub use pair::{PipeThrough, ReadableWritablePair};
+pub use rate_limit::{rate_limit, RateLimitOptions};
+pub use rechunk::rechunk;
+use sink_and_stream::SinkAndStream;
+pub use split::{lines, split_by};
+pub use sse::{SseEvent, SseStream};
+pub use strip_bom::strip_bom;
+pub use text_decoder::{TextDecoderStream, Utf8DecoderStream};
+pub use time::{debounce, sample, throttle, Debounce, Sample, Throttle};
+pub use transformer::{TransformStreamDefaultController, Transformer};
+
+mod batch;
+mod compression;
+#[cfg(feature = "digest")]
+mod digest;
+mod inspect;
+mod into_underlying_transformer;
+#[cfg(feature = "serde")]
+mod json_lines;
+pub mod length_delimited;
+mod pair;
+mod rate_limit;
+mod rechunk;
+mod sink_and_stream;
+mod split;
+mod sse;
+mod strip_bom;
 pub mod sys;
+mod text_decoder;
+mod time;
+mod transformer;
 
 /// A [`TransformStream`](https://developer.mozilla.org/en-US/docs/Web/API/TransformStream).
 ///
 /// `TransformStream`s can be created from a [raw JavaScript stream](sys::TransformStream) with
-/// [`from_raw`](Self::from_raw), and can be converted back with [`into_raw`](Self::into_raw).
+/// [`from_raw`](Self::from_raw), from a Rust [`Transformer`](Transformer) with
+/// [`from_transformer`](Self::from_transformer), or from a Rust [`Sink`](Sink) and
+/// [`Stream`](Stream) pair with [`from_sink_and_stream`](Self::from_sink_and_stream).
+/// They can be converted back with [`into_raw`](Self::into_raw).
 ///
-/// Use [`readable`](Self::readable) and [`writable`](Self::writable) to access the readable and
-/// writable side of the transform stream.
+/// Use [`take_readable`](Self::take_readable) and [`take_writable`](Self::take_writable)
+/// to take the readable and writable side of the transform stream,
+/// or [`into_pair`](Self::into_pair) to take ownership of both sides at once.
 /// These can then be converted into a Rust [`Stream`](futures::Stream) and [`Sink`](futures::Sink)
 /// respectively using [`into_stream`](super::ReadableStream::into_stream)
 /// and [`into_sink`](super::WritableStream::into_sink).
 #[derive(Debug)]
 pub struct TransformStream {
     raw: sys::TransformStream,
+    readable_taken: bool,
+    writable_taken: bool,
 }
 
 impl TransformStream {
     /// Creates a new `TransformStream` from a [JavaScript stream](sys::TransformStream).
     #[inline]
     pub fn from_raw(raw: sys::TransformStream) -> Self {
-        Self { raw }
+        Self {
+            raw,
+            readable_taken: false,
+            writable_taken: false,
+        }
+    }
+
+    /// Creates a new identity `TransformStream`, which forwards all chunks written
+    /// to its writable side unchanged to its readable side.
+    ///
+    /// This uses the default queuing strategies, i.e. a high water mark of 1 chunk
+    /// for the writable side and 0 chunks for the readable side.
+    #[inline]
+    pub fn new_identity() -> Self {
+        Self::from_raw(sys::TransformStream::new())
+    }
+
+    /// Creates a new identity `TransformStream` with the given queuing strategies
+    /// for its writable and readable side.
+    ///
+    /// Since the chunks are forwarded unchanged, this can be used as a buffer
+    /// between a producer and a consumer, which can hold up to the sum of both
+    /// high water marks before applying backpressure to the producer.
+    pub fn new_identity_with_strategies(
+        writable_strategy: QueuingStrategy,
+        readable_strategy: QueuingStrategy,
+    ) -> Self {
+        Self::from_raw(sys::TransformStream::new_with_strategies(
+            &JsValue::UNDEFINED,
+            writable_strategy.as_raw(),
+            readable_strategy.as_raw(),
+        ))
+    }
+
+    /// Creates a new `TransformStream` from a Rust [`Transformer`](Transformer).
+    ///
+    /// The transformer's methods are called by the JavaScript transform stream
+    /// whenever it needs to start, transform a chunk or flush. The resulting stream can be used
+    /// like any other transform stream, for example with
+    /// [`pipe_through`](super::ReadableStream::pipe_through).
+    ///
+    /// This uses the default queuing strategies, i.e. a high water mark of 1 chunk
+    /// for the writable side and 0 chunks for the readable side.
+    pub fn from_transformer<T>(transformer: T) -> Self
+    where
+        T: Transformer + 'static,
+    {
+        Self::from_transformer_with_strategies(
+            transformer,
+            QueuingStrategy::new(1.0),
+            QueuingStrategy::new(0.0),
+        )
+    }
+
+    /// Creates a new `TransformStream` from a Rust [`Transformer`](Transformer) with the given
+    /// queuing strategies for its writable and readable side.
+    ///
+    /// See [`from_transformer`](Self::from_transformer) for more details.
+    pub fn from_transformer_with_strategies<T>(
+        transformer: T,
+        writable_strategy: QueuingStrategy,
+        readable_strategy: QueuingStrategy,
+    ) -> Self
+    where
+        T: Transformer + 'static,
+    {
+        let transformer = IntoUnderlyingTransformer::new(Box::new(transformer));
+        Self::from_raw(sys::TransformStream::new_with_transformer(
+            transformer,
+            writable_strategy.as_raw(),
+            readable_strategy.as_raw(),
+        ))
+    }
+
+    /// Creates a new `TransformStream` from a Rust [`Sink`](Sink) and [`Stream`](Stream) pair.
+    ///
+    /// Chunks written to the writable side are sent into the given `sink`,
+    /// and the readable side produces the items of the given `stream`.
+    /// This is useful for codecs that expose their input and output as two separate halves.
+    ///
+    /// Each write waits until the chunk was sent into the `sink`. The items of the `stream`
+    /// are enqueued on the readable side as soon as they are produced, and an error item
+    /// errors both sides. Closing the writable side closes the `sink`, and then closes the
+    /// readable side once the `stream` has ended.
+    pub fn from_sink_and_stream<Si, St>(sink: Si, stream: St) -> Self
+    where
+        Si: Sink<JsValue, Error = JsValue> + 'static,
+        St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+    {
+        Self::from_transformer(SinkAndStream::new(sink, stream))
     }
 
     /// Acquires a reference to the underlying [JavaScript stream](sys::TransformStream).
+    ///
+    /// The raw stream gives access to both sides, even if they were already
+    /// [taken](Self::take_readable).
     #[inline]
     pub fn as_raw(&self) -> &sys::TransformStream {
         &self.raw
     }
 
     /// Consumes this `TransformStream`, returning the underlying [JavaScript stream](sys::TransformStream).
+    ///
+    /// The raw stream gives access to both sides, even if they were already
+    /// [taken](Self::take_readable).
     #[inline]
     pub fn into_raw(self) -> sys::TransformStream {
         self.raw
     }
 
     /// Returns the readable side of the transform stream.
+    ///
+    /// Every call returns a new handle to the same JavaScript stream, so locking one of them
+    /// (e.g. by [getting a reader](super::ReadableStream::get_reader)) also locks all others.
+    ///
+    /// **Panics** if the readable side was already taken. For a non-panicking variant,
+    /// use [`take_readable`](Self::take_readable).
+    #[deprecated(
+        note = "use `take_readable` or `into_pair`, which hand out the readable side only once"
+    )]
     #[inline]
     pub fn readable(&self) -> ReadableStream {
+        if self.readable_taken {
+            wasm_bindgen::throw_str("readable side already taken");
+        }
         ReadableStream::from_raw(self.as_raw().readable())
     }
 
     /// Returns the writable side of the transform stream.
+    ///
+    /// Every call returns a new handle to the same JavaScript stream, so locking one of them
+    /// (e.g. by [getting a writer](super::WritableStream::get_writer)) also locks all others.
+    ///
+    /// **Panics** if the writable side was already taken. For a non-panicking variant,
+    /// use [`take_writable`](Self::take_writable).
+    #[deprecated(
+        note = "use `take_writable` or `into_pair`, which hand out the writable side only once"
+    )]
     #[inline]
     pub fn writable(&self) -> WritableStream {
+        if self.writable_taken {
+            wasm_bindgen::throw_str("writable side already taken");
+        }
         WritableStream::from_raw(self.as_raw().writable())
     }
+
+    /// Takes the readable side of the transform stream.
+    ///
+    /// This returns `None` if the readable side was already taken,
+    /// so that it has at most one owner.
+    pub fn take_readable(&mut self) -> Option<ReadableStream> {
+        if self.readable_taken {
+            return None;
+        }
+        self.readable_taken = true;
+        Some(ReadableStream::from_raw(self.as_raw().readable()))
+    }
+
+    /// Takes the writable side of the transform stream.
+    ///
+    /// This returns `None` if the writable side was already taken,
+    /// so that it has at most one owner.
+    pub fn take_writable(&mut self) -> Option<WritableStream> {
+        if self.writable_taken {
+            return None;
+        }
+        self.writable_taken = true;
+        Some(WritableStream::from_raw(self.as_raw().writable()))
+    }
+
+    /// Returns `true` if the readable side was already [taken](Self::take_readable).
+    #[inline]
+    pub fn is_readable_taken(&self) -> bool {
+        self.readable_taken
+    }
+
+    /// Returns `true` if the writable side was already [taken](Self::take_writable).
+    #[inline]
+    pub fn is_writable_taken(&self) -> bool {
+        self.writable_taken
+    }
+
+    /// Consumes this `TransformStream`, returning its readable and writable side
+    /// as a [`ReadableWritablePair`](ReadableWritablePair).
+    ///
+    /// This makes sure that each side has exactly one owner.
+    ///
+    /// **Panics** if the readable or writable side was already taken
+    /// with [`take_readable`](Self::take_readable) or [`take_writable`](Self::take_writable).
+    /// For a non-panicking variant, use [`try_into_pair`](Self::try_into_pair).
+    pub fn into_pair(self) -> ReadableWritablePair {
+        self.try_into_pair()
+            .map_err(|(err, _)| err)
+            .expect_throw("readable or writable side already taken")
+    }
+
+    /// Try to consume this `TransformStream`, returning its readable and writable side
+    /// as a [`ReadableWritablePair`](ReadableWritablePair).
+    ///
+    /// If the readable or writable side was already taken, then this returns an error
+    /// along with the original `TransformStream`.
+    pub fn try_into_pair(mut self) -> Result<ReadableWritablePair, (js_sys::Error, Self)> {
+        if let Err(err) = self.check_not_taken() {
+            return Err((err, self));
+        }
+        let readable = self.take_readable().unwrap_throw();
+        let writable = self.take_writable().unwrap_throw();
+        Ok(ReadableWritablePair::new(readable, writable))
+    }
+
+    /// Returns an error if the readable or writable side was already taken.
+    pub(crate) fn check_not_taken(&self) -> Result<(), js_sys::Error> {
+        if self.readable_taken || self.writable_taken {
+            return Err(js_sys::TypeError::new("readable or writable side already taken").into());
+        }
+        Ok(())
+    }
 }
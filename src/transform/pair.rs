@@ -0,0 +1,139 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use wasm_bindgen::JsCast;
+
+use crate::readable::{self, PipeOptions, ReadableStream};
+use crate::writable::WritableStream;
+
+use super::{sys, TransformStream};
+
+/// A transform that a [`ReadableStream`](ReadableStream) can be
+/// [piped through](ReadableStream::pipe_through), i.e. a writable side which accepts
+/// chunks of type `T` and a readable side.
+///
+/// This is implemented for [`&TransformStream`](TransformStream), whose sides can still be
+/// accessed after piping, and for [`ReadableWritablePair`](ReadableWritablePair), which is
+/// consumed and keeps the chunk type of its readable side.
+pub trait PipeThrough<T> {
+    /// The chunk type of the readable side.
+    type Output: JsCast;
+    /// The error returned when the pipe cannot be started.
+    type Error: fmt::Debug;
+
+    /// Pipes the given raw `readable` stream through this transform,
+    /// returning the readable side.
+    fn pipe_from(
+        self,
+        readable: &readable::sys::ReadableStream,
+        options: &PipeOptions,
+    ) -> Result<ReadableStream<Self::Output>, Self::Error>;
+}
+
+impl<T> PipeThrough<T> for &TransformStream {
+    type Output = wasm_bindgen::JsValue;
+    type Error = js_sys::Error;
+
+    fn pipe_from(
+        self,
+        readable: &readable::sys::ReadableStream,
+        options: &PipeOptions,
+    ) -> Result<ReadableStream, js_sys::Error> {
+        // Piping hands out both sides, so neither of them may have another owner.
+        self.check_not_taken()?;
+        let raw = readable.pipe_through(self.as_raw(), options.clone().into_raw())?;
+        Ok(ReadableStream::from_raw(raw))
+    }
+}
+
+/// The readable and writable side of a [`TransformStream`](TransformStream), or any other
+/// [`ReadableWritablePair`](https://streams.spec.whatwg.org/#dictdef-readablewritablepair)
+/// that can be [piped through](ReadableStream::pipe_through).
+///
+/// Chunks written to the writable side are of type `W`, and chunks read from the readable side
+/// are of type `R`. This owns both sides, so each side has exactly one owner.
+/// Use [`into_parts`](Self::into_parts) to take them apart.
+pub struct ReadableWritablePair<R = wasm_bindgen::JsValue, W = wasm_bindgen::JsValue> {
+    readable: ReadableStream<R>,
+    writable: WritableStream<W>,
+}
+
+impl<R, W> fmt::Debug for ReadableWritablePair<R, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadableWritablePair")
+            .field("readable", &self.readable)
+            .field("writable", &self.writable)
+            .finish()
+    }
+}
+
+impl<R: JsCast, W: JsCast> ReadableWritablePair<R, W> {
+    /// Creates a new `ReadableWritablePair` from its readable and writable side.
+    #[inline]
+    pub fn new(readable: ReadableStream<R>, writable: WritableStream<W>) -> Self {
+        Self { readable, writable }
+    }
+
+    /// Acquires a reference to the readable side.
+    #[inline]
+    pub fn readable(&self) -> &ReadableStream<R> {
+        &self.readable
+    }
+
+    /// Acquires a mutable reference to the readable side.
+    #[inline]
+    pub fn readable_mut(&mut self) -> &mut ReadableStream<R> {
+        &mut self.readable
+    }
+
+    /// Acquires a reference to the writable side.
+    #[inline]
+    pub fn writable(&self) -> &WritableStream<W> {
+        &self.writable
+    }
+
+    /// Acquires a mutable reference to the writable side.
+    #[inline]
+    pub fn writable_mut(&mut self) -> &mut WritableStream<W> {
+        &mut self.writable
+    }
+
+    /// Consumes this pair, returning its readable and writable side.
+    #[inline]
+    pub fn into_parts(self) -> (ReadableStream<R>, WritableStream<W>) {
+        (self.readable, self.writable)
+    }
+
+    /// Converts this pair into a [raw pair](sys::ReadableWritablePair), i.e. a plain
+    /// JavaScript object with a `readable` and a `writable` property.
+    pub fn into_raw(self) -> sys::ReadableWritablePair {
+        sys::ReadableWritablePair::new(self.readable.as_raw(), self.writable.as_raw())
+    }
+}
+
+impl<R: JsCast, W: JsCast> PipeThrough<W> for ReadableWritablePair<R, W> {
+    type Output = R;
+    type Error = (js_sys::Error, Self);
+
+    fn pipe_from(
+        self,
+        readable: &readable::sys::ReadableStream,
+        options: &PipeOptions,
+    ) -> Result<ReadableStream<R>, Self::Error> {
+        let raw = sys::ReadableWritablePair::new(self.readable.as_raw(), self.writable.as_raw());
+        match readable.pipe_through_pair(&raw, options.clone().into_raw()) {
+            Ok(output) => Ok(ReadableStream::from_raw(output).cast()),
+            Err(error) => Err((error, self)),
+        }
+    }
+}
+
+impl TryFrom<TransformStream> for ReadableWritablePair {
+    type Error = (js_sys::Error, TransformStream);
+
+    /// Equivalent to [`TransformStream::try_into_pair`](TransformStream::try_into_pair).
+    #[inline]
+    fn try_from(transform: TransformStream) -> Result<Self, Self::Error> {
+        transform.try_into_pair()
+    }
+}
@@ -0,0 +1,108 @@
+use futures::future::{FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+
+use crate::util::{chunk_byte_length, sleep};
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Options for the [`rate_limit`](rate_limit) transform stream.
+#[derive(Clone, Debug)]
+pub struct RateLimitOptions {
+    bytes_per_second: f64,
+    burst: Option<f64>,
+}
+
+impl RateLimitOptions {
+    /// Creates a new set of options which limits the throughput to `bytes_per_second`.
+    ///
+    /// By default, the burst size is equal to one second's worth of bytes.
+    ///
+    /// **Panics** if `bytes_per_second` is not a positive number.
+    pub fn new(bytes_per_second: f64) -> Self {
+        assert!(
+            bytes_per_second > 0.0,
+            "bytes per second must be a positive number"
+        );
+        Self {
+            bytes_per_second,
+            burst: None,
+        }
+    }
+
+    /// Sets the maximum number of bytes that can pass through without any delay,
+    /// after the stream has been idle for a while.
+    pub fn burst(&mut self, bytes: u64) -> &mut Self {
+        self.burst = Some(bytes as f64);
+        self
+    }
+}
+
+/// Creates an identity [`TransformStream`](TransformStream) that limits the throughput of
+/// the stream to a given number of bytes per second.
+///
+/// Chunks are passed through unchanged, but are delayed using timers such that the average
+/// throughput does not exceed the configured rate. This uses a
+/// [token bucket](https://en.wikipedia.org/wiki/Token_bucket): after the stream has been idle,
+/// up to [`burst`](RateLimitOptions::burst) bytes can pass through immediately.
+/// Chunks larger than the burst size are not split, they are delayed for as long as
+/// it takes to transfer them at the configured rate.
+///
+/// Only chunks that are an [`ArrayBuffer`](js_sys::ArrayBuffer) or an
+/// [`ArrayBufferView`](https://developer.mozilla.org/en-US/docs/Web/API/ArrayBufferView)
+/// (such as a [`Uint8Array`](js_sys::Uint8Array)) count towards the limit.
+///
+/// Since the transform stream applies backpressure while it delays a chunk, piping a stream
+/// through this transform also slows down reading from the source stream.
+pub fn rate_limit(options: &RateLimitOptions) -> TransformStream {
+    let burst = options.burst.unwrap_or(options.bytes_per_second);
+    TransformStream::from_transformer(RateLimit {
+        bytes_per_millisecond: options.bytes_per_second / 1000.0,
+        burst,
+        tokens: burst,
+        last_refill: js_sys::Date::now(),
+    })
+}
+
+#[derive(Debug)]
+struct RateLimit {
+    bytes_per_millisecond: f64,
+    burst: f64,
+    /// The number of bytes that can currently pass through without delay.
+    /// This becomes negative while a chunk is being delayed.
+    tokens: f64,
+    last_refill: f64,
+}
+
+impl RateLimit {
+    /// Takes the tokens for a chunk of the given length,
+    /// and returns how many milliseconds the chunk must be delayed.
+    fn take(&mut self, length: f64) -> f64 {
+        let now = js_sys::Date::now();
+        let elapsed = (now - self.last_refill).max(0.0);
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.bytes_per_millisecond).min(self.burst);
+        self.tokens -= length;
+        if self.tokens < 0.0 {
+            -self.tokens / self.bytes_per_millisecond
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Transformer for RateLimit {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let delay = self.take(f64::from(chunk_byte_length(&chunk)));
+        async move {
+            if delay > 0.0 {
+                sleep(delay).await?;
+            }
+            controller.enqueue(&chunk)
+        }
+        .boxed_local()
+    }
+}
@@ -0,0 +1,103 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Creates a [`TransformStream`](TransformStream) that splits and combines
+/// [`Uint8Array`](Uint8Array) chunks into chunks of exactly `size` bytes.
+///
+/// Only the last chunk may be shorter, if the total length of the stream is not a multiple
+/// of `size`. Empty chunks are ignored. Any chunk that is not a `Uint8Array` errors the stream.
+///
+/// Where possible, the output chunks are views into the input chunks rather than copies.
+///
+/// **Panics** if `size` is 0.
+pub fn rechunk(size: usize) -> TransformStream {
+    assert!(size > 0, "chunk size must be greater than zero");
+    TransformStream::from_transformer(Rechunk {
+        size,
+        pending: Vec::new(),
+    })
+}
+
+#[derive(Debug)]
+struct Rechunk {
+    size: usize,
+    /// The bytes of the next output chunk, which is not yet full.
+    pending: Vec<u8>,
+}
+
+impl Rechunk {
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let chunk = chunk
+            .dyn_into::<Uint8Array>()
+            .map_err(|_| js_sys::TypeError::new("chunk must be a Uint8Array"))?;
+        let length = chunk.length() as usize;
+        let mut offset = 0;
+
+        // Complete the pending chunk first
+        if !self.pending.is_empty() {
+            let needed = (self.size - self.pending.len()).min(length);
+            let start = self.pending.len();
+            self.pending.resize(start + needed, 0);
+            chunk
+                .subarray(0, needed as u32)
+                .copy_to(&mut self.pending[start..]);
+            offset = needed;
+            if self.pending.len() < self.size {
+                return Ok(());
+            }
+            let full = Uint8Array::from(&self.pending[..]);
+            self.pending.clear();
+            controller.enqueue(&full)?;
+        }
+
+        // Pass on full chunks as views into the input chunk
+        while length - offset >= self.size {
+            let end = offset + self.size;
+            controller.enqueue(&chunk.subarray(offset as u32, end as u32))?;
+            offset = end;
+        }
+
+        // Keep the rest for the next chunk
+        if offset < length {
+            self.pending.resize(length - offset, 0);
+            chunk
+                .subarray(offset as u32, length as u32)
+                .copy_to(&mut self.pending);
+        }
+        Ok(())
+    }
+
+    fn flush_sync(&mut self, controller: &TransformStreamDefaultController) -> Result<(), JsValue> {
+        if !self.pending.is_empty() {
+            let last = Uint8Array::from(&self.pending[..]);
+            self.pending.clear();
+            controller.enqueue(&last)?;
+        }
+        Ok(())
+    }
+}
+
+impl Transformer for Rechunk {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.flush_sync(controller)).boxed_local()
+    }
+}
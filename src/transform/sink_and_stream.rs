@@ -0,0 +1,90 @@
+use core::pin::Pin;
+
+use futures::future::{ready, FutureExt, LocalBoxFuture, RemoteHandle};
+use futures::sink::{Sink, SinkExt};
+use futures::stream::{Stream, StreamExt};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+use super::{TransformStreamDefaultController, Transformer};
+
+/// A [`Transformer`](Transformer) that sends every chunk into a Rust [`Sink`](Sink),
+/// and enqueues the items of a separate Rust [`Stream`](Stream) on the readable side.
+pub(super) struct SinkAndStream<Si, St> {
+    sink: Pin<Box<Si>>,
+    stream: Option<St>,
+    pump: Option<RemoteHandle<()>>,
+}
+
+impl<Si, St> SinkAndStream<Si, St> {
+    pub(super) fn new(sink: Si, stream: St) -> Self {
+        Self {
+            sink: Box::pin(sink),
+            stream: Some(stream),
+            pump: None,
+        }
+    }
+}
+
+/// Enqueues the items of `stream` until it ends, errors, or the readable side is canceled.
+async fn pump<St>(stream: St, controller: TransformStreamDefaultController)
+where
+    St: Stream<Item = Result<JsValue, JsValue>>,
+{
+    let mut stream = Box::pin(stream);
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(chunk) => {
+                if controller.enqueue(&chunk).is_err() {
+                    // The readable side was canceled or errored
+                    return;
+                }
+            }
+            Err(error) => {
+                controller.error(&error);
+                return;
+            }
+        }
+    }
+}
+
+impl<Si, St> Transformer for SinkAndStream<Si, St>
+where
+    Si: Sink<JsValue, Error = JsValue> + 'static,
+    St: Stream<Item = Result<JsValue, JsValue>> + 'static,
+{
+    fn start<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        if let Some(stream) = self.stream.take() {
+            let (pump, handle) = pump(stream, controller.clone()).remote_handle();
+            spawn_local(pump);
+            self.pump = Some(handle);
+        }
+        ready(Ok(())).boxed_local()
+    }
+
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.sink.send(chunk).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            self.sink.close().await?;
+            // Keep the readable side open until the stream has produced all of its items.
+            if let Some(pump) = self.pump.take() {
+                pump.await;
+            }
+            Ok(())
+        }
+        .boxed_local()
+    }
+}
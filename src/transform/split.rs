@@ -0,0 +1,141 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+/// Creates a [`TransformStream`](TransformStream) that splits a stream into one chunk per line.
+///
+/// Lines are separated by `\n` or `\r\n`. The line separators are not included in the output.
+/// Partial lines are buffered across chunks, and any trailing data after the last
+/// line separator is output as the final line.
+///
+/// See [`split_by`](split_by) for the supported chunk types.
+pub fn lines() -> TransformStream {
+    TransformStream::from_transformer(Splitter::new(b"\n", true))
+}
+
+/// Creates a [`TransformStream`](TransformStream) that splits a stream into one chunk per
+/// segment separated by the given `delimiter`.
+///
+/// The delimiters are not included in the output. Partial segments are buffered across chunks,
+/// and any trailing data after the last delimiter is output as the final segment.
+///
+/// The stream can either be a byte stream or a text stream:
+///
+/// * If the chunks are [`Uint8Array`](Uint8Array)s, then every segment is output
+///   as a `Uint8Array`.
+/// * If the chunks are strings, then they are split on the UTF-8 encoding of the delimiter,
+///   and every segment is output as a string.
+///
+/// Any other chunk, or mixing both types of chunks, errors the stream with a
+/// [`TypeError`](js_sys::TypeError).
+///
+/// **Panics** if `delimiter` is empty.
+pub fn split_by(delimiter: &[u8]) -> TransformStream {
+    assert!(!delimiter.is_empty(), "delimiter must not be empty");
+    TransformStream::from_transformer(Splitter::new(delimiter, false))
+}
+
+#[derive(Debug)]
+struct Splitter {
+    delimiter: Vec<u8>,
+    /// Whether to remove a trailing `\r` from every segment.
+    strip_cr: bool,
+    /// Whether the chunks are strings, or `None` if no chunk has been seen yet.
+    text: Option<bool>,
+    /// The data after the last delimiter.
+    buffer: Vec<u8>,
+}
+
+impl Splitter {
+    fn new(delimiter: &[u8], strip_cr: bool) -> Self {
+        Self {
+            delimiter: delimiter.to_vec(),
+            strip_cr,
+            text: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn enqueue_segment(
+        &self,
+        mut segment: &[u8],
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        if self.strip_cr {
+            segment = segment.strip_suffix(b"\r").unwrap_or(segment);
+        }
+        if self.text == Some(true) {
+            controller.enqueue(&JsValue::from_str(&String::from_utf8_lossy(segment)))
+        } else {
+            controller.enqueue(&Uint8Array::from(segment))
+        }
+    }
+
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let (text, bytes) = match chunk.as_string() {
+            Some(text) => (true, text.into_bytes()),
+            None => match chunk.dyn_into::<Uint8Array>() {
+                Ok(chunk) => (false, chunk.to_vec()),
+                Err(_) => {
+                    return Err(
+                        js_sys::TypeError::new("chunk must be a string or a Uint8Array").into(),
+                    )
+                }
+            },
+        };
+        if *self.text.get_or_insert(text) != text {
+            return Err(js_sys::TypeError::new("cannot mix string and Uint8Array chunks").into());
+        }
+
+        // A delimiter may start in the data we already buffered.
+        let mut search_from = self.buffer.len().saturating_sub(self.delimiter.len() - 1);
+        self.buffer.extend_from_slice(&bytes);
+        let mut segment_start = 0;
+        while let Some(pos) = find(&self.buffer[search_from..], &self.delimiter) {
+            let segment_end = search_from + pos;
+            self.enqueue_segment(&self.buffer[segment_start..segment_end], controller)?;
+            segment_start = segment_end + self.delimiter.len();
+            search_from = segment_start;
+        }
+        self.buffer.drain(..segment_start);
+        Ok(())
+    }
+
+    fn flush_sync(&mut self, controller: &TransformStreamDefaultController) -> Result<(), JsValue> {
+        if !self.buffer.is_empty() {
+            self.enqueue_segment(&self.buffer, controller)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+impl Transformer for Splitter {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.flush_sync(controller)).boxed_local()
+    }
+}
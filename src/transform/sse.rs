@@ -0,0 +1,237 @@
+use core::pin::Pin;
+use std::collections::VecDeque;
+
+use futures::ready;
+use futures::stream::{FusedStream, Stream, StreamExt};
+use futures::task::{Context, Poll};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::text_decoder::Utf8Decoder;
+use crate::readable::{IntoStream, ReadableStream};
+
+/// An event parsed from a [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+/// stream by [`SseStream`](SseStream).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The last event ID received on the stream, if any.
+    ///
+    /// As with `EventSource`, this is remembered across events until the server changes it.
+    pub id: Option<String>,
+    /// The event type, which is `"message"` unless the server specified another type.
+    pub event: String,
+    /// The event data. Multiple `data` fields are joined with a newline.
+    pub data: String,
+    /// The reconnection time in milliseconds, if the server specified it for this event.
+    ///
+    /// The server can also set the reconnection time without sending any data.
+    /// Use [`SseStream::reconnection_time`](SseStream::reconnection_time) to get
+    /// the latest reconnection time in either case.
+    pub retry: Option<u32>,
+}
+
+/// A [`Stream`](Stream) of [server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+/// parsed from a `text/event-stream` [`ReadableStream`](ReadableStream).
+///
+/// This allows consuming server-sent events from any readable stream, such as the body of a
+/// [`fetch()`](https://developer.mozilla.org/en-US/docs/Web/API/fetch) response.
+/// Unlike `EventSource`, this supports any request method and custom headers.
+///
+/// The stream can either be a byte stream or a text stream. Byte streams are decoded as UTF-8.
+/// Comment lines are ignored, and events without any data are not yielded.
+/// An incomplete event at the end of the stream is discarded.
+///
+/// Errors from the `ReadableStream` are passed through as-is, and end this stream.
+/// This stream holds a reader, and therefore locks the `ReadableStream`.
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct SseStream {
+    inner: IntoStream<'static>,
+    decoder: Utf8Decoder,
+    parser: SseParser,
+    done: bool,
+}
+
+impl SseStream {
+    /// Creates a new `SseStream` which parses events from the given `readable` stream.
+    ///
+    /// **Panics** if the stream is already locked to a reader. For a non-panicking variant,
+    /// use [`try_new`](Self::try_new).
+    pub fn new(readable: ReadableStream) -> Self {
+        Self::try_new(readable)
+            .map_err(|(err, _)| err)
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to create a new `SseStream` which parses events from the given `readable` stream.
+    ///
+    /// If the stream is already locked to a reader, then this returns an error
+    /// along with the original `ReadableStream`.
+    pub fn try_new(readable: ReadableStream) -> Result<Self, (js_sys::Error, ReadableStream)> {
+        Ok(Self {
+            inner: readable.try_into_stream()?,
+            decoder: Utf8Decoder::default(),
+            parser: SseParser::default(),
+            done: false,
+        })
+    }
+
+    /// Returns the latest reconnection time in milliseconds, if the server specified it.
+    ///
+    /// As with `EventSource`, this is updated as soon as a `retry` field is received,
+    /// even if the event containing it has no data and is therefore not yielded.
+    #[inline]
+    pub fn reconnection_time(&self) -> Option<u32> {
+        self.parser.reconnection_time
+    }
+
+    fn feed_chunk(&mut self, chunk: JsValue) -> Result<(), JsValue> {
+        if let Some(text) = chunk.as_string() {
+            self.parser.feed(&text);
+            return Ok(());
+        }
+        match chunk.dyn_into::<Uint8Array>() {
+            Ok(chunk) => {
+                let text = self.decoder.decode(chunk.to_vec());
+                self.parser.feed(&text);
+                Ok(())
+            }
+            Err(_) => Err(js_sys::TypeError::new("chunk must be a string or a Uint8Array").into()),
+        }
+    }
+}
+
+impl FusedStream for SseStream {
+    fn is_terminated(&self) -> bool {
+        self.done && self.parser.events.is_empty()
+    }
+}
+
+impl Stream for SseStream {
+    type Item = Result<SseEvent, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(event) = this.parser.events.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match ready!(this.inner.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => {
+                    if let Err(error) = this.feed_chunk(chunk) {
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                }
+                Some(Err(error)) => {
+                    // The readable stream errored, and won't produce any more chunks.
+                    this.done = true;
+                    return Poll::Ready(Some(Err(error)));
+                }
+                None => {
+                    // Any remaining bytes can only complete the last line, but the last event
+                    // is incomplete anyway and will be discarded.
+                    this.parser.feed(&this.decoder.finish());
+                    this.done = true;
+                }
+            }
+        }
+    }
+}
+
+/// An incremental parser for the `text/event-stream` format.
+#[derive(Debug, Default)]
+struct SseParser {
+    /// The text after the last line ending.
+    buffer: String,
+    /// Whether the last line ended with a `\r`, which may be followed by a `\n`.
+    skip_lf: bool,
+    last_event_id: Option<String>,
+    event: String,
+    data: String,
+    retry: Option<u32>,
+    reconnection_time: Option<u32>,
+    events: VecDeque<SseEvent>,
+}
+
+impl SseParser {
+    fn feed(&mut self, text: &str) {
+        self.buffer.push_str(text);
+        let mut start = 0;
+        loop {
+            if self.skip_lf && start < self.buffer.len() {
+                if self.buffer[start..].starts_with('\n') {
+                    start += 1;
+                }
+                self.skip_lf = false;
+            }
+            let end = match self.buffer[start..].find(['\r', '\n']) {
+                Some(pos) => start + pos,
+                None => break,
+            };
+            self.skip_lf = self.buffer[end..].starts_with('\r');
+            let line = self.buffer[start..end].to_owned();
+            self.process_line(&line);
+            start = end + 1;
+        }
+        self.buffer.drain(..start);
+    }
+
+    fn process_line(&mut self, line: &str) {
+        if line.is_empty() {
+            self.dispatch();
+            return;
+        }
+        if line.starts_with(':') {
+            // Comment
+            return;
+        }
+        let (field, value) = match line.find(':') {
+            Some(pos) => {
+                let value = &line[pos + 1..];
+                (&line[..pos], value.strip_prefix(' ').unwrap_or(value))
+            }
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_owned(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => {
+                self.last_event_id = Some(value.to_owned()).filter(|id| !id.is_empty());
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                self.retry = value.parse().ok();
+                if self.retry.is_some() {
+                    self.reconnection_time = self.retry;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self) {
+        let event = std::mem::take(&mut self.event);
+        let mut data = std::mem::take(&mut self.data);
+        let retry = self.retry.take();
+        if data.is_empty() {
+            return;
+        }
+        data.pop();
+        self.events.push_back(SseEvent {
+            id: self.last_event_id.clone(),
+            event: if event.is_empty() {
+                "message".to_owned()
+            } else {
+                event
+            },
+            data,
+            retry,
+        });
+    }
+}
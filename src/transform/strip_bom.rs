@@ -0,0 +1,152 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{TransformStream, TransformStreamDefaultController, Transformer};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+
+/// Creates a [`TransformStream`](TransformStream) that removes a leading
+/// [byte order mark](https://en.wikipedia.org/wiki/Byte_order_mark) from a stream.
+///
+/// * If the first chunk is a string, a leading `U+FEFF` character is removed.
+/// * If the first chunks are [`Uint8Array`](Uint8Array)s, a leading UTF-8 (`EF BB BF`)
+///   or UTF-16 (`FE FF` or `FF FE`) byte order mark is removed.
+///   The byte order mark may be split across multiple chunks.
+///
+/// All other chunks are passed through untouched.
+pub fn strip_bom() -> TransformStream {
+    TransformStream::from_transformer(StripBom::default())
+}
+
+#[derive(Debug, Default)]
+struct StripBom {
+    /// Whether we've seen enough of the stream to know if it starts with a BOM.
+    done: bool,
+    /// The leading bytes that might still be the start of a BOM.
+    pending: Vec<u8>,
+}
+
+enum BomMatch {
+    /// The bytes start with a BOM of the given length.
+    Full(usize),
+    /// The bytes could still be the start of a BOM.
+    Partial,
+    /// The bytes do not start with a BOM.
+    None,
+}
+
+fn match_bom(bytes: &[u8]) -> BomMatch {
+    let mut partial = false;
+    for bom in &[UTF8_BOM, UTF16_BE_BOM, UTF16_LE_BOM] {
+        if bytes.starts_with(bom) {
+            return BomMatch::Full(bom.len());
+        }
+        if bom.starts_with(bytes) {
+            partial = true;
+        }
+    }
+    if partial {
+        BomMatch::Partial
+    } else {
+        BomMatch::None
+    }
+}
+
+impl StripBom {
+    fn flush_pending(
+        &mut self,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        if !self.pending.is_empty() {
+            let pending = Uint8Array::from(&self.pending[..]);
+            self.pending.clear();
+            controller.enqueue(&pending)?;
+        }
+        Ok(())
+    }
+
+    fn transform_bytes(
+        &mut self,
+        chunk: Uint8Array,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let pending_len = self.pending.len();
+        // We never need more than the length of the longest BOM.
+        let needed = (UTF8_BOM.len() - pending_len).min(chunk.length() as usize);
+        let mut prefix = self.pending.clone();
+        prefix.extend(chunk.subarray(0, needed as u32).to_vec());
+        match match_bom(&prefix) {
+            BomMatch::Full(bom_len) => {
+                self.done = true;
+                self.pending.clear();
+                let rest = chunk.subarray((bom_len - pending_len) as u32, chunk.length());
+                if rest.length() > 0 {
+                    controller.enqueue(&rest)?;
+                }
+            }
+            BomMatch::Partial if prefix.len() < UTF8_BOM.len() => {
+                // Wait for more bytes before deciding.
+                self.pending = prefix;
+            }
+            BomMatch::Partial | BomMatch::None => {
+                self.done = true;
+                self.flush_pending(controller)?;
+                controller.enqueue(&chunk)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        if self.done {
+            return controller.enqueue(&chunk);
+        }
+        if let Some(text) = chunk.as_string() {
+            if text.is_empty() {
+                return controller.enqueue(&chunk);
+            }
+            self.done = true;
+            self.flush_pending(controller)?;
+            return match text.strip_prefix('\u{FEFF}') {
+                Some("") => Ok(()),
+                Some(rest) => controller.enqueue(&JsValue::from_str(rest)),
+                None => controller.enqueue(&chunk),
+            };
+        }
+        match chunk.dyn_into::<Uint8Array>() {
+            Ok(bytes) if bytes.length() == 0 => controller.enqueue(&bytes),
+            Ok(bytes) => self.transform_bytes(bytes, controller),
+            Err(chunk) => {
+                self.done = true;
+                self.flush_pending(controller)?;
+                controller.enqueue(&chunk)
+            }
+        }
+    }
+}
+
+impl Transformer for StripBom {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // The stream ended while we were still waiting for the rest of a BOM.
+        ready(self.flush_pending(controller)).boxed_local()
+    }
+}
@@ -1,22 +1,223 @@
 //! Raw bindings to JavaScript objects used
 //! by a [`TransformStream`](https://developer.mozilla.org/en-US/docs/Web/API/TransformStream).
+use js_sys::{Function, Object};
 use wasm_bindgen::prelude::*;
 
+use crate::queuing_strategy::sys::QueuingStrategy;
 use crate::readable::sys::ReadableStream;
+use crate::util::has_object_properties;
 use crate::writable::sys::WritableStream;
 
+use super::into_underlying_transformer::IntoUnderlyingTransformer;
+
 #[wasm_bindgen]
 extern "C" {
     /// A raw [`TransformStream`](https://developer.mozilla.org/en-US/docs/Web/API/TransformStream).
+    ///
+    /// Type checks such as [`dyn_into`][wasm_bindgen::JsCast::dyn_into] only check that
+    /// the object has a `readable` and a `writable` property, so they also accept streams
+    /// from other realms and from polyfills, as well as plain readable/writable pairs.
+    #[wasm_bindgen(is_type_of = is_transform_stream)]
     #[derive(Clone, Debug)]
     pub type TransformStream;
 
     #[wasm_bindgen(constructor)]
     pub fn new() -> TransformStream;
 
+    /// Creates a new stream from a [transformer](Transformer) and queuing strategies.
+    ///
+    /// Use [`new_with_transformer_and_strategies`](Self::new_with_transformer_and_strategies)
+    /// to catch errors thrown by the constructor.
+    #[wasm_bindgen(constructor)]
+    pub fn new_with_strategies(
+        transformer: &JsValue,
+        writable_strategy: &QueuingStrategy,
+        readable_strategy: &QueuingStrategy,
+    ) -> TransformStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new_with_transformer_and_strategies(
+        transformer: &Transformer,
+        writable_strategy: &QueuingStrategy,
+        readable_strategy: &QueuingStrategy,
+    ) -> Result<TransformStream, JsValue>;
+
+    #[wasm_bindgen(constructor)]
+    pub(crate) fn new_with_transformer(
+        transformer: IntoUnderlyingTransformer,
+        writable_strategy: &QueuingStrategy,
+        readable_strategy: &QueuingStrategy,
+    ) -> TransformStream;
+
     #[wasm_bindgen(method, getter, js_name = readable)]
     pub fn readable(this: &TransformStream) -> ReadableStream;
 
     #[wasm_bindgen(method, getter, js_name = writable)]
     pub fn writable(this: &TransformStream) -> WritableStream;
 }
+
+fn is_transform_stream(value: &JsValue) -> bool {
+    has_object_properties(value, &["readable", "writable"])
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`TransformStreamDefaultController`](https://developer.mozilla.org/en-US/docs/Web/API/TransformStreamDefaultController).
+    #[derive(Clone, Debug)]
+    pub type TransformStreamDefaultController;
+
+    #[wasm_bindgen(method, getter, js_name = desiredSize)]
+    pub fn desired_size(this: &TransformStreamDefaultController) -> Option<f64>;
+
+    #[wasm_bindgen(method, catch, js_name = enqueue)]
+    pub fn enqueue(this: &TransformStreamDefaultController, chunk: &JsValue)
+        -> Result<(), JsValue>;
+
+    #[wasm_bindgen(method, js_name = error)]
+    pub fn error(this: &TransformStreamDefaultController, error: &JsValue);
+
+    #[wasm_bindgen(method, js_name = terminate)]
+    pub fn terminate(this: &TransformStreamDefaultController);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [transformer](https://developer.mozilla.org/en-US/docs/Web/API/TransformStream/TransformStream#transformer)
+    /// object, passed to [`TransformStream::new_with_strategies`].
+    ///
+    /// Create an empty one with [`Transformer::new`](Transformer::new), then set its methods.
+    /// Each method receives a [`TransformStreamDefaultController`], and may return a promise.
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type Transformer;
+
+    #[wasm_bindgen(method, getter, js_name = start)]
+    pub fn start(this: &Transformer) -> Option<Function>;
+
+    /// Sets the `start(controller)` method, which is called when the stream is constructed.
+    #[wasm_bindgen(method, setter, js_name = start)]
+    pub fn set_start(this: &Transformer, start: &Function);
+
+    #[wasm_bindgen(method, getter, js_name = transform)]
+    pub fn transform(this: &Transformer) -> Option<Function>;
+
+    /// Sets the `transform(chunk, controller)` method, which is called for every written chunk.
+    ///
+    /// If not set, every chunk is enqueued to the readable side unchanged.
+    #[wasm_bindgen(method, setter, js_name = transform)]
+    pub fn set_transform(this: &Transformer, transform: &Function);
+
+    #[wasm_bindgen(method, getter, js_name = flush)]
+    pub fn flush(this: &Transformer) -> Option<Function>;
+
+    /// Sets the `flush(controller)` method, which is called after all chunks were transformed
+    /// and the writable side is about to close.
+    #[wasm_bindgen(method, setter, js_name = flush)]
+    pub fn set_flush(this: &Transformer, flush: &Function);
+
+    #[wasm_bindgen(method, getter, js_name = readableType)]
+    pub fn readable_type(this: &Transformer) -> JsValue;
+
+    /// Sets the `readableType` property.
+    ///
+    /// This is reserved for future use. The constructor throws a `RangeError`
+    /// if it is set to anything other than `undefined`.
+    #[wasm_bindgen(method, setter, js_name = readableType)]
+    pub fn set_readable_type(this: &Transformer, readable_type: &JsValue);
+
+    #[wasm_bindgen(method, getter, js_name = writableType)]
+    pub fn writable_type(this: &Transformer) -> JsValue;
+
+    /// Sets the `writableType` property.
+    ///
+    /// This is reserved for future use. The constructor throws a `RangeError`
+    /// if it is set to anything other than `undefined`.
+    #[wasm_bindgen(method, setter, js_name = writableType)]
+    pub fn set_writable_type(this: &Transformer, writable_type: &JsValue);
+}
+
+impl Transformer {
+    /// Creates a new, empty transformer object.
+    pub fn new() -> Self {
+        wasm_bindgen::JsCast::unchecked_into(Object::new())
+    }
+}
+
+impl Default for Transformer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`ReadableWritablePair`](https://streams.spec.whatwg.org/#dictdef-readablewritablepair)
+    /// dictionary, i.e. a plain object with a `readable` and a `writable` property.
+    ///
+    /// This can be passed to [`pipe_through_pair`](ReadableStream::pipe_through_pair)
+    /// instead of an actual [`TransformStream`].
+    #[wasm_bindgen(extends = Object)]
+    #[derive(Clone, Debug)]
+    pub type ReadableWritablePair;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &ReadableWritablePair) -> ReadableStream;
+
+    #[wasm_bindgen(method, setter, js_name = readable)]
+    pub fn set_readable(this: &ReadableWritablePair, readable: &ReadableStream);
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &ReadableWritablePair) -> WritableStream;
+
+    #[wasm_bindgen(method, setter, js_name = writable)]
+    pub fn set_writable(this: &ReadableWritablePair, writable: &WritableStream);
+}
+
+impl ReadableWritablePair {
+    /// Creates a new pair object with the given `readable` and `writable` side.
+    pub fn new(readable: &ReadableStream, writable: &WritableStream) -> Self {
+        let pair: Self = wasm_bindgen::JsCast::unchecked_into(Object::new());
+        pair.set_readable(readable);
+        pair.set_writable(writable);
+        pair
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`CompressionStream`](https://developer.mozilla.org/en-US/docs/Web/API/CompressionStream).
+    ///
+    /// This has the same `readable` and `writable` properties as a [`TransformStream`],
+    /// so it can be [cast](wasm_bindgen::JsCast::unchecked_into) to one.
+    #[derive(Clone, Debug)]
+    pub type CompressionStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(format: &str) -> Result<CompressionStream, JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`DecompressionStream`](https://developer.mozilla.org/en-US/docs/Web/API/DecompressionStream).
+    ///
+    /// This has the same `readable` and `writable` properties as a [`TransformStream`],
+    /// so it can be [cast](wasm_bindgen::JsCast::unchecked_into) to one.
+    #[derive(Clone, Debug)]
+    pub type DecompressionStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(format: &str) -> Result<DecompressionStream, JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`TextDecoderStream`](https://developer.mozilla.org/en-US/docs/Web/API/TextDecoderStream).
+    ///
+    /// This has the same `readable` and `writable` properties as a [`TransformStream`],
+    /// so it can be [cast](wasm_bindgen::JsCast::unchecked_into) to one.
+    #[derive(Clone, Debug)]
+    pub type TextDecoderStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new() -> Result<TextDecoderStream, JsValue>;
+}
@@ -0,0 +1,240 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::{ArrayBuffer, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::{sys, TransformStream, TransformStreamDefaultController, Transformer};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// A [`TextDecoderStream`](https://developer.mozilla.org/en-US/docs/Web/API/TextDecoderStream)
+/// which decodes UTF-8 bytes into strings.
+///
+/// This transforms a stream of [`Uint8Array`](Uint8Array) chunks into a stream of strings.
+/// Use [`as_transform_stream`](Self::as_transform_stream) to
+/// [pipe](crate::ReadableStream::pipe_through) a readable stream through it.
+///
+/// Not all browsers support `TextDecoderStream`. Use [`is_supported`](Self::is_supported)
+/// to check for support first, and fall back to [`Utf8DecoderStream`](Utf8DecoderStream)
+/// which has the same API.
+#[derive(Debug)]
+pub struct TextDecoderStream {
+    inner: TransformStream,
+}
+
+// No `Default` implementation, since creating the stream can fail.
+#[allow(clippy::new_without_default)]
+impl TextDecoderStream {
+    /// Returns whether the current environment supports `TextDecoderStream`.
+    pub fn is_supported() -> bool {
+        crate::support::supports_text_decoder_stream()
+    }
+
+    /// Creates a new `TextDecoderStream` for UTF-8.
+    ///
+    /// **Panics** if `TextDecoderStream` is not supported.
+    /// For a non-panicking variant, use [`try_new`](Self::try_new).
+    pub fn new() -> Self {
+        Self::try_new().expect_throw("TextDecoderStream is not supported")
+    }
+
+    /// Try to create a new `TextDecoderStream` for UTF-8.
+    ///
+    /// If `TextDecoderStream` is not supported, then this returns an error.
+    pub fn try_new() -> Result<Self, JsValue> {
+        let raw = sys::TextDecoderStream::new()?;
+        Ok(Self {
+            inner: TransformStream::from_raw(raw.unchecked_into()),
+        })
+    }
+
+    /// Acquires a reference to this stream as a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn as_transform_stream(&self) -> &TransformStream {
+        &self.inner
+    }
+
+    /// Converts this stream into a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn into_transform_stream(self) -> TransformStream {
+        self.inner
+    }
+}
+
+impl From<TextDecoderStream> for TransformStream {
+    #[inline]
+    fn from(stream: TextDecoderStream) -> Self {
+        stream.into_transform_stream()
+    }
+}
+
+/// A transform stream which decodes UTF-8 bytes into strings, implemented in Rust.
+///
+/// This is a fallback for [`TextDecoderStream`](TextDecoderStream) with the same API and
+/// behavior, for environments where the native class is not available:
+///
+/// * Multi-byte sequences may be split across chunks.
+/// * A leading UTF-8 byte order mark is removed.
+/// * Invalid sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// Chunks must be [`Uint8Array`](Uint8Array)s or [`ArrayBuffer`](ArrayBuffer)s.
+/// Any other chunk errors the stream with a [`TypeError`](js_sys::TypeError).
+#[derive(Debug)]
+pub struct Utf8DecoderStream {
+    inner: TransformStream,
+}
+
+// No `Default` implementation, to keep the same API as `TextDecoderStream`.
+#[allow(clippy::new_without_default)]
+impl Utf8DecoderStream {
+    /// Returns whether the current environment supports `Utf8DecoderStream`.
+    ///
+    /// This always returns `true`.
+    pub fn is_supported() -> bool {
+        true
+    }
+
+    /// Creates a new `Utf8DecoderStream`.
+    pub fn new() -> Self {
+        Self {
+            inner: TransformStream::from_transformer(Utf8Decoder::default()),
+        }
+    }
+
+    /// Creates a new `Utf8DecoderStream`.
+    ///
+    /// This never fails, and exists for compatibility with
+    /// [`TextDecoderStream::try_new`](TextDecoderStream::try_new).
+    pub fn try_new() -> Result<Self, JsValue> {
+        Ok(Self::new())
+    }
+
+    /// Acquires a reference to this stream as a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn as_transform_stream(&self) -> &TransformStream {
+        &self.inner
+    }
+
+    /// Converts this stream into a [`TransformStream`](TransformStream).
+    #[inline]
+    pub fn into_transform_stream(self) -> TransformStream {
+        self.inner
+    }
+}
+
+impl From<Utf8DecoderStream> for TransformStream {
+    #[inline]
+    fn from(stream: Utf8DecoderStream) -> Self {
+        stream.into_transform_stream()
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Utf8Decoder {
+    /// Whether we've seen enough of the stream to know if it starts with a BOM.
+    bom_seen: bool,
+    /// The trailing bytes of an incomplete sequence, or the start of a possible BOM.
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    /// Decodes the next bytes of the stream, keeping any incomplete sequence at the end
+    /// for the next call.
+    pub(super) fn decode(&mut self, mut bytes: Vec<u8>) -> String {
+        let pending = std::mem::take(&mut self.pending);
+        if !pending.is_empty() {
+            bytes.splice(..0, pending);
+        }
+        if !self.bom_seen {
+            if bytes.len() < UTF8_BOM.len() && UTF8_BOM.starts_with(&bytes) {
+                // Wait for more bytes before deciding.
+                self.pending = bytes;
+                return String::new();
+            }
+            self.bom_seen = true;
+            if bytes.starts_with(UTF8_BOM) {
+                bytes.drain(..UTF8_BOM.len());
+            }
+        }
+        let mut output = String::with_capacity(bytes.len());
+        let mut rest = &bytes[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    output.push_str(valid);
+                    break;
+                }
+                Err(error) => {
+                    let (valid, after_valid) = rest.split_at(error.valid_up_to());
+                    // This cannot fail, since these bytes were just validated.
+                    output.push_str(std::str::from_utf8(valid).unwrap_throw());
+                    match error.error_len() {
+                        Some(len) => {
+                            output.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after_valid[len..];
+                        }
+                        None => {
+                            // Incomplete sequence at the end, wait for more bytes.
+                            self.pending = after_valid.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    fn transform_sync(
+        &mut self,
+        chunk: JsValue,
+        controller: &TransformStreamDefaultController,
+    ) -> Result<(), JsValue> {
+        let chunk = if let Some(buffer) = chunk.dyn_ref::<ArrayBuffer>() {
+            Uint8Array::new(buffer)
+        } else {
+            chunk.dyn_into::<Uint8Array>().map_err(|_| {
+                js_sys::TypeError::new("chunk must be a Uint8Array or an ArrayBuffer")
+            })?
+        };
+        let output = self.decode(chunk.to_vec());
+        if !output.is_empty() {
+            controller.enqueue(&JsValue::from(output))?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the remaining bytes at the end of the stream.
+    pub(super) fn finish(&mut self) -> String {
+        // The stream ended in the middle of a sequence, which is invalid.
+        // A lone partial BOM is not a BOM either, so it's decoded the same way.
+        let output = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending.clear();
+        output
+    }
+
+    fn flush_sync(&mut self, controller: &TransformStreamDefaultController) -> Result<(), JsValue> {
+        let output = self.finish();
+        if !output.is_empty() {
+            controller.enqueue(&JsValue::from(output))?;
+        }
+        Ok(())
+    }
+}
+
+impl Transformer for Utf8Decoder {
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.transform_sync(chunk, controller)).boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(self.flush_sync(controller)).boxed_local()
+    }
+}
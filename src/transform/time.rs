@@ -0,0 +1,299 @@
+use core::pin::Pin;
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::future::{ready, Future};
+use futures::sink::SinkExt;
+use futures::stream::{FusedStream, Stream, StreamExt};
+use futures::task::{Context, Poll};
+use wasm_bindgen::prelude::*;
+
+use crate::util::{check_timers, sleep, Sleep};
+
+use super::TransformStream;
+
+/// Creates a [`TransformStream`](TransformStream) from a Rust [`Stream`](Stream) adapter,
+/// which is applied to the chunks written to the writable side.
+///
+/// If the adapter cannot be created because the global scope has no timers,
+/// both sides of the returned stream error instead.
+fn from_adapter<F, St>(adapter: F) -> TransformStream
+where
+    F: FnOnce(mpsc::Receiver<JsValue>) -> Result<St, JsValue>,
+    St: Stream<Item = JsValue> + 'static,
+{
+    let (sender, receiver) = mpsc::channel(0);
+    let stream = match adapter(receiver) {
+        Ok(stream) => stream,
+        Err(error) => {
+            let sink = futures::sink::unfold((), {
+                let error = error.clone();
+                move |(), _chunk: JsValue| ready(Err(error.clone()))
+            });
+            let stream = futures::stream::once(ready(Err(error)));
+            return TransformStream::from_sink_and_stream(sink, stream);
+        }
+    };
+    let sink = sender
+        .sink_map_err(|_| JsValue::from(js_sys::Error::new("the readable side was cancelled")));
+    TransformStream::from_sink_and_stream(sink, stream.map(Ok))
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Polls a timer of a `Stream` adapter.
+///
+/// The adapters check for timers when they are created, so starting a timer cannot fail
+/// afterwards. Should it fail anyway, the timer fires right away rather than stalling.
+fn poll_timer(timer: &mut Sleep, cx: &mut Context<'_>) -> Poll<()> {
+    Pin::new(timer).poll(cx).map(|_| ())
+}
+
+/// Creates a [`TransformStream`](TransformStream) that only passes on a chunk once no other
+/// chunk has been written for the given `duration`.
+///
+/// When the writable side is closed, the last pending chunk is passed on immediately.
+/// See [`Debounce`](Debounce) for the equivalent Rust [`Stream`](Stream) adapter.
+///
+/// See also the notes on [time-based transforms](super#time-based-transforms).
+pub fn debounce(duration: Duration) -> TransformStream {
+    from_adapter(|chunks| Debounce::new(chunks, duration))
+}
+
+/// Creates a [`TransformStream`](TransformStream) that passes on at most one chunk
+/// per `duration`.
+///
+/// A chunk is passed on right away if no chunk was passed on during the last `duration`,
+/// otherwise it is dropped. See [`Throttle`](Throttle) for the equivalent Rust
+/// [`Stream`](Stream) adapter.
+///
+/// See also the notes on [time-based transforms](super#time-based-transforms).
+pub fn throttle(duration: Duration) -> TransformStream {
+    from_adapter(|chunks| Throttle::new(chunks, duration))
+}
+
+/// Creates a [`TransformStream`](TransformStream) that passes on the most recently written
+/// chunk once every `duration`.
+///
+/// If no chunk was written since the previous sample, nothing is passed on. When the writable
+/// side is closed, the last chunk is passed on immediately if it was not yet sampled.
+/// See [`Sample`](Sample) for the equivalent Rust [`Stream`](Stream) adapter.
+///
+/// See also the notes on [time-based transforms](super#time-based-transforms).
+pub fn sample(duration: Duration) -> TransformStream {
+    from_adapter(|chunks| Sample::new(chunks, duration))
+}
+
+/// A [`Stream`](Stream) adapter that only yields an item once the inner stream has not
+/// produced another item for a given duration.
+///
+/// When the inner stream ends, the last pending item is yielded immediately.
+/// See also [`debounce`](debounce).
+///
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Debounce<St: Stream> {
+    stream: St,
+    duration: f64,
+    pending: Option<St::Item>,
+    timer: Option<Sleep>,
+    done: bool,
+}
+
+impl<St: Stream + Unpin> Debounce<St> {
+    /// Creates a new `Debounce` adapter for the given `stream`.
+    ///
+    /// If the global scope has no timers, such as in a worklet, then this returns an error.
+    /// See [time-based transforms](super#time-based-transforms).
+    pub fn new(stream: St, duration: Duration) -> Result<Self, JsValue> {
+        check_timers()?;
+        Ok(Self {
+            stream,
+            duration: millis(duration),
+            pending: None,
+            timer: None,
+            done: false,
+        })
+    }
+}
+
+impl<St: Stream + Unpin> Stream for Debounce<St>
+where
+    St::Item: Unpin,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done {
+            match this.stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.pending = Some(item);
+                    this.timer = Some(sleep(this.duration));
+                }
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => break,
+            }
+        }
+        if this.done {
+            this.timer = None;
+            return Poll::Ready(this.pending.take());
+        }
+        match this.timer.as_mut() {
+            Some(timer) => match poll_timer(timer, cx) {
+                Poll::Ready(()) => {
+                    this.timer = None;
+                    Poll::Ready(this.pending.take())
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<St: Stream + Unpin> FusedStream for Debounce<St>
+where
+    St::Item: Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.done && self.pending.is_none()
+    }
+}
+
+/// A [`Stream`](Stream) adapter that yields at most one item of the inner stream
+/// per given duration.
+///
+/// An item is yielded right away if no item was yielded during the last duration,
+/// otherwise it is dropped. See also [`throttle`](throttle).
+///
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Throttle<St> {
+    stream: St,
+    duration: f64,
+    /// Runs while items are being dropped.
+    window: Option<Sleep>,
+}
+
+impl<St: Stream + Unpin> Throttle<St> {
+    /// Creates a new `Throttle` adapter for the given `stream`.
+    ///
+    /// If the global scope has no timers, such as in a worklet, then this returns an error.
+    /// See [time-based transforms](super#time-based-transforms).
+    pub fn new(stream: St, duration: Duration) -> Result<Self, JsValue> {
+        check_timers()?;
+        Ok(Self {
+            stream,
+            duration: millis(duration),
+            window: None,
+        })
+    }
+}
+
+impl<St: Stream + Unpin> Stream for Throttle<St> {
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if let Some(window) = this.window.as_mut() {
+                if poll_timer(window, cx).is_ready() {
+                    this.window = None;
+                }
+            }
+            match this.stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => {
+                    if this.window.is_none() {
+                        this.window = Some(sleep(this.duration));
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// A [`Stream`](Stream) adapter that yields the most recent item of the inner stream
+/// once every given duration.
+///
+/// If the inner stream did not produce an item since the previous sample, nothing is yielded.
+/// When the inner stream ends, its last item is yielded immediately if it was not yet sampled.
+/// See also [`sample`](sample).
+///
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Sample<St: Stream> {
+    stream: St,
+    duration: f64,
+    latest: Option<St::Item>,
+    timer: Option<Sleep>,
+    done: bool,
+}
+
+impl<St: Stream + Unpin> Sample<St> {
+    /// Creates a new `Sample` adapter for the given `stream`.
+    ///
+    /// The first sample is taken one `duration` after the adapter is first polled.
+    ///
+    /// If the global scope has no timers, such as in a worklet, then this returns an error.
+    /// See [time-based transforms](super#time-based-transforms).
+    pub fn new(stream: St, duration: Duration) -> Result<Self, JsValue> {
+        check_timers()?;
+        Ok(Self {
+            stream,
+            duration: millis(duration),
+            latest: None,
+            timer: None,
+            done: false,
+        })
+    }
+}
+
+impl<St: Stream + Unpin> Stream for Sample<St>
+where
+    St::Item: Unpin,
+{
+    type Item = St::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done {
+            match this.stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => this.latest = Some(item),
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => break,
+            }
+        }
+        if this.done {
+            this.timer = None;
+            return Poll::Ready(this.latest.take());
+        }
+        loop {
+            let duration = this.duration;
+            let timer = this.timer.get_or_insert_with(|| sleep(duration));
+            match poll_timer(timer, cx) {
+                Poll::Ready(()) => {
+                    this.timer = None;
+                    if let Some(item) = this.latest.take() {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<St: Stream + Unpin> FusedStream for Sample<St>
+where
+    St::Item: Unpin,
+{
+    fn is_terminated(&self) -> bool {
+        self.done && self.latest.is_none()
+    }
+}
@@ -0,0 +1,97 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+
+use super::sys;
+
+/// A Rust implementation of a JavaScript [transformer](https://streams.spec.whatwg.org/#transformer-api),
+/// which defines the behavior of a [`TransformStream`](super::TransformStream).
+///
+/// Use [`TransformStream::from_transformer`](super::TransformStream::from_transformer)
+/// to create a transform stream from a `Transformer`.
+///
+/// All methods return a boxed future, which allows them to perform asynchronous work.
+/// The transform stream waits for each future to complete before calling the next method.
+/// Synchronous implementations can simply return [`ready(result).boxed_local()`](futures::future::ready).
+///
+/// If any of these futures returns an error, the transform stream becomes errored.
+pub trait Transformer {
+    /// Called once when the transform stream is created,
+    /// before any chunks are written to its writable side.
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+
+    /// Transforms a single `chunk` written to the writable side,
+    /// and enqueues the results on the readable side using the `controller`.
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+
+    /// Called after all chunks written to the writable side have been transformed,
+    /// right before the readable side is closed.
+    fn flush<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+}
+
+/// A [`TransformStreamDefaultController`](https://developer.mozilla.org/en-US/docs/Web/API/TransformStreamDefaultController)
+/// that is passed to the methods of a [`Transformer`](Transformer).
+#[derive(Clone, Debug)]
+pub struct TransformStreamDefaultController {
+    raw: sys::TransformStreamDefaultController,
+}
+
+impl TransformStreamDefaultController {
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn from_raw(raw: sys::TransformStreamDefaultController) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript controller](sys::TransformStreamDefaultController).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::TransformStreamDefaultController {
+        &self.raw
+    }
+
+    /// Returns the desired size to fill the readable side's internal queue.
+    ///
+    /// It can be negative, if the queue is over-full.
+    /// It will be `None` if the readable side is errored.
+    #[inline]
+    pub fn desired_size(&self) -> Option<f64> {
+        self.as_raw().desired_size()
+    }
+
+    /// Enqueues the given `chunk` on the readable side.
+    ///
+    /// This returns an error if the readable side can no longer accept chunks,
+    /// e.g. because it was canceled.
+    #[inline]
+    pub fn enqueue(&self, chunk: &JsValue) -> Result<(), JsValue> {
+        self.as_raw().enqueue(chunk)
+    }
+
+    /// Errors both the readable side and the writable side with the given `error`.
+    #[inline]
+    pub fn error(&self, error: &JsValue) {
+        self.as_raw().error(error)
+    }
+
+    /// Closes the readable side and errors the writable side.
+    ///
+    /// This is useful when the transformer only needs to consume a portion of the chunks
+    /// written to the writable side.
+    #[inline]
+    pub fn terminate(&self) {
+        self.as_raw().terminate()
+    }
+}
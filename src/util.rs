@@ -1,6 +1,19 @@
-use js_sys::Promise;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::future::{select, Either};
+use futures::ready;
+
+use js_sys::{ArrayBuffer, Function, Promise, Uint8Array};
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::AbortSignal;
+
+use crate::StreamError;
 
 pub(crate) async fn promise_to_void_future(promise: Promise) -> Result<(), JsValue> {
     let js_value = JsFuture::from(promise).await?;
@@ -8,3 +21,251 @@ pub(crate) async fn promise_to_void_future(promise: Promise) -> Result<(), JsVal
     let _ = js_value;
     Ok(())
 }
+
+/// Waits for the given promise like [`promise_to_void_future`], but stops waiting as soon as
+/// the given `signal` is aborted, returning an [`Aborted`](StreamError::Aborted) error
+/// with its abort reason.
+pub(crate) async fn promise_to_void_future_with_signal(
+    promise: Promise,
+    signal: &AbortSignal,
+) -> Result<(), StreamError> {
+    match select(JsFuture::from(promise), aborted(signal)).await {
+        Either::Left((result, _)) => result
+            .map(|_| ())
+            .map_err(|error| StreamError::from_js_with_signal(error, signal)),
+        Either::Right((reason, _)) => Err(StreamError::Aborted(reason)),
+    }
+}
+
+/// Waits for the given promise in the background, discarding its result.
+///
+/// This marks a rejection of the promise as handled.
+pub(crate) fn detach_promise(promise: Promise) {
+    spawn_local(async move {
+        let _ = JsFuture::from(promise).await;
+    });
+}
+
+/// Converts a future into a promise, like [`future_to_promise`](wasm_bindgen_futures::future_to_promise),
+/// but polls it once right away.
+///
+/// If the future completes immediately, its side effects (such as dropping resources) happen
+/// synchronously instead of on the next tick. Otherwise, the future continues in the background.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn future_to_promise_eager<F>(fut: F) -> Promise
+where
+    F: Future<Output = Result<JsValue, JsValue>> + 'static,
+{
+    let mut fut = Box::pin(fut);
+    // The executor polls the future again with its own waker, so a noop waker is fine here.
+    let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+    match fut.as_mut().poll(&mut cx) {
+        Poll::Ready(Ok(value)) => Promise::resolve(&value),
+        Poll::Ready(Err(error)) => Promise::reject(&error),
+        Poll::Pending => wasm_bindgen_futures::future_to_promise(fut),
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    // Available on both windows and workers, but not in worklets.
+    #[wasm_bindgen(catch, js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: f64) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = clearTimeout)]
+    fn clear_timeout(handle: &JsValue);
+}
+
+/// A future which resolves after a timeout, returned by [`sleep`].
+///
+/// Dropping the future before it resolves clears the underlying timer.
+#[must_use = "futures do nothing unless polled"]
+pub(crate) struct Sleep {
+    handle: Option<JsValue>,
+    // Set if the timer could not be started.
+    error: Option<JsValue>,
+    promise: JsFuture,
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("pending", &self.handle.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Future for Sleep {
+    type Output = Result<(), JsValue>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(error) = self.error.take() {
+            return Poll::Ready(Err(error));
+        }
+        // The promise never rejects.
+        let _ = ready!(Pin::new(&mut self.promise).poll(cx));
+        self.handle = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            clear_timeout(&handle);
+        }
+    }
+}
+
+fn no_timers_error() -> JsValue {
+    js_sys::Error::new("setTimeout() is not available in this global scope").into()
+}
+
+/// Returns an error if the global scope has no `setTimeout()`, such as in a worklet.
+pub(crate) fn check_timers() -> Result<(), JsValue> {
+    match js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout")) {
+        Ok(set_timeout) if set_timeout.is_function() => Ok(()),
+        _ => Err(no_timers_error()),
+    }
+}
+
+/// Returns a future which resolves after the given number of milliseconds.
+///
+/// If the global scope has no `setTimeout()`, such as in a worklet, the future
+/// resolves with an error right away.
+pub(crate) fn sleep(millis: f64) -> Sleep {
+    let mut result = Ok(JsValue::UNDEFINED);
+    let promise = Promise::new(&mut |resolve, _reject| {
+        result = set_timeout(&resolve, millis);
+    });
+    let (handle, error) = match result {
+        Ok(handle) => (Some(handle), None),
+        Err(_) => (None, Some(no_timers_error())),
+    };
+    Sleep {
+        handle,
+        error,
+        promise: JsFuture::from(promise),
+    }
+}
+
+/// A future which resolves with the [abort reason](AbortSignal::reason) of a signal
+/// once it is aborted, returned by [`aborted`].
+///
+/// Dropping the future before it resolves removes its event listener from the signal.
+#[must_use = "futures do nothing unless polled"]
+pub(crate) struct Aborted {
+    signal: AbortSignal,
+    listener: Option<Closure<dyn FnMut()>>,
+    promise: JsFuture,
+}
+
+impl Aborted {
+    fn remove_listener(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            let _ = self
+                .signal
+                .remove_event_listener_with_callback("abort", listener.as_ref().unchecked_ref());
+        }
+    }
+}
+
+impl Future for Aborted {
+    type Output = JsValue;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<JsValue> {
+        // The promise never rejects.
+        let reason = ready!(Pin::new(&mut self.promise).poll(cx)).unwrap_or_else(|error| error);
+        self.remove_listener();
+        Poll::Ready(reason)
+    }
+}
+
+impl Drop for Aborted {
+    fn drop(&mut self) {
+        self.remove_listener();
+    }
+}
+
+/// Returns a future which resolves with the abort reason of the given `signal`
+/// once it is aborted.
+pub(crate) fn aborted(signal: &AbortSignal) -> Aborted {
+    let mut resolve: Option<Function> = None;
+    let promise = Promise::new(&mut |resolve_fn, _reject| {
+        resolve = Some(resolve_fn);
+    });
+    let resolve = resolve.unwrap_throw();
+    let listener = if signal.aborted() {
+        let _ = resolve.call1(&JsValue::UNDEFINED, &signal.reason());
+        None
+    } else {
+        let target = signal.clone();
+        let listener = Closure::<dyn FnMut()>::new(move || {
+            let _ = resolve.call1(&JsValue::UNDEFINED, &target.reason());
+        });
+        let _ = signal.add_event_listener_with_callback("abort", listener.as_ref().unchecked_ref());
+        Some(listener)
+    };
+    Aborted {
+        signal: signal.clone(),
+        listener,
+        promise: JsFuture::from(promise),
+    }
+}
+
+/// Creates a `DOMException` named `TimeoutError` with the given message,
+/// which is classified as [`StreamError::Timeout`](crate::StreamError::Timeout).
+pub(crate) fn timeout_error(message: &str) -> web_sys::DomException {
+    web_sys::DomException::new_with_message_and_name(message, "TimeoutError").unwrap_throw()
+}
+
+/// Returns the byte length of the given chunk.
+///
+/// Only chunks that are an [`ArrayBuffer`](ArrayBuffer) or an
+/// [`ArrayBufferView`](https://developer.mozilla.org/en-US/docs/Web/API/ArrayBufferView)
+/// have a byte length, all other chunks count as zero bytes.
+pub(crate) fn chunk_byte_length(chunk: &JsValue) -> u32 {
+    if ArrayBuffer::is_view(chunk) {
+        // All ArrayBufferViews have a byteLength, so treat it like a Uint8Array.
+        chunk.unchecked_ref::<Uint8Array>().byte_length()
+    } else if let Some(buffer) = chunk.dyn_ref::<ArrayBuffer>() {
+        buffer.byte_length()
+    } else {
+        0
+    }
+}
+
+/// Returns `true` if the given value is an object with a method for each of the given `names`.
+///
+/// Unlike `instanceof`, this also accepts objects from other realms (such as iframes or workers)
+/// and objects created by a polyfill.
+pub(crate) fn has_methods(value: &JsValue, names: &[&str]) -> bool {
+    value.is_object()
+        && names.iter().all(|name| {
+            js_sys::Reflect::get(value, &JsValue::from_str(name)).is_ok_and(|m| m.is_function())
+        })
+}
+
+/// Returns `true` if the given value is an object with an object property
+/// for each of the given `names`.
+pub(crate) fn has_object_properties(value: &JsValue, names: &[&str]) -> bool {
+    value.is_object()
+        && names.iter().all(|name| {
+            js_sys::Reflect::get(value, &JsValue::from_str(name)).is_ok_and(|p| p.is_object())
+        })
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn io_error_to_js(error: io::Error) -> JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+pub(crate) fn js_to_io_error(js_value: JsValue) -> io::Error {
+    let message = match js_value.dyn_ref::<js_sys::Error>() {
+        Some(error) => String::from(error.message()),
+        None => js_value
+            .as_string()
+            .unwrap_or_else(|| format!("{:?}", js_value)),
+    };
+    io::Error::other(message)
+}
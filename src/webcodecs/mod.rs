@@ -0,0 +1,352 @@
+//! Typed streams of [WebCodecs](https://developer.mozilla.org/en-US/docs/Web/API/WebCodecs_API)
+//! chunks.
+//!
+//! This module provides aliases for readable and writable streams of
+//! [`VideoFrame`](sys::VideoFrame)s, [`AudioData`](sys::AudioData),
+//! [`EncodedVideoChunk`](sys::EncodedVideoChunk)s and
+//! [`EncodedAudioChunk`](sys::EncodedAudioChunk)s. Use [`cast`](ReadableStream::cast)
+//! to turn an untyped stream into one of these. Reading a chunk of a different type from
+//! a typed stream results in a [`TypeError`](js_sys::TypeError), so a stream that produces
+//! the wrong kind of media is caught on the first read.
+//!
+//! For [insertable streams](https://developer.mozilla.org/en-US/docs/Web/API/Insertable_Streams_for_MediaStreamTrack_API),
+//! [`video_frames_from_track`](video_frames_from_track) and
+//! [`audio_data_from_track`](audio_data_from_track) read the media of a
+//! [`MediaStreamTrack`](MediaStreamTrack), and [`track_from_video_frames`](track_from_video_frames)
+//! and [`track_from_audio_data`](track_from_audio_data) create a new track from written media.
+//! [`TrackProcessor`](TrackProcessor) and [`TrackGenerator`](TrackGenerator) give access to
+//! the underlying objects, and [`transform_video_track`](transform_video_track) connects both
+//! through a Rust [`Transformer`](Transformer), e.g. to apply a video effect.
+//!
+//! Frames and audio data hold on to (graphics) memory until they are closed, so every chunk
+//! read from these streams must eventually be closed or written to another stream.
+//!
+//! This module requires the `webcodecs` feature.
+use std::marker::PhantomData;
+
+use js_sys::Object;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::MediaStreamTrack;
+
+use crate::readable::{PipeHandle, PipeOptions, ReadableStream};
+use crate::transform::{TransformStream, Transformer};
+use crate::writable::WritableStream;
+
+pub mod sys;
+
+/// A [`ReadableStream`](ReadableStream) of [`VideoFrame`](sys::VideoFrame)s.
+pub type VideoFrameStream = ReadableStream<sys::VideoFrame>;
+/// A [`WritableStream`](WritableStream) of [`VideoFrame`](sys::VideoFrame)s.
+pub type VideoFrameSink = WritableStream<sys::VideoFrame>;
+/// A [`ReadableStream`](ReadableStream) of [`AudioData`](sys::AudioData).
+pub type AudioDataStream = ReadableStream<sys::AudioData>;
+/// A [`WritableStream`](WritableStream) of [`AudioData`](sys::AudioData).
+pub type AudioDataSink = WritableStream<sys::AudioData>;
+/// A [`ReadableStream`](ReadableStream) of [`EncodedVideoChunk`](sys::EncodedVideoChunk)s.
+pub type EncodedVideoChunkStream = ReadableStream<sys::EncodedVideoChunk>;
+/// A [`WritableStream`](WritableStream) of [`EncodedVideoChunk`](sys::EncodedVideoChunk)s.
+pub type EncodedVideoChunkSink = WritableStream<sys::EncodedVideoChunk>;
+/// A [`ReadableStream`](ReadableStream) of [`EncodedAudioChunk`](sys::EncodedAudioChunk)s.
+pub type EncodedAudioChunkStream = ReadableStream<sys::EncodedAudioChunk>;
+/// A [`WritableStream`](WritableStream) of [`EncodedAudioChunk`](sys::EncodedAudioChunk)s.
+pub type EncodedAudioChunkSink = WritableStream<sys::EncodedAudioChunk>;
+
+/// A [`MediaStreamTrackProcessor`](https://developer.mozilla.org/en-US/docs/Web/API/MediaStreamTrackProcessor),
+/// which exposes the media of a track as a [`ReadableStream`](ReadableStream) of `T` chunks.
+///
+/// Use [`video`](TrackProcessor::video) to read the [`VideoFrame`](sys::VideoFrame)s of
+/// a video track, or [`audio`](TrackProcessor::audio) to read the [`AudioData`](sys::AudioData)
+/// of an audio track.
+#[derive(Debug)]
+pub struct TrackProcessor<T> {
+    raw: sys::MediaStreamTrackProcessor,
+    _marker: PhantomData<T>,
+}
+
+impl TrackProcessor<sys::VideoFrame> {
+    /// Creates a new processor for the given video `track`.
+    ///
+    /// If the browser does not support `MediaStreamTrackProcessor`, or if the track is not
+    /// a video track, then this returns an error.
+    pub fn video(track: &MediaStreamTrack) -> Result<Self, JsValue> {
+        Self::new(track, "video", None)
+    }
+
+    /// Creates a new processor for the given video `track`, which buffers at most
+    /// `max_buffer_size` frames that have not been read yet.
+    ///
+    /// When the buffer is full, the oldest frame is dropped for every new frame.
+    /// See [`video`](Self::video) for more details.
+    pub fn video_with_max_buffer_size(
+        track: &MediaStreamTrack,
+        max_buffer_size: u32,
+    ) -> Result<Self, JsValue> {
+        Self::new(track, "video", Some(max_buffer_size))
+    }
+}
+
+impl TrackProcessor<sys::AudioData> {
+    /// Creates a new processor for the given audio `track`.
+    ///
+    /// If the browser does not support `MediaStreamTrackProcessor`, or if the track is not
+    /// an audio track, then this returns an error.
+    pub fn audio(track: &MediaStreamTrack) -> Result<Self, JsValue> {
+        Self::new(track, "audio", None)
+    }
+
+    /// Creates a new processor for the given audio `track`, which buffers at most
+    /// `max_buffer_size` chunks that have not been read yet.
+    ///
+    /// When the buffer is full, the oldest chunk is dropped for every new chunk.
+    /// See [`audio`](Self::audio) for more details.
+    pub fn audio_with_max_buffer_size(
+        track: &MediaStreamTrack,
+        max_buffer_size: u32,
+    ) -> Result<Self, JsValue> {
+        Self::new(track, "audio", Some(max_buffer_size))
+    }
+}
+
+impl<T: JsCast> TrackProcessor<T> {
+    fn new(
+        track: &MediaStreamTrack,
+        kind: &str,
+        max_buffer_size: Option<u32>,
+    ) -> Result<Self, JsValue> {
+        process_track(track, kind, max_buffer_size).map(Self::from_raw)
+    }
+
+    /// Creates a new `TrackProcessor` from a [JavaScript object](sys::MediaStreamTrackProcessor).
+    ///
+    /// The chunk type `T` is not checked until the chunks are read.
+    #[inline]
+    pub fn from_raw(raw: sys::MediaStreamTrackProcessor) -> Self {
+        Self {
+            raw,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](sys::MediaStreamTrackProcessor).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::MediaStreamTrackProcessor {
+        &self.raw
+    }
+
+    /// Consumes this `TrackProcessor`, returning the underlying
+    /// [JavaScript object](sys::MediaStreamTrackProcessor).
+    #[inline]
+    pub fn into_raw(self) -> sys::MediaStreamTrackProcessor {
+        self.raw
+    }
+
+    /// Returns the stream of media of the track.
+    ///
+    /// Every call returns the same underlying stream.
+    #[inline]
+    pub fn readable(&self) -> ReadableStream<T> {
+        ReadableStream::from_raw(self.as_raw().readable()).cast()
+    }
+}
+
+/// A [`MediaStreamTrackGenerator`](https://developer.mozilla.org/en-US/docs/Web/API/MediaStreamTrackGenerator),
+/// which creates a new track from the `T` chunks written to a [`WritableStream`](WritableStream).
+///
+/// Use [`video`](TrackGenerator::video) to create a video track from
+/// [`VideoFrame`](sys::VideoFrame)s, or [`audio`](TrackGenerator::audio) to create an audio
+/// track from [`AudioData`](sys::AudioData).
+#[derive(Debug)]
+pub struct TrackGenerator<T> {
+    raw: sys::MediaStreamTrackGenerator,
+    _marker: PhantomData<T>,
+}
+
+impl TrackGenerator<sys::VideoFrame> {
+    /// Creates a new generator for a video track.
+    ///
+    /// If the browser does not support `MediaStreamTrackGenerator`, then this returns an error.
+    pub fn video() -> Result<Self, JsValue> {
+        Self::new("video")
+    }
+}
+
+impl TrackGenerator<sys::AudioData> {
+    /// Creates a new generator for an audio track.
+    ///
+    /// If the browser does not support `MediaStreamTrackGenerator`, then this returns an error.
+    pub fn audio() -> Result<Self, JsValue> {
+        Self::new("audio")
+    }
+}
+
+impl<T: JsCast> TrackGenerator<T> {
+    fn new(kind: &str) -> Result<Self, JsValue> {
+        generate_track(kind).map(Self::from_raw)
+    }
+
+    /// Creates a new `TrackGenerator` from a [JavaScript object](sys::MediaStreamTrackGenerator).
+    #[inline]
+    pub fn from_raw(raw: sys::MediaStreamTrackGenerator) -> Self {
+        Self {
+            raw,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](sys::MediaStreamTrackGenerator).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::MediaStreamTrackGenerator {
+        &self.raw
+    }
+
+    /// Consumes this `TrackGenerator`, returning the underlying
+    /// [JavaScript object](sys::MediaStreamTrackGenerator).
+    #[inline]
+    pub fn into_raw(self) -> sys::MediaStreamTrackGenerator {
+        self.raw
+    }
+
+    /// Returns the generated track, e.g. to add it to a `MediaStream` for playback.
+    #[inline]
+    pub fn track(&self) -> MediaStreamTrack {
+        self.raw.clone().into()
+    }
+
+    /// Returns the stream to write the media of the track to.
+    ///
+    /// Every call returns the same underlying stream.
+    #[inline]
+    pub fn writable(&self) -> WritableStream<T> {
+        WritableStream::from_raw(self.as_raw().writable()).cast()
+    }
+}
+
+/// Returns a stream of the [`VideoFrame`](sys::VideoFrame)s of the given video `track`.
+///
+/// This creates a [`MediaStreamTrackProcessor`](sys::MediaStreamTrackProcessor) for the track.
+/// If the browser does not support it, or if the track is not a video track,
+/// then this returns an error.
+pub fn video_frames_from_track(track: &MediaStreamTrack) -> Result<VideoFrameStream, JsValue> {
+    process_track(track, "video", None)
+        .map(|processor| ReadableStream::from_raw(processor.readable()).cast())
+}
+
+/// Returns a stream of the [`AudioData`](sys::AudioData) of the given audio `track`.
+///
+/// This creates a [`MediaStreamTrackProcessor`](sys::MediaStreamTrackProcessor) for the track.
+/// If the browser does not support it, or if the track is not an audio track,
+/// then this returns an error.
+pub fn audio_data_from_track(track: &MediaStreamTrack) -> Result<AudioDataStream, JsValue> {
+    process_track(track, "audio", None)
+        .map(|processor| ReadableStream::from_raw(processor.readable()).cast())
+}
+
+/// Creates a new video track, along with a stream to write its [`VideoFrame`](sys::VideoFrame)s.
+///
+/// The track is a [`MediaStreamTrackGenerator`](sys::MediaStreamTrackGenerator).
+/// If the browser does not support it, then this returns an error.
+pub fn track_from_video_frames() -> Result<(MediaStreamTrack, VideoFrameSink), JsValue> {
+    generate_track("video").map(split_generator)
+}
+
+/// Creates a new audio track, along with a stream to write its [`AudioData`](sys::AudioData).
+///
+/// The track is a [`MediaStreamTrackGenerator`](sys::MediaStreamTrackGenerator).
+/// If the browser does not support it, then this returns an error.
+pub fn track_from_audio_data() -> Result<(MediaStreamTrack, AudioDataSink), JsValue> {
+    generate_track("audio").map(split_generator)
+}
+
+/// Applies the given `transformer` to every [`VideoFrame`](sys::VideoFrame) of the given video
+/// `track`, returning a new track with the transformed frames.
+///
+/// The frames of `track` are [processed](TrackProcessor), piped through a
+/// [`TransformStream`](TransformStream) created from the `transformer`, and written to a
+/// [generated](TrackGenerator) track. The transformer must enqueue `VideoFrame`s, and must
+/// [close](sys::VideoFrame::close) every input frame that it does not enqueue.
+///
+/// The pipe runs in the background. Use the returned [`PipeHandle`](PipeHandle) to stop it,
+/// or to wait for the input track to end.
+pub fn transform_video_track<Tr>(
+    track: &MediaStreamTrack,
+    transformer: Tr,
+) -> Result<(MediaStreamTrack, PipeHandle), JsValue>
+where
+    Tr: Transformer + 'static,
+{
+    transform_track(
+        TrackProcessor::video(track)?,
+        TrackGenerator::video()?,
+        transformer,
+    )
+}
+
+/// Applies the given `transformer` to every [`AudioData`](sys::AudioData) of the given audio
+/// `track`, returning a new track with the transformed audio.
+///
+/// See [`transform_video_track`](transform_video_track) for more details.
+pub fn transform_audio_track<Tr>(
+    track: &MediaStreamTrack,
+    transformer: Tr,
+) -> Result<(MediaStreamTrack, PipeHandle), JsValue>
+where
+    Tr: Transformer + 'static,
+{
+    transform_track(
+        TrackProcessor::audio(track)?,
+        TrackGenerator::audio()?,
+        transformer,
+    )
+}
+
+fn transform_track<T, Tr>(
+    processor: TrackProcessor<T>,
+    generator: TrackGenerator<T>,
+    transformer: Tr,
+) -> Result<(MediaStreamTrack, PipeHandle), JsValue>
+where
+    T: JsCast,
+    Tr: Transformer + 'static,
+{
+    let transform = TransformStream::from_transformer(transformer);
+    let transformed = processor.readable().try_pipe_through(&transform)?;
+    let handle = transformed.pipe_to_spawned(generator.writable().cast(), &PipeOptions::new());
+    Ok((generator.track(), handle))
+}
+
+fn process_track(
+    track: &MediaStreamTrack,
+    kind: &str,
+    max_buffer_size: Option<u32>,
+) -> Result<sys::MediaStreamTrackProcessor, JsValue> {
+    if !sys::MediaStreamTrackProcessor::is_supported() {
+        return Err(js_sys::TypeError::new("MediaStreamTrackProcessor is not supported").into());
+    }
+    if track.kind() != kind {
+        let message = format!("expected a {} track, got a {} track", kind, track.kind());
+        return Err(js_sys::TypeError::new(&message).into());
+    }
+    let init = Object::new();
+    js_sys::Reflect::set(&init, &"track".into(), track)?;
+    if let Some(max_buffer_size) = max_buffer_size {
+        js_sys::Reflect::set(&init, &"maxBufferSize".into(), &max_buffer_size.into())?;
+    }
+    Ok(sys::MediaStreamTrackProcessor::new(&init)?)
+}
+
+fn generate_track(kind: &str) -> Result<sys::MediaStreamTrackGenerator, JsValue> {
+    if !sys::MediaStreamTrackGenerator::is_supported() {
+        return Err(js_sys::TypeError::new("MediaStreamTrackGenerator is not supported").into());
+    }
+    let init = Object::new();
+    js_sys::Reflect::set(&init, &"kind".into(), &kind.into())?;
+    Ok(sys::MediaStreamTrackGenerator::new(&init)?)
+}
+
+fn split_generator<T: JsCast>(
+    generator: sys::MediaStreamTrackGenerator,
+) -> (MediaStreamTrack, WritableStream<T>) {
+    let writable = WritableStream::from_raw(generator.writable()).cast();
+    (generator.into(), writable)
+}
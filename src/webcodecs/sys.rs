@@ -0,0 +1,166 @@
+//! Raw bindings to the chunk types of [WebCodecs](https://developer.mozilla.org/en-US/docs/Web/API/WebCodecs_API)
+//! and to the [insertable streams](https://developer.mozilla.org/en-US/docs/Web/API/Insertable_Streams_for_MediaStreamTrack_API)
+//! for `MediaStreamTrack`s.
+//!
+//! These represent the same JavaScript objects as the corresponding `web_sys` types,
+//! which are only available with `--cfg=web_sys_unstable_apis`.
+use js_sys::{Error, Object};
+use wasm_bindgen::prelude::*;
+use web_sys::MediaStreamTrack;
+
+use crate::readable::sys::ReadableStream;
+use crate::writable::sys::WritableStream;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`VideoFrame`](https://developer.mozilla.org/en-US/docs/Web/API/VideoFrame).
+    ///
+    /// A frame holds on to graphics memory until it is [closed](Self::close).
+    #[derive(Clone, Debug)]
+    pub type VideoFrame;
+
+    #[wasm_bindgen(method, getter, js_name = timestamp)]
+    pub fn timestamp(this: &VideoFrame) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = duration)]
+    pub fn duration(this: &VideoFrame) -> Option<f64>;
+
+    #[wasm_bindgen(method, getter, js_name = format)]
+    pub fn format(this: &VideoFrame) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = displayWidth)]
+    pub fn display_width(this: &VideoFrame) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = displayHeight)]
+    pub fn display_height(this: &VideoFrame) -> u32;
+
+    #[wasm_bindgen(method, catch, js_name = clone)]
+    pub fn clone_frame(this: &VideoFrame) -> Result<VideoFrame, Error>;
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close(this: &VideoFrame);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`AudioData`](https://developer.mozilla.org/en-US/docs/Web/API/AudioData).
+    ///
+    /// The audio data holds on to its memory until it is [closed](Self::close).
+    #[derive(Clone, Debug)]
+    pub type AudioData;
+
+    #[wasm_bindgen(method, getter, js_name = timestamp)]
+    pub fn timestamp(this: &AudioData) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = duration)]
+    pub fn duration(this: &AudioData) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = format)]
+    pub fn format(this: &AudioData) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = sampleRate)]
+    pub fn sample_rate(this: &AudioData) -> f32;
+
+    #[wasm_bindgen(method, getter, js_name = numberOfFrames)]
+    pub fn number_of_frames(this: &AudioData) -> u32;
+
+    #[wasm_bindgen(method, getter, js_name = numberOfChannels)]
+    pub fn number_of_channels(this: &AudioData) -> u32;
+
+    #[wasm_bindgen(method, catch, js_name = clone)]
+    pub fn clone_data(this: &AudioData) -> Result<AudioData, Error>;
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close(this: &AudioData);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`EncodedVideoChunk`](https://developer.mozilla.org/en-US/docs/Web/API/EncodedVideoChunk).
+    #[derive(Clone, Debug)]
+    pub type EncodedVideoChunk;
+
+    #[wasm_bindgen(method, getter, js_name = type)]
+    pub fn type_(this: &EncodedVideoChunk) -> String;
+
+    #[wasm_bindgen(method, getter, js_name = timestamp)]
+    pub fn timestamp(this: &EncodedVideoChunk) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = duration)]
+    pub fn duration(this: &EncodedVideoChunk) -> Option<f64>;
+
+    #[wasm_bindgen(method, getter, js_name = byteLength)]
+    pub fn byte_length(this: &EncodedVideoChunk) -> u32;
+
+    #[wasm_bindgen(method, catch, js_name = copyTo)]
+    pub fn copy_to(this: &EncodedVideoChunk, destination: &mut [u8]) -> Result<(), Error>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`EncodedAudioChunk`](https://developer.mozilla.org/en-US/docs/Web/API/EncodedAudioChunk).
+    #[derive(Clone, Debug)]
+    pub type EncodedAudioChunk;
+
+    #[wasm_bindgen(method, getter, js_name = type)]
+    pub fn type_(this: &EncodedAudioChunk) -> String;
+
+    #[wasm_bindgen(method, getter, js_name = timestamp)]
+    pub fn timestamp(this: &EncodedAudioChunk) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = duration)]
+    pub fn duration(this: &EncodedAudioChunk) -> Option<f64>;
+
+    #[wasm_bindgen(method, getter, js_name = byteLength)]
+    pub fn byte_length(this: &EncodedAudioChunk) -> u32;
+
+    #[wasm_bindgen(method, catch, js_name = copyTo)]
+    pub fn copy_to(this: &EncodedAudioChunk, destination: &mut [u8]) -> Result<(), Error>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`MediaStreamTrackProcessor`](https://developer.mozilla.org/en-US/docs/Web/API/MediaStreamTrackProcessor).
+    ///
+    /// Not all browsers support this API yet, see [`is_supported`](Self::is_supported).
+    #[derive(Clone, Debug)]
+    pub type MediaStreamTrackProcessor;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(init: &Object) -> Result<MediaStreamTrackProcessor, Error>;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &MediaStreamTrackProcessor) -> ReadableStream;
+}
+
+impl MediaStreamTrackProcessor {
+    /// Returns `true` if the browser supports `MediaStreamTrackProcessor`.
+    pub fn is_supported() -> bool {
+        js_sys::Reflect::get(&js_sys::global(), &"MediaStreamTrackProcessor".into())
+            .is_ok_and(|constructor| constructor.is_function())
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`MediaStreamTrackGenerator`](https://developer.mozilla.org/en-US/docs/Web/API/MediaStreamTrackGenerator).
+    ///
+    /// Not all browsers support this API yet, see [`is_supported`](Self::is_supported).
+    #[wasm_bindgen(extends = MediaStreamTrack)]
+    #[derive(Clone, Debug)]
+    pub type MediaStreamTrackGenerator;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(init: &Object) -> Result<MediaStreamTrackGenerator, Error>;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &MediaStreamTrackGenerator) -> WritableStream;
+}
+
+impl MediaStreamTrackGenerator {
+    /// Returns `true` if the browser supports `MediaStreamTrackGenerator`.
+    pub fn is_supported() -> bool {
+        js_sys::Reflect::get(&js_sys::global(), &"MediaStreamTrackGenerator".into())
+            .is_ok_and(|constructor| constructor.is_function())
+    }
+}
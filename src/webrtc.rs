@@ -0,0 +1,346 @@
+//! Integration with [WebRTC data channels](https://developer.mozilla.org/en-US/docs/Web/API/RTCDataChannel).
+//!
+//! Use [`duplex_from_data_channel`](duplex_from_data_channel) to wrap an
+//! [`RtcDataChannel`](RtcDataChannel) into a byte [`DuplexStream`](DuplexStream),
+//! for example to transfer a file over a peer-to-peer connection.
+//!
+//! This module requires the `webrtc` feature.
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::{ArrayBuffer, Function, Promise, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{MessageEvent, RtcDataChannel, RtcDataChannelState, RtcDataChannelType};
+
+use crate::duplex::DuplexStream;
+use crate::queuing_strategy::QueuingStrategy;
+use crate::readable::{ReadableStream, ReadableStreamDefaultController, UnderlyingSource};
+use crate::writable::{UnderlyingSink, WritableStream, WritableStreamDefaultController};
+
+/// Options for [`duplex_from_data_channel`](duplex_from_data_channel).
+#[derive(Clone, Debug)]
+pub struct DataChannelOptions {
+    read_buffer: usize,
+    write_buffer: u32,
+}
+
+impl Default for DataChannelOptions {
+    fn default() -> Self {
+        Self {
+            read_buffer: 64,
+            write_buffer: 1024 * 1024,
+        }
+    }
+}
+
+impl DataChannelOptions {
+    /// Creates a new set of data channel options with the default values.
+    ///
+    /// Equivalent to [`DataChannelOptions::default`](Default::default).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of received messages that are buffered until they are read.
+    /// Defaults to 64 messages.
+    ///
+    /// A data channel cannot stop receiving messages. If the readable stream falls behind by
+    /// more than this many messages, the channel is closed and the readable stream errors.
+    pub fn read_buffer(&mut self, messages: usize) -> &mut Self {
+        self.read_buffer = messages;
+        self
+    }
+
+    /// Sets the maximum number of bytes that may be queued by the channel's
+    /// [`bufferedAmount`](https://developer.mozilla.org/en-US/docs/Web/API/RTCDataChannel/bufferedAmount)
+    /// before a write waits for it to drain. Defaults to 1 MiB.
+    pub fn write_buffer(&mut self, bytes: u32) -> &mut Self {
+        self.write_buffer = bytes;
+        self
+    }
+}
+
+/// Wraps an [`RtcDataChannel`](RtcDataChannel) into a [`DuplexStream`](DuplexStream) of bytes.
+///
+/// Each received message becomes a [`Uint8Array`](Uint8Array) chunk of the readable stream,
+/// and each chunk written to the writable stream is sent as a binary message.
+///
+/// Writes wait until the channel is open. When the channel's `bufferedAmount` exceeds the
+/// configured [write buffer](DataChannelOptions::write_buffer), writes wait for the
+/// [`bufferedamountlow`](https://developer.mozilla.org/en-US/docs/Web/API/RTCDataChannel/bufferedamountlow_event)
+/// event, which fires once `bufferedAmount` drops back to the write buffer.
+///
+/// * Closing or aborting the writable stream, or cancelling the readable stream,
+///   closes the channel.
+/// * When the channel closes, the readable stream closes.
+///   When the channel errors, the readable stream errors.
+///
+/// This takes over the channel's `onopen`, `onmessage`, `onbufferedamountlow`, `onerror` and
+/// `onclose` handlers, sets its `binaryType` to `"arraybuffer"`, and sets its
+/// `bufferedAmountLowThreshold` to the write buffer.
+pub fn duplex_from_data_channel(
+    channel: &RtcDataChannel,
+    options: &DataChannelOptions,
+) -> DuplexStream<Uint8Array, Uint8Array> {
+    channel.set_binary_type(RtcDataChannelType::Arraybuffer);
+    // Writes wait for `bufferedAmount <= write_buffer`, so the event must fire at that point.
+    channel.set_buffered_amount_low_threshold(options.write_buffer);
+
+    let mut open_callbacks: Option<(Function, Function)> = None;
+    let opened = Promise::new(&mut |resolve, reject| {
+        open_callbacks = Some((resolve, reject));
+    });
+    let (resolve_open, reject_open) = open_callbacks.unwrap_throw();
+    match channel.ready_state() {
+        RtcDataChannelState::Open => {
+            let _ = resolve_open.call0(&JsValue::UNDEFINED);
+        }
+        RtcDataChannelState::Closing | RtcDataChannelState::Closed => {
+            let error = js_sys::Error::new("RTCDataChannel closed");
+            let _ = reject_open.call1(&JsValue::UNDEFINED, &error);
+        }
+        _ => {}
+    }
+
+    let shared = Rc::new(Shared {
+        channel: channel.clone(),
+        controller: RefCell::new(None),
+        opened,
+        buffered_amount_low: RefCell::new(None),
+        handlers: RefCell::new(None),
+    });
+
+    let on_open = Closure::<dyn FnMut()>::new(move || {
+        let _ = resolve_open.call0(&JsValue::UNDEFINED);
+    });
+    channel.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+    let read_buffer = options.read_buffer;
+    let weak = Rc::downgrade(&shared);
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let shared = match weak.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+        let controller = match shared.controller.borrow().clone() {
+            Some(controller) => controller,
+            None => return,
+        };
+        let data = event.data();
+        let chunk = match data.dyn_ref::<ArrayBuffer>() {
+            Some(buffer) => Uint8Array::new(buffer).into(),
+            // Text messages are encoded as UTF-8.
+            None => Uint8Array::from(data.as_string().unwrap_or_default().as_bytes()).into(),
+        };
+        if controller.enqueue(&chunk).is_err() {
+            // The readable stream was already closed or cancelled.
+            return;
+        }
+        if matches!(controller.desired_size(), Some(size) if size < 0.0) {
+            controller.error(&js_sys::Error::new(&format!(
+                "more than {} messages were received without being read",
+                read_buffer
+            )));
+            shared.channel.close();
+        }
+    });
+    channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    let weak = Rc::downgrade(&shared);
+    let on_buffered_amount_low = Closure::<dyn FnMut()>::new(move || {
+        if let Some(shared) = weak.upgrade() {
+            shared.notify_buffered_amount_low();
+        }
+    });
+    channel.set_onbufferedamountlow(Some(on_buffered_amount_low.as_ref().unchecked_ref()));
+
+    let weak = Rc::downgrade(&shared);
+    let reject_open_on_error = reject_open.clone();
+    let on_error = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        let error = js_sys::Reflect::get(&event, &"error".into())
+            .ok()
+            .filter(|error| !error.is_undefined())
+            .unwrap_or_else(|| js_sys::Error::new("RTCDataChannel error").into());
+        let _ = reject_open_on_error.call1(&JsValue::UNDEFINED, &error);
+        if let Some(shared) = weak.upgrade() {
+            let controller = shared.controller.borrow_mut().take();
+            if let Some(controller) = controller {
+                controller.error(&error);
+            }
+        }
+    });
+    channel.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+    let weak = Rc::downgrade(&shared);
+    let on_close = Closure::<dyn FnMut()>::new(move || {
+        // Rejecting has no effect if the channel opened before.
+        let error = js_sys::Error::new("RTCDataChannel closed");
+        let _ = reject_open.call1(&JsValue::UNDEFINED, &error);
+        let shared = match weak.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+        let controller = shared.controller.borrow_mut().take();
+        if let Some(controller) = controller {
+            let _ = controller.close();
+        }
+        // Wake up any pending write, so it can fail.
+        shared.notify_buffered_amount_low();
+    });
+    channel.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    *shared.handlers.borrow_mut() = Some(Handlers {
+        _on_open: on_open,
+        _on_message: on_message,
+        _on_buffered_amount_low: on_buffered_amount_low,
+        _on_error: on_error,
+        _on_close: on_close,
+    });
+
+    let readable = ReadableStream::from_underlying_source_with_strategy(
+        DataChannelSource {
+            shared: shared.clone(),
+        },
+        QueuingStrategy::new(options.read_buffer as f64),
+    );
+    let writable = WritableStream::from_underlying_sink(DataChannelSink {
+        shared,
+        write_buffer: options.write_buffer,
+    });
+    DuplexStream::new(readable.cast(), writable.cast())
+}
+
+/// State shared between the event handlers, the source and the sink.
+struct Shared {
+    channel: RtcDataChannel,
+    controller: RefCell<Option<ReadableStreamDefaultController>>,
+    /// Resolves when the channel opens, rejects when it errors or closes before opening.
+    opened: Promise,
+    /// Resolves the promise of a write that is waiting for the buffered amount to drop.
+    buffered_amount_low: RefCell<Option<Function>>,
+    handlers: RefCell<Option<Handlers>>,
+}
+
+/// The event handlers installed on the channel, kept alive for as long as the streams.
+struct Handlers {
+    _on_open: Closure<dyn FnMut()>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_buffered_amount_low: Closure<dyn FnMut()>,
+    _on_error: Closure<dyn FnMut(JsValue)>,
+    _on_close: Closure<dyn FnMut()>,
+}
+
+impl Shared {
+    fn notify_buffered_amount_low(&self) {
+        let resolve = self.buffered_amount_low.borrow_mut().take();
+        if let Some(resolve) = resolve {
+            let _ = resolve.call0(&JsValue::UNDEFINED);
+        }
+    }
+
+    fn check_open(&self) -> Result<(), JsValue> {
+        if self.channel.ready_state() == RtcDataChannelState::Open {
+            Ok(())
+        } else {
+            Err(js_sys::Error::new("RTCDataChannel is not open").into())
+        }
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        // Remove the handlers before their closures are dropped.
+        self.channel.set_onopen(None);
+        self.channel.set_onmessage(None);
+        self.channel.set_onbufferedamountlow(None);
+        self.channel.set_onerror(None);
+        self.channel.set_onclose(None);
+    }
+}
+
+struct DataChannelSource {
+    shared: Rc<Shared>,
+}
+
+impl UnderlyingSource for DataChannelSource {
+    fn start<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        if self.shared.channel.ready_state() == RtcDataChannelState::Closed {
+            // The channel closed before the adapter was created.
+            let _ = controller.close();
+        } else {
+            *self.shared.controller.borrow_mut() = Some(controller.clone());
+        }
+        ready(Ok(())).boxed_local()
+    }
+
+    fn pull<'a>(
+        &'a mut self,
+        _controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Messages are enqueued as soon as they arrive.
+        ready(Ok(())).boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.shared.controller.borrow_mut().take();
+        self.shared.channel.close();
+        ready(Ok(())).boxed_local()
+    }
+}
+
+struct DataChannelSink {
+    shared: Rc<Shared>,
+    write_buffer: u32,
+}
+
+impl UnderlyingSink for DataChannelSink {
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Wait for the channel to open before writing.
+        JsFuture::from(self.shared.opened.clone())
+            .map(|result| result.map(|_| ()))
+            .boxed_local()
+    }
+
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            let shared = &self.shared;
+            while shared.channel.buffered_amount() > self.write_buffer {
+                shared.check_open()?;
+                let drained = Promise::new(&mut |resolve, _reject| {
+                    *shared.buffered_amount_low.borrow_mut() = Some(resolve);
+                });
+                JsFuture::from(drained).await?;
+            }
+            shared.check_open()?;
+            match chunk.dyn_ref::<Uint8Array>() {
+                Some(chunk) => shared.channel.send_with_array_buffer_view(chunk),
+                None => Err(js_sys::TypeError::new("chunk must be a Uint8Array").into()),
+            }
+        }
+        .boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // The channel still sends all buffered messages before closing.
+        self.shared.channel.close();
+        ready(Ok(())).boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.shared.channel.close();
+        ready(Ok(())).boxed_local()
+    }
+}
@@ -0,0 +1,356 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use js_sys::{ArrayBuffer, Function, Object, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{BinaryType, CloseEvent, MessageEvent, WebSocket};
+
+use crate::duplex::DuplexStream;
+use crate::queuing_strategy::QueuingStrategy;
+use crate::readable::{ReadableStream, ReadableStreamDefaultController, UnderlyingSource};
+use crate::util::sleep;
+use crate::writable::{UnderlyingSink, WritableStream, WritableStreamDefaultController};
+
+/// The close code for a normal closure.
+const NORMAL_CLOSURE: u16 = 1000;
+/// The close code sent when the peer sends more messages than can be buffered.
+const POLICY_VIOLATION: u16 = 1008;
+/// The close code sent when the writable stream is aborted.
+///
+/// Scripts may only close a `WebSocket` with code 1000 or an application-defined code
+/// between 3000 and 4999, so this cannot be 1011 (internal error).
+const ABORTED: u16 = 4000;
+/// The maximum length of a close reason in bytes.
+const MAX_REASON_LEN: usize = 123;
+
+/// Options for [`duplex_from_web_socket`](super::duplex_from_web_socket).
+#[derive(Clone, Debug)]
+pub struct WebSocketAdapterOptions {
+    read_buffer: usize,
+    write_buffer: u32,
+    poll_interval: Duration,
+}
+
+impl Default for WebSocketAdapterOptions {
+    fn default() -> Self {
+        Self {
+            read_buffer: 64,
+            write_buffer: 64 * 1024,
+            poll_interval: Duration::from_millis(10),
+        }
+    }
+}
+
+impl WebSocketAdapterOptions {
+    /// Creates a new set of adapter options with the default values.
+    ///
+    /// Equivalent to [`WebSocketAdapterOptions::default`](Default::default).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the maximum number of received messages that are buffered until they are read.
+    /// Defaults to 64 messages.
+    ///
+    /// A `WebSocket` cannot stop receiving messages. If the readable stream falls behind by more
+    /// than this many messages, the connection is closed with code 1008 (policy violation)
+    /// and the readable stream errors.
+    ///
+    /// **Panics** if `messages` is zero, since then any message that arrives while no read
+    /// is pending would close the connection.
+    pub fn read_buffer(&mut self, messages: usize) -> &mut Self {
+        assert!(messages > 0, "read_buffer must be greater than zero");
+        self.read_buffer = messages;
+        self
+    }
+
+    /// Sets the maximum number of bytes that may be queued by the `WebSocket`'s
+    /// [`bufferedAmount`](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket/bufferedAmount)
+    /// before a write waits for it to drain. Defaults to 64 KiB.
+    pub fn write_buffer(&mut self, bytes: u32) -> &mut Self {
+        self.write_buffer = bytes;
+        self
+    }
+
+    /// Sets how often `bufferedAmount` is checked while waiting for it to drain.
+    /// Defaults to 10 milliseconds.
+    pub fn poll_interval(&mut self, interval: Duration) -> &mut Self {
+        self.poll_interval = interval;
+        self
+    }
+}
+
+/// State shared between the event handlers, the source and the sink.
+struct Shared {
+    socket: WebSocket,
+    controller: RefCell<Option<ReadableStreamDefaultController>>,
+    /// Resolves when the socket opens, rejects when it closes before opening.
+    opened: Promise,
+    handlers: RefCell<Option<Handlers>>,
+}
+
+/// The event handlers installed on the socket, kept alive for as long as the streams.
+struct Handlers {
+    _on_open: Closure<dyn FnMut()>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        // Remove the handlers before their closures are dropped.
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        self.socket.set_onopen(None);
+    }
+}
+
+pub(super) fn duplex_from_web_socket(
+    socket: &WebSocket,
+    options: &WebSocketAdapterOptions,
+) -> DuplexStream {
+    socket.set_binary_type(BinaryType::Arraybuffer);
+
+    let mut open_callbacks: Option<(Function, Function)> = None;
+    let opened = Promise::new(&mut |resolve, reject| {
+        open_callbacks = Some((resolve, reject));
+    });
+    let (resolve_open, reject_open) = open_callbacks.unwrap_throw();
+    match socket.ready_state() {
+        WebSocket::OPEN => {
+            let _ = resolve_open.call0(&JsValue::UNDEFINED);
+        }
+        WebSocket::CLOSING | WebSocket::CLOSED => {
+            // The socket will never open, so writes must fail instead of waiting forever.
+            let error = js_sys::Error::new("WebSocket closed");
+            let _ = reject_open.call1(&JsValue::UNDEFINED, &error);
+        }
+        _ => {}
+    }
+
+    let shared = Rc::new(Shared {
+        socket: socket.clone(),
+        controller: RefCell::new(None),
+        opened,
+        handlers: RefCell::new(None),
+    });
+
+    let on_open = Closure::<dyn FnMut()>::new(move || {
+        let _ = resolve_open.call0(&JsValue::UNDEFINED);
+    });
+    socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+    let read_buffer = options.read_buffer;
+    let weak = Rc::downgrade(&shared);
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let shared = match weak.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+        let controller = match shared.controller.borrow().clone() {
+            Some(controller) => controller,
+            None => return,
+        };
+        if controller.enqueue(&event.data()).is_err() {
+            // The readable stream was already closed or cancelled.
+            return;
+        }
+        if matches!(controller.desired_size(), Some(size) if size < 0.0) {
+            let error = js_sys::Error::new(&format!(
+                "more than {} messages were received without being read",
+                read_buffer
+            ));
+            controller.error(&error);
+            let _ = shared
+                .socket
+                .close_with_code_and_reason(POLICY_VIOLATION, "read buffer overflow");
+        }
+    });
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    let weak = Rc::downgrade(&shared);
+    let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |event: CloseEvent| {
+        let error = close_error(&event);
+        let _ = reject_open.call1(&JsValue::UNDEFINED, &error);
+        let shared = match weak.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+        let controller = shared.controller.borrow_mut().take();
+        if let Some(controller) = controller {
+            if event.was_clean() {
+                let _ = controller.close();
+            } else {
+                controller.error(&error);
+            }
+        }
+    });
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+    *shared.handlers.borrow_mut() = Some(Handlers {
+        _on_open: on_open,
+        _on_message: on_message,
+        _on_close: on_close,
+    });
+
+    let readable = ReadableStream::from_underlying_source_with_strategy(
+        WebSocketSource {
+            shared: shared.clone(),
+        },
+        QueuingStrategy::new(options.read_buffer as f64),
+    );
+    let writable = WritableStream::from_underlying_sink(WebSocketSink {
+        shared,
+        write_buffer: options.write_buffer,
+        poll_interval: options.poll_interval,
+    });
+    DuplexStream::new(readable, writable)
+}
+
+/// Returns the error for a socket that closed with the given event.
+fn close_error(event: &CloseEvent) -> JsValue {
+    let message = if event.reason().is_empty() {
+        format!("WebSocket closed with code {}", event.code())
+    } else {
+        format!(
+            "WebSocket closed with code {}: {}",
+            event.code(),
+            event.reason()
+        )
+    };
+    js_sys::Error::new(&message).into()
+}
+
+struct WebSocketSource {
+    shared: Rc<Shared>,
+}
+
+impl UnderlyingSource for WebSocketSource {
+    fn start<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        if self.shared.socket.ready_state() == WebSocket::CLOSED {
+            // The socket closed before the adapter was created.
+            let _ = controller.close();
+        } else {
+            *self.shared.controller.borrow_mut() = Some(controller.clone());
+        }
+        ready(Ok(())).boxed_local()
+    }
+
+    fn pull<'a>(
+        &'a mut self,
+        _controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Messages are enqueued as soon as they arrive.
+        ready(Ok(())).boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // A `WebSocket` cannot stop receiving while it keeps sending,
+        // so this also ends the writable stream.
+        self.shared.controller.borrow_mut().take();
+        let result = self.shared.socket.close_with_code(NORMAL_CLOSURE);
+        ready(result).boxed_local()
+    }
+}
+
+struct WebSocketSink {
+    shared: Rc<Shared>,
+    write_buffer: u32,
+    poll_interval: Duration,
+}
+
+impl WebSocketSink {
+    /// Waits until the socket's buffered amount drops to at most `limit` bytes.
+    async fn drain(&self, limit: u32) -> Result<(), JsValue> {
+        let socket = &self.shared.socket;
+        while socket.buffered_amount() > limit {
+            if socket.ready_state() != WebSocket::OPEN {
+                return Err(js_sys::Error::new("WebSocket is not open").into());
+            }
+            sleep(self.poll_interval.as_secs_f64() * 1000.0).await?;
+        }
+        Ok(())
+    }
+}
+
+impl UnderlyingSink for WebSocketSink {
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Wait for the socket to open before writing.
+        JsFuture::from(self.shared.opened.clone())
+            .map(|result| result.map(|_| ()))
+            .boxed_local()
+    }
+
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            self.drain(self.write_buffer).await?;
+            let socket = &self.shared.socket;
+            if socket.ready_state() != WebSocket::OPEN {
+                return Err(js_sys::Error::new("WebSocket is not open").into());
+            }
+            if let Some(text) = chunk.as_string() {
+                socket.send_with_str(&text)
+            } else if let Some(buffer) = chunk.dyn_ref::<ArrayBuffer>() {
+                socket.send_with_array_buffer(buffer)
+            } else if ArrayBuffer::is_view(&chunk) {
+                socket.send_with_array_buffer_view(chunk.unchecked_ref::<Object>())
+            } else {
+                Err(js_sys::TypeError::new(
+                    "chunk must be a string, ArrayBuffer or ArrayBufferView",
+                )
+                .into())
+            }
+        }
+        .boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            // Send all remaining messages before closing the connection.
+            self.drain(0).await?;
+            self.shared.socket.close_with_code(NORMAL_CLOSURE)
+        }
+        .boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let result = self
+            .shared
+            .socket
+            .close_with_code_and_reason(ABORTED, &abort_reason(&reason));
+        ready(result).boxed_local()
+    }
+}
+
+/// Returns the close reason for aborting the writable stream with the given `reason`,
+/// truncated to the maximum length of a close reason.
+fn abort_reason(reason: &JsValue) -> String {
+    let mut message = if let Some(error) = reason.dyn_ref::<js_sys::Error>() {
+        String::from(error.message())
+    } else if let Some(message) = reason.as_string() {
+        message
+    } else {
+        String::from("aborted")
+    };
+    if message.len() > MAX_REASON_LEN {
+        let mut end = MAX_REASON_LEN;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+    }
+    message
+}
@@ -0,0 +1,257 @@
+//! Bindings and conversions for
+//! [`WebSocketStream`](https://github.com/ricea/websocketstream-explainer),
+//! a WebSocket API based on streams.
+//!
+//! Not all browsers support `WebSocketStream` yet. Use
+//! [`WebSocketStream::is_supported`](WebSocketStream::is_supported) to check for support
+//! at runtime.
+//!
+//! For browsers without `WebSocketStream`, use [`duplex_from_web_socket`](duplex_from_web_socket)
+//! to wrap a classic [`WebSocket`](web_sys::WebSocket) into a [`DuplexStream`](DuplexStream)
+//! instead.
+//!
+//! This module requires the `websocket` feature.
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::duplex::DuplexStream;
+use crate::readable::ReadableStream;
+use crate::writable::WritableStream;
+use crate::StreamError;
+
+pub use adapter::WebSocketAdapterOptions;
+pub use options::WebSocketStreamOptions;
+
+mod adapter;
+mod options;
+pub mod sys;
+
+/// Wraps a classic [`WebSocket`](web_sys::WebSocket) into a [`DuplexStream`](DuplexStream).
+///
+/// Each received message becomes a chunk of the readable stream: a `String` for a text
+/// message, or an [`ArrayBuffer`](js_sys::ArrayBuffer) for a binary message.
+/// Each chunk written to the writable stream is sent as a message, and must be a string,
+/// an `ArrayBuffer` or an `ArrayBufferView` (such as a [`Uint8Array`](js_sys::Uint8Array)).
+///
+/// Writes wait until the socket is open, and apply backpressure by waiting for the socket's
+/// [`bufferedAmount`](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket/bufferedAmount)
+/// to drain below the configured [write buffer](WebSocketAdapterOptions::write_buffer).
+/// A `WebSocket` cannot stop receiving messages, so at most
+/// [`read_buffer`](WebSocketAdapterOptions::read_buffer) unread messages are buffered before
+/// the connection is closed with an error.
+///
+/// * Closing the writable stream sends all buffered messages, and then closes the connection
+///   with code 1000.
+/// * Aborting the writable stream closes the connection right away with code 4000, using
+///   the abort reason's message as the close reason.
+/// * Cancelling the readable stream closes the connection right away with code 1000.
+///   A `WebSocket` cannot be closed in one direction only, so this also ends the writable
+///   stream: further writes fail, and messages that were not yet sent are discarded.
+/// * When the connection closes cleanly, the readable stream closes. Otherwise, it errors
+///   with an error mentioning the close code and reason.
+///
+/// This takes over the socket's `onopen`, `onmessage` and `onclose` handlers, and sets its
+/// [`binaryType`](https://developer.mozilla.org/en-US/docs/Web/API/WebSocket/binaryType)
+/// to `"arraybuffer"`.
+pub fn duplex_from_web_socket(
+    socket: &web_sys::WebSocket,
+    options: &WebSocketAdapterOptions,
+) -> DuplexStream {
+    adapter::duplex_from_web_socket(socket, options)
+}
+
+/// A [`WebSocketStream`](https://github.com/ricea/websocketstream-explainer).
+///
+/// `WebSocketStream`s can be created with [`new`](Self::new), or from a
+/// [raw JavaScript object](sys::WebSocketStream) with [`from_raw`](Self::from_raw).
+///
+/// Wait for [`opened`](Self::opened) to get a [`WebSocketConnection`](WebSocketConnection)
+/// with the readable and writable side of the socket.
+#[derive(Debug)]
+pub struct WebSocketStream {
+    raw: sys::WebSocketStream,
+}
+
+impl WebSocketStream {
+    /// Returns `true` if the browser supports `WebSocketStream`.
+    #[inline]
+    pub fn is_supported() -> bool {
+        sys::WebSocketStream::is_supported()
+    }
+
+    /// Opens a new WebSocket connection to the given `url`.
+    ///
+    /// If the browser does not support `WebSocketStream`, or if the URL is invalid,
+    /// then this returns an error.
+    #[inline]
+    pub fn new(url: &str) -> Result<Self, JsValue> {
+        Self::new_with_options(url, &WebSocketStreamOptions::default())
+    }
+
+    /// Opens a new WebSocket connection to the given `url` with the given `options`.
+    ///
+    /// If the browser does not support `WebSocketStream`, or if the URL is invalid,
+    /// then this returns an error.
+    pub fn new_with_options(url: &str, options: &WebSocketStreamOptions) -> Result<Self, JsValue> {
+        if !Self::is_supported() {
+            return Err(js_sys::TypeError::new("WebSocketStream is not supported").into());
+        }
+        let raw = sys::WebSocketStream::new_with_options(url, options.clone().into_raw())?;
+        Ok(Self { raw })
+    }
+
+    /// Creates a new `WebSocketStream` from a [JavaScript stream](sys::WebSocketStream).
+    #[inline]
+    pub fn from_raw(raw: sys::WebSocketStream) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript stream](sys::WebSocketStream).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::WebSocketStream {
+        &self.raw
+    }
+
+    /// Consumes this `WebSocketStream`, returning the underlying [JavaScript stream](sys::WebSocketStream).
+    #[inline]
+    pub fn into_raw(self) -> sys::WebSocketStream {
+        self.raw
+    }
+
+    /// Returns the URL of this WebSocket.
+    #[inline]
+    pub fn url(&self) -> String {
+        self.as_raw().url()
+    }
+
+    /// Waits for the connection to be established.
+    ///
+    /// This returns an error if the connection could not be established.
+    pub async fn opened(&self) -> Result<WebSocketConnection, StreamError> {
+        let info = JsFuture::from(self.as_raw().opened())
+            .await
+            .map_err(StreamError::from)?;
+        Ok(WebSocketConnection::from_raw(info.unchecked_into()))
+    }
+
+    /// Waits for the connection to be closed, returning the close code and reason
+    /// sent by the server.
+    ///
+    /// This returns an error if the connection was closed uncleanly.
+    pub async fn closed(&self) -> Result<WebSocketCloseInfo, StreamError> {
+        let result = JsFuture::from(self.as_raw().closed())
+            .await
+            .map_err(StreamError::from)?;
+        let result = result.unchecked_into::<sys::WebSocketCloseResult>();
+        Ok(WebSocketCloseInfo {
+            close_code: result.close_code(),
+            reason: result.reason().unwrap_or_default(),
+        })
+    }
+
+    /// Closes the connection.
+    #[inline]
+    pub fn close(&self) {
+        self.as_raw().close()
+    }
+
+    /// Closes the connection with the given close code and reason.
+    ///
+    /// The close code must be either 1000, or in the range 3000 to 4999, and the reason must
+    /// be at most 123 bytes long when encoded as UTF-8. Otherwise, this returns an error
+    /// and the connection stays open.
+    #[inline]
+    pub fn close_with_info(&self, close_code: u16, reason: &str) -> Result<(), JsValue> {
+        self.as_raw().close_with_info(sys::WebSocketCloseInfo::new(
+            Some(close_code),
+            reason.into(),
+        ))
+    }
+}
+
+/// An open connection of a [`WebSocketStream`](WebSocketStream),
+/// returned by [`opened`](WebSocketStream::opened).
+///
+/// Each message received from the server is a chunk in the [`readable`](Self::readable) stream,
+/// either a `String` for a text message or an [`ArrayBuffer`](js_sys::ArrayBuffer)
+/// for a binary message. Each chunk written to the [`writable`](Self::writable) stream
+/// is sent as a message.
+#[derive(Debug)]
+pub struct WebSocketConnection {
+    readable: ReadableStream,
+    writable: WritableStream,
+    protocol: String,
+    extensions: String,
+}
+
+impl WebSocketConnection {
+    /// Creates a new `WebSocketConnection` from the [result](sys::WebSocketOpenInfo)
+    /// of the `opened` promise.
+    pub fn from_raw(raw: sys::WebSocketOpenInfo) -> Self {
+        Self {
+            readable: ReadableStream::from_raw(raw.readable()),
+            writable: WritableStream::from_raw(raw.writable()),
+            protocol: raw.protocol(),
+            extensions: raw.extensions(),
+        }
+    }
+
+    /// Returns the subprotocol selected by the server.
+    #[inline]
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// Returns the extensions selected by the server.
+    #[inline]
+    pub fn extensions(&self) -> &str {
+        &self.extensions
+    }
+
+    /// Acquires a mutable reference to the stream of received messages.
+    #[inline]
+    pub fn readable(&mut self) -> &mut ReadableStream {
+        &mut self.readable
+    }
+
+    /// Acquires a mutable reference to the stream of messages to send.
+    #[inline]
+    pub fn writable(&mut self) -> &mut WritableStream {
+        &mut self.writable
+    }
+
+    /// Consumes this `WebSocketConnection`, returning its readable and writable stream.
+    #[inline]
+    pub fn into_parts(self) -> (ReadableStream, WritableStream) {
+        (self.readable, self.writable)
+    }
+
+    /// Converts this `WebSocketConnection` into a [`DuplexStream`](DuplexStream).
+    #[inline]
+    pub fn into_duplex(self) -> DuplexStream {
+        DuplexStream::new(self.readable, self.writable)
+    }
+}
+
+/// The close code and reason of a closed [`WebSocketStream`](WebSocketStream),
+/// returned by [`closed`](WebSocketStream::closed).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebSocketCloseInfo {
+    close_code: Option<u16>,
+    reason: String,
+}
+
+impl WebSocketCloseInfo {
+    /// Returns the close code sent by the server, if any.
+    #[inline]
+    pub fn close_code(&self) -> Option<u16> {
+        self.close_code
+    }
+
+    /// Returns the close reason sent by the server.
+    #[inline]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
@@ -0,0 +1,38 @@
+use super::sys;
+use web_sys::AbortSignal;
+
+/// Options for [`WebSocketStream::new_with_options`](super::WebSocketStream::new_with_options).
+#[derive(Clone, Debug, Default)]
+pub struct WebSocketStreamOptions {
+    protocols: Vec<String>,
+    signal: Option<AbortSignal>,
+}
+
+impl WebSocketStreamOptions {
+    /// Creates a blank new set of WebSocket options.
+    ///
+    /// Equivalent to [`WebSocketStreamOptions::default`](Default::default).
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Convert this to a raw [`WebSocketStreamOptions`](sys::WebSocketStreamOptions) object.
+    pub fn into_raw(self) -> sys::WebSocketStreamOptions {
+        sys::WebSocketStreamOptions::new(self.protocols, self.signal)
+    }
+
+    /// Sets the subprotocols that the client supports, in order of preference.
+    /// The server selects one of them, see
+    /// [`WebSocketConnection::protocol`](super::WebSocketConnection::protocol).
+    pub fn protocols<S: AsRef<str>>(&mut self, protocols: &[S]) -> &mut Self {
+        self.protocols = protocols.iter().map(|p| p.as_ref().to_owned()).collect();
+        self
+    }
+
+    /// Sets an abort signal to abort the opening handshake.
+    /// Once the connection is open, aborting the signal has no effect.
+    pub fn signal(&mut self, signal: AbortSignal) -> &mut Self {
+        self.signal = Some(signal);
+        self
+    }
+}
@@ -0,0 +1,136 @@
+//! Raw bindings to JavaScript objects used
+//! by a [`WebSocketStream`](https://github.com/ricea/websocketstream-explainer).
+use js_sys::{Array, Error, Promise};
+use wasm_bindgen::prelude::*;
+use web_sys::AbortSignal;
+
+use crate::readable::sys::ReadableStream;
+use crate::writable::sys::WritableStream;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`WebSocketStream`](https://github.com/ricea/websocketstream-explainer).
+    ///
+    /// Not all browsers support this API yet, see [`is_supported`](Self::is_supported).
+    #[derive(Clone, Debug)]
+    pub type WebSocketStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(url: &str) -> Result<WebSocketStream, Error>;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new_with_options(
+        url: &str,
+        options: WebSocketStreamOptions,
+    ) -> Result<WebSocketStream, Error>;
+
+    #[wasm_bindgen(method, getter, js_name = url)]
+    pub fn url(this: &WebSocketStream) -> String;
+
+    #[wasm_bindgen(method, getter, js_name = opened)]
+    pub fn opened(this: &WebSocketStream) -> Promise;
+
+    #[wasm_bindgen(method, getter, js_name = closed)]
+    pub fn closed(this: &WebSocketStream) -> Promise;
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close(this: &WebSocketStream);
+
+    #[wasm_bindgen(method, catch, js_name = close)]
+    pub fn close_with_info(this: &WebSocketStream, info: WebSocketCloseInfo)
+        -> Result<(), JsValue>;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// The result of the [`opened`](WebSocketStream::opened) promise.
+    #[derive(Clone, Debug)]
+    pub type WebSocketOpenInfo;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &WebSocketOpenInfo) -> ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &WebSocketOpenInfo) -> WritableStream;
+
+    #[wasm_bindgen(method, getter, js_name = protocol)]
+    pub fn protocol(this: &WebSocketOpenInfo) -> String;
+
+    #[wasm_bindgen(method, getter, js_name = extensions)]
+    pub fn extensions(this: &WebSocketOpenInfo) -> String;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// The result of the [`closed`](WebSocketStream::closed) promise.
+    #[derive(Clone, Debug)]
+    pub type WebSocketCloseResult;
+
+    #[wasm_bindgen(method, getter, js_name = closeCode)]
+    pub fn close_code(this: &WebSocketCloseResult) -> Option<u16>;
+
+    #[wasm_bindgen(method, getter, js_name = reason)]
+    pub fn reason(this: &WebSocketCloseResult) -> Option<String>;
+}
+
+impl WebSocketStream {
+    /// Returns `true` if the browser supports `WebSocketStream`.
+    pub fn is_supported() -> bool {
+        js_sys::Reflect::get(&js_sys::global(), &"WebSocketStream".into())
+            .is_ok_and(|constructor| constructor.is_function())
+    }
+}
+
+/// Raw options for the [`WebSocketStream`](WebSocketStream) constructor.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct WebSocketStreamOptions {
+    protocols: Vec<String>,
+    signal: Option<AbortSignal>,
+}
+
+impl WebSocketStreamOptions {
+    pub fn new(protocols: Vec<String>, signal: Option<AbortSignal>) -> Self {
+        Self { protocols, signal }
+    }
+}
+
+#[wasm_bindgen]
+impl WebSocketStreamOptions {
+    #[wasm_bindgen(getter, js_name = protocols)]
+    pub fn protocols(&self) -> Array {
+        self.protocols.iter().map(JsValue::from).collect()
+    }
+
+    #[wasm_bindgen(getter, js_name = signal)]
+    pub fn signal(&self) -> Option<AbortSignal> {
+        self.signal.clone()
+    }
+}
+
+/// Raw options for [`close()`](WebSocketStream::close_with_info).
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct WebSocketCloseInfo {
+    close_code: Option<u16>,
+    reason: String,
+}
+
+impl WebSocketCloseInfo {
+    pub fn new(close_code: Option<u16>, reason: String) -> Self {
+        Self { close_code, reason }
+    }
+}
+
+#[wasm_bindgen]
+impl WebSocketCloseInfo {
+    #[wasm_bindgen(getter, js_name = closeCode)]
+    pub fn close_code(&self) -> Option<u16> {
+        self.close_code
+    }
+
+    #[wasm_bindgen(getter, js_name = reason)]
+    pub fn reason(&self) -> String {
+        self.reason.clone()
+    }
+}
@@ -0,0 +1,275 @@
+//! Bindings and conversions for
+//! [WebTransport](https://developer.mozilla.org/en-US/docs/Web/API/WebTransport_API).
+//!
+//! [`WebTransport`](WebTransport) exposes its datagrams and streams as this crate's
+//! [`ReadableStream`](ReadableStream), [`WritableStream`](WritableStream) and
+//! [`DuplexStream`](DuplexStream) types, typed with [`Uint8Array`](Uint8Array) chunks.
+//!
+//! Not all browsers support WebTransport yet. Use
+//! [`WebTransport::is_supported`](WebTransport::is_supported) to check for support at runtime.
+//!
+//! This module requires the `webtransport` feature.
+use core::pin::Pin;
+
+use futures::stream::{FusedStream, Stream};
+use futures::task::{Context, Poll};
+use js_sys::{Object, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::duplex::DuplexStream;
+use crate::readable::{self, IntoStream, ReadableStream};
+use crate::util::promise_to_void_future;
+use crate::writable::WritableStream;
+use crate::StreamError;
+
+pub mod sys;
+
+/// A [`WebTransport`](https://developer.mozilla.org/en-US/docs/Web/API/WebTransport) session.
+///
+/// `WebTransport`s can be created with [`new`](Self::new), or from a
+/// [raw JavaScript object](sys::WebTransport) with [`from_raw`](Self::from_raw).
+#[derive(Debug)]
+pub struct WebTransport {
+    raw: sys::WebTransport,
+}
+
+impl WebTransport {
+    /// Returns `true` if the browser supports `WebTransport`.
+    #[inline]
+    pub fn is_supported() -> bool {
+        sys::WebTransport::is_supported()
+    }
+
+    /// Opens a new WebTransport session to the given `url`.
+    ///
+    /// If the browser does not support `WebTransport`, or if the URL is invalid,
+    /// then this returns an error.
+    pub fn new(url: &str) -> Result<Self, JsValue> {
+        Self::new_with_options(url, &Object::new())
+    }
+
+    /// Opens a new WebTransport session to the given `url` with the given raw
+    /// [options](https://developer.mozilla.org/en-US/docs/Web/API/WebTransport/WebTransport#options).
+    ///
+    /// If the browser does not support `WebTransport`, or if the URL is invalid,
+    /// then this returns an error.
+    pub fn new_with_options(url: &str, options: &Object) -> Result<Self, JsValue> {
+        if !Self::is_supported() {
+            return Err(js_sys::TypeError::new("WebTransport is not supported").into());
+        }
+        let raw = sys::WebTransport::new_with_options(url, options)?;
+        Ok(Self { raw })
+    }
+
+    /// Creates a new `WebTransport` from a [JavaScript object](sys::WebTransport).
+    #[inline]
+    pub fn from_raw(raw: sys::WebTransport) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript object](sys::WebTransport).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::WebTransport {
+        &self.raw
+    }
+
+    /// Consumes this `WebTransport`, returning the underlying [JavaScript object](sys::WebTransport).
+    #[inline]
+    pub fn into_raw(self) -> sys::WebTransport {
+        self.raw
+    }
+
+    /// Waits for the session to be established.
+    ///
+    /// This returns an error if the session could not be established.
+    pub async fn ready(&self) -> Result<(), StreamError> {
+        promise_to_void_future(self.as_raw().ready())
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Waits for the session to be closed, returning the close code and reason
+    /// sent by the server.
+    ///
+    /// This returns an error if the session was closed abruptly.
+    pub async fn closed(&self) -> Result<WebTransportCloseInfo, StreamError> {
+        let result = JsFuture::from(self.as_raw().closed())
+            .await
+            .map_err(StreamError::from)?;
+        let result = result.unchecked_into::<sys::WebTransportCloseResult>();
+        Ok(WebTransportCloseInfo {
+            close_code: result.close_code().unwrap_or(0),
+            reason: result.reason().unwrap_or_default(),
+        })
+    }
+
+    /// Closes the session.
+    #[inline]
+    pub fn close(&self) {
+        self.as_raw().close()
+    }
+
+    /// Closes the session with the given application close code and reason.
+    #[inline]
+    pub fn close_with_info(&self, close_code: u32, reason: &str) {
+        self.as_raw()
+            .close_with_info(sys::WebTransportCloseInfo::new(close_code, reason.into()))
+    }
+
+    /// Returns the datagrams of this session, as a [`DuplexStream`](DuplexStream).
+    ///
+    /// Each chunk is a single datagram. Datagrams are unreliable: they may be dropped or
+    /// arrive out of order, and they must be at most [`max_datagram_size`](Self::max_datagram_size)
+    /// bytes long.
+    pub fn datagrams(&self) -> DuplexStream<Uint8Array, Uint8Array> {
+        let datagrams = self.as_raw().datagrams();
+        DuplexStream::new(
+            ReadableStream::from_raw(datagrams.readable()).cast(),
+            WritableStream::from_raw(datagrams.writable()).cast(),
+        )
+    }
+
+    /// Returns the maximum size of a datagram, in bytes.
+    #[inline]
+    pub fn max_datagram_size(&self) -> u32 {
+        self.as_raw().datagrams().max_datagram_size()
+    }
+
+    /// Opens a new unidirectional stream to the server.
+    pub async fn create_unidirectional_stream(
+        &self,
+    ) -> Result<WritableStream<Uint8Array>, StreamError> {
+        let raw = JsFuture::from(self.as_raw().create_unidirectional_stream())
+            .await
+            .map_err(StreamError::from)?;
+        Ok(WritableStream::from_raw(raw.unchecked_into()).cast())
+    }
+
+    /// Opens a new bidirectional stream to the server.
+    pub async fn create_bidirectional_stream(
+        &self,
+    ) -> Result<DuplexStream<Uint8Array, Uint8Array>, StreamError> {
+        let raw = JsFuture::from(self.as_raw().create_bidirectional_stream())
+            .await
+            .map_err(StreamError::from)?;
+        Ok(bidirectional_stream(raw))
+    }
+
+    /// Returns a [`Stream`](Stream) of unidirectional streams opened by the server.
+    ///
+    /// **Panics** if the incoming streams are already locked,
+    /// e.g. because this method was called before. For a non-panicking variant,
+    /// use [`try_incoming_unidirectional_streams`](Self::try_incoming_unidirectional_streams).
+    pub fn incoming_unidirectional_streams(&self) -> IncomingStreams<ReadableStream<Uint8Array>> {
+        self.try_incoming_unidirectional_streams()
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to return a [`Stream`](Stream) of unidirectional streams opened by the server.
+    ///
+    /// If the incoming streams are already locked, then this returns an error.
+    pub fn try_incoming_unidirectional_streams(
+        &self,
+    ) -> Result<IncomingStreams<ReadableStream<Uint8Array>>, js_sys::Error> {
+        IncomingStreams::try_new(self.as_raw().incoming_unidirectional_streams(), |raw| {
+            ReadableStream::from_raw(raw.unchecked_into()).cast()
+        })
+    }
+
+    /// Returns a [`Stream`](Stream) of bidirectional streams opened by the server.
+    ///
+    /// **Panics** if the incoming streams are already locked,
+    /// e.g. because this method was called before. For a non-panicking variant,
+    /// use [`try_incoming_bidirectional_streams`](Self::try_incoming_bidirectional_streams).
+    pub fn incoming_bidirectional_streams(
+        &self,
+    ) -> IncomingStreams<DuplexStream<Uint8Array, Uint8Array>> {
+        self.try_incoming_bidirectional_streams()
+            .expect_throw("already locked to a reader")
+    }
+
+    /// Try to return a [`Stream`](Stream) of bidirectional streams opened by the server.
+    ///
+    /// If the incoming streams are already locked, then this returns an error.
+    pub fn try_incoming_bidirectional_streams(
+        &self,
+    ) -> Result<IncomingStreams<DuplexStream<Uint8Array, Uint8Array>>, js_sys::Error> {
+        IncomingStreams::try_new(
+            self.as_raw().incoming_bidirectional_streams(),
+            bidirectional_stream,
+        )
+    }
+}
+
+fn bidirectional_stream(raw: JsValue) -> DuplexStream<Uint8Array, Uint8Array> {
+    let raw = raw.unchecked_into::<sys::WebTransportBidirectionalStream>();
+    DuplexStream::new(
+        ReadableStream::from_raw(raw.readable()).cast(),
+        WritableStream::from_raw(raw.writable()).cast(),
+    )
+}
+
+/// A [`Stream`](Stream) of incoming streams of a [`WebTransport`](WebTransport) session.
+///
+/// This stream holds a reader, and therefore locks the session's incoming streams.
+/// When this stream is dropped, it also drops its reader which in turn
+/// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct IncomingStreams<T> {
+    inner: IntoStream<'static>,
+    convert: fn(JsValue) -> T,
+}
+
+impl<T> IncomingStreams<T> {
+    fn try_new(
+        raw: readable::sys::ReadableStream,
+        convert: fn(JsValue) -> T,
+    ) -> Result<Self, js_sys::Error> {
+        let inner = ReadableStream::from_raw(raw)
+            .try_into_stream()
+            .map_err(|(err, _)| err)?;
+        Ok(Self { inner, convert })
+    }
+}
+
+impl<T> FusedStream for IncomingStreams<T> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
+
+impl<T> Stream for IncomingStreams<T> {
+    type Item = Result<T, JsValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let convert = self.convert;
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|item| item.map(|result| result.map(convert)))
+    }
+}
+
+/// The close code and reason of a closed [`WebTransport`](WebTransport) session,
+/// returned by [`closed`](WebTransport::closed).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebTransportCloseInfo {
+    close_code: u32,
+    reason: String,
+}
+
+impl WebTransportCloseInfo {
+    /// Returns the application close code sent by the server.
+    #[inline]
+    pub fn close_code(&self) -> u32 {
+        self.close_code
+    }
+
+    /// Returns the close reason sent by the server.
+    #[inline]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
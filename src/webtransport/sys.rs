@@ -0,0 +1,128 @@
+//! Raw bindings to JavaScript objects used
+//! by a [`WebTransport`](https://developer.mozilla.org/en-US/docs/Web/API/WebTransport).
+use js_sys::{Error, Object, Promise};
+use wasm_bindgen::prelude::*;
+
+use crate::readable::sys::ReadableStream;
+use crate::writable::sys::WritableStream;
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`WebTransport`](https://developer.mozilla.org/en-US/docs/Web/API/WebTransport).
+    ///
+    /// This represents the same JavaScript objects as `web_sys::WebTransport`, which is
+    /// only available with `--cfg=web_sys_unstable_apis`.
+    /// Not all browsers support this API yet, see [`is_supported`](Self::is_supported).
+    #[derive(Clone, Debug)]
+    pub type WebTransport;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(url: &str) -> Result<WebTransport, Error>;
+
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new_with_options(url: &str, options: &Object) -> Result<WebTransport, Error>;
+
+    #[wasm_bindgen(method, getter, js_name = ready)]
+    pub fn ready(this: &WebTransport) -> Promise;
+
+    #[wasm_bindgen(method, getter, js_name = closed)]
+    pub fn closed(this: &WebTransport) -> Promise;
+
+    #[wasm_bindgen(method, getter, js_name = datagrams)]
+    pub fn datagrams(this: &WebTransport) -> WebTransportDatagramDuplexStream;
+
+    #[wasm_bindgen(method, getter, js_name = incomingBidirectionalStreams)]
+    pub fn incoming_bidirectional_streams(this: &WebTransport) -> ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name = incomingUnidirectionalStreams)]
+    pub fn incoming_unidirectional_streams(this: &WebTransport) -> ReadableStream;
+
+    #[wasm_bindgen(method, js_name = createBidirectionalStream)]
+    pub fn create_bidirectional_stream(this: &WebTransport) -> Promise;
+
+    #[wasm_bindgen(method, js_name = createUnidirectionalStream)]
+    pub fn create_unidirectional_stream(this: &WebTransport) -> Promise;
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close(this: &WebTransport);
+
+    #[wasm_bindgen(method, js_name = close)]
+    pub fn close_with_info(this: &WebTransport, info: WebTransportCloseInfo);
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`WebTransportDatagramDuplexStream`](https://developer.mozilla.org/en-US/docs/Web/API/WebTransportDatagramDuplexStream).
+    #[derive(Clone, Debug)]
+    pub type WebTransportDatagramDuplexStream;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &WebTransportDatagramDuplexStream) -> ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &WebTransportDatagramDuplexStream) -> WritableStream;
+
+    #[wasm_bindgen(method, getter, js_name = maxDatagramSize)]
+    pub fn max_datagram_size(this: &WebTransportDatagramDuplexStream) -> u32;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`WebTransportBidirectionalStream`](https://developer.mozilla.org/en-US/docs/Web/API/WebTransportBidirectionalStream).
+    #[derive(Clone, Debug)]
+    pub type WebTransportBidirectionalStream;
+
+    #[wasm_bindgen(method, getter, js_name = readable)]
+    pub fn readable(this: &WebTransportBidirectionalStream) -> ReadableStream;
+
+    #[wasm_bindgen(method, getter, js_name = writable)]
+    pub fn writable(this: &WebTransportBidirectionalStream) -> WritableStream;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// The result of the [`closed`](WebTransport::closed) promise.
+    #[derive(Clone, Debug)]
+    pub type WebTransportCloseResult;
+
+    #[wasm_bindgen(method, getter, js_name = closeCode)]
+    pub fn close_code(this: &WebTransportCloseResult) -> Option<u32>;
+
+    #[wasm_bindgen(method, getter, js_name = reason)]
+    pub fn reason(this: &WebTransportCloseResult) -> Option<String>;
+}
+
+impl WebTransport {
+    /// Returns `true` if the browser supports `WebTransport`.
+    pub fn is_supported() -> bool {
+        js_sys::Reflect::get(&js_sys::global(), &"WebTransport".into())
+            .is_ok_and(|constructor| constructor.is_function())
+    }
+}
+
+/// Raw options for [`close()`](WebTransport::close_with_info).
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct WebTransportCloseInfo {
+    close_code: u32,
+    reason: String,
+}
+
+impl WebTransportCloseInfo {
+    pub fn new(close_code: u32, reason: String) -> Self {
+        Self { close_code, reason }
+    }
+}
+
+#[wasm_bindgen]
+impl WebTransportCloseInfo {
+    #[wasm_bindgen(getter, js_name = closeCode)]
+    pub fn close_code(&self) -> u32 {
+        self.close_code
+    }
+
+    #[wasm_bindgen(getter, js_name = reason)]
+    pub fn reason(&self) -> String {
+        self.reason.clone()
+    }
+}
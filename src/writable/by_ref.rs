@@ -0,0 +1,211 @@
+use core::pin::Pin;
+use std::collections::VecDeque;
+
+use futures::future::Future;
+use futures::ready;
+use futures::sink::Sink;
+use futures::task::{Context, Poll};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::StreamError;
+
+use super::{sys, WritableStream};
+
+/// The writes started by polling a `&mut WritableStream`, which hold the stream's lock
+/// until they have completed.
+pub(super) struct PendingWrites {
+    writer: sys::WritableStreamDefaultWriter,
+    ready_fut: Option<JsFuture>,
+    write_futs: VecDeque<JsFuture>,
+    close_fut: Option<JsFuture>,
+}
+
+impl PendingWrites {
+    fn new(writer: sys::WritableStreamDefaultWriter) -> Self {
+        Self {
+            writer,
+            ready_fut: None,
+            write_futs: VecDeque::new(),
+            close_fut: None,
+        }
+    }
+
+    /// Polls the pending writes in order, until one of them is still pending.
+    fn poll_writes(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), JsValue>> {
+        while let Some(fut) = self.write_futs.front_mut() {
+            ready!(Pin::new(fut).poll(cx))?;
+            self.write_futs.pop_front();
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), JsValue>> {
+        // Report errors from writes that have already completed
+        if let Poll::Ready(Err(js_value)) = self.poll_writes(cx) {
+            return Poll::Ready(Err(js_value));
+        }
+        if self.ready_fut.is_none() {
+            // If the queue still has room, we're ready without waiting for a promise
+            if matches!(self.writer.desired_size(), Some(size) if size > 0.0) {
+                return Poll::Ready(Ok(()));
+            }
+            self.ready_fut = Some(JsFuture::from(self.writer.ready()));
+        }
+        let js_result = ready!(Pin::new(self.ready_fut.as_mut().unwrap_throw()).poll(cx));
+        self.ready_fut = None;
+        Poll::Ready(js_result.map(|_| ()))
+    }
+
+    fn start_send(&mut self, item: JsValue) {
+        trace_event!("writing chunk to writable stream");
+        // Queue the write, without waiting for it to complete
+        let fut = JsFuture::from(self.writer.write(item));
+        self.write_futs.push_back(fut);
+    }
+
+    fn poll_close(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), JsValue>> {
+        // Wait for all pending writes to complete first
+        ready!(self.poll_writes(cx))?;
+        let writer = &self.writer;
+        let fut = self.close_fut.get_or_insert_with(|| {
+            trace_event!("closing writable stream");
+            JsFuture::from(writer.close())
+        });
+        let js_result = ready!(Pin::new(fut).poll(cx));
+        Poll::Ready(js_result.map(|_| ()))
+    }
+}
+
+impl Drop for PendingWrites {
+    fn drop(&mut self) {
+        // Any writes that are still pending continue in the background.
+        self.writer.release_lock();
+    }
+}
+
+impl<T: JsCast> WritableStream<T> {
+    /// Returns a [`Sink`](Sink) that writes chunks of type `T` to this stream without
+    /// consuming it.
+    ///
+    /// This is the typed counterpart of the `Sink<JsValue>` implementation for
+    /// `&mut WritableStream`, and behaves in the same way.
+    #[inline]
+    pub fn by_ref_sink(&mut self) -> ByRefSink<'_, T> {
+        ByRefSink { stream: self }
+    }
+
+    /// Returns the pending writes, acquiring a writer first if there are none.
+    fn pending_writes(&mut self) -> Result<&mut PendingWrites, StreamError> {
+        if self.pending_writes.is_none() {
+            let writer = self.raw.get_writer()?;
+            self.pending_writes = Some(PendingWrites::new(writer));
+        }
+        Ok(self.pending_writes.as_mut().unwrap_throw())
+    }
+
+    /// Releases the writer's lock after the writes encountered an error.
+    fn release_on_error<R>(&mut self, result: Result<R, JsValue>) -> Result<R, StreamError> {
+        if result.is_err() {
+            self.pending_writes = None;
+        }
+        result.map_err(StreamError::from)
+    }
+
+    fn poll_ready_by_ref(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), StreamError>> {
+        let result = ready!(self.pending_writes()?.poll_ready(cx));
+        Poll::Ready(self.release_on_error(result))
+    }
+
+    fn start_send_by_ref(&mut self, item: JsValue) -> Result<(), StreamError> {
+        self.pending_writes()?.start_send(item);
+        Ok(())
+    }
+
+    fn poll_flush_by_ref(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), StreamError>> {
+        let pending = match &mut self.pending_writes {
+            Some(pending) => pending,
+            // Nothing was written yet
+            None => return Poll::Ready(Ok(())),
+        };
+        let result = ready!(pending.poll_writes(cx));
+        // All writes have completed, release the lock until the next write.
+        self.pending_writes = None;
+        Poll::Ready(result.map_err(StreamError::from))
+    }
+
+    fn poll_close_by_ref(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), StreamError>> {
+        let result = ready!(self.pending_writes()?.poll_close(cx));
+        self.pending_writes = None;
+        Poll::Ready(result.map_err(StreamError::from))
+    }
+}
+
+/// Writes chunks to a [`WritableStream`](WritableStream) without converting it
+/// into a [`Sink`](Sink) first, so a `&mut WritableStream` can be passed directly to any
+/// [`SinkExt`](futures::SinkExt) combinator.
+///
+/// The first poll acquires a writer, and the writer's lock is
+/// [released](https://streams.spec.whatwg.org/#release-a-lock) again once the sink is flushed
+/// or closed, when it encounters an error, or when the stream is dropped.
+/// While the writer is held, the stream is [locked](WritableStream::is_locked).
+/// If the stream is already locked to another writer, this returns a `TypeError`.
+///
+/// This behaves the same as [`into_sink`](WritableStream::into_sink): sending a chunk does not
+/// wait for it to be written, so flush the sink to find out whether all writes succeeded.
+/// Dropping the stream before flushing discards the results of any pending writes.
+/// Unlike `into_sink`, errors are reported as [`StreamError`](StreamError)s.
+///
+/// For a `WritableStream<T>`, use [`by_ref_sink`](WritableStream::by_ref_sink) instead.
+impl Sink<JsValue> for &mut WritableStream {
+    type Error = StreamError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_ready_by_ref(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: JsValue) -> Result<(), Self::Error> {
+        self.get_mut().start_send_by_ref(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_flush_by_ref(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_close_by_ref(cx)
+    }
+}
+
+/// A [`Sink`](Sink) that writes chunks of type `T` to a borrowed
+/// [`WritableStream`](WritableStream).
+///
+/// This is created by [`WritableStream::by_ref_sink`](WritableStream::by_ref_sink).
+#[must_use = "sinks do nothing unless polled"]
+#[derive(Debug)]
+pub struct ByRefSink<'stream, T = JsValue> {
+    stream: &'stream mut WritableStream<T>,
+}
+
+impl<T> Unpin for ByRefSink<'_, T> {}
+
+impl<T: JsCast> Sink<T> for ByRefSink<'_, T> {
+    type Error = StreamError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().stream.poll_ready_by_ref(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.get_mut().stream.start_send_by_ref(item.into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().stream.poll_flush_by_ref(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().stream.poll_close_by_ref(cx)
+    }
+}
@@ -0,0 +1,22 @@
+/// What a writer does with its lock on the stream when it is dropped.
+///
+/// See [`WritableStreamDefaultWriter::set_drop_policy`](super::WritableStreamDefaultWriter::set_drop_policy).
+///
+/// The default policy is [`Release`](WriterDropPolicy::Release).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriterDropPolicy {
+    /// [Release](https://streams.spec.whatwg.org/#release-a-lock) the writer's lock.
+    ///
+    /// Any pending writes continue in the background.
+    #[default]
+    Release,
+    /// [Abort](https://streams.spec.whatwg.org/#abort-a-writable-stream) the stream,
+    /// and then release the writer's lock.
+    ///
+    /// Any queued-up writes are discarded.
+    AbortThenRelease,
+    /// Do not release the writer's lock.
+    ///
+    /// The stream stays locked forever, and can no longer be written by anyone else.
+    Leak,
+}
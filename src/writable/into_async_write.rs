@@ -0,0 +1,102 @@
+use core::pin::Pin;
+use std::io;
+
+use futures::io::AsyncWrite;
+use futures::ready;
+use futures::sink::Sink;
+use futures::task::{Context, Poll};
+use js_sys::Uint8Array;
+use wasm_bindgen::JsValue;
+
+use crate::util::js_to_io_error;
+
+use super::IntoSink;
+
+/// An [`AsyncWrite`](AsyncWrite) for the [`into_async_write`](super::WritableStream::into_async_write)
+/// method.
+///
+/// This writer holds a writer, and therefore locks the [`WritableStream`](super::WritableStream).
+/// When this writer is dropped, it also drops its writer which in turn
+/// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+///
+/// Every call to [`poll_write`](AsyncWrite::poll_write) writes the given bytes
+/// as a single [`Uint8Array`](Uint8Array) chunk, after waiting for the writer to be
+/// [ready](super::WritableStreamDefaultWriter::ready).
+#[must_use = "writers do nothing unless polled"]
+#[derive(Debug)]
+pub struct IntoAsyncWrite<'writer> {
+    sink: IntoSink<'writer>,
+}
+
+impl<'writer> IntoAsyncWrite<'writer> {
+    #[inline]
+    pub(super) fn new(sink: IntoSink<'writer>) -> Self {
+        Self { sink }
+    }
+
+    /// Closes the stream when this writer is dropped, unless it was already closed.
+    ///
+    /// See [`IntoSink.close_on_drop`](IntoSink::close_on_drop) for more details.
+    pub fn close_on_drop(self) -> Self {
+        Self::new(self.sink.close_on_drop())
+    }
+
+    /// Aborts the stream with the given `reason` when this writer is dropped,
+    /// unless it was already closed.
+    ///
+    /// See [`IntoSink.abort_on_drop`](IntoSink::abort_on_drop) for more details.
+    pub fn abort_on_drop(self, reason: JsValue) -> Self {
+        Self::new(self.sink.abort_on_drop(reason))
+    }
+}
+
+impl AsyncWrite for IntoAsyncWrite<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        ready!(Pin::new(&mut self.sink).poll_ready(cx)).map_err(js_to_io_error)?;
+        Pin::new(&mut self.sink)
+            .start_send(Uint8Array::from(buf).into())
+            .map_err(js_to_io_error)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.sink)
+            .poll_flush(cx)
+            .map_err(js_to_io_error)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.sink)
+            .poll_close(cx)
+            .map_err(js_to_io_error)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for IntoAsyncWrite<'_> {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        AsyncWrite::poll_write(self, cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_flush(self, cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_close(self, cx)
+    }
+}
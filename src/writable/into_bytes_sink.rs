@@ -0,0 +1,83 @@
+use core::pin::Pin;
+
+use futures::sink::Sink;
+use futures::task::{Context, Poll};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use super::IntoSink;
+
+/// A [`Sink`](Sink) of bytes for the [`into_bytes_sink`](super::WritableStream::into_bytes_sink)
+/// method.
+///
+/// This sink holds a writer, and therefore locks the [`WritableStream`](super::WritableStream).
+/// When this sink is dropped, it also drops its writer which in turn
+/// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+///
+/// This sink accepts any item that can be viewed as bytes, such as `Vec<u8>`, `&[u8]`,
+/// or [`Bytes`](https://docs.rs/bytes/1/bytes/struct.Bytes.html). Every item is copied into
+/// a new [`Uint8Array`](Uint8Array), which is then written to the stream as a single chunk.
+/// A scratch array cannot be reused across writes, since the stream may still hold on to
+/// previously written chunks, e.g. in its queue or further down a pipe.
+#[derive(Debug)]
+pub struct IntoBytesSink<'writer> {
+    sink: IntoSink<'writer, Uint8Array>,
+}
+
+impl<'writer> IntoBytesSink<'writer> {
+    #[inline]
+    pub(super) fn new(sink: IntoSink<'writer, Uint8Array>) -> Self {
+        Self { sink }
+    }
+
+    /// Closes the stream when this sink is dropped, unless it was already closed.
+    ///
+    /// See [`IntoSink.close_on_drop`](IntoSink::close_on_drop) for more details.
+    pub fn close_on_drop(self) -> Self {
+        Self::new(self.sink.close_on_drop())
+    }
+
+    /// Aborts the stream with the given `reason` when this sink is dropped,
+    /// unless it was already closed.
+    ///
+    /// See [`IntoSink.abort_on_drop`](IntoSink::abort_on_drop) for more details.
+    pub fn abort_on_drop(self, reason: JsValue) -> Self {
+        Self::new(self.sink.abort_on_drop(reason))
+    }
+
+    /// Returns the desired size to fill the stream's internal queue.
+    ///
+    /// See [`IntoSink.desired_size`](IntoSink::desired_size) for more details.
+    #[inline]
+    pub fn desired_size(&self) -> Option<f64> {
+        self.sink.desired_size()
+    }
+
+    /// Returns whether the stream is currently applying backpressure.
+    ///
+    /// See [`IntoSink.has_backpressure`](IntoSink::has_backpressure) for more details.
+    #[inline]
+    pub fn has_backpressure(&self) -> bool {
+        self.sink.has_backpressure()
+    }
+}
+
+impl<B: AsRef<[u8]>> Sink<B> for IntoBytesSink<'_> {
+    type Error = JsValue;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.sink).poll_ready(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: B) -> Result<(), Self::Error> {
+        Pin::new(&mut self.sink).start_send(Uint8Array::from(item.as_ref()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.sink).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.sink).poll_close(cx)
+    }
+}
@@ -1,12 +1,17 @@
 use core::pin::Pin;
+use std::collections::VecDeque;
 
 use futures::future::Future;
 use futures::ready;
 use futures::sink::Sink;
 use futures::task::{Context, Poll};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
 
+use crate::error::ClassifyErrors;
+use crate::util::detach_promise;
+
 use super::WritableStreamDefaultWriter;
 
 /// A [`Sink`](Sink) for the [`into_sink`](super::WritableStream::into_sink) method.
@@ -14,34 +19,127 @@ use super::WritableStreamDefaultWriter;
 /// This sink holds a writer, and therefore locks the [`WritableStream`](super::WritableStream).
 /// When this sink is dropped, it also drops its writer which in turn
 /// [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+/// Use [`close_on_drop`](Self::close_on_drop) or [`abort_on_drop`](Self::abort_on_drop)
+/// to also close or abort the stream if the sink is dropped before it was closed.
+///
+/// The sink is ready to accept a new chunk as long as the stream's internal queue has room,
+/// as indicated by its [desired size](super::WritableStreamDefaultWriter::desired_size).
+/// Sending a chunk does not wait for it to be written. Instead, flushing the sink waits until
+/// all chunks sent so far have been written.
 #[derive(Debug)]
-pub struct IntoSink<'writer> {
-    writer: Option<WritableStreamDefaultWriter<'writer>>,
+pub struct IntoSink<'writer, T = JsValue> {
+    writer: Option<WritableStreamDefaultWriter<'writer, T>>,
     ready_fut: Option<JsFuture>,
-    write_fut: Option<JsFuture>,
+    write_futs: VecDeque<JsFuture>,
     close_fut: Option<JsFuture>,
+    on_drop: OnDrop,
+}
+
+/// What to do with the stream when an [`IntoSink`](IntoSink) is dropped before it was closed.
+#[derive(Debug)]
+enum OnDrop {
+    Release,
+    Close,
+    Abort(JsValue),
 }
 
-impl<'writer> IntoSink<'writer> {
+impl<'writer, T: JsCast> IntoSink<'writer, T> {
     #[inline]
-    pub(super) fn new(writer: WritableStreamDefaultWriter) -> IntoSink {
-        IntoSink {
+    pub(super) fn new(writer: WritableStreamDefaultWriter<'writer, T>) -> Self {
+        Self {
             writer: Some(writer),
             ready_fut: None,
-            write_fut: None,
+            write_futs: VecDeque::new(),
             close_fut: None,
+            on_drop: OnDrop::Release,
+        }
+    }
+
+    /// [Closes](https://streams.spec.whatwg.org/#close-a-writable-stream) the stream
+    /// when this sink is dropped, unless the sink was already closed or has encountered an error.
+    ///
+    /// Any chunks that were sent before the sink was dropped are still written to the stream.
+    /// Note that there is no way to find out whether closing the stream succeeded. To handle
+    /// any errors, explicitly [close](futures::sink::SinkExt::close) the sink instead.
+    pub fn close_on_drop(mut self) -> Self {
+        self.on_drop = OnDrop::Close;
+        self
+    }
+
+    /// [Aborts](https://streams.spec.whatwg.org/#abort-a-writable-stream) the stream with the
+    /// given `reason` when this sink is dropped, unless the sink was already closed
+    /// or has encountered an error.
+    ///
+    /// Any chunks that were sent but not yet written are discarded.
+    pub fn abort_on_drop(mut self, reason: JsValue) -> Self {
+        self.on_drop = OnDrop::Abort(reason);
+        self
+    }
+
+    /// Returns the desired size to fill the stream's internal queue.
+    ///
+    /// See [`WritableStreamDefaultWriter.desired_size`](super::WritableStreamDefaultWriter::desired_size)
+    /// for more details. This also returns `None` once the sink has encountered an error
+    /// or has been closed.
+    #[inline]
+    pub fn desired_size(&self) -> Option<f64> {
+        self.writer.as_ref()?.desired_size()
+    }
+
+    /// Returns whether the stream is currently applying backpressure,
+    /// i.e. whether its internal queue is full.
+    ///
+    /// While this is `true`, [`poll_ready`](Sink::poll_ready) will wait for the queue to drain.
+    #[inline]
+    pub fn has_backpressure(&self) -> bool {
+        !matches!(self.desired_size(), Some(size) if size > 0.0)
+    }
+
+    /// Converts the errors of this sink into [`StreamError`](crate::StreamError)s.
+    ///
+    /// This allows distinguishing a stream which was [aborted](crate::StreamError::Aborted)
+    /// from one which failed for another reason. See [`ClassifyErrors`](ClassifyErrors)
+    /// for more details.
+    #[inline]
+    pub fn classify_errors(self) -> ClassifyErrors<Self> {
+        ClassifyErrors::new(self)
+    }
+
+    /// Polls the pending writes in order, until one of them is still pending.
+    ///
+    /// Returns an error as soon as a write has failed.
+    fn poll_writes(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), JsValue>> {
+        while let Some(fut) = self.write_futs.front_mut() {
+            let js_result = ready!(Pin::new(fut).poll(cx));
+            self.write_futs.pop_front();
+            if let Err(js_value) = js_result {
+                // Error, drop writer and any other pending writes
+                debug_event!(error = ?js_value, "writing to writable stream failed");
+                self.write_futs.clear();
+                self.writer = None;
+                return Poll::Ready(Err(js_value));
+            }
         }
+        Poll::Ready(Ok(()))
     }
 }
 
-impl<'writer> Sink<JsValue> for IntoSink<'writer> {
+impl<'writer, T: JsCast> Sink<T> for IntoSink<'writer, T> {
     type Error = JsValue;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Report errors from writes that have already completed
+        if let Poll::Ready(Err(js_value)) = self.poll_writes(cx) {
+            return Poll::Ready(Err(js_value));
+        }
+
         if self.ready_fut.is_none() {
-            // No pending ready future, start reading the next chunk
             match self.writer.as_ref() {
                 Some(writer) => {
+                    // If the queue still has room, we're ready without waiting for a promise
+                    if !self.has_backpressure() {
+                        return Poll::Ready(Ok(()));
+                    }
                     // Create future for ready promise
                     let fut = JsFuture::from(writer.as_raw().ready());
                     self.as_mut().ready_fut = Some(fut);
@@ -66,18 +164,20 @@ impl<'writer> Sink<JsValue> for IntoSink<'writer> {
             }
             Err(js_value) => {
                 // Error, drop writer
+                self.as_mut().write_futs.clear();
                 self.as_mut().writer = None;
                 Err(js_value)
             }
         })
     }
 
-    fn start_send(mut self: Pin<&mut Self>, item: JsValue) -> Result<(), Self::Error> {
+    fn start_send(mut self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
         match self.writer.as_ref() {
             Some(writer) => {
-                let fut = JsFuture::from(writer.as_raw().write(item));
-                // Set or replace the pending write future
-                self.as_mut().write_fut = Some(fut);
+                trace_event!("writing chunk to writable stream");
+                let fut = JsFuture::from(writer.as_raw().write(item.into()));
+                // Queue the write, without waiting for it to complete
+                self.as_mut().write_futs.push_back(fut);
                 Ok(())
             }
             None => {
@@ -88,35 +188,20 @@ impl<'writer> Sink<JsValue> for IntoSink<'writer> {
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        // If we're not writing, then there's nothing to flush
-        if self.write_fut.is_none() {
-            return Poll::Ready(Ok(()));
-        }
-
-        // Poll the write future
-        let js_result = ready!(Pin::new(self.as_mut().write_fut.as_mut().unwrap_throw()).poll(cx));
-        self.as_mut().write_fut = None;
-
-        // Write future completed
-        Poll::Ready(match js_result {
-            Ok(js_value) => {
-                debug_assert!(js_value.is_undefined());
-                Ok(())
-            }
-            Err(js_value) => {
-                // Error, drop writer
-                self.as_mut().writer = None;
-                Err(js_value)
-            }
-        })
+        // Wait for all pending writes to complete
+        self.poll_writes(cx)
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Wait for all pending writes to complete first
+        ready!(self.poll_writes(cx))?;
+
         if self.close_fut.is_none() {
             // No pending close future, start closing the stream
             match self.writer.as_ref() {
                 Some(writer) => {
                     // Create future for close promise
+                    trace_event!("closing writable stream");
                     let fut = JsFuture::from(writer.as_raw().close());
                     self.as_mut().close_fut = Some(fut);
                 }
@@ -139,7 +224,31 @@ impl<'writer> Sink<JsValue> for IntoSink<'writer> {
                 debug_assert!(js_value.is_undefined());
                 Ok(())
             }
-            Err(js_value) => Err(js_value),
+            Err(js_value) => {
+                debug_event!(error = ?js_value, "closing writable stream failed");
+                Err(js_value)
+            }
         })
     }
 }
+
+impl<T> Drop for IntoSink<'_, T> {
+    fn drop(&mut self) {
+        // The writer is dropped once the sink has closed or encountered an error.
+        let writer = match &self.writer {
+            Some(writer) if self.close_fut.is_none() => writer,
+            _ => return,
+        };
+        match &self.on_drop {
+            OnDrop::Release => {}
+            OnDrop::Close => {
+                debug_event!("closing writable stream on drop");
+                detach_promise(writer.raw.close());
+            }
+            OnDrop::Abort(reason) => {
+                debug_event!(reason = ?reason, "aborting writable stream on drop");
+                detach_promise(writer.raw.abort_with_reason(reason));
+            }
+        }
+    }
+}
@@ -2,85 +2,148 @@ use std::cell::RefCell;
 use std::pin::Pin;
 use std::rc::Rc;
 
+use futures::future::{FutureExt, LocalBoxFuture};
 use futures::sink::{Sink, SinkExt};
 use js_sys::Promise;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
 
+use crate::util::future_to_promise_eager;
+
+use super::sys;
+use super::underlying_sink::{UnderlyingSink, WritableStreamDefaultController};
+
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 #[wasm_bindgen]
 pub(crate) struct IntoUnderlyingSink {
-    inner: Rc<RefCell<Inner>>,
+    inner: Rc<RefCell<Box<dyn UnderlyingSink>>>,
 }
 
 impl IntoUnderlyingSink {
-    pub fn new(sink: Box<dyn Sink<JsValue, Error = JsValue>>) -> Self {
+    pub fn new(sink: Box<dyn UnderlyingSink>) -> Self {
         IntoUnderlyingSink {
-            inner: Rc::new(RefCell::new(Inner::new(sink))),
+            inner: Rc::new(RefCell::new(sink)),
         }
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 #[wasm_bindgen]
 impl IntoUnderlyingSink {
-    pub fn write(&mut self, chunk: JsValue) -> Promise {
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn start(&mut self, controller: sys::WritableStreamDefaultController) -> Promise {
         let inner = self.inner.clone();
         future_to_promise(async move {
+            // This mutable borrow can never panic, since the WritableStream always waits
+            // for start() to finish before calling any other method.
+            let mut inner = inner.try_borrow_mut().unwrap_throw();
+            let controller = WritableStreamDefaultController::from_raw(controller);
+            inner.start(&controller).await.map(|_| JsValue::undefined())
+        })
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub fn write(
+        &mut self,
+        chunk: JsValue,
+        controller: sys::WritableStreamDefaultController,
+    ) -> Promise {
+        let inner = self.inner.clone();
+        future_to_promise(in_span!("write", async move {
             // This mutable borrow can never panic, since the WritableStream always queues
             // each operation on the underlying sink.
             let mut inner = inner.try_borrow_mut().unwrap_throw();
-            inner.write(chunk).await.map(|_| JsValue::undefined())
-        })
+            let controller = WritableStreamDefaultController::from_raw(controller);
+            trace_event!("writing chunk to underlying sink");
+            let result = inner.write(chunk, &controller).await;
+            if let Err(err) = &result {
+                debug_event!(error = ?err, "writing to underlying sink failed");
+            }
+            result.map(|_| JsValue::undefined())
+        }))
     }
 
+    #[allow(clippy::await_holding_refcell_ref)]
     pub fn close(self) -> Promise {
+        trace_event!("closing underlying sink");
         future_to_promise(async move {
             let mut inner = self.inner.try_borrow_mut().unwrap_throw();
             inner.close().await.map(|_| JsValue::undefined())
         })
     }
 
+    #[allow(clippy::await_holding_refcell_ref)]
     pub fn abort(self, reason: JsValue) -> Promise {
-        future_to_promise(async move {
+        debug_event!(reason = ?reason, "underlying sink aborted");
+        // The WritableStream waits for any pending write before aborting, so the borrow below
+        // is available right away. This lets the sink drop its resources (e.g. the Rust sink)
+        // before returning to JavaScript.
+        future_to_promise_eager(async move {
             let mut inner = self.inner.try_borrow_mut().unwrap_throw();
             inner.abort(reason).await.map(|_| JsValue::undefined())
         })
     }
 }
 
-struct Inner {
+/// An [`UnderlyingSink`](UnderlyingSink) that forwards all chunks to a [`Sink`](Sink).
+pub(crate) struct SinkUnderlyingSink {
     sink: Option<Pin<Box<dyn Sink<JsValue, Error = JsValue>>>>,
+    abort_handler: Option<Box<dyn FnOnce(JsValue)>>,
 }
 
-impl Inner {
-    fn new(sink: Box<dyn Sink<JsValue, Error = JsValue>>) -> Self {
-        Inner {
+impl SinkUnderlyingSink {
+    pub fn new(sink: Box<dyn Sink<JsValue, Error = JsValue>>) -> Self {
+        SinkUnderlyingSink {
             sink: Some(sink.into()),
+            abort_handler: None,
         }
     }
 
-    async fn write(&mut self, chunk: JsValue) -> Result<(), JsValue> {
-        // The stream should still exist, since write() will not be called again
-        // after the sink has closed, aborted or encountered an error.
-        let sink = self.sink.as_mut().unwrap_throw();
-        match sink.send(chunk).await {
-            Ok(()) => Ok(()),
-            Err(err) => {
-                // The stream encountered an error, drop it.
-                self.sink = None;
-                Err(err)
+    pub fn with_abort_handler(mut self, abort_handler: Box<dyn FnOnce(JsValue)>) -> Self {
+        self.abort_handler = Some(abort_handler);
+        self
+    }
+}
+
+impl UnderlyingSink for SinkUnderlyingSink {
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            // The stream should still exist, since write() will not be called again
+            // after the sink has closed, aborted or encountered an error.
+            let sink = self.sink.as_mut().unwrap_throw();
+            match sink.send(chunk).await {
+                Ok(()) => Ok(()),
+                Err(err) => {
+                    // The stream encountered an error, drop it.
+                    self.sink = None;
+                    Err(err)
+                }
             }
         }
+        .boxed_local()
     }
 
-    async fn close(&mut self) -> Result<(), JsValue> {
-        let sink = self.sink.as_mut().unwrap_throw();
-        let result = sink.close().await;
-        self.sink = None;
-        result
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            let sink = self.sink.as_mut().unwrap_throw();
+            let result = sink.close().await;
+            self.sink = None;
+            result
+        }
+        .boxed_local()
     }
 
-    async fn abort(&mut self, _reason: JsValue) -> Result<(), JsValue> {
+    fn abort<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        // Drop the sink before notifying the handler, so the handler can clean up
+        // any state that was shared with the sink.
         self.sink = None;
-        Ok(())
+        if let Some(abort_handler) = self.abort_handler.take() {
+            abort_handler(reason);
+        }
+        futures::future::ready(Ok(())).boxed_local()
     }
 }
@@ -1,38 +1,74 @@
 //! Bindings and conversions for
 //! [writable streams](https://developer.mozilla.org/en-US/docs/Web/API/WritableStream).
+use std::fmt;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use futures::Sink;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
 
+pub use by_ref::ByRefSink;
+pub use drop_policy::WriterDropPolicy;
+pub use into_async_write::IntoAsyncWrite;
+pub use into_bytes_sink::IntoBytesSink;
 pub use into_sink::IntoSink;
-use into_underlying_sink::IntoUnderlyingSink;
+use into_underlying_sink::{IntoUnderlyingSink, SinkUnderlyingSink};
+pub use switchable::{BoxSink, SwitchableWritableStream};
+pub use underlying_sink::{UnderlyingSink, WritableStreamDefaultController};
 
-use crate::util::promise_to_void_future;
+use crate::queuing_strategy::QueuingStrategy;
+use crate::readable::PipeOptions;
+use crate::transform::TransformStream;
+use crate::util::{detach_promise, promise_to_void_future, promise_to_void_future_with_signal};
+use crate::StreamError;
 
+mod by_ref;
+mod drop_policy;
+mod into_async_write;
+mod into_bytes_sink;
 mod into_sink;
 mod into_underlying_sink;
+mod switchable;
 pub mod sys;
+mod underlying_sink;
+mod write_timeout;
 
 /// A [`WritableStream`](https://developer.mozilla.org/en-US/docs/Web/API/WritableStream).
 ///
 /// `WritableStream`s can be created from a [raw JavaScript stream](sys::WritableStream) with
-/// [`from_raw`](Self::from_raw), or from a Rust [`Sink`](Sink)
-/// with [`from_sink`](Self::from_sink).
+/// [`from_raw`](Self::from_raw), from a Rust [`Sink`](Sink)
+/// with [`from_sink`](Self::from_sink), or from a Rust [`UnderlyingSink`](UnderlyingSink)
+/// with [`from_underlying_sink`](Self::from_underlying_sink).
 ///
 /// They can be converted into a [raw JavaScript stream](sys::WritableStream) with
 /// [`into_raw`](Self::into_raw), or into a Rust [`Sink`](Sink)
 /// with [`into_sink`](Self::into_sink).
-#[derive(Debug)]
-pub struct WritableStream {
+///
+/// By default, chunks are represented by their raw [`JsValue`](JsValue).
+/// Use [`cast`](Self::cast) to get a `WritableStream<T>` which only accepts chunks of type `T`,
+/// e.g. a `WritableStream<Uint8Array>` for a stream of bytes.
+pub struct WritableStream<T = JsValue> {
     raw: sys::WritableStream,
+    pending_writes: Option<by_ref::PendingWrites>,
+    _chunk: PhantomData<T>,
+}
+
+impl<T> fmt::Debug for WritableStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WritableStream")
+            .field("raw", &self.raw)
+            .finish()
+    }
 }
 
 impl WritableStream {
     /// Creates a new `WritableStream` from a [JavaScript stream](sys::WritableStream).
     #[inline]
     pub fn from_raw(raw: sys::WritableStream) -> Self {
-        Self { raw }
+        Self::from_raw_unchecked(raw)
     }
 
     /// Creates a new `WritableStream` from a [`Sink`](Sink).
@@ -44,11 +80,91 @@ impl WritableStream {
     where
         Si: Sink<JsValue, Error = JsValue> + 'static,
     {
-        let sink = IntoUnderlyingSink::new(Box::new(sink));
+        Self::from_underlying_sink(SinkUnderlyingSink::new(Box::new(sink)))
+    }
+
+    /// Creates a new `WritableStream` from a [`Sink`](Sink) with the given
+    /// [queuing strategy](QueuingStrategy).
+    ///
+    /// The JavaScript stream queues up chunks written by the producer while the sink is busy,
+    /// until their total size reaches the strategy's high water mark.
+    /// Only then will [`desired_size`](WritableStreamDefaultWriter::desired_size) drop to zero
+    /// and the producer experience backpressure.
+    ///
+    /// **Note:** the high water mark should be greater than zero. Otherwise, the stream
+    /// applies backpressure even when its queue is empty, which breaks piping to it.
+    ///
+    /// See [`from_sink`](Self::from_sink) for more details.
+    pub fn from_sink_with_strategy<Si>(sink: Si, strategy: QueuingStrategy) -> Self
+    where
+        Si: Sink<JsValue, Error = JsValue> + 'static,
+    {
+        Self::from_underlying_sink_with_strategy(SinkUnderlyingSink::new(Box::new(sink)), strategy)
+    }
+
+    /// Creates a new `WritableStream` from a [`Sink`](Sink), calling `abort_handler`
+    /// with the abort reason when the stream is [aborted](Self::abort_with_reason).
+    ///
+    /// When the stream is aborted, the sink is dropped without being closed,
+    /// and then the `abort_handler` is called. This can be used to clean up
+    /// any partially written state.
+    ///
+    /// See [`from_sink`](Self::from_sink) for more details.
+    pub fn from_sink_with_abort_handler<Si, F>(sink: Si, abort_handler: F) -> Self
+    where
+        Si: Sink<JsValue, Error = JsValue> + 'static,
+        F: FnOnce(JsValue) + 'static,
+    {
+        let sink =
+            SinkUnderlyingSink::new(Box::new(sink)).with_abort_handler(Box::new(abort_handler));
+        Self::from_underlying_sink(sink)
+    }
+
+    /// Creates a new `WritableStream` from an [`UnderlyingSink`](UnderlyingSink).
+    ///
+    /// Compared to [`from_sink`](Self::from_sink), this gives the sink access to the stream's
+    /// [controller](WritableStreamDefaultController) and to the reason with which the stream
+    /// was aborted.
+    pub fn from_underlying_sink<Si>(sink: Si) -> Self
+    where
+        Si: UnderlyingSink + 'static,
+    {
         // Use the default queuing strategy (with a HWM of 1 chunk).
         // We shouldn't set HWM to 0, since that would break piping to the writable stream.
-        let raw = sys::WritableStream::new_with_sink(sink);
-        WritableStream { raw }
+        Self::from_underlying_sink_with_strategy(sink, QueuingStrategy::new(1.0))
+    }
+
+    /// Creates a new `WritableStream` from an [`UnderlyingSink`](UnderlyingSink) with the given
+    /// [queuing strategy](QueuingStrategy).
+    ///
+    /// See [`from_sink_with_strategy`](Self::from_sink_with_strategy) for more details.
+    pub fn from_underlying_sink_with_strategy<Si>(sink: Si, strategy: QueuingStrategy) -> Self
+    where
+        Si: UnderlyingSink + 'static,
+    {
+        let sink = IntoUnderlyingSink::new(Box::new(sink));
+        Self::from_raw(sys::WritableStream::new_with_sink(sink, strategy.as_raw()))
+    }
+}
+
+impl<T: JsCast> WritableStream<T> {
+    #[inline]
+    fn from_raw_unchecked(raw: sys::WritableStream) -> Self {
+        Self {
+            raw,
+            pending_writes: None,
+            _chunk: PhantomData,
+        }
+    }
+
+    /// Casts this stream into a `WritableStream` with chunks of type `U`.
+    ///
+    /// This does not check whether the underlying sink actually accepts chunks of type `U`.
+    ///
+    /// Use `cast::<JsValue>()` to go back to an untyped stream.
+    #[inline]
+    pub fn cast<U: JsCast>(self) -> WritableStream<U> {
+        WritableStream::from_raw_unchecked(self.raw)
     }
 
     /// Acquires a reference to the underlying [JavaScript stream](sys::WritableStream).
@@ -74,7 +190,12 @@ impl WritableStream {
     /// and it is to be immediately moved to an errored state, with any queued-up writes discarded.
     ///
     /// If the stream is currently locked to a writer, then this returns an error.
-    pub async fn abort(&mut self) -> Result<(), JsValue> {
+    pub async fn abort(&mut self) -> Result<(), StreamError> {
+        self.abort_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`abort`](Self::abort), but returns the raw JavaScript error.
+    pub async fn abort_js(&mut self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().abort()).await
     }
 
@@ -83,10 +204,53 @@ impl WritableStream {
     /// and it is to be immediately moved to an errored state, with any queued-up writes discarded.
     ///
     /// If the stream is currently locked to a writer, then this returns an error.
-    pub async fn abort_with_reason(&mut self, reason: &JsValue) -> Result<(), JsValue> {
+    pub async fn abort_with_reason(&mut self, reason: &JsValue) -> Result<(), StreamError> {
+        self.abort_with_reason_js(reason)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Like [`abort_with_reason`](Self::abort_with_reason), but returns the raw JavaScript error.
+    pub async fn abort_with_reason_js(&mut self, reason: &JsValue) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().abort_with_reason(reason)).await
     }
 
+    /// Returns the reason why the stream errored, if it has errored.
+    ///
+    /// This is the error which caused the stream to error, such as the reason it was
+    /// [aborted](Self::abort_with_reason) with, or the error returned by its underlying sink.
+    /// It is the same value as the rejection of a writer's [`closed`](WritableStreamDefaultWriter::closed),
+    /// but it can still be retrieved after that writer has been dropped, e.g. for error reporting
+    /// after a [pipe](crate::ReadableStream::pipe_to) has finished.
+    ///
+    /// This returns `None` if the stream is still writable or has closed successfully.
+    ///
+    /// **Panics** if the stream is currently locked to a writer. For a non-panicking variant,
+    /// use [`try_closed_reason`](Self::try_closed_reason).
+    pub async fn closed_reason(&mut self) -> Option<JsValue> {
+        self.try_closed_reason()
+            .await
+            .expect_throw("already locked to a writer")
+    }
+
+    /// Try to retrieve the reason why the stream errored, if it has errored.
+    ///
+    /// See [`closed_reason`](Self::closed_reason) for more details.
+    ///
+    /// If the stream is currently locked to a writer, then this returns an error.
+    pub async fn try_closed_reason(&mut self) -> Result<Option<JsValue>, js_sys::Error> {
+        // The ready promise of a new writer is rejected with the stored error
+        // if and only if the stream is errored or erroring.
+        let writer = self.as_raw().get_writer()?;
+        let promises = js_sys::Array::of2(
+            &writer.ready(),
+            &js_sys::Promise::resolve(&JsValue::UNDEFINED),
+        );
+        let result = JsFuture::from(js_sys::Promise::race(&promises)).await;
+        writer.release_lock();
+        Ok(result.err())
+    }
+
     /// Creates a [writer](WritableStreamDefaultWriter) and
     /// [locks](https://streams.spec.whatwg.org/#lock) the stream to the new writer.
     ///
@@ -95,7 +259,7 @@ impl WritableStream {
     /// **Panics** if the stream is already locked to a writer. For a non-panicking variant,
     /// use [`try_get_writer`](Self::try_get_writer).
     #[inline]
-    pub fn get_writer(&mut self) -> WritableStreamDefaultWriter {
+    pub fn get_writer(&mut self) -> WritableStreamDefaultWriter<'_, T> {
         self.try_get_writer()
             .expect_throw("already locked to a writer")
     }
@@ -106,46 +270,194 @@ impl WritableStream {
     /// While the stream is locked, no other writer can be acquired until this one is released.
     ///
     /// If the stream is already locked to a writer, then this returns an error.
-    pub fn try_get_writer(&mut self) -> Result<WritableStreamDefaultWriter, js_sys::Error> {
-        Ok(WritableStreamDefaultWriter {
-            raw: self.as_raw().get_writer()?,
-            _stream: PhantomData,
-        })
+    pub fn try_get_writer(&mut self) -> Result<WritableStreamDefaultWriter<'_, T>, js_sys::Error> {
+        Ok(WritableStreamDefaultWriter::new(
+            self.as_raw().get_writer()?,
+        ))
     }
 
     /// Converts this `WritableStream` into a [`Sink`](Sink).
     ///
-    /// Items and errors are represented by their raw [`JsValue`](JsValue).
+    /// Items are chunks of type `T`, and errors are represented by their raw [`JsValue`](JsValue).
     /// Use [`with`](futures::SinkExt::with) and/or [`sink_map_err`](futures::SinkExt::sink_map_err)
     /// on the returned stream to convert them to a more appropriate type.
     ///
     /// **Panics** if the stream is already locked to a writer. For a non-panicking variant,
     /// use [`try_into_sink`](Self::try_into_sink).
     #[inline]
-    pub fn into_sink(self) -> IntoSink<'static> {
+    pub fn into_sink(self) -> IntoSink<'static, T> {
         self.try_into_sink()
             .expect_throw("already locked to a writer")
     }
 
     /// Try to convert this `WritableStream` into a [`Sink`](Sink).
     ///
-    /// Items and errors are represented by their raw [`JsValue`](JsValue).
+    /// Items are chunks of type `T`, and errors are represented by their raw [`JsValue`](JsValue).
     /// Use [`with`](futures::SinkExt::with) and/or [`sink_map_err`](futures::SinkExt::sink_map_err)
     /// on the returned stream to convert them to a more appropriate type.
     ///
     /// If the stream is already locked to a writer, then this returns an error
     /// along with the original `WritableStream`.
-    pub fn try_into_sink(self) -> Result<IntoSink<'static>, (js_sys::Error, Self)> {
+    pub fn try_into_sink(self) -> Result<IntoSink<'static, T>, (js_sys::Error, Self)> {
         let raw_writer = match self.as_raw().get_writer() {
             Ok(raw_writer) => raw_writer,
             Err(err) => return Err((err, self)),
         };
-        let writer = WritableStreamDefaultWriter {
-            raw: raw_writer,
-            _stream: PhantomData,
-        };
+        let writer = WritableStreamDefaultWriter::new(raw_writer);
         Ok(writer.into_sink())
     }
+
+    /// Converts this `WritableStream` into a [`Sink`](Sink) of bytes.
+    ///
+    /// The returned sink accepts both `Vec<u8>` and `&[u8]` items, and writes each item
+    /// to the stream as a single [`Uint8Array`](js_sys::Uint8Array) chunk.
+    ///
+    /// **Panics** if the stream is already locked to a writer. For a non-panicking variant,
+    /// use [`try_into_bytes_sink`](Self::try_into_bytes_sink).
+    #[inline]
+    pub fn into_bytes_sink(self) -> IntoBytesSink<'static> {
+        self.try_into_bytes_sink()
+            .expect_throw("already locked to a writer")
+    }
+
+    /// Try to convert this `WritableStream` into a [`Sink`](Sink) of bytes.
+    ///
+    /// The returned sink accepts both `Vec<u8>` and `&[u8]` items, and writes each item
+    /// to the stream as a single [`Uint8Array`](js_sys::Uint8Array) chunk.
+    ///
+    /// If the stream is already locked to a writer, then this returns an error
+    /// along with the original `WritableStream`.
+    pub fn try_into_bytes_sink(self) -> Result<IntoBytesSink<'static>, (js_sys::Error, Self)> {
+        let raw_writer = match self.as_raw().get_writer() {
+            Ok(raw_writer) => raw_writer,
+            Err(err) => return Err((err, self)),
+        };
+        let writer = WritableStreamDefaultWriter::<JsValue>::new(raw_writer);
+        Ok(writer.into_bytes_sink())
+    }
+
+    /// Converts this `WritableStream` into an [`AsyncWrite`](futures::io::AsyncWrite).
+    ///
+    /// Each write is sent to the stream as a [`Uint8Array`](js_sys::Uint8Array) chunk.
+    /// [Flushing](futures::io::AsyncWrite::poll_flush) waits for the last write to complete,
+    /// and [closing](futures::io::AsyncWrite::poll_close) closes the stream.
+    ///
+    /// **Panics** if the stream is already locked to a writer. For a non-panicking variant,
+    /// use [`try_into_async_write`](Self::try_into_async_write).
+    #[inline]
+    pub fn into_async_write(self) -> IntoAsyncWrite<'static> {
+        self.try_into_async_write()
+            .expect_throw("already locked to a writer")
+    }
+
+    /// Try to convert this `WritableStream` into an [`AsyncWrite`](futures::io::AsyncWrite).
+    ///
+    /// Each write is sent to the stream as a [`Uint8Array`](js_sys::Uint8Array) chunk.
+    /// [Flushing](futures::io::AsyncWrite::poll_flush) waits for the last write to complete,
+    /// and [closing](futures::io::AsyncWrite::poll_close) closes the stream.
+    ///
+    /// If the stream is already locked to a writer, then this returns an error
+    /// along with the original `WritableStream`.
+    pub fn try_into_async_write(self) -> Result<IntoAsyncWrite<'static>, (js_sys::Error, Self)> {
+        let raw_writer = match self.as_raw().get_writer() {
+            Ok(raw_writer) => raw_writer,
+            Err(err) => return Err((err, self)),
+        };
+        let writer = WritableStreamDefaultWriter::<JsValue>::new(raw_writer);
+        Ok(writer.into_async_write())
+    }
+
+    /// Returns a new `WritableStream` which forwards all chunks to this stream,
+    /// calling `f` with a reference to every chunk as it is written.
+    ///
+    /// This pipes the readable side of a [`transform::inspect`](crate::transform::inspect)
+    /// stream to this stream, and returns its writable side. Closing or aborting the returned
+    /// stream closes or aborts this stream, and errors propagate as with
+    /// [`pipe_to`](crate::ReadableStream::pipe_to). Chunks which are not of type `T` are
+    /// passed through, but are not passed to `f`.
+    ///
+    /// **Panics** if this stream is already locked to a writer. For a non-panicking variant,
+    /// use [`try_inspect_writes`](Self::try_inspect_writes).
+    pub fn inspect_writes<F>(self, f: F) -> WritableStream<T>
+    where
+        F: FnMut(&T) + 'static,
+    {
+        self.try_inspect_writes(f)
+            .expect_throw("already locked to a writer")
+    }
+
+    /// Try to create a new `WritableStream` which forwards all chunks to this stream,
+    /// calling `f` with a reference to every chunk as it is written.
+    ///
+    /// See [`inspect_writes`](Self::inspect_writes) for more details.
+    ///
+    /// If the stream is already locked to a writer, then this returns an error
+    /// along with the original `WritableStream`.
+    pub fn try_inspect_writes<F>(self, mut f: F) -> Result<WritableStream<T>, (js_sys::Error, Self)>
+    where
+        F: FnMut(&T) + 'static,
+    {
+        if self.is_locked() {
+            return Err((
+                js_sys::TypeError::new("already locked to a writer").into(),
+                self,
+            ));
+        }
+        let transform = crate::transform::inspect(move |chunk| {
+            if let Some(chunk) = chunk.dyn_ref::<T>() {
+                f(chunk)
+            }
+        });
+        Ok(self.pipe_from_transform(transform))
+    }
+
+    /// Pipes the readable side of `transform` into this stream, and returns the
+    /// writable side of `transform`.
+    ///
+    /// The stream must not be locked. Errors are reported to the writer of the returned stream.
+    pub(crate) fn pipe_from_transform(self, transform: TransformStream) -> WritableStream<T> {
+        let (readable, writable) = transform.into_pair().into_parts();
+        let promise = readable
+            .as_raw()
+            .pipe_to(self.as_raw(), PipeOptions::default().into_raw());
+        detach_promise(promise);
+        writable.cast()
+    }
+
+    /// Returns a new `WritableStream` which forwards all chunks to this stream, and errors
+    /// if a write to this stream does not complete within the given `duration`.
+    ///
+    /// When a write times out, the returned stream errors with a `DOMException` named
+    /// `TimeoutError`, which is classified as [`StreamError::Timeout`](StreamError::Timeout),
+    /// and this stream is aborted with that same error. Closing or aborting the returned stream
+    /// closes or aborts this stream. This is useful to detect a dead connection while
+    /// streaming a request body or writing to a socket.
+    ///
+    /// **Panics** if this stream is already locked to a writer. For a non-panicking variant,
+    /// use [`try_write_timeout`](Self::try_write_timeout).
+    pub fn write_timeout(self, duration: Duration) -> WritableStream<T> {
+        self.try_write_timeout(duration)
+            .expect_throw("already locked to a writer")
+    }
+
+    /// Tries to create a new `WritableStream` which forwards all chunks to this stream, and
+    /// errors if a write to this stream does not complete within the given `duration`.
+    ///
+    /// See [`write_timeout`](Self::write_timeout) for more details.
+    ///
+    /// If the stream is already locked to a writer, then this returns an error
+    /// along with the original `WritableStream`.
+    pub fn try_write_timeout(
+        self,
+        duration: Duration,
+    ) -> Result<WritableStream<T>, (js_sys::Error, Self)> {
+        let raw_writer = match self.as_raw().get_writer() {
+            Ok(raw_writer) => raw_writer,
+            Err(err) => return Err((err, self)),
+        };
+        let sink = write_timeout::WriteTimeoutSink::new(raw_writer, duration);
+        Ok(WritableStream::from_underlying_sink(sink).cast())
+    }
 }
 
 impl<Si> From<Si> for WritableStream
@@ -165,28 +477,86 @@ where
 /// This is returned by the [`get_writer`](WritableStream::get_writer) method.
 ///
 /// When the writer is dropped, it automatically [releases its lock](https://streams.spec.whatwg.org/#release-a-lock).
+/// This can be changed with [`set_drop_policy`](Self::set_drop_policy).
+///
+/// Only chunks of type `T` can be written with this writer.
 #[derive(Debug)]
-pub struct WritableStreamDefaultWriter<'stream> {
+pub struct WritableStreamDefaultWriter<'stream, T = JsValue> {
     raw: sys::WritableStreamDefaultWriter,
-    _stream: PhantomData<&'stream mut WritableStream>,
+    drop_policy: WriterDropPolicy,
+    _stream: PhantomData<&'stream mut WritableStream<T>>,
 }
 
-impl<'stream> WritableStreamDefaultWriter<'stream> {
+impl<'stream, T: JsCast> WritableStreamDefaultWriter<'stream, T> {
+    #[inline]
+    fn new(raw: sys::WritableStreamDefaultWriter) -> Self {
+        Self {
+            raw,
+            drop_policy: WriterDropPolicy::default(),
+            _stream: PhantomData,
+        }
+    }
+
+    fn cast<U: JsCast>(mut self) -> WritableStreamDefaultWriter<'stream, U> {
+        // Move the raw writer out, and leave behind a value that doesn't need to be dropped.
+        let raw = std::mem::replace(&mut self.raw, JsValue::UNDEFINED.unchecked_into());
+        let drop_policy = self.drop_policy;
+        // Skip our Drop implementation, since the lock is transferred to the new writer.
+        std::mem::forget(self);
+        let mut writer = WritableStreamDefaultWriter::new(raw);
+        writer.drop_policy = drop_policy;
+        writer
+    }
+
     /// Acquires a reference to the underlying [JavaScript writer](sys::WritableStreamDefaultWriter).
     #[inline]
     pub fn as_raw(&self) -> &sys::WritableStreamDefaultWriter {
         &self.raw
     }
 
+    /// Returns what this writer does with its lock when it is dropped.
+    #[inline]
+    pub fn drop_policy(&self) -> WriterDropPolicy {
+        self.drop_policy
+    }
+
+    /// Sets what this writer does with its lock when it is dropped.
+    ///
+    /// The policy also applies when a [`Sink`](Sink) or [`AsyncWrite`](futures::io::AsyncWrite)
+    /// created from this writer is dropped. See [`WriterDropPolicy`](WriterDropPolicy)
+    /// for the available policies.
+    #[inline]
+    pub fn set_drop_policy(&mut self, policy: WriterDropPolicy) -> &mut Self {
+        self.drop_policy = policy;
+        self
+    }
+
     /// Waits for the stream to become closed.
     ///
     /// This returns an error if the stream ever errors, or if the writer's lock is
     /// [released](https://streams.spec.whatwg.org/#release-a-lock) before the stream finishes
     /// closing.
-    pub async fn closed(&self) -> Result<(), JsValue> {
+    pub async fn closed(&self) -> Result<(), StreamError> {
+        self.closed_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`closed`](Self::closed), but returns the raw JavaScript error.
+    pub async fn closed_js(&self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().closed()).await
     }
 
+    /// Waits for the stream to become closed, or for the given `signal` to be aborted.
+    ///
+    /// This behaves like [`closed`](Self::closed), except that it returns an
+    /// [`Aborted`](StreamError::Aborted) error with the signal's abort reason
+    /// as soon as the signal is aborted. The writer is not affected by the signal.
+    pub async fn closed_with_signal(
+        &self,
+        signal: &impl AsRef<AbortSignal>,
+    ) -> Result<(), StreamError> {
+        promise_to_void_future_with_signal(self.as_raw().closed(), signal.as_ref()).await
+    }
+
     /// Returns the desired size to fill the stream's internal queue.
     ///
     /// * It can be negative, if the queue is over-full.
@@ -208,7 +578,12 @@ impl<'stream> WritableStreamDefaultWriter<'stream> {
     /// This returns an error if the stream ever errors, or if the writer's lock is
     /// [released](https://streams.spec.whatwg.org/#release-a-lock) before the stream finishes
     /// closing.
-    pub async fn ready(&self) -> Result<(), JsValue> {
+    pub async fn ready(&self) -> Result<(), StreamError> {
+        self.ready_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`ready`](Self::ready), but returns the raw JavaScript error.
+    pub async fn ready_js(&self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().ready()).await
     }
 
@@ -216,7 +591,12 @@ impl<'stream> WritableStreamDefaultWriter<'stream> {
     /// signaling that the producer can no longer successfully write to the stream.
     ///
     /// Equivalent to [`WritableStream.abort`](WritableStream::abort).
-    pub async fn abort(&mut self) -> Result<(), JsValue> {
+    pub async fn abort(&mut self) -> Result<(), StreamError> {
+        self.abort_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`abort`](Self::abort), but returns the raw JavaScript error.
+    pub async fn abort_js(&mut self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().abort()).await
     }
 
@@ -224,7 +604,14 @@ impl<'stream> WritableStreamDefaultWriter<'stream> {
     /// given `reason`, signaling that the producer can no longer successfully write to the stream.
     ///
     /// Equivalent to [`WritableStream.abort_with_reason`](WritableStream::abort_with_reason).
-    pub async fn abort_with_reason(&mut self, reason: &JsValue) -> Result<(), JsValue> {
+    pub async fn abort_with_reason(&mut self, reason: &JsValue) -> Result<(), StreamError> {
+        self.abort_with_reason_js(reason)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Like [`abort_with_reason`](Self::abort_with_reason), but returns the raw JavaScript error.
+    pub async fn abort_with_reason_js(&mut self, reason: &JsValue) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().abort_with_reason(reason)).await
     }
 
@@ -238,8 +625,50 @@ impl<'stream> WritableStreamDefaultWriter<'stream> {
     /// Note that what "success" means is up to the underlying sink; it might indicate simply
     /// that the chunk has been accepted, and not necessarily that it is safely saved to
     /// its ultimate destination.
-    pub async fn write(&mut self, chunk: JsValue) -> Result<(), JsValue> {
-        promise_to_void_future(self.as_raw().write(chunk)).await
+    pub async fn write(&mut self, chunk: T) -> Result<(), StreamError> {
+        self.write_js(chunk).await.map_err(StreamError::from)
+    }
+
+    /// Like [`write`](Self::write), but returns the raw JavaScript error.
+    pub async fn write_js(&mut self, chunk: T) -> Result<(), JsValue> {
+        let promise = self.as_raw().write(chunk.into());
+        in_span!("write", promise_to_void_future(promise)).await
+    }
+
+    /// Writes the given `chunk` to the writable stream, but stops waiting for the write
+    /// to finish as soon as the given `signal` is aborted.
+    ///
+    /// This behaves like [`write`](Self::write), except that it returns an
+    /// [`Aborted`](StreamError::Aborted) error with the signal's abort reason as soon as
+    /// the signal is aborted. The chunk has already been queued at that point,
+    /// so it may still be written. To discard it, [`abort`](Self::abort_with_reason)
+    /// the stream as well.
+    pub async fn write_with_signal(
+        &mut self,
+        chunk: T,
+        signal: &impl AsRef<AbortSignal>,
+    ) -> Result<(), StreamError> {
+        promise_to_void_future_with_signal(self.as_raw().write(chunk.into()), signal.as_ref()).await
+    }
+
+    /// Writes the given `chunk` to the writable stream, without waiting for the write
+    /// to finish.
+    ///
+    /// This waits until the stream is [ready](Self::ready) to accept a new chunk, and then
+    /// queues the chunk for writing. This returns `Err(error)` if the stream is errored
+    /// while waiting.
+    ///
+    /// If the write fails, the stream becomes errored. The error is then reported by the next
+    /// operation on this writer, such as [`feed`](Self::feed) or [`close`](Self::close).
+    pub async fn feed(&mut self, chunk: T) -> Result<(), StreamError> {
+        self.feed_js(chunk).await.map_err(StreamError::from)
+    }
+
+    /// Like [`feed`](Self::feed), but returns the raw JavaScript error.
+    pub async fn feed_js(&mut self, chunk: T) -> Result<(), JsValue> {
+        self.ready_js().await?;
+        detach_promise(self.as_raw().write(chunk.into()));
+        Ok(())
     }
 
     /// Closes the stream.
@@ -250,7 +679,12 @@ impl<'stream> WritableStreamDefaultWriter<'stream> {
     ///
     /// This returns `Ok(())` if all remaining chunks are successfully written and the stream
     /// successfully closes, or `Err(error)` if an error is encountered during this process.
-    pub async fn close(&mut self) -> Result<(), JsValue> {
+    pub async fn close(&mut self) -> Result<(), StreamError> {
+        self.close_js().await.map_err(StreamError::from)
+    }
+
+    /// Like [`close`](Self::close), but returns the raw JavaScript error.
+    pub async fn close_js(&mut self) -> Result<(), JsValue> {
         promise_to_void_future(self.as_raw().close()).await
     }
 
@@ -261,13 +695,78 @@ impl<'stream> WritableStreamDefaultWriter<'stream> {
     /// usable. This allows writing only a few chunks through the `Sink`, while still allowing
     /// another writer to write more chunks later on.
     #[inline]
-    pub fn into_sink(self) -> IntoSink<'stream> {
+    pub fn into_sink(self) -> IntoSink<'stream, T> {
         IntoSink::new(self)
     }
+
+    /// Converts this `WritableStreamDefaultWriter` into a [`Sink`](Sink) of bytes.
+    ///
+    /// This is similar to [`WritableStream.into_bytes_sink`](WritableStream::into_bytes_sink),
+    /// except that after the returned `Sink` is dropped, the original `WritableStream` is still
+    /// usable.
+    #[inline]
+    pub fn into_bytes_sink(self) -> IntoBytesSink<'stream> {
+        IntoBytesSink::new(self.cast().into_sink())
+    }
+
+    /// Converts this `WritableStreamDefaultWriter` into an [`AsyncWrite`](futures::io::AsyncWrite).
+    ///
+    /// This is similar to [`WritableStream.into_async_write`](WritableStream::into_async_write),
+    /// except that after the returned `AsyncWrite` is dropped, the original `WritableStream` is
+    /// still usable.
+    #[inline]
+    pub fn into_async_write(self) -> IntoAsyncWrite<'stream> {
+        IntoAsyncWrite::new(self.cast().into_sink())
+    }
+}
+
+impl<'stream, T: JsCast + From<js_sys::Uint8Array>> WritableStreamDefaultWriter<'stream, T> {
+    /// Writes all of `data` to the writable stream, split into
+    /// [`Uint8Array`](js_sys::Uint8Array) chunks of at most `chunk_size` bytes.
+    ///
+    /// Each chunk waits until the stream is [ready](Self::ready) to accept it, so this
+    /// respects the stream's backpressure. This returns `Ok(())` once the last chunk has been
+    /// written successfully, or `Err(error)` if any write fails.
+    ///
+    /// **Panics** if `chunk_size` is zero.
+    pub async fn write_all(&mut self, data: &[u8], chunk_size: usize) -> Result<(), StreamError> {
+        self.write_all_js(data, chunk_size)
+            .await
+            .map_err(StreamError::from)
+    }
+
+    /// Like [`write_all`](Self::write_all), but returns the raw JavaScript error.
+    ///
+    /// **Panics** if `chunk_size` is zero.
+    pub async fn write_all_js(&mut self, data: &[u8], chunk_size: usize) -> Result<(), JsValue> {
+        assert!(chunk_size > 0, "chunk size must be non-zero");
+        let mut last_write = None;
+        for chunk in data.chunks(chunk_size) {
+            // A failed write errors the stream, which is then reported by ready() or
+            // by the last write.
+            if let Some(promise) = last_write.take() {
+                detach_promise(promise);
+            }
+            self.ready_js().await?;
+            let chunk = T::from(js_sys::Uint8Array::from(chunk));
+            last_write = Some(self.as_raw().write(chunk.into()));
+        }
+        match last_write {
+            Some(promise) => promise_to_void_future(promise).await,
+            None => Ok(()),
+        }
+    }
 }
 
-impl Drop for WritableStreamDefaultWriter<'_> {
+impl<T> Drop for WritableStreamDefaultWriter<'_, T> {
     fn drop(&mut self) {
-        self.as_raw().release_lock()
+        match self.drop_policy {
+            WriterDropPolicy::Release => self.raw.release_lock(),
+            WriterDropPolicy::AbortThenRelease => {
+                detach_promise(self.raw.abort());
+                self.raw.release_lock();
+            }
+            WriterDropPolicy::Leak => {}
+        }
     }
 }
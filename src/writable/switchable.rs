@@ -0,0 +1,140 @@
+use core::pin::Pin;
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+use futures::lock::Mutex;
+use futures::ready;
+use futures::sink::{Sink, SinkExt};
+use futures::task::{Context, Poll};
+use wasm_bindgen::prelude::*;
+
+use super::{sys, WritableStream};
+
+/// A boxed [`Sink`](Sink) of raw [`JsValue`](JsValue)s, as used by
+/// [`SwitchableWritableStream`](SwitchableWritableStream).
+pub type BoxSink = Pin<Box<dyn Sink<JsValue, Error = JsValue>>>;
+
+/// A [`WritableStream`](WritableStream) that forwards all written chunks to a current
+/// destination [`Sink`](Sink), which can be swapped out for a different sink at any time.
+///
+/// This can be used to implement log rotation, or to fail over to a backup destination
+/// in the middle of a stream.
+///
+/// Use [`writable`](Self::writable) to access the writable stream,
+/// and [`swap`](Self::swap) to change its destination.
+pub struct SwitchableWritableStream {
+    raw: sys::WritableStream,
+    current: Rc<Mutex<BoxSink>>,
+}
+
+impl SwitchableWritableStream {
+    /// Creates a new `SwitchableWritableStream` that initially writes to the given `sink`.
+    ///
+    /// Items and errors must be represented as raw [`JsValue`](JsValue)s.
+    pub fn new<Si>(sink: Si) -> Self
+    where
+        Si: Sink<JsValue, Error = JsValue> + 'static,
+    {
+        let current: Rc<Mutex<BoxSink>> = Rc::new(Mutex::new(Box::pin(sink)));
+        let raw = WritableStream::from_sink(SwitchSink::new(current.clone())).into_raw();
+        Self { raw, current }
+    }
+
+    /// Returns the writable stream that forwards chunks to the current destination.
+    #[inline]
+    pub fn writable(&self) -> WritableStream {
+        WritableStream::from_raw(self.raw.clone())
+    }
+
+    /// Replaces the current destination with the given `sink`.
+    ///
+    /// This waits until any in-flight write to the current destination has completed
+    /// and the destination has been flushed, so chunks are never split or reordered
+    /// between the old and the new destination.
+    /// All chunks written after the swap completes go to the new destination.
+    ///
+    /// This returns the previous destination, which is neither closed nor aborted.
+    /// If flushing the previous destination fails, the swap is not performed and
+    /// the error is returned instead.
+    pub async fn swap<Si>(&self, sink: Si) -> Result<BoxSink, JsValue>
+    where
+        Si: Sink<JsValue, Error = JsValue> + 'static,
+    {
+        let mut current = self.current.lock().await;
+        current.flush().await?;
+        Ok(std::mem::replace(&mut *current, Box::pin(sink)))
+    }
+}
+
+impl std::fmt::Debug for SwitchableWritableStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SwitchableWritableStream")
+            .field("raw", &self.raw)
+            .finish()
+    }
+}
+
+/// The sink underlying a [`SwitchableWritableStream`](SwitchableWritableStream).
+///
+/// Every operation locks the current destination for its entire duration,
+/// which allows [`swap`](SwitchableWritableStream::swap) to wait for it to finish.
+struct SwitchSink {
+    current: Rc<Mutex<BoxSink>>,
+    op: Option<LocalBoxFuture<'static, Result<(), JsValue>>>,
+    closing: bool,
+}
+
+impl SwitchSink {
+    fn new(current: Rc<Mutex<BoxSink>>) -> Self {
+        Self {
+            current,
+            op: None,
+            closing: false,
+        }
+    }
+
+    fn poll_op(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), JsValue>> {
+        let result = match self.op.as_mut() {
+            Some(op) => ready!(op.as_mut().poll(cx)),
+            None => Ok(()),
+        };
+        self.op = None;
+        Poll::Ready(result)
+    }
+}
+
+impl Sink<JsValue> for SwitchSink {
+    type Error = JsValue;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_op(cx)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: JsValue) -> Result<(), Self::Error> {
+        let current = self.current.clone();
+        self.op = Some(Box::pin(async move {
+            let mut current = current.lock().await;
+            current.send(item).await
+        }));
+        Ok(())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_op(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if !self.closing {
+            ready!(self.poll_op(cx))?;
+            let current = self.current.clone();
+            self.op = Some(Box::pin(async move {
+                let mut current = current.lock().await;
+                current.close().await
+            }));
+            self.closing = true;
+        }
+        // If a previous call returned `Pending`, only drive the existing close operation,
+        // so the destination is never closed twice.
+        self.poll_op(cx)
+    }
+}
@@ -2,12 +2,21 @@
 //! by a [`WritableStream`](https://developer.mozilla.org/en-US/docs/Web/API/WritableStream).
 use js_sys::{Error, Promise};
 use wasm_bindgen::prelude::*;
+use web_sys::AbortSignal;
+
+use crate::queuing_strategy::sys::QueuingStrategy;
+use crate::util::has_methods;
 
 use super::into_underlying_sink::IntoUnderlyingSink;
 
 #[wasm_bindgen]
 extern "C" {
     /// A raw [`WritableStream`](https://developer.mozilla.org/en-US/docs/Web/API/WritableStream).
+    ///
+    /// Type checks such as [`dyn_into`][wasm_bindgen::JsCast::dyn_into] only check that
+    /// the object has a `getWriter()` and an `abort()` method, so they also accept streams
+    /// from other realms and from polyfills.
+    #[wasm_bindgen(is_type_of = is_writable_stream)]
     #[derive(Clone, Debug)]
     pub type WritableStream;
 
@@ -15,7 +24,10 @@ extern "C" {
     pub fn new() -> WritableStream;
 
     #[wasm_bindgen(constructor)]
-    pub(crate) fn new_with_sink(sink: IntoUnderlyingSink) -> WritableStream;
+    pub(crate) fn new_with_sink(
+        sink: IntoUnderlyingSink,
+        strategy: &QueuingStrategy,
+    ) -> WritableStream;
 
     #[wasm_bindgen(method, getter, js_name = locked)]
     pub fn is_locked(this: &WritableStream) -> bool;
@@ -33,6 +45,7 @@ extern "C" {
 #[wasm_bindgen]
 extern "C" {
     /// A raw [`WritableStreamDefaultWriter`](https://developer.mozilla.org/en-US/docs/Web/API/WritableStreamDefaultWriter).
+    #[wasm_bindgen(is_type_of = is_writer)]
     #[derive(Clone, Debug)]
     pub type WritableStreamDefaultWriter;
 
@@ -60,3 +73,27 @@ extern "C" {
     #[wasm_bindgen(method, js_name = releaseLock)]
     pub fn release_lock(this: &WritableStreamDefaultWriter);
 }
+
+#[wasm_bindgen]
+extern "C" {
+    /// A raw [`WritableStreamDefaultController`](https://developer.mozilla.org/en-US/docs/Web/API/WritableStreamDefaultController).
+    #[derive(Clone, Debug)]
+    pub type WritableStreamDefaultController;
+
+    /// The [`AbortSignal`](AbortSignal) which is aborted when the stream is aborted.
+    ///
+    /// Older browsers do not support this property yet, in which case this returns `None`.
+    #[wasm_bindgen(method, getter, js_name = signal)]
+    pub fn signal(this: &WritableStreamDefaultController) -> Option<AbortSignal>;
+
+    #[wasm_bindgen(method, js_name = error)]
+    pub fn error(this: &WritableStreamDefaultController, error: &JsValue);
+}
+
+fn is_writable_stream(value: &JsValue) -> bool {
+    has_methods(value, &["getWriter", "abort"])
+}
+
+fn is_writer(value: &JsValue) -> bool {
+    has_methods(value, &["write", "close", "abort", "releaseLock"])
+}
@@ -0,0 +1,90 @@
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+use web_sys::AbortSignal;
+
+use super::sys;
+
+/// A Rust implementation of a JavaScript [underlying sink](https://streams.spec.whatwg.org/#underlying-sink-api),
+/// which defines the behavior of a [`WritableStream`](super::WritableStream).
+///
+/// Use [`WritableStream::from_underlying_sink`](super::WritableStream::from_underlying_sink)
+/// to create a writable stream from an `UnderlyingSink`.
+///
+/// Unlike a [`Sink`](futures::Sink), an `UnderlyingSink` has access to the stream's
+/// [controller](WritableStreamDefaultController) and can observe the reason
+/// with which the stream was aborted.
+///
+/// All methods return a boxed future, which allows them to perform asynchronous work.
+/// The writable stream waits for each future to complete before calling the next method.
+/// If any of these futures returns an error, the writable stream becomes errored.
+pub trait UnderlyingSink {
+    /// Called once when the writable stream is created, before any chunks are written.
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+
+    /// Writes a single `chunk` to the sink.
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>>;
+
+    /// Called after all chunks have been written, when the producer closes the stream.
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+
+    /// Called when the producer aborts the stream with the given `reason`.
+    ///
+    /// Any chunks that were still queued up in the stream are discarded.
+    fn abort<'a>(&'a mut self, _reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Ok(())).boxed_local()
+    }
+}
+
+/// A [`WritableStreamDefaultController`](https://developer.mozilla.org/en-US/docs/Web/API/WritableStreamDefaultController)
+/// that is passed to the methods of an [`UnderlyingSink`](UnderlyingSink).
+#[derive(Clone, Debug)]
+pub struct WritableStreamDefaultController {
+    raw: sys::WritableStreamDefaultController,
+}
+
+impl WritableStreamDefaultController {
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn from_raw(raw: sys::WritableStreamDefaultController) -> Self {
+        Self { raw }
+    }
+
+    /// Acquires a reference to the underlying [JavaScript controller](sys::WritableStreamDefaultController).
+    #[inline]
+    pub fn as_raw(&self) -> &sys::WritableStreamDefaultController {
+        &self.raw
+    }
+
+    /// Returns an [`AbortSignal`](AbortSignal) which is aborted as soon as the producer
+    /// [aborts](super::WritableStream::abort_with_reason) the stream.
+    ///
+    /// The stream waits for an in-flight [`write`](UnderlyingSink::write) to finish before it
+    /// calls [`abort`](UnderlyingSink::abort) on the sink. A sink can listen to this signal
+    /// instead, in order to stop long-running work during a write right away.
+    ///
+    /// Older browsers do not support this signal yet, in which case this returns `None`.
+    #[inline]
+    pub fn signal(&self) -> Option<AbortSignal> {
+        self.as_raw().signal()
+    }
+
+    /// Errors the writable stream with the given `error`.
+    ///
+    /// Future writes to the stream will fail, and the underlying sink's
+    /// [`abort`](UnderlyingSink::abort) method will be called.
+    #[inline]
+    pub fn error(&self, error: &JsValue) {
+        self.as_raw().error(error)
+    }
+}
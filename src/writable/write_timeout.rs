@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use futures::future::{select, Either, FutureExt, LocalBoxFuture};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::util::{promise_to_void_future, sleep, timeout_error};
+
+use super::{sys, UnderlyingSink, WritableStreamDefaultController};
+
+/// An [`UnderlyingSink`](UnderlyingSink) that writes to another stream, and errors if a write
+/// does not complete within a given time.
+pub(super) struct WriteTimeoutSink {
+    writer: sys::WritableStreamDefaultWriter,
+    millis: f64,
+}
+
+impl WriteTimeoutSink {
+    pub fn new(writer: sys::WritableStreamDefaultWriter, duration: Duration) -> Self {
+        Self {
+            writer,
+            millis: duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+impl UnderlyingSink for WriteTimeoutSink {
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            let write = JsFuture::from(self.writer.write(chunk));
+            match select(write, sleep(self.millis)).await {
+                Either::Left((result, _)) => result.map(|_| ()),
+                Either::Right((Err(error), _)) => Err(error),
+                Either::Right((Ok(()), _)) => {
+                    let error = JsValue::from(timeout_error(&format!(
+                        "write did not complete within {}ms",
+                        self.millis
+                    )));
+                    // The abort rejects the pending write, which is no longer awaited.
+                    let _ = self.writer.abort_with_reason(&error);
+                    Err(error)
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        promise_to_void_future(self.writer.close()).boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        promise_to_void_future(self.writer.abort_with_reason(&reason)).boxed_local()
+    }
+}
@@ -12,3 +12,54 @@ export function new_readable_stream_from_array(chunks) {
         }
     });
 }
+
+export function new_readable_byte_stream_from_array(chunks) {
+    return new ReadableStream({
+        type: "bytes",
+        start(controller) {
+            for (let chunk of chunks) {
+                controller.enqueue(chunk);
+            }
+            controller.close();
+        }
+    });
+}
+
+export function new_async_generator_from_array(values) {
+    return (async function* () {
+        for (let value of values) {
+            yield value;
+        }
+    })();
+}
+
+export function new_observable_from_array(values) {
+    return {
+        subscribe(observer, { signal }) {
+            for (let value of values) {
+                if (signal.aborted) {
+                    return;
+                }
+                observer.next(value);
+            }
+            observer.complete();
+        }
+    };
+}
+
+export function new_foreign_readable_stream_from_array(chunks) {
+    // Mimics a stream from another realm or a polyfill, which is not an instance
+    // of this realm's ReadableStream class.
+    const stream = new_readable_stream_from_array(chunks);
+    return {
+        get locked() {
+            return stream.locked;
+        },
+        cancel(reason) {
+            return stream.cancel(reason);
+        },
+        getReader(options) {
+            return stream.getReader(options);
+        }
+    };
+}
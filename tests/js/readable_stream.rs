@@ -6,4 +6,8 @@ use wasm_streams::readable::*;
 extern "C" {
     pub fn new_noop_readable_stream() -> sys::ReadableStream;
     pub fn new_readable_stream_from_array(chunks: Box<[JsValue]>) -> sys::ReadableStream;
+    pub fn new_readable_byte_stream_from_array(chunks: Box<[JsValue]>) -> sys::ReadableStream;
+    pub fn new_async_generator_from_array(values: Box<[JsValue]>) -> JsValue;
+    pub fn new_observable_from_array(values: Box<[JsValue]>) -> JsValue;
+    pub fn new_foreign_readable_stream_from_array(chunks: Box<[JsValue]>) -> JsValue;
 }
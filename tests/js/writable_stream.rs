@@ -35,7 +35,7 @@ impl RecordingWritableStream {
     pub fn events(&self) -> Vec<String> {
         self.raw
             .events()
-            .into_iter()
+            .iter()
             .map(|x| x.as_string().unwrap())
             .collect::<Vec<_>>()
     }
@@ -0,0 +1,79 @@
+//! Tests for the `node` module, which must be run in Node.js:
+//! `wasm-pack test --node --features node --test node`
+#![cfg(all(target_arch = "wasm32", feature = "node"))]
+
+use futures::channel::mpsc;
+use futures::stream::{iter, StreamExt, TryStreamExt};
+use futures::SinkExt;
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::duplex::DuplexStream;
+use wasm_streams::node::*;
+use wasm_streams::{ReadableStream, WritableStream};
+
+#[wasm_bindgen_test]
+async fn test_node_readable_to_web() {
+    let chunks = Array::of2(&JsValue::from("Hello"), &JsValue::from("world!"));
+    let readable = sys::Readable::from_iterable(&chunks).unwrap();
+    let readable = readable_to_web(&readable).unwrap();
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        chunks,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_node_readable_round_trip() {
+    let readable = ReadableStream::from_stream(iter(vec![
+        Ok(JsValue::from("Hello")),
+        Ok(JsValue::from("world!")),
+    ]));
+    let readable = readable_to_web(&readable_from_web(readable).unwrap()).unwrap();
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        chunks,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_node_writable_round_trip() {
+    let (sender, receiver) = mpsc::unbounded::<JsValue>();
+    let writable =
+        WritableStream::from_sink(sender.sink_map_err(|_| JsValue::from_str("receiver dropped")));
+    let mut writable = writable_to_web(&writable_from_web(writable).unwrap()).unwrap();
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer.write(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+    drop(writer);
+
+    let chunks = receiver.collect::<Vec<_>>().await;
+    assert_eq!(
+        chunks,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_node_duplex_from_web_locked() {
+    let readable = ReadableStream::from_stream(iter(Vec::<Result<JsValue, JsValue>>::new()));
+    let (sender, _receiver) = mpsc::unbounded::<JsValue>();
+    let writable =
+        WritableStream::from_sink(sender.sink_map_err(|_| JsValue::from_str("receiver dropped")));
+    let _reader = readable.as_raw().get_reader().unwrap();
+    let duplex = DuplexStream::new(readable, writable);
+    assert!(duplex_from_web(duplex).is_err());
+}
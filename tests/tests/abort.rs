@@ -0,0 +1,62 @@
+use futures::channel::mpsc;
+use futures::task::Poll;
+use futures::{poll, FutureExt, SinkExt};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::abort::*;
+use wasm_streams::readable::{PipeOptions, ReadError};
+use wasm_streams::{ReadableStream, StreamError, WritableStream};
+
+#[wasm_bindgen_test]
+async fn test_abort_controller() {
+    let controller = AbortController::new();
+    let signal = controller.signal();
+    assert!(!signal.is_aborted());
+    assert_eq!(signal.reason(), None);
+
+    let mut wait = signal.wait().boxed_local();
+    assert_eq!(poll!(&mut wait), Poll::Pending);
+
+    controller.abort_with_reason(&JsValue::from("oops"));
+    assert!(controller.signal().is_aborted());
+    assert_eq!(signal.reason(), Some(JsValue::from("oops")));
+    assert_eq!(wait.await, JsValue::from("oops"));
+}
+
+#[wasm_bindgen_test]
+async fn test_abort_signal_any() {
+    let first = AbortController::new();
+    let second = AbortController::new();
+    let linked = AbortSignal::any(&[first.signal(), second.signal()]);
+    assert!(!linked.is_aborted());
+
+    second.abort_with_reason(&JsValue::from("second"));
+    assert_eq!(linked.wait().await, JsValue::from("second"));
+
+    // Already aborted signals abort the linked signal immediately
+    let aborted = AbortSignal::aborted_with_reason(&JsValue::from("early"));
+    let linked = AbortSignal::any(&[AbortController::new().signal(), aborted]);
+    assert_eq!(linked.reason(), Some(JsValue::from("early")));
+}
+
+#[wasm_bindgen_test]
+async fn test_abort_signal_with_streams() {
+    let controller = AbortController::new();
+    controller.abort_with_reason(&JsValue::from("oops"));
+
+    let mut readable = ReadableStream::from_stream(futures::stream::pending());
+    let mut reader = readable.get_reader();
+    assert_eq!(
+        reader.read_with_signal(&controller.signal()).await,
+        Err(ReadError::Aborted(JsValue::from("oops")))
+    );
+    reader.release_lock();
+
+    let mut options = PipeOptions::new();
+    options.signal(controller.signal());
+    let (sink, _stream) = mpsc::unbounded::<JsValue>();
+    let mut writable = WritableStream::from_sink(sink.sink_map_err(|_| JsValue::NULL));
+    let result = readable.pipe_to_with_options(&mut writable, &options).await;
+    assert_eq!(result, Err(StreamError::Aborted(JsValue::from("oops"))));
+}
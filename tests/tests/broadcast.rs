@@ -0,0 +1,115 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::future::join;
+use futures::stream::{iter, pending};
+use futures::TryStreamExt;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+
+use wasm_streams::broadcast::{Broadcast, BroadcastPolicy};
+use wasm_streams::ReadableStream;
+
+use crate::js::*;
+
+fn numbers(count: u32) -> Vec<JsValue> {
+    (0..count).map(JsValue::from).collect()
+}
+
+#[wasm_bindgen_test]
+async fn test_broadcast_lossless() {
+    let chunks = numbers(5);
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+    let broadcast = Broadcast::new(readable, BroadcastPolicy::Lossless);
+    let first = broadcast.subscribe();
+    let second = broadcast.subscribe();
+    assert_eq!(broadcast.subscriber_count(), 2);
+
+    let (first_chunks, second_chunks) = join(
+        first.into_stream().try_collect::<Vec<_>>(),
+        second.into_stream().try_collect::<Vec<_>>(),
+    )
+    .await;
+    assert_eq!(first_chunks.unwrap(), chunks);
+    assert_eq!(second_chunks.unwrap(), chunks);
+}
+
+#[wasm_bindgen_test]
+async fn test_broadcast_latest() {
+    let chunks = numbers(20);
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+    let broadcast = Broadcast::new(readable, BroadcastPolicy::Latest);
+    let subscriber = broadcast.subscribe();
+
+    let received = subscriber
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    // Chunks may be skipped, but the order is preserved and the last chunk is never lost
+    assert!(received.len() <= chunks.len());
+    assert_eq!(received.last(), chunks.last());
+    let mut remaining = chunks.iter();
+    for chunk in &received {
+        assert!(remaining.any(|c| c == chunk));
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_broadcast_error() {
+    let stream = iter(vec![Ok(JsValue::from(1)), Err(JsValue::from_str("oops"))]);
+    let broadcast = Broadcast::new(ReadableStream::from_stream(stream), Default::default());
+    let subscriber = broadcast.subscribe();
+
+    let mut stream = subscriber.into_stream();
+    assert_eq!(stream.try_next().await.unwrap(), Some(JsValue::from(1)));
+    assert_eq!(stream.try_next().await, Err(JsValue::from_str("oops")));
+
+    // Late subscribers see the error as well
+    let mut late = broadcast.subscribe().into_stream();
+    assert_eq!(late.try_next().await, Err(JsValue::from_str("oops")));
+}
+
+#[wasm_bindgen_test]
+async fn test_broadcast_cancel_subscriber() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        numbers(3).into_boxed_slice(),
+    ));
+    let broadcast = Broadcast::new(readable, BroadcastPolicy::Lossless);
+    let mut first = broadcast.subscribe();
+    let second = broadcast.subscribe();
+    assert_eq!(broadcast.subscriber_count(), 2);
+
+    first.cancel().await.unwrap();
+    assert_eq!(broadcast.subscriber_count(), 1);
+
+    // The remaining subscriber is no longer held back by the cancelled one
+    let second_chunks = second.into_stream().try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(second_chunks, numbers(3));
+}
+
+#[wasm_bindgen_test]
+async fn test_broadcast_cancel_while_reading() {
+    let cancelled = Rc::new(Cell::new(false));
+    let readable = ReadableStream::from_stream_with_cancel_handler(pending(), {
+        let cancelled = cancelled.clone();
+        move |_| cancelled.set(true)
+    });
+    let broadcast = Broadcast::new(readable, BroadcastPolicy::Lossless);
+    let mut subscriber = broadcast.subscribe();
+    drop(broadcast);
+
+    // The source stream never produces a chunk, but is still cancelled
+    // once the last subscriber leaves
+    subscriber.cancel().await.unwrap();
+    while !cancelled.get() {
+        JsFuture::from(js_sys::Promise::resolve(&JsValue::undefined()))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+use futures::stream::iter;
+use js_sys::{Promise, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{Cache, Request, ResponseInit};
+
+use wasm_streams::cache::*;
+use wasm_streams::ReadableStream;
+
+async fn open_cache(name: &str) -> Cache {
+    let caches = web_sys::window().unwrap().caches().unwrap();
+    JsFuture::from(caches.open(name))
+        .await
+        .unwrap()
+        .unchecked_into()
+}
+
+async fn sleep(millis: i32) {
+    let promise = Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .unwrap()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, millis)
+            .unwrap();
+    });
+    JsFuture::from(promise).await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_cache_put_and_match_stream() {
+    let cache = open_cache("test_cache_put_and_match_stream").await;
+    let request = Request::new_with_str("https://example.com/put").unwrap();
+    assert!(match_stream(&cache, &request).await.unwrap().is_none());
+
+    let stream = iter(vec![
+        Ok::<_, JsValue>(b"Hello, ".to_vec()),
+        Ok(b"world!".to_vec()),
+    ]);
+    put_stream(&cache, &request, stream, &ResponseInit::new())
+        .await
+        .unwrap();
+
+    let body = match_stream(&cache, &request).await.unwrap().unwrap();
+    assert_eq!(body.collect_bytes().await.unwrap(), b"Hello, world!");
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_tee_into_cache() {
+    let cache = open_cache("test_readable_stream_tee_into_cache").await;
+    let request = Request::new_with_str("https://example.com/tee").unwrap();
+
+    let stream = iter(vec![
+        Ok(JsValue::from(Uint8Array::from(&b"Hello, "[..]))),
+        Ok(JsValue::from(Uint8Array::from(&b"world!"[..]))),
+    ]);
+    let readable = ReadableStream::from_stream(stream).tee_into_cache(&cache, &request);
+    assert_eq!(readable.collect_bytes().await.unwrap(), b"Hello, world!");
+
+    // The copy is stored in the background
+    let mut cached = None;
+    for _ in 0..50 {
+        cached = match_stream(&cache, &request).await.unwrap();
+        if cached.is_some() {
+            break;
+        }
+        sleep(10).await;
+    }
+    let cached = cached.expect("response was not cached");
+    assert_eq!(cached.collect_bytes().await.unwrap(), b"Hello, world!");
+}
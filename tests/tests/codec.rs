@@ -0,0 +1,83 @@
+use bytes::BytesMut;
+use futures::stream::{iter, StreamExt, TryStreamExt};
+use js_sys::{JsString, Uint8Array};
+use tokio_util::codec::{Encoder, FramedRead, LinesCodec};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::codec::*;
+use wasm_streams::ReadableStream;
+
+fn bytes_stream(chunks: &[&str]) -> ReadableStream {
+    let chunks = chunks
+        .iter()
+        .map(|chunk| Ok(Uint8Array::from(chunk.as_bytes()).into()))
+        .collect::<Vec<Result<JsValue, JsValue>>>();
+    ReadableStream::from_stream(iter(chunks))
+}
+
+#[wasm_bindgen_test]
+async fn test_codec_framed_read() {
+    let readable = bytes_stream(&["Hello\nwor", "ld!\n"]);
+    let lines = FramedRead::new(readable.into_async_read(), LinesCodec::new())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(lines, vec!["Hello", "world!"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_codec_framed_read_empty_chunk() {
+    let readable = bytes_stream(&["Hello\nwor", "", "ld!\n"]);
+    let lines = FramedRead::new(readable.into_async_read(), LinesCodec::new())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(lines, vec!["Hello", "world!"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_codec_decoder_transform() {
+    let mut readable = bytes_stream(&["Hello\nwor", "ld!\n", "last"]);
+    let lines = readable
+        .pipe_through(&decoder_transform(LinesCodec::new()))
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            JsValue::from("Hello"),
+            JsValue::from("world!"),
+            JsValue::from("last")
+        ]
+    );
+}
+
+struct JsLines;
+
+impl Encoder<JsString> for JsLines {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: JsString, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(String::from(item).as_bytes());
+        dst.extend_from_slice(b"\n");
+        Ok(())
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_codec_encoder_transform() {
+    let mut readable = ReadableStream::from_stream(iter(vec![
+        Ok(JsValue::from("Hello")),
+        Ok(JsValue::from("world!")),
+    ]));
+    let chunks = readable
+        .pipe_through(&encoder_transform::<JsString, _>(JsLines))
+        .into_stream()
+        .map(|chunk| Uint8Array::from(chunk.unwrap()).to_vec())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(chunks, vec![b"Hello\n".to_vec(), b"world!\n".to_vec()]);
+}
@@ -0,0 +1,51 @@
+use futures::stream::TryStreamExt;
+use js_sys::{Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+use wasm_streams::deno::*;
+
+use crate::js::*;
+
+#[wasm_bindgen_test]
+fn test_deno_is_not_deno() {
+    assert!(!is_deno());
+}
+
+#[wasm_bindgen_test]
+async fn test_deno_open_outside_deno() {
+    assert!(FsFile::open("file.txt", &OpenOptions::new()).await.is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_deno_open_options() {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).create_new(false);
+    let raw = options.into_raw();
+    let get = |name: &str| Reflect::get(&raw, &JsValue::from_str(name)).unwrap();
+    assert_eq!(get("write"), JsValue::TRUE);
+    assert_eq!(get("create"), JsValue::TRUE);
+    assert_eq!(get("createNew"), JsValue::FALSE);
+    assert!(get("read").is_undefined());
+}
+
+#[wasm_bindgen_test]
+async fn test_deno_conn_from_raw() {
+    // Any object with a `readable` and a `writable` property is accepted.
+    let raw = Object::new();
+    let chunk = Uint8Array::from(&[1u8, 2, 3][..]);
+    let readable = new_readable_stream_from_array(vec![chunk.into()].into_boxed_slice());
+    Reflect::set(&raw, &"readable".into(), &readable).unwrap();
+    Reflect::set(&raw, &"writable".into(), &new_noop_writable_stream()).unwrap();
+    let conn = Conn::from_raw(raw.unchecked_into());
+    assert!(!conn.writable().is_locked());
+    let (readable, _writable) = conn.into_duplex().into_parts();
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].to_vec(), vec![1, 2, 3]);
+}
@@ -0,0 +1,79 @@
+use std::convert::TryFrom;
+
+use futures::future::join;
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use futures::{SinkExt, StreamExt, TryStreamExt};
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::duplex::*;
+use wasm_streams::TransformStream;
+
+use crate::js::*;
+
+#[wasm_bindgen_test]
+async fn test_duplex_stream_into_duplex() {
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+    let duplex = DuplexStream::try_from(transform).unwrap().into_duplex();
+    let (mut stream, mut sink) = duplex.split();
+    join(
+        async {
+            sink.send(JsValue::from("Hello")).await.unwrap();
+            sink.send(JsValue::from("world!")).await.unwrap();
+            sink.close().await.unwrap();
+        },
+        async {
+            let chunks = (&mut stream).try_collect::<Vec<_>>().await.unwrap();
+            assert_eq!(
+                chunks,
+                vec![JsValue::from("Hello"), JsValue::from("world!")]
+            );
+        },
+    )
+    .await;
+    assert!(stream.next().await.is_none());
+}
+
+#[wasm_bindgen_test]
+async fn test_duplex_stream_into_async_duplex() {
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+    let (mut read, mut write) = DuplexStream::try_from(transform)
+        .unwrap()
+        .into_async_duplex()
+        .split();
+    join(
+        async {
+            write.write_all(&[1, 2, 3]).await.unwrap();
+            write.close().await.unwrap();
+        },
+        async {
+            let mut buf = Vec::new();
+            read.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, vec![1, 2, 3]);
+        },
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+fn test_duplex_stream_from_raw_pair() {
+    let transform = new_noop_transform_stream();
+    let duplex = DuplexStream::<JsValue, JsValue>::from_raw_pair(&transform).unwrap();
+    assert!(!duplex.readable().is_locked());
+    assert!(!duplex.writable().is_locked());
+
+    let pair = Object::new();
+    Reflect::set(&pair, &"readable".into(), &JsValue::from(42)).unwrap();
+    assert!(DuplexStream::<JsValue, JsValue>::from_raw_pair(&pair).is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_duplex_stream_locked() {
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+    let writer = transform.as_raw().writable().get_writer().unwrap();
+    let duplex = DuplexStream::try_from(transform).unwrap();
+    let (_, duplex) = duplex.try_into_duplex().unwrap_err();
+    writer.release_lock();
+    assert!(duplex.try_into_duplex().is_ok());
+}
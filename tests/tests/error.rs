@@ -0,0 +1,56 @@
+use futures::stream::iter;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::readable::*;
+use wasm_streams::StreamError;
+
+#[wasm_bindgen_test]
+async fn test_stream_error_type_error() {
+    let mut readable = ReadableStream::from_stream(iter(vec![Ok(JsValue::from("Hello"))]));
+    let _reader = readable.as_raw().get_reader();
+
+    // Cancelling a locked stream rejects with a TypeError
+    let error = readable.cancel().await.unwrap_err();
+    assert!(matches!(error, StreamError::TypeError(_)));
+    assert_eq!(error.name().as_deref(), Some("TypeError"));
+    assert!(error.to_string().starts_with("TypeError"));
+}
+
+#[wasm_bindgen_test]
+async fn test_stream_error_js_variant() {
+    let mut readable = ReadableStream::from_stream(iter(vec![Ok(JsValue::from("Hello"))]));
+    let _reader = readable.as_raw().get_reader();
+
+    // The raw variant returns the original error value
+    let error = readable.cancel_js().await.unwrap_err();
+    assert!(error.is_instance_of::<js_sys::TypeError>());
+}
+
+#[wasm_bindgen_test]
+async fn test_stream_error_other() {
+    let mut readable =
+        ReadableStream::from_stream(iter(vec![Err::<JsValue, _>(JsValue::from_str("oops"))]));
+    let mut reader = readable.get_reader();
+    let error = StreamError::from(reader.read().await.unwrap_err());
+    assert_eq!(error, StreamError::Other(JsValue::from_str("oops")));
+    assert_eq!(error.name(), None);
+    assert_eq!(error.to_string(), "oops");
+    assert_eq!(error.into_js(), JsValue::from_str("oops"));
+}
+
+#[wasm_bindgen_test]
+fn test_stream_error_classify() {
+    let range_error = StreamError::from(JsValue::from(js_sys::RangeError::new("too big")));
+    assert!(matches!(range_error, StreamError::RangeError(_)));
+    assert_eq!(range_error.to_string(), "RangeError: too big");
+
+    let error = StreamError::from(js_sys::Error::new("failed"));
+    assert!(matches!(error, StreamError::Error(_)));
+    assert_eq!(error.message().as_deref(), Some("failed"));
+
+    let abort = web_sys::DomException::new_with_message_and_name("stop", "AbortError").unwrap();
+    let abort = StreamError::from(JsValue::from(abort));
+    assert!(abort.is_aborted());
+    assert_eq!(abort.to_string(), "AbortError: stop");
+}
@@ -0,0 +1,72 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{FileSystemDirectoryHandle, FileSystemFileHandle, FileSystemGetFileOptions};
+
+use wasm_streams::file_system::*;
+use wasm_streams::ReadableStream;
+
+async fn file_handle(name: &str) -> FileSystemFileHandle {
+    let storage = web_sys::window().unwrap().navigator().storage();
+    let root = JsFuture::from(storage.get_directory())
+        .await
+        .unwrap()
+        .unchecked_into::<FileSystemDirectoryHandle>();
+    let options = FileSystemGetFileOptions::new();
+    options.set_create(true);
+    JsFuture::from(root.get_file_handle_with_options(name, &options))
+        .await
+        .unwrap()
+        .unchecked_into()
+}
+
+async fn file_contents(handle: &FileSystemFileHandle) -> String {
+    let file = JsFuture::from(handle.get_file()).await.unwrap();
+    let text = JsFuture::from(file.unchecked_into::<web_sys::Blob>().text())
+        .await
+        .unwrap();
+    text.as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_to_file() {
+    let handle = file_handle("test_pipe_to_file.txt").await;
+    let stream = futures::stream::iter(vec![
+        Ok(JsValue::from(Uint8Array::from(&b"Hello, "[..]))),
+        Ok(JsValue::from(Uint8Array::from(&b"world!"[..]))),
+    ]);
+    let mut readable = ReadableStream::from_stream(stream);
+    readable.pipe_to_file(&handle).await.unwrap();
+    assert_eq!(file_contents(&handle).await, "Hello, world!");
+
+    // An erroring stream leaves the file untouched
+    let stream = futures::stream::iter(vec![
+        Ok(JsValue::from(Uint8Array::from(&b"Goodbye"[..]))),
+        Err(JsValue::from_str("oops")),
+    ]);
+    let mut readable = ReadableStream::from_stream(stream);
+    assert!(readable.pipe_to_file(&handle).await.is_err());
+    assert_eq!(file_contents(&handle).await, "Hello, world!");
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_file_stream_seek_and_truncate() {
+    let handle = file_handle("test_writable_file_stream.txt").await;
+    let mut file = WritableFileStream::create(&handle).await.unwrap();
+    file.write_bytes(b"Hello, world!").await.unwrap();
+    file.seek(7.0).await.unwrap();
+    file.write_bytes(b"there").await.unwrap();
+    file.truncate(12.0).await.unwrap();
+    file.close().await.unwrap();
+    assert_eq!(file_contents(&handle).await, "Hello, there");
+
+    let mut file = WritableFileStream::create_keeping_existing_data(&handle)
+        .await
+        .unwrap();
+    file.seek(12.0).await.unwrap();
+    file.write_bytes(b"!").await.unwrap();
+    file.abort().await.unwrap();
+    assert_eq!(file_contents(&handle).await, "Hello, there");
+}
@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use futures::stream::{iter, pending};
+use futures::StreamExt;
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{Request, RequestInit, Response, ResponseInit};
+
+use wasm_streams::http::{response_from_stream, RequestExt, ResponseExt};
+use wasm_streams::ReadableStream;
+
+fn bytes_stream(chunks: Vec<&'static [u8]>) -> ReadableStream {
+    ReadableStream::from_stream(iter(chunks).map(|chunk| Ok(Uint8Array::from(chunk).into())))
+}
+
+#[wasm_bindgen_test]
+async fn test_response_from_readable() {
+    let readable = bytes_stream(vec![&[1, 2, 3], &[4, 5]]);
+    let response = Response::from_readable(readable, &ResponseInit::new()).unwrap();
+
+    let body = ReadableStream::from_response_body(&response).unwrap();
+    assert_eq!(body.collect_bytes().await.unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+#[wasm_bindgen_test]
+async fn test_response_without_body() {
+    let response = Response::new().unwrap();
+    assert!(ReadableStream::from_response_body(&response).is_none());
+}
+
+#[wasm_bindgen_test]
+async fn test_request_from_readable() {
+    let readable = bytes_stream(vec![&[1, 2, 3], &[4, 5]]);
+    let init = RequestInit::new();
+    init.set_method("POST");
+    let request = Request::from_readable("https://example.com/", readable, &init).unwrap();
+    assert_eq!(request.method(), "POST");
+
+    let buffer = JsFuture::from(request.array_buffer().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(Uint8Array::new(&buffer).to_vec(), vec![1, 2, 3, 4, 5]);
+}
+
+#[wasm_bindgen_test]
+async fn test_response_from_stream() {
+    let stream = iter(vec![Ok::<_, JsValue>(vec![1, 2, 3]), Ok(vec![4, 5])]);
+    let response = response_from_stream(stream, &ResponseInit::new()).unwrap();
+
+    let body = ReadableStream::from_response_body(&response).unwrap();
+    assert_eq!(body.collect_bytes().await.unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_response_from_stream_cancel() {
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(dropped.clone());
+    let stream = pending::<Result<Vec<u8>, JsValue>>().map(move |chunk| {
+        let _ = &flag;
+        chunk
+    });
+    let response = response_from_stream(stream, &ResponseInit::new()).unwrap();
+    assert!(!dropped.get());
+
+    let mut body = ReadableStream::from_response_body(&response).unwrap();
+    body.cancel().await.unwrap();
+    assert!(dropped.get());
+}
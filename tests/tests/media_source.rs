@@ -0,0 +1,39 @@
+use js_sys::{Promise, Uint8Array};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use wasm_bindgen_test::*;
+use web_sys::{HtmlMediaElement, MediaSource, Url};
+
+use wasm_streams::media_source::*;
+
+/// Attaches a new `MediaSource` to a video element, and waits for it to open.
+async fn open_media_source() -> MediaSource {
+    let media_source = MediaSource::new().unwrap();
+    let video = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("video")
+        .unwrap()
+        .unchecked_into::<HtmlMediaElement>();
+    let opened = Promise::new(&mut |resolve, _| media_source.set_onsourceopen(Some(&resolve)));
+    video.set_src(&Url::create_object_url_with_source(&media_source).unwrap());
+    JsFuture::from(opened).await.unwrap();
+    media_source
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_from_source_buffer_invalid_data() {
+    let mime = "video/webm; codecs=\"vp8\"";
+    if !MediaSource::is_type_supported(mime) {
+        return;
+    }
+    let media_source = open_media_source().await;
+    let buffer = media_source.add_source_buffer(mime).unwrap();
+    let mut writable = writable_from_source_buffer(&buffer, &SourceBufferSinkOptions::new());
+
+    // The buffer fails to parse the chunk, so the writable stream errors
+    let mut writer = writable.get_writer();
+    let chunk = Uint8Array::from(&b"not a webm file"[..]);
+    assert!(writer.write(chunk).await.is_err());
+}
@@ -0,0 +1,61 @@
+use futures::channel::mpsc;
+use futures::stream::iter;
+use futures::{SinkExt, StreamExt, TryStreamExt};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::metered::Metered;
+use wasm_streams::{ReadableStream, WritableStream};
+
+#[wasm_bindgen_test]
+async fn test_metered_readable_stream() {
+    let chunks = vec![
+        Ok(Uint8Array::from(&[1, 2, 3][..]).into()),
+        Ok(JsValue::from("not bytes")),
+        Ok(Uint8Array::from(&[4, 5][..]).into()),
+    ];
+    let metered = Metered::readable(ReadableStream::from_stream(iter(chunks)));
+    let handle = metered.handle();
+    assert_eq!(handle.chunks(), 0);
+    assert_eq!(handle.bytes(), 0);
+    assert!(!handle.is_finished());
+
+    let output = metered
+        .into_inner()
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output.len(), 3);
+    assert_eq!(handle.chunks(), 3);
+    assert_eq!(handle.bytes(), 5);
+    assert!(handle.is_finished());
+}
+
+#[wasm_bindgen_test]
+async fn test_metered_writable_stream() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let mut metered = Metered::writable(WritableStream::from_sink(sink).cast::<Uint8Array>());
+    let handle = metered.handle();
+
+    let mut writer = metered.get_mut().get_writer();
+    writer.write(Uint8Array::from(&[1, 2][..])).await.unwrap();
+    assert_eq!(handle.chunks(), 1);
+    writer.write(Uint8Array::from(&[3][..])).await.unwrap();
+    writer.close().await.unwrap();
+
+    assert_eq!(stream.collect::<Vec<_>>().await.len(), 2);
+    assert_eq!(handle.chunks(), 2);
+    assert_eq!(handle.bytes(), 3);
+    assert!(handle.is_finished());
+}
+
+#[wasm_bindgen_test]
+fn test_metered_try_readable_locked() {
+    let mut readable = ReadableStream::from_stream(futures::stream::pending());
+    let _stream = readable.lines();
+    let (_, readable) = Metered::try_readable(readable).unwrap_err();
+    assert!(readable.is_locked());
+}
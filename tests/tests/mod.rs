@@ -1,4 +1,35 @@
+mod abort;
+mod broadcast;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "tokio")]
+mod codec;
+#[cfg(feature = "deno")]
+mod deno;
+mod duplex;
+mod error;
+#[cfg(feature = "file-system")]
+mod file_system;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "media-source")]
+mod media_source;
+mod metered;
 mod pipe;
 mod readable_stream;
+mod resumable;
+#[cfg(feature = "serial")]
+mod serial;
+mod support;
+#[cfg(feature = "transfer")]
+mod transfer;
 mod transform_stream;
+#[cfg(feature = "webcodecs")]
+mod webcodecs;
+#[cfg(feature = "webrtc")]
+mod webrtc;
+#[cfg(feature = "websocket")]
+mod websocket;
+#[cfg(feature = "webtransport")]
+mod webtransport;
 mod writable_stream;
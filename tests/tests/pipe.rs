@@ -1,11 +1,19 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
 use futures::channel::mpsc;
-use futures::stream::iter;
-use futures::{SinkExt, StreamExt};
+use futures::future::join;
+use futures::stream::{iter, pending};
+use futures::{SinkExt, StreamExt, TryStreamExt};
+use js_sys::Uint8Array;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::*;
 
 use wasm_streams::readable::*;
+use wasm_streams::transform::*;
 use wasm_streams::writable::*;
+use wasm_streams::StreamError;
 
 use crate::js::*;
 
@@ -76,3 +84,271 @@ async fn test_pipe_prevent_close() {
     // Readable stream must be closed
     readable.get_reader().closed().await.unwrap();
 }
+
+#[wasm_bindgen_test]
+async fn test_pipe_through() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let transform = TransformStream::from_raw(new_uppercase_transform_stream());
+
+    let output = readable.pipe_through(&transform);
+    assert!(readable.is_locked());
+
+    let output = output.into_stream().try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(
+        output,
+        vec![JsValue::from("HELLO"), JsValue::from("WORLD!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_through_sink_and_stream() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let stream = stream.map(|chunk| Ok(JsValue::from(chunk.as_string().unwrap().to_uppercase())));
+    let transform = TransformStream::from_sink_and_stream(sink, stream);
+    assert!(transform
+        .as_raw()
+        .is_instance_of::<wasm_streams::transform::sys::TransformStream>());
+
+    let output = readable.pipe_through(&transform);
+    let output = output.into_stream().try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(
+        output,
+        vec![JsValue::from("HELLO"), JsValue::from("WORLD!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_through_sink_and_stream_error() {
+    let chunks = vec![JsValue::from("Hello")];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let sink = futures::sink::drain().sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let stream = iter(vec![Ok(JsValue::from("a")), Err(JsValue::from_str("oops"))]);
+    let transform = TransformStream::from_sink_and_stream(sink, stream);
+
+    let output = readable.pipe_through(&transform);
+    let mut output = output.into_stream();
+    assert_eq!(output.next().await.unwrap().unwrap(), JsValue::from("a"));
+    assert_eq!(
+        output.next().await.unwrap().unwrap_err(),
+        JsValue::from_str("oops")
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_through_locked() {
+    let mut readable = ReadableStream::from_raw(new_noop_readable_stream());
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+
+    // Lock the writable side of the transform stream
+    let mut writable = WritableStream::from_raw(transform.as_raw().writable());
+    let _writer = writable.get_writer();
+
+    assert!(readable.try_pipe_through(&transform).is_err());
+    assert!(!readable.is_locked());
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_to_spawned() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink(sink);
+
+    let handle = readable.pipe_to_spawned(writable, &PipeOptions::new());
+    let output = stream.collect::<Vec<_>>();
+    let (result, output) = futures::future::join(handle, output).await;
+    result.unwrap();
+    assert_eq!(output.len(), 2);
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_to_spawned_progress_and_abort() {
+    // Never close the source, so the pipe can only complete by aborting it
+    let stream = iter(vec![Ok(Uint8Array::from(&[1, 2, 3][..]).into())]).chain(pending());
+    let readable = ReadableStream::from_stream(stream);
+
+    let recording_stream = RecordingWritableStream::new();
+    let writable = WritableStream::from_raw(recording_stream.stream());
+
+    let mut options = PipeOptions::new();
+    options.prevent_close(true);
+    let handle = readable.pipe_to_spawned(writable, &options);
+    // Wait for the first chunk to be piped
+    while handle.chunks_piped() < 1 {
+        JsFuture::from(js_sys::Promise::resolve(&JsValue::undefined()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(handle.chunks_piped(), 1);
+    assert_eq!(handle.bytes_piped(), 3);
+
+    handle.abort();
+    assert!(handle.await.unwrap_err().is_aborted());
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_to_spawned_prevent_close() {
+    let stream = iter(vec![Ok(Uint8Array::from(&[1, 2, 3][..]).into())]);
+    let readable = ReadableStream::from_stream(stream);
+
+    let recording_stream = RecordingWritableStream::new();
+    let writable = WritableStream::from_raw(recording_stream.stream());
+
+    let mut options = PipeOptions::new();
+    options.prevent_close(true);
+    let handle = readable.pipe_to_spawned(writable, &options);
+    let meter = handle.meter();
+    // The source closes normally, so the handle resolves without closing the destination
+    handle.await.unwrap();
+    assert_eq!(meter.chunks(), 1);
+    assert_eq!(recording_stream.events().first().unwrap(), "write");
+    assert!(!recording_stream.events().contains(&"close".to_string()));
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_to_spawned_closes_dest() {
+    let stream = iter(vec![Ok(JsValue::from("Hello"))]);
+    let readable = ReadableStream::from_stream(stream);
+
+    let recording_stream = RecordingWritableStream::new();
+    let writable = WritableStream::from_raw(recording_stream.stream());
+
+    let handle = readable.pipe_to_spawned(writable, &PipeOptions::new());
+    handle.await.unwrap();
+    assert_eq!(recording_stream.events(), vec!["write", "Hello", "close"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_abort_with_reason_classified() {
+    let mut readable = ReadableStream::from_stream(pending());
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+    let (output, mut writable) = transform.into_pair().into_parts();
+    let output = output.into_stream();
+
+    let controller = web_sys::AbortController::new().unwrap();
+    let signal = controller.signal();
+    let mut options = PipeOptions::new();
+    options.signal(signal.clone());
+    let reason = JsValue::from_str("cancelled by user");
+    let (result, _) = join(
+        readable.pipe_to_with_options(&mut writable, &options),
+        async { controller.abort_with_reason(&reason) },
+    )
+    .await;
+    assert_eq!(result.unwrap_err(), StreamError::Aborted(reason.clone()));
+
+    // The destination was aborted with the custom reason,
+    // which is only recognized as an abort with the signal
+    let mut output = output.classify_errors().with_signal(&signal);
+    assert_eq!(
+        output.next().await.unwrap().unwrap_err(),
+        StreamError::Aborted(reason)
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_through_with_all_options() {
+    let cancelled = Rc::new(Cell::new(false));
+    let mut readable = ReadableStream::from_stream_with_cancel_handler(
+        iter(vec![Ok(JsValue::from("Hello"))]).chain(pending()),
+        {
+            let cancelled = cancelled.clone();
+            move |_| cancelled.set(true)
+        },
+    );
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+
+    let controller = web_sys::AbortController::new().unwrap();
+    let mut options = PipeOptions::new();
+    options
+        .prevent_close(true)
+        .prevent_cancel(true)
+        .prevent_abort(true)
+        .signal(controller.signal());
+    let raw_options: wasm_streams::readable::sys::PipeOptions = options.clone().into();
+    assert!(raw_options.prevent_close());
+    assert!(raw_options.prevent_cancel());
+    assert!(raw_options.prevent_abort());
+    assert!(raw_options.signal().is_some());
+
+    let mut output = readable
+        .pipe_through_with_options(&transform, &options)
+        .into_stream();
+    assert_eq!(output.next().await, Some(Ok(JsValue::from("Hello"))));
+
+    // Aborting the pipe neither cancels the source nor aborts the transform
+    controller.abort();
+    while readable.is_locked() {
+        JsFuture::from(js_sys::Promise::resolve(&JsValue::undefined()))
+            .await
+            .unwrap();
+    }
+    assert!(!cancelled.get());
+    let mut writable = WritableStream::from_raw(transform.as_raw().writable());
+    let mut writer = writable.get_writer();
+    let (write, next) = join(writer.write(JsValue::from("again")), output.next()).await;
+    write.unwrap();
+    assert_eq!(next, Some(Ok(JsValue::from("again"))));
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_pair() {
+    let (writable, readable) = wasm_streams::pipe(1);
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let (_, result) = join(
+        async {
+            let mut sink = writable.into_sink();
+            sink.send_all(&mut iter(chunks.clone()).map(Ok))
+                .await
+                .unwrap();
+            sink.close().await.unwrap();
+        },
+        readable.into_stream().try_collect::<Vec<_>>(),
+    )
+    .await;
+    assert_eq!(result, Ok(chunks));
+}
+
+#[wasm_bindgen_test]
+async fn test_pipe_pair_abort_and_cancel() {
+    // Aborting the writable stream errors the readable stream
+    let (mut writable, mut readable) = wasm_streams::pipe(1);
+    writable
+        .abort_with_reason(&JsValue::from("aborted"))
+        .await
+        .unwrap();
+    let mut reader = readable.get_reader();
+    assert_eq!(
+        reader.read().await,
+        Err(ReadError::Js(JsValue::from("aborted")))
+    );
+
+    // Cancelling the readable stream errors the writable stream
+    let (mut writable, mut readable) = wasm_streams::pipe(1);
+    readable
+        .cancel_with_reason(&JsValue::from("cancelled"))
+        .await
+        .unwrap();
+    let mut writer = writable.get_writer();
+    assert_eq!(
+        writer
+            .write(JsValue::from("Hello"))
+            .await
+            .unwrap_err()
+            .into_js(),
+        JsValue::from("cancelled")
+    );
+}
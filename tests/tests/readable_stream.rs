@@ -1,12 +1,21 @@
+use std::cell::RefCell;
 use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
 
+use futures::future::{ready, LocalBoxFuture};
+use futures::io::{AsyncBufReadExt, AsyncReadExt, Cursor};
 use futures::stream::{iter, StreamExt, TryStreamExt};
 use futures::task::Poll;
 use futures::{poll, FutureExt};
+use js_sys::{JsString, Uint8Array};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
 use wasm_bindgen_test::*;
 
 use wasm_streams::readable::*;
+use wasm_streams::QueuingStrategy;
 
 use crate::js::*;
 
@@ -24,6 +33,56 @@ async fn test_readable_stream_new() {
     reader.closed().await.unwrap();
 }
 
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_foreign_stream() {
+    let raw = new_foreign_readable_stream_from_array(
+        vec![JsValue::from("Hello"), JsValue::from("world!")].into_boxed_slice(),
+    );
+    assert!(!raw.is_instance_of::<web_sys::ReadableStream>());
+    let raw = raw.dyn_into::<sys::ReadableStream>().unwrap();
+    let readable = ReadableStream::from_raw(raw);
+    assert!(!readable.is_locked());
+
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        chunks,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+
+    assert!(JsValue::from(js_sys::Object::new())
+        .dyn_into::<sys::ReadableStream>()
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+fn test_readable_stream_reader_type_checks() {
+    let raw = new_readable_byte_stream_from_array(vec![].into_boxed_slice());
+    let default_reader = JsValue::from(raw.get_reader().unwrap());
+    assert!(default_reader
+        .dyn_ref::<sys::ReadableStreamDefaultReader>()
+        .is_some());
+    assert!(default_reader
+        .dyn_ref::<sys::ReadableStreamBYOBReader>()
+        .is_none());
+    default_reader
+        .unchecked_ref::<sys::ReadableStreamDefaultReader>()
+        .release_lock()
+        .unwrap();
+
+    let mut readable = ReadableStream::from_raw(raw);
+    let byob_reader = JsValue::from(readable.get_byob_reader().as_raw().clone());
+    assert!(byob_reader
+        .dyn_ref::<sys::ReadableStreamBYOBReader>()
+        .is_some());
+    assert!(byob_reader
+        .dyn_ref::<sys::ReadableStreamDefaultReader>()
+        .is_none());
+}
+
 #[wasm_bindgen_test]
 async fn test_readable_stream_into_stream() {
     let readable = ReadableStream::from_raw(new_readable_stream_from_array(
@@ -141,6 +200,334 @@ async fn test_readable_stream_abort_read() {
     reader.release_lock();
 }
 
+#[wasm_bindgen_test]
+async fn test_readable_stream_drop_policy() {
+    let mut readable = ReadableStream::from_raw(new_noop_readable_stream());
+    let mut reader = readable.get_reader();
+    reader.set_drop_policy(ReaderDropPolicy::CancelThenRelease);
+
+    // Leave a read pending, which would prevent releasing the lock
+    let mut fut = reader.read().boxed_local();
+    assert_eq!(poll!(&mut fut), Poll::Pending);
+    drop(fut);
+
+    // Dropping the reader cancels the stream and releases the lock
+    drop(reader);
+    assert!(!readable.is_locked());
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read().await.unwrap(), None);
+    reader.set_drop_policy(ReaderDropPolicy::Leak);
+    drop(reader);
+    assert!(readable.is_locked());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_by_ref() {
+    let stream = iter(vec!["Hello", "world", "!"]).map(|s| Ok(JsValue::from(s)));
+    let mut readable = ReadableStream::from_stream(stream);
+
+    assert_eq!(
+        (&mut readable).next().await,
+        Some(Ok(JsValue::from("Hello")))
+    );
+    let second = StreamExt::take(&mut readable, 1)
+        .try_collect::<Vec<_>>()
+        .await;
+    assert_eq!(second, Ok(vec![JsValue::from("world")]));
+    // The lock is released between reads
+    assert!(!readable.is_locked());
+
+    let mut stream = readable.into_stream();
+    assert_eq!(stream.next().await, Some(Ok(JsValue::from("!"))));
+    assert_eq!(stream.next().await, None);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_stream_into_reader() {
+    let stream = iter(vec!["Hello", "world!"]).map(|s| Ok(JsValue::from(s)));
+    let mut readable = ReadableStream::from_stream(stream);
+    let mut stream = readable.get_reader().into_stream();
+
+    // Start a read, and hand it over to the reader before it completes
+    assert_eq!(poll!(stream.next()), Poll::Pending);
+    let mut reader = stream.into_reader().unwrap();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("world!")));
+    assert_eq!(reader.read().await.unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_stream_into_inner() {
+    let stream = iter(vec!["a", "b", "c"]).map(|s| Ok(JsValue::from(s)));
+    let readable = ReadableStream::from_stream(stream);
+    let raw = readable.as_raw().clone();
+    let mut stream = readable.into_stream();
+
+    // Without any read in flight, the original stream is returned
+    assert_eq!(stream.next().await, Some(Ok(JsValue::from("a"))));
+    let readable = stream.into_inner();
+    assert_eq!(JsValue::from(readable.as_raw()), JsValue::from(raw));
+    assert!(!readable.is_locked());
+
+    // With a read in flight, its chunk is still returned
+    let mut stream = readable.into_stream();
+    assert_eq!(poll!(stream.next()), Poll::Pending);
+    let chunks = stream
+        .into_inner()
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await;
+    assert_eq!(chunks, Ok(vec![JsValue::from("b"), JsValue::from("c")]));
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_async_iterator() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+
+    let iterator = readable.into_async_iterator(false);
+    let stream = AsyncIterableStream::from_iterator(iterator.unchecked_into());
+    assert_eq!(stream.try_collect::<Vec<_>>().await, Ok(chunks));
+}
+
+#[wasm_bindgen_test]
+async fn test_async_iterable_stream() {
+    let values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
+
+    let generator = new_async_generator_from_array(values.clone().into_boxed_slice());
+    let stream = AsyncIterableStream::new(&generator).unwrap();
+    assert_eq!(stream.try_collect::<Vec<_>>().await, Ok(values.clone()));
+
+    // Synchronous iterables are supported too
+    let array = values.iter().collect::<js_sys::Array>();
+    let stream = AsyncIterableStream::new(&array).unwrap();
+    assert_eq!(stream.try_collect::<Vec<_>>().await, Ok(values));
+
+    assert!(AsyncIterableStream::new(&JsValue::from(42)).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_async_iterator_from_stream() {
+    let values = vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)];
+
+    // Consume the iterator through its [Symbol.asyncIterator]() method
+    let iterator = async_iterator_from_stream(iter(values.clone()).map(Ok));
+    let stream = AsyncIterableStream::new(&iterator).unwrap();
+    assert_eq!(stream.try_collect::<Vec<_>>().await, Ok(values.clone()));
+
+    // An error ends the iteration
+    let iterator = async_iterator_from_stream(iter(vec![
+        Ok(JsValue::from(1)),
+        Err(JsValue::from("oops")),
+        Ok(JsValue::from(2)),
+    ]));
+    let stream = AsyncIterableStream::new(&iterator).unwrap();
+    assert_eq!(
+        stream.collect::<Vec<_>>().await,
+        vec![Ok(JsValue::from(1)), Err(JsValue::from("oops"))]
+    );
+
+    // Ending the iterator early releases the reader's lock
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        values.clone().into_boxed_slice(),
+    ));
+    let raw = readable.as_raw().clone();
+    let iterator = readable.into_stream().into_async_iterator();
+    let next = JsFuture::from(iterator.next().unwrap()).await.unwrap();
+    assert_eq!(
+        js_sys::Reflect::get(&next, &"value".into()).unwrap(),
+        JsValue::from(1)
+    );
+    let return_fn: js_sys::Function = js_sys::Reflect::get(&iterator, &"return".into())
+        .unwrap()
+        .unchecked_into();
+    let result = return_fn.call0(&iterator).unwrap();
+    JsFuture::from(result.unchecked_into::<js_sys::Promise>())
+        .await
+        .unwrap();
+    let mut readable = ReadableStream::from_raw(raw);
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from(2)));
+}
+
+#[wasm_bindgen_test]
+async fn test_async_iterator_from_stream_return_during_next() {
+    let iterator = async_iterator_from_stream(futures::stream::pending());
+    let next = JsFuture::from(iterator.next().unwrap());
+
+    // Ending the iterator does not wait for the pending next() call forever
+    let return_fn: js_sys::Function = js_sys::Reflect::get(&iterator, &"return".into())
+        .unwrap()
+        .unchecked_into();
+    let result = return_fn.call0(&iterator).unwrap();
+    JsFuture::from(result.unchecked_into::<js_sys::Promise>())
+        .await
+        .unwrap();
+
+    // and the pending next() call ends the iteration
+    let next = next.await.unwrap();
+    assert_eq!(
+        js_sys::Reflect::get(&next, &"done".into()).unwrap(),
+        JsValue::TRUE
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_readable_stream_try_into_async_iterator_locked() {
+    let readable = ReadableStream::from_raw(new_noop_readable_stream());
+    let _reader = readable.as_raw().get_reader().unwrap();
+    let (err, _) = readable.try_into_async_iterator(false).unwrap_err();
+    assert!(err.is_instance_of::<js_sys::TypeError>());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_js_iterable() {
+    let values = vec![JsValue::from("a"), JsValue::from("b")];
+
+    let array = values.iter().collect::<js_sys::Array>();
+    let readable = ReadableStream::from_js_iterable(&array).unwrap();
+    let chunks = readable.into_stream().try_collect::<Vec<_>>().await;
+    assert_eq!(chunks, Ok(values.clone()));
+
+    let generator = new_async_generator_from_array(values.clone().into_boxed_slice());
+    let readable = ReadableStream::from_js_iterable(&generator).unwrap();
+    let chunks = readable.into_stream().try_collect::<Vec<_>>().await;
+    assert_eq!(chunks, Ok(values));
+
+    assert!(ReadableStream::from_js_iterable(&JsValue::NULL).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_observable() {
+    let values = vec![JsValue::from("a"), JsValue::from("b"), JsValue::from("c")];
+    let observable = new_observable_from_array(values.clone().into_boxed_slice());
+
+    let readable = ReadableStream::from_observable(&observable).unwrap();
+    let chunks = readable.into_stream().try_collect::<Vec<_>>().await;
+    assert_eq!(chunks, Ok(values.clone()));
+
+    // Values emitted while the buffer is full are dropped.
+    let readable =
+        ReadableStream::from_observable_with_strategy(&observable, QueuingStrategy::new(2.0))
+            .unwrap();
+    let chunks = readable.into_stream().try_collect::<Vec<_>>().await;
+    assert_eq!(chunks, Ok(values[..2].to_vec()));
+
+    assert!(ReadableStream::from_observable(&JsValue::NULL).is_err());
+}
+
+#[cfg(feature = "event-target")]
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_event_target() {
+    let target = web_sys::EventTarget::new().unwrap();
+    let mut readable =
+        ReadableStream::from_event_target_with_strategy(&target, "ping", QueuingStrategy::new(2.0));
+    let mut reader = readable.get_reader();
+
+    for _ in 0..3 {
+        let event = web_sys::Event::new("ping").unwrap();
+        target.dispatch_event(&event).unwrap();
+    }
+    target
+        .dispatch_event(&web_sys::Event::new("pong").unwrap())
+        .unwrap();
+
+    // The third event arrived while the buffer was full.
+    assert_eq!(reader.read().await.unwrap().unwrap().type_(), "ping");
+    assert_eq!(reader.read().await.unwrap().unwrap().type_(), "ping");
+    let mut next_read = reader.read().boxed_local();
+    assert_eq!(poll!(&mut next_read), Poll::Pending);
+
+    target
+        .dispatch_event(&web_sys::Event::new("ping").unwrap())
+        .unwrap();
+    assert_eq!(next_read.await.unwrap().unwrap().type_(), "ping");
+
+    // Cancelling removes the listener.
+    reader.cancel().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_interval() {
+    let start = js_sys::Date::now();
+    let mut readable = ReadableStream::interval(Duration::from_millis(20));
+    let mut reader = readable.get_reader();
+
+    let mut last = start;
+    for _ in 0..3 {
+        let tick = reader.read().await.unwrap().unwrap().value_of();
+        assert!(tick >= last);
+        last = tick;
+    }
+    assert!(last - start >= 40.0);
+
+    reader.cancel().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_timeout_between_chunks() {
+    let stream = iter(vec![Ok(JsValue::from("Hello"))]).chain(futures::stream::pending());
+    let mut readable =
+        ReadableStream::from_stream(stream).timeout_between_chunks(Duration::from_millis(20));
+    let mut reader = readable.get_reader();
+
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
+    match reader.read().await.unwrap_err() {
+        ReadError::Js(error) => assert!(matches!(
+            wasm_streams::StreamError::from_js(error),
+            wasm_streams::StreamError::Timeout(_)
+        )),
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_reader_read_timeout() {
+    let (sender, receiver) = futures::channel::mpsc::unbounded::<Result<JsValue, JsValue>>();
+    let mut readable = ReadableStream::from_stream(receiver);
+    let mut reader = readable.get_reader();
+
+    let timeout = Duration::from_millis(20);
+    assert_eq!(reader.read_timeout(timeout).await, Err(ReadError::Timeout));
+
+    // The pending read is kept, so the chunk is not lost
+    sender.unbounded_send(Ok(JsValue::from("Hello"))).unwrap();
+    assert_eq!(
+        reader.read_timeout(timeout).await,
+        Ok(Some(JsValue::from("Hello")))
+    );
+
+    sender.close_channel();
+    assert_eq!(reader.read_timeout(timeout).await, Ok(None));
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_reader_read_with_signal() {
+    let (sender, receiver) = futures::channel::mpsc::unbounded::<Result<JsValue, JsValue>>();
+    let mut readable = ReadableStream::from_stream(receiver);
+    let mut reader = readable.get_reader();
+
+    let controller = web_sys::AbortController::new().unwrap();
+    let signal = controller.signal();
+    let mut read = reader.read_with_signal(&signal).boxed_local();
+    assert_eq!(poll!(&mut read), Poll::Pending);
+    controller.abort_with_reason(&JsValue::from("oops"));
+    assert_eq!(read.await, Err(ReadError::Aborted(JsValue::from("oops"))));
+
+    // The pending read is kept, so the chunk is not lost
+    sender.unbounded_send(Ok(JsValue::from("Hello"))).unwrap();
+    assert_eq!(reader.read().await, Ok(Some(JsValue::from("Hello"))));
+
+    // An already aborted signal fails immediately
+    assert!(reader
+        .closed_with_signal(&signal)
+        .await
+        .unwrap_err()
+        .is_aborted());
+}
+
 #[wasm_bindgen_test]
 async fn test_readable_stream_from_stream_then_into_stream() {
     let stream = iter(vec!["Hello", "world!"]).map(|s| Ok(JsValue::from(s)));
@@ -183,3 +570,956 @@ async fn test_readable_stream_tee() {
     assert_eq!(left_chunks, chunks);
     assert_eq!(right_chunks, chunks);
 }
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_tee_n() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+
+    let branches = readable.tee_n(5);
+    assert_eq!(branches.len(), 5);
+
+    for branch in branches {
+        let branch_chunks = branch.into_stream().try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(branch_chunks, chunks);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_tee_n_cancel_some() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+
+    let mut branches = readable.tee_n(3);
+    let last = branches.pop().unwrap();
+    for mut branch in branches {
+        branch.cancel().await.unwrap();
+    }
+
+    // The stream is not cancelled while one branch remains
+    let last_chunks = last.into_stream().try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(last_chunks, chunks);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_inspect() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let mut readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let inspected = readable.inspect({
+        let seen = seen.clone();
+        move |chunk| seen.borrow_mut().push(chunk.clone())
+    });
+    let output = inspected
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, chunks);
+    assert_eq!(*seen.borrow(), chunks);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_take_and_skip() {
+    let mut readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        (0..5)
+            .map(JsValue::from)
+            .collect::<Vec<_>>()
+            .into_boxed_slice(),
+    ));
+
+    let header = readable.take(2).await.unwrap();
+    assert_eq!(header, vec![JsValue::from(0), JsValue::from(1)]);
+    assert!(!readable.is_locked());
+
+    assert_eq!(readable.skip(1).await.unwrap(), 1);
+    assert!(!readable.is_locked());
+
+    // The rest of the stream is still usable
+    let rest = readable.take(10).await.unwrap();
+    assert_eq!(rest, vec![JsValue::from(3), JsValue::from(4)]);
+    assert_eq!(readable.skip(1).await.unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_merge() {
+    let first = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from(1), JsValue::from(2)].into_boxed_slice(),
+    ));
+    let second = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from(3)].into_boxed_slice(),
+    ));
+
+    let merged = ReadableStream::merge(vec![first, second]);
+    let mut chunks = merged
+        .into_stream()
+        .map_ok(|chunk| chunk.as_f64().unwrap() as u32)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    chunks.sort_unstable();
+    assert_eq!(chunks, vec![1, 2, 3]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_merge_error() {
+    let first = ReadableStream::from_stream(futures::stream::pending());
+    let second =
+        ReadableStream::from_stream(futures::stream::iter(vec![Err(JsValue::from_str("oops"))]));
+
+    let merged = ReadableStream::merge(vec![first, second]);
+    let result = merged.into_stream().try_collect::<Vec<_>>().await;
+    assert_eq!(result, Err(JsValue::from_str("oops")));
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_chain() {
+    let first = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from(1), JsValue::from(2)].into_boxed_slice(),
+    ));
+    let second = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from(3)].into_boxed_slice(),
+    ));
+
+    let chunks = first
+        .chain(second)
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        chunks,
+        vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_concat_cancel() {
+    struct DropCounter(Rc<RefCell<u32>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            *self.0.borrow_mut() += 1;
+        }
+    }
+
+    let cancelled = Rc::new(RefCell::new(0));
+    let streams = (0..3)
+        .map(|i| {
+            let counter = DropCounter(cancelled.clone());
+            let stream = iter(vec![Ok(JsValue::from(i))])
+                .chain(futures::stream::pending())
+                .map(move |chunk| {
+                    let _ = &counter;
+                    chunk
+                });
+            ReadableStream::from_stream(stream)
+        })
+        .collect::<Vec<_>>();
+
+    let mut concatenated = ReadableStream::concat(streams);
+    let mut reader = concatenated.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from(0)));
+    reader.cancel().await.unwrap();
+    drop(reader);
+
+    // Let the cancellations propagate
+    for _ in 0..10 {
+        JsFuture::from(js_sys::Promise::resolve(&JsValue::undefined()))
+            .await
+            .unwrap();
+    }
+    // The current stream and the two tails are all cancelled
+    assert_eq!(*cancelled.borrow(), 3);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_stream_with_cancel_handler() {
+    let resource = Rc::new(());
+    let stream = futures::stream::pending().map({
+        let resource = resource.clone();
+        move |chunk| {
+            let _ = &resource;
+            chunk
+        }
+    });
+    let reason = Rc::new(RefCell::new(None));
+    let readable = ReadableStream::from_stream_with_cancel_handler(stream, {
+        let reason = reason.clone();
+        move |r| *reason.borrow_mut() = Some(r)
+    });
+    assert_eq!(Rc::strong_count(&resource), 2);
+
+    let promise = readable.as_raw().cancel_with_reason(&JsValue::from("oops"));
+    // The stream is dropped and the handler is called before cancel() returns
+    assert_eq!(Rc::strong_count(&resource), 1);
+    assert_eq!(*reason.borrow(), Some(JsValue::from("oops")));
+    JsFuture::from(promise).await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_stream_cancel_during_pull() {
+    let resource = Rc::new(());
+    let stream = futures::stream::pending().map({
+        let resource = resource.clone();
+        move |chunk| {
+            let _ = &resource;
+            chunk
+        }
+    });
+    let mut readable = ReadableStream::from_stream(stream);
+    let mut reader = readable.get_reader();
+    // Wait until the stream is pulling, which borrows the underlying source
+    assert_eq!(
+        reader.read_timeout(Duration::from_millis(10)).await,
+        Err(ReadError::Timeout)
+    );
+
+    let promise = reader.as_raw().cancel();
+    // The pending pull and the stream are dropped before cancel() returns
+    assert_eq!(Rc::strong_count(&resource), 1);
+    JsFuture::from(promise).await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_zip() {
+    let left = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from(1), JsValue::from(2), JsValue::from(3)].into_boxed_slice(),
+    ));
+    let right = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from("a"), JsValue::from("b")].into_boxed_slice(),
+    ));
+
+    let zipped = ReadableStream::zip(left, right);
+    let pairs = zipped
+        .into_stream()
+        .map_ok(|pair| (pair.get(0), pair.get(1)))
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        pairs,
+        vec![
+            (JsValue::from(1), JsValue::from("a")),
+            (JsValue::from(2), JsValue::from("b")),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_zip_error() {
+    // The left stream never produces a chunk, but the error of the right stream
+    // is reported right away
+    let left = ReadableStream::from_stream(futures::stream::pending());
+    let right = ReadableStream::from_stream(iter(vec![Err(JsValue::from("oops"))]));
+
+    let mut zipped = ReadableStream::zip(left, right);
+    let mut reader = zipped.get_reader();
+    assert_eq!(
+        reader.read().await,
+        Err(ReadError::Js(JsValue::from("oops")))
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_try_merge_locked() {
+    let first = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from(1)].into_boxed_slice(),
+    ));
+    let raw = new_readable_stream_from_array(vec![JsValue::from(2)].into_boxed_slice());
+    let _reader = raw.get_reader().unwrap();
+    let second = ReadableStream::from_raw(raw);
+
+    let (_, streams) = ReadableStream::try_merge(vec![first, second]).unwrap_err();
+    assert!(!streams[0].is_locked());
+    assert!(streams[1].is_locked());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_try_tee_n_locked() {
+    for n in [1, 3] {
+        let raw = new_readable_stream_from_array(vec![JsValue::from("Hello")].into_boxed_slice());
+        let _reader = raw.get_reader().unwrap();
+        let readable = ReadableStream::from_raw(raw);
+
+        let (_, readable) = readable.try_tee_n(n).unwrap_err();
+        assert!(readable.is_locked());
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_pausable() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from("Hello"), JsValue::from("world!")].into_boxed_slice(),
+    ));
+    let (mut readable, handle) = readable.pausable();
+
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
+
+    handle.pause();
+    assert!(handle.is_paused());
+    let mut fut = reader.read().boxed_local();
+    // Give the stream a few chances to make progress
+    for _ in 0..10 {
+        assert_eq!(poll!(&mut fut), Poll::Pending);
+        JsFuture::from(js_sys::Promise::resolve(&JsValue::undefined()))
+            .await
+            .unwrap();
+    }
+
+    handle.resume();
+    assert_eq!(fut.await.unwrap(), Some(JsValue::from("world!")));
+    assert_eq!(reader.read().await.unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_byte_stream_byob_reader() {
+    let mut readable = ReadableStream::from_raw(new_readable_byte_stream_from_array(
+        vec![
+            Uint8Array::from(&[1, 2, 3, 4, 5][..]).into(),
+            Uint8Array::from(&[6, 7][..]).into(),
+        ]
+        .into_boxed_slice(),
+    ));
+
+    let mut reader = readable.get_byob_reader();
+    let mut dst = [0u8; 3];
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 3);
+    assert_eq!(&dst, &[1, 2, 3]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 2);
+    assert_eq!(&dst[..2], &[4, 5]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 2);
+    assert_eq!(&dst[..2], &[6, 7]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 0);
+    reader.closed().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_byte_stream_byob_reader_read_with_signal() {
+    let (sender, receiver) = futures::channel::mpsc::unbounded::<std::io::Result<Vec<u8>>>();
+    let mut readable = ReadableStream::from_async_read(receiver.into_async_read(), 8);
+    let mut reader = readable.get_byob_reader();
+
+    let controller = web_sys::AbortController::new().unwrap();
+    let signal = controller.signal();
+    let mut dst = [0u8; 8];
+    let mut read = reader.read_with_signal(&mut dst, &signal).boxed_local();
+    assert_eq!(poll!(&mut read), Poll::Pending);
+    controller.abort_with_reason(&JsValue::from("oops"));
+    match read.await {
+        Err(wasm_streams::StreamError::Aborted(reason)) => {
+            assert_eq!(reason, JsValue::from("oops"))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    // The pending read is kept, so no bytes are lost, even with a smaller buffer
+    sender.unbounded_send(Ok(vec![1, 2, 3, 4, 5])).unwrap();
+    let mut dst = [0u8; 3];
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 3);
+    assert_eq!(&dst, &[1, 2, 3]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 2);
+    assert_eq!(&dst[..2], &[4, 5]);
+
+    sender.close_channel();
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_byte_stream_from_raw_underlying_source() {
+    let source = sys::UnderlyingSource::new();
+    source.set_type(sys::ReadableStreamType::Bytes);
+    source.set_auto_allocate_chunk_size(4);
+    assert_eq!(source.type_(), Some(sys::ReadableStreamType::Bytes));
+    assert_eq!(source.auto_allocate_chunk_size(), Some(4));
+
+    let mut next = 1u8;
+    let pull = Closure::<dyn FnMut(sys::ReadableByteStreamController)>::new(
+        move |controller: sys::ReadableByteStreamController| {
+            if next > 6 {
+                controller.close();
+                controller.byob_request().unwrap().respond(0);
+                return;
+            }
+            // Fill the view of the BYOB request in place
+            let request = controller.byob_request().unwrap();
+            let view = request.view().unwrap().unchecked_into::<Uint8Array>();
+            let len = view.length().min(4);
+            for i in 0..len {
+                view.set_index(i, next);
+                next += 1;
+            }
+            request.respond(len);
+        },
+    );
+    js_sys::Reflect::set(&source, &"pull".into(), &pull.into_js_value()).unwrap();
+    let raw = sys::ReadableStream::new_with_underlying_source(
+        &source,
+        QueuingStrategy::new(0.0).as_raw(),
+    )
+    .unwrap();
+    let mut readable = ReadableStream::from_raw(raw);
+
+    let mut reader = readable.get_byob_reader();
+    let mut dst = [0u8; 3];
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 3);
+    assert_eq!(&dst, &[1, 2, 3]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 3);
+    assert_eq!(&dst, &[4, 5, 6]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 0);
+    reader.closed().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_byob_reader_not_byte_stream() {
+    let mut readable = ReadableStream::from_raw(new_noop_readable_stream());
+    assert!(readable.try_get_byob_reader().is_err());
+    assert!(!readable.is_locked());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_byte_stream_from_async_read() {
+    let async_read = Cursor::new(vec![1, 2, 3, 4, 5, 6]);
+    let mut readable = ReadableStream::from_async_read(async_read, 4);
+
+    let mut reader = readable.get_reader();
+    let chunk = reader.read().await.unwrap().unwrap();
+    assert_eq!(Uint8Array::from(chunk).to_vec(), vec![1, 2, 3, 4]);
+    let chunk = reader.read().await.unwrap().unwrap();
+    assert_eq!(Uint8Array::from(chunk).to_vec(), vec![5, 6]);
+    assert_eq!(reader.read().await.unwrap(), None);
+    reader.closed().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_byte_stream_from_async_read_byob_reader() {
+    let async_read = Cursor::new(vec![1, 2, 3, 4, 5, 6]);
+    let mut readable = ReadableStream::from_async_read(async_read, 4);
+
+    let mut reader = readable.get_byob_reader();
+    let mut dst = [0u8; 5];
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 5);
+    assert_eq!(&dst, &[1, 2, 3, 4, 5]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 1);
+    assert_eq!(&dst[..1], &[6]);
+    assert_eq!(reader.read(&mut dst).await.unwrap(), 0);
+    reader.closed().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_async_read() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![
+            Uint8Array::from(&[1, 2, 3][..]).into(),
+            Uint8Array::from(&[4, 5, 6][..]).into(),
+        ]
+        .into_boxed_slice(),
+    ));
+    let mut async_read = readable.into_async_read();
+
+    let mut dst = [0u8; 2];
+    async_read.read_exact(&mut dst).await.unwrap();
+    assert_eq!(&dst, &[1, 2]);
+    let mut rest = Vec::new();
+    async_read.read_to_end(&mut rest).await.unwrap();
+    assert_eq!(rest, vec![3, 4, 5, 6]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_async_read_empty_chunk() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![
+            Uint8Array::from(&[1, 2][..]).into(),
+            Uint8Array::new_with_length(0).into(),
+            Uint8Array::from(&[3][..]).into(),
+        ]
+        .into_boxed_slice(),
+    ));
+    let mut async_read = readable.into_async_read();
+
+    let mut output = Vec::new();
+    async_read.read_to_end(&mut output).await.unwrap();
+    assert_eq!(output, vec![1, 2, 3]);
+}
+
+#[wasm_bindgen_test]
+fn test_readable_stream_into_async_read_impl_unpin() {
+    let readable = ReadableStream::from_raw(new_noop_readable_stream());
+    let async_read: IntoAsyncRead = readable.into_async_read();
+
+    let _ = Pin::new(&async_read); // must be Unpin for this to work
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_async_read_buffered() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![
+            Uint8Array::from(&b"Hello\nwor"[..]).into(),
+            Uint8Array::from(&b"ld!\n"[..]).into(),
+        ]
+        .into_boxed_slice(),
+    ));
+    let mut async_read = readable.into_async_read();
+
+    assert_eq!(async_read.fill_buf().await.unwrap(), b"Hello\nwor");
+    async_read.consume_unpin(2);
+    assert_eq!(async_read.fill_buf().await.unwrap(), b"llo\nwor");
+
+    let mut line = String::new();
+    async_read.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "llo\n");
+    line.clear();
+    async_read.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "world!\n");
+    assert_eq!(async_read.fill_buf().await.unwrap(), b"");
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_async_read_invalid_chunk() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from("Hello")].into_boxed_slice(),
+    ));
+    let mut async_read = readable.into_async_read();
+
+    let mut dst = [0u8; 2];
+    let err = async_read.read(&mut dst).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+struct CountingSource {
+    next: u32,
+    end: u32,
+    cancel_reason: Rc<RefCell<Option<JsValue>>>,
+}
+
+impl UnderlyingSource for CountingSource {
+    fn pull<'a>(
+        &'a mut self,
+        controller: &'a ReadableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            // Enqueue two chunks per pull
+            for _ in 0..2 {
+                if self.next == self.end {
+                    return controller.close();
+                }
+                controller.enqueue(&JsValue::from(self.next))?;
+                self.next += 1;
+            }
+            Ok(())
+        }
+        .boxed_local()
+    }
+
+    fn cancel<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        *self.cancel_reason.borrow_mut() = Some(reason);
+        ready(Ok(())).boxed_local()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_underlying_source() {
+    let readable = ReadableStream::from_underlying_source(CountingSource {
+        next: 0,
+        end: 5,
+        cancel_reason: Default::default(),
+    });
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(chunks, (0..5).map(JsValue::from).collect::<Vec<_>>());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_underlying_source_cancel() {
+    let cancel_reason = Rc::new(RefCell::new(None));
+    let mut readable = ReadableStream::from_underlying_source(CountingSource {
+        next: 0,
+        end: 100,
+        cancel_reason: cancel_reason.clone(),
+    });
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from(0)));
+    reader
+        .cancel_with_reason(&JsValue::from("done"))
+        .await
+        .unwrap();
+    assert_eq!(*cancel_reason.borrow(), Some(JsValue::from("done")));
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_stream_with_strategy() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]),
+        Uint8Array::from(&[4, 5][..]),
+    ];
+    let sizes = Rc::new(RefCell::new(Vec::new()));
+    let strategy = QueuingStrategy::with_size(4.0, {
+        let sizes = sizes.clone();
+        move |chunk| {
+            let size = chunk.unchecked_ref::<Uint8Array>().byte_length() as f64;
+            sizes.borrow_mut().push(size);
+            size
+        }
+    });
+    let stream = iter(chunks.clone()).map(|chunk| Ok(chunk.into()));
+    let readable = ReadableStream::from_stream_with_strategy(stream, strategy);
+
+    let output = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        output,
+        chunks.into_iter().map(JsValue::from).collect::<Vec<_>>()
+    );
+    assert_eq!(*sizes.borrow(), vec![3.0, 2.0]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_raw_parts() {
+    let stream = iter(vec![
+        Ok(JsValue::from("Hello")),
+        Ok(JsValue::from("world!")),
+    ]);
+    let (source, strategy) = into_raw_parts(stream, QueuingStrategy::count(1.0));
+
+    // Construct the stream ourselves, as if it were a subclass or a polyfill
+    let constructor = js_sys::Reflect::get(&js_sys::global(), &"ReadableStream".into()).unwrap();
+    let raw = js_sys::Reflect::construct(
+        constructor.unchecked_ref::<js_sys::Function>(),
+        &js_sys::Array::of2(&source, &strategy),
+    )
+    .unwrap();
+    let readable = ReadableStream::from_raw(raw.unchecked_into());
+
+    let output = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        output,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_stream_fills_queue_up_to_high_water_mark() {
+    let polled = Rc::new(RefCell::new(0));
+    let stream = iter(0..10).map({
+        let polled = polled.clone();
+        move |i| {
+            *polled.borrow_mut() += 1;
+            Ok(JsValue::from(i))
+        }
+    });
+    let readable = ReadableStream::from_stream_with_strategy(stream, QueuingStrategy::count(4.0));
+
+    // Let the stream fill its queue
+    for _ in 0..10 {
+        JsFuture::from(js_sys::Promise::resolve(&JsValue::undefined()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(*polled.borrow(), 4);
+
+    let output = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, (0..10).map(JsValue::from).collect::<Vec<_>>());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_stream_with_strategy_read_to_end() {
+    // Enqueueing while the queue has room re-triggers pull(), which must not pull again
+    // from the stream after it has closed.
+    let stream = iter(0..3).map(|i| Ok(JsValue::from(i)));
+    let mut readable =
+        ReadableStream::from_stream_with_strategy(stream, QueuingStrategy::count(4.0));
+    let mut reader = readable.get_reader();
+    for i in 0..3 {
+        assert_eq!(reader.read().await.unwrap(), Some(JsValue::from(i)));
+    }
+    assert_eq!(reader.read().await.unwrap(), None);
+    reader.closed().await.unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_stream_with_byte_length_strategy() {
+    let strategy = QueuingStrategy::byte_length(16.0);
+    assert_eq!(strategy.high_water_mark(), 16.0);
+
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]),
+        Uint8Array::from(&[4, 5][..]),
+    ];
+    let stream = iter(chunks.clone()).map(|chunk| Ok(chunk.into()));
+    let readable = ReadableStream::from_stream_with_strategy(stream, strategy);
+    let output = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        output,
+        chunks.into_iter().map(JsValue::from).collect::<Vec<_>>()
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_cast() {
+    let mut readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![
+            JsValue::from("Hello"),
+            JsValue::from(42),
+            JsValue::from("world!"),
+        ]
+        .into_boxed_slice(),
+    ))
+    .cast::<JsString>();
+
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsString::from("Hello")));
+    match reader.read().await.unwrap_err() {
+        ReadError::InvalidChunk { chunk, expected } => {
+            assert_eq!(chunk, JsValue::from(42));
+            assert_eq!(expected, "js_sys::JsString");
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+    // An invalid chunk does not end the stream
+    assert_eq!(reader.read().await.unwrap(), Some(JsString::from("world!")));
+    assert_eq!(reader.read().await.unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_cast_into_stream() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from("Hello"), JsValue::from("world!")].into_boxed_slice(),
+    ))
+    .cast::<JsString>();
+
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        chunks,
+        vec![JsString::from("Hello"), JsString::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_bytes_stream() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let output = readable
+        .into_bytes_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, vec![vec![1, 2, 3], vec![4, 5]]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_bytes_stream_invalid_chunk() {
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        vec![JsValue::from("Hello")].into_boxed_slice(),
+    ));
+    let mut stream = readable.into_bytes_stream();
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert!(error.is_instance_of::<js_sys::TypeError>());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_read_to_end() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read_to_end().await.unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_read_exact() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read_exact(2).await.unwrap(), vec![1, 2]);
+    assert_eq!(reader.read_exact(2).await.unwrap(), vec![3, 4]);
+    assert_eq!(
+        reader.read_exact(2).await.unwrap_err(),
+        ReadError::UnexpectedEof
+    );
+    // The remaining bytes are not lost
+    assert_eq!(reader.read_to_end().await.unwrap(), vec![5]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_collect_bytes() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    assert_eq!(readable.collect_bytes().await.unwrap(), vec![1, 2, 3, 4, 5]);
+}
+
+#[cfg(feature = "blob")]
+#[wasm_bindgen_test]
+async fn test_readable_stream_collect_blob() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let blob = readable.collect_blob("text/plain").await.unwrap();
+    assert_eq!(blob.size(), 5.0);
+    assert_eq!(blob.type_(), "text/plain");
+
+    let buffer = JsFuture::from(blob.array_buffer()).await.unwrap();
+    assert_eq!(Uint8Array::new(&buffer).to_vec(), vec![1, 2, 3, 4, 5]);
+}
+
+#[cfg(feature = "blob")]
+#[wasm_bindgen_test]
+async fn test_readable_stream_from_blob() {
+    let parts = js_sys::Array::of1(&Uint8Array::from(&[1, 2, 3][..]));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).unwrap();
+    let readable = ReadableStream::from_blob(&blob);
+    assert_eq!(readable.collect_bytes().await.unwrap(), vec![1, 2, 3]);
+}
+
+#[cfg(feature = "blob")]
+#[wasm_bindgen_test]
+async fn test_blob_reader_seek() {
+    use futures::io::AsyncSeekExt;
+    use std::io::SeekFrom;
+
+    let data = (0..10).collect::<Vec<u8>>();
+    let parts = js_sys::Array::of1(&Uint8Array::from(&data[..]));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).unwrap();
+    let mut reader = BlobReader::new(&blob);
+    assert_eq!(reader.size(), 10);
+
+    let mut buf = [0u8; 3];
+    reader.read_exact(&mut buf).await.unwrap();
+    assert_eq!(buf, [0, 1, 2]);
+
+    // Seek forwards and read until the end
+    assert_eq!(reader.seek(SeekFrom::Start(7)).await.unwrap(), 7);
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).await.unwrap();
+    assert_eq!(rest, vec![7, 8, 9]);
+
+    // Seek backwards, relative to the end
+    assert_eq!(reader.seek(SeekFrom::End(-5)).await.unwrap(), 5);
+    reader.read_exact(&mut buf[..2]).await.unwrap();
+    assert_eq!(buf[..2], [5, 6]);
+    assert_eq!(reader.position(), 7);
+
+    assert!(reader.seek(SeekFrom::Current(-8)).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_stream_buffered() {
+    let stream = iter(0..10).map(|i| Ok(JsValue::from(i)));
+    let readable = ReadableStream::from_stream(stream);
+    let output = readable
+        .into_stream_buffered(4)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, (0..10).map(JsValue::from).collect::<Vec<_>>());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_into_stream_buffered_error() {
+    let stream = iter(vec![Ok(JsValue::from(1)), Err(JsValue::from("oops"))]);
+    let readable = ReadableStream::from_stream(stream);
+    let mut stream = readable.into_stream_buffered(4);
+    assert_eq!(stream.next().await, Some(Ok(JsValue::from(1))));
+    assert_eq!(stream.next().await, Some(Err(JsValue::from("oops"))));
+    assert_eq!(stream.next().await, None);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_read_into() {
+    let chunks = vec![
+        Uint8Array::from(&[1, 2, 3][..]).into(),
+        Uint8Array::from(&[][..]).into(),
+        Uint8Array::from(&[4, 5][..]).into(),
+    ];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let mut reader = readable.get_reader();
+    let mut buf = [0u8; 2];
+    assert_eq!(reader.read_into(&mut buf).await.unwrap(), 2);
+    assert_eq!(buf, [1, 2]);
+    assert_eq!(reader.read_into(&mut buf).await.unwrap(), 1);
+    assert_eq!(buf[..1], [3]);
+    assert_eq!(reader.read_into(&mut buf).await.unwrap(), 2);
+    assert_eq!(buf, [4, 5]);
+    assert_eq!(reader.read_into(&mut buf).await.unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_read_into_then_read() {
+    let chunks = vec![Uint8Array::from(&[1, 2, 3, 4][..]).into()];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()))
+            .cast::<Uint8Array>();
+    let mut reader = readable.get_reader();
+    let mut buf = [0u8; 1];
+    assert_eq!(reader.read_into(&mut buf).await.unwrap(), 1);
+    assert_eq!(buf, [1]);
+    let rest = reader.read().await.unwrap().unwrap();
+    assert_eq!(rest.to_vec(), vec![2, 3, 4]);
+    assert!(reader.read().await.unwrap().is_none());
+}
+
+#[cfg(feature = "bytes")]
+#[wasm_bindgen_test]
+async fn test_readable_stream_read_bytes() {
+    let chunks = vec![Uint8Array::from(&[1, 2, 3][..]).into(), JsValue::from(42)];
+    let mut readable =
+        ReadableStream::from_raw(new_readable_stream_from_array(chunks.into_boxed_slice()));
+    let mut reader = readable.get_reader();
+    assert_eq!(
+        reader.read_bytes().await.unwrap(),
+        Some(bytes::Bytes::from_static(&[1, 2, 3]))
+    );
+    assert!(matches!(
+        reader.read_bytes().await.unwrap_err(),
+        ReadError::InvalidChunk { .. }
+    ));
+    assert_eq!(reader.read_bytes().await.unwrap(), None);
+}
@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::ready;
+use futures::stream::{iter, TryStreamExt};
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::resumable::*;
+use wasm_streams::ReadableStream;
+
+const DATA: &[u8] = b"Hello, world!";
+
+/// Returns a stream of the bytes from `offset`, which errors halfway on the first attempt.
+fn flaky_stream(offset: u64, first: bool) -> ReadableStream<Uint8Array> {
+    let rest = &DATA[offset as usize..];
+    let (head, tail) = rest.split_at(rest.len() / 2);
+    let mut chunks = vec![Ok(JsValue::from(Uint8Array::from(head)))];
+    if first {
+        chunks.push(Err(JsValue::from_str("network error")));
+    } else {
+        chunks.push(Ok(JsValue::from(Uint8Array::from(tail))));
+    }
+    ReadableStream::from_stream(iter(chunks)).cast()
+}
+
+#[wasm_bindgen_test]
+async fn test_resumable_stream() {
+    let offsets = Rc::new(RefCell::new(Vec::new()));
+    let factory = {
+        let offsets = offsets.clone();
+        move |offset| {
+            let first = offsets.borrow().is_empty();
+            offsets.borrow_mut().push(offset);
+            ready(Ok(flaky_stream(offset, first)))
+        }
+    };
+    let mut policy = RetryPolicy::new();
+    policy.initial_delay(Duration::ZERO);
+    let stream = ResumableStream::new(factory, policy);
+
+    let chunks = stream.try_collect::<Vec<_>>().await.unwrap();
+    let bytes = chunks.iter().flat_map(|c| c.to_vec()).collect::<Vec<_>>();
+    assert_eq!(bytes, DATA);
+    assert_eq!(*offsets.borrow(), vec![0, 6]);
+}
+
+#[wasm_bindgen_test]
+async fn test_resumable_stream_gives_up() {
+    let mut policy = RetryPolicy::new();
+    policy
+        .max_retries(2)
+        .initial_delay(Duration::ZERO)
+        .retry_if(|_| true);
+    let attempts = Rc::new(RefCell::new(0));
+    let factory = {
+        let attempts = attempts.clone();
+        move |_| {
+            *attempts.borrow_mut() += 1;
+            ready(Err::<ReadableStream<Uint8Array>, _>(JsValue::from_str(
+                "unreachable",
+            )))
+        }
+    };
+    let mut stream = ResumableStream::new(factory, policy);
+    assert_eq!(
+        stream.try_next().await.unwrap_err(),
+        JsValue::from_str("unreachable")
+    );
+    assert!(stream.try_next().await.unwrap().is_none());
+    assert_eq!(*attempts.borrow(), 3);
+}
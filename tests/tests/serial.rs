@@ -0,0 +1,24 @@
+use wasm_bindgen_test::*;
+
+use wasm_streams::serial::*;
+
+#[wasm_bindgen_test]
+fn test_serial_options() {
+    let mut options = SerialOptions::new(115_200);
+    options
+        .data_bits(7)
+        .parity(Parity::Even)
+        .flow_control(FlowControl::Hardware);
+    let raw = options.into_raw();
+    assert_eq!(raw.baud_rate(), 115_200);
+    assert_eq!(raw.data_bits(), Some(7));
+    assert_eq!(raw.stop_bits(), None);
+    assert_eq!(raw.parity().as_deref(), Some("even"));
+    assert_eq!(raw.flow_control().as_deref(), Some("hardware"));
+}
+
+#[wasm_bindgen_test]
+async fn test_serial_port_request_without_gesture() {
+    // Without support this fails right away, and with support it requires a user gesture.
+    assert!(SerialPort::request().await.is_err());
+}
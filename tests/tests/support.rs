@@ -0,0 +1,38 @@
+use wasm_bindgen_test::*;
+
+use wasm_streams::support::*;
+use wasm_streams::transform::{CompressionStream, DecompressionStream, TextDecoderStream};
+
+#[wasm_bindgen_test]
+fn test_support_streams() {
+    assert!(supports_readable_stream());
+    assert!(supports_writable_stream());
+    assert!(supports_transform_stream());
+    // Cached results stay the same.
+    assert_eq!(
+        supports_readable_byte_streams(),
+        supports_readable_byte_streams()
+    );
+    assert_eq!(supports_byob_reader(), supports_byob_reader());
+    // BYOB readers are only available on readable byte streams.
+    assert!(!supports_byob_reader() || supports_readable_byte_streams());
+}
+
+#[wasm_bindgen_test]
+fn test_support_transform_classes() {
+    assert_eq!(
+        supports_compression_streams(),
+        CompressionStream::is_supported() && DecompressionStream::is_supported()
+    );
+    assert_eq!(
+        supports_text_decoder_stream(),
+        TextDecoderStream::is_supported()
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_support_transferable_streams() {
+    // The detection transfers a stream to a new `MessageChannel`, and is only done once.
+    let supported = supports_transferable_streams();
+    assert_eq!(supports_transferable_streams(), supported);
+}
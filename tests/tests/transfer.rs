@@ -0,0 +1,73 @@
+use futures::stream::TryStreamExt;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+use web_sys::MessageChannel;
+
+use wasm_streams::ReadableStream;
+
+use crate::js::*;
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_post_to() {
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let readable = ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ));
+
+    let channel = MessageChannel::new().unwrap();
+    readable.post_to(&channel.port1());
+    let received = ReadableStream::receive_from(&channel.port2());
+    assert_eq!(
+        received.into_stream().try_collect::<Vec<_>>().await,
+        Ok(chunks)
+    );
+}
+
+#[wasm_bindgen_test]
+fn test_readable_stream_try_post_to_locked() {
+    let raw = new_readable_stream_from_array(vec![JsValue::from("Hello")].into_boxed_slice());
+    let _reader = raw.get_reader().unwrap();
+    let readable = ReadableStream::from_raw(raw);
+
+    let channel = MessageChannel::new().unwrap();
+    assert!(readable.try_post_to(&channel.port1()).is_err());
+}
+
+#[cfg(feature = "worker")]
+#[wasm_bindgen_test]
+async fn test_handle_worker_message() {
+    use js_sys::{Object, Reflect};
+    use wasm_streams::transfer::*;
+    use wasm_streams::TransformStream;
+
+    register_worker_transform("identity", || {
+        TransformStream::from_raw(new_noop_transform_stream())
+    });
+    assert!(!handle_worker_message(&JsValue::from("Hello")));
+
+    // Simulate the message that pipe_through_worker sends to the worker
+    let chunks = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    let input = MessageChannel::new().unwrap();
+    let output = MessageChannel::new().unwrap();
+    let message = Object::new();
+    Reflect::set(
+        &message,
+        &"type".into(),
+        &"wasm-streams:pipe-through".into(),
+    )
+    .unwrap();
+    Reflect::set(&message, &"transform".into(), &"identity".into()).unwrap();
+    Reflect::set(&message, &"input".into(), &input.port2()).unwrap();
+    Reflect::set(&message, &"output".into(), &output.port2()).unwrap();
+    assert!(handle_worker_message(&message));
+
+    ReadableStream::from_raw(new_readable_stream_from_array(
+        chunks.clone().into_boxed_slice(),
+    ))
+    .post_to(&input.port1());
+    let received = ReadableStream::receive_from(&output.port1());
+    assert_eq!(
+        received.into_stream().try_collect::<Vec<_>>().await,
+        Ok(chunks)
+    );
+}
@@ -1,24 +1,31 @@
-use futures::future::join;
+use futures::future::{join, ready, FutureExt, LocalBoxFuture};
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt, TryStreamExt};
+use js_sys::Uint8Array;
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 
+use wasm_streams::readable::ReadError;
 use wasm_streams::transform::*;
+use wasm_streams::{QueuingStrategy, ReadableStream};
 
 use crate::js::*;
 
 #[wasm_bindgen_test]
 async fn test_transform_stream_new() {
-    let transform = TransformStream::from_raw(new_noop_transform_stream());
+    let mut transform = TransformStream::from_raw(new_noop_transform_stream());
+    let mut writable = transform.take_writable().unwrap();
+    let mut readable = transform.take_readable().unwrap();
     join(
         async {
-            let mut writable = transform.writable();
             let mut writer = writable.get_writer();
             writer.write(JsValue::from("Hello")).await.unwrap();
             writer.write(JsValue::from("world!")).await.unwrap();
             writer.close().await.unwrap();
         },
         async {
-            let mut readable = transform.readable();
             let mut reader = readable.get_reader();
             assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
             assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("world!")));
@@ -28,19 +35,101 @@ async fn test_transform_stream_new() {
     .await;
 }
 
+#[wasm_bindgen_test]
+async fn test_transform_stream_into_pair() {
+    let transform = TransformStream::from_raw(new_noop_transform_stream());
+    let (mut readable, mut writable) = transform.into_pair().into_parts();
+    join(
+        async {
+            let mut writer = writable.get_writer();
+            writer.write(JsValue::from("Hello")).await.unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            let mut reader = readable.get_reader();
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
+            assert_eq!(reader.read().await.unwrap(), None);
+        },
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_take_readable_writable() {
+    let mut transform = TransformStream::from_raw(new_noop_transform_stream());
+    let mut writable = transform.take_writable().unwrap();
+    let mut readable = transform.take_readable().unwrap();
+    assert!(transform.is_readable_taken());
+    assert!(transform.is_writable_taken());
+    assert!(transform.take_readable().is_none());
+    assert!(transform.take_writable().is_none());
+    join(
+        async {
+            let mut writer = writable.get_writer();
+            writer.write(JsValue::from("Hello")).await.unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            let mut reader = readable.get_reader();
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
+            assert_eq!(reader.read().await.unwrap(), None);
+        },
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+fn test_transform_stream_taken_side() {
+    let mut transform = TransformStream::from_raw(new_noop_transform_stream());
+    let _writable = transform.take_writable().unwrap();
+
+    // The writable side already has an owner, so it cannot be handed out again
+    let mut readable = ReadableStream::from_raw(new_noop_readable_stream());
+    assert!(readable.try_pipe_through(&transform).is_err());
+    assert!(!readable.is_locked());
+    let (_, transform) = transform.try_into_pair().unwrap_err();
+    assert!(transform.is_writable_taken());
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_pipe_through_pair() {
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(vec![
+        Ok(JsValue::from("Hello")),
+        Ok(JsValue::from("world!")),
+    ]));
+    let pair = TransformStream::from_raw(new_uppercase_transform_stream()).into_pair();
+    let mut output = readable.pipe_through(pair);
+    assert!(readable.is_locked());
+    let mut reader = output.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("HELLO")));
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("WORLD!")));
+    assert_eq!(reader.read().await.unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+fn test_readable_stream_try_pipe_through_pair_locked() {
+    let mut readable = ReadableStream::from_raw(new_noop_readable_stream());
+    let _output = readable.pipe_through(&TransformStream::from_raw(new_noop_transform_stream()));
+    let pair = TransformStream::from_raw(new_noop_transform_stream()).into_pair();
+    // The pair is returned along with the error, so both sides can still be used
+    let (_, pair) = readable.try_pipe_through(pair).unwrap_err();
+    assert!(!pair.readable().is_locked());
+    assert!(!pair.writable().is_locked());
+}
+
 #[wasm_bindgen_test]
 async fn test_transform_stream_new_uppercase() {
-    let transform = TransformStream::from_raw(new_uppercase_transform_stream());
+    let mut transform = TransformStream::from_raw(new_uppercase_transform_stream());
+    let mut writable = transform.take_writable().unwrap();
+    let mut readable = transform.take_readable().unwrap();
     join(
         async {
-            let mut writable = transform.writable();
             let mut writer = writable.get_writer();
             writer.write(JsValue::from("Hello")).await.unwrap();
             writer.write(JsValue::from("world!")).await.unwrap();
             writer.close().await.unwrap();
         },
         async {
-            let mut readable = transform.readable();
             let mut reader = readable.get_reader();
             assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("HELLO")));
             assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("WORLD!")));
@@ -49,3 +138,713 @@ async fn test_transform_stream_new_uppercase() {
     )
     .await;
 }
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_new_with_raw_transformer() {
+    let transformer = sys::Transformer::new();
+    let transform = Closure::<dyn FnMut(JsValue, sys::TransformStreamDefaultController)>::new(
+        |chunk: JsValue, controller: sys::TransformStreamDefaultController| {
+            let chunk = chunk.as_string().unwrap().to_uppercase();
+            controller.enqueue(&chunk.into()).unwrap();
+        },
+    );
+    let flush = Closure::<dyn FnMut(sys::TransformStreamDefaultController)>::new(
+        |controller: sys::TransformStreamDefaultController| {
+            controller.enqueue(&"END".into()).unwrap();
+        },
+    );
+    transformer.set_transform(transform.as_ref().unchecked_ref());
+    transformer.set_flush(flush.as_ref().unchecked_ref());
+    assert!(transformer.transform().is_some());
+    assert!(transformer.start().is_none());
+
+    let strategy = QueuingStrategy::new(1.0);
+    let mut transform = TransformStream::from_raw(
+        sys::TransformStream::new_with_transformer_and_strategies(
+            &transformer,
+            strategy.as_raw(),
+            strategy.as_raw(),
+        )
+        .unwrap(),
+    );
+    let mut readable = transform.take_readable().unwrap();
+    let mut writable = transform.take_writable().unwrap();
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    let mut reader = readable.get_reader();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("HELLO")));
+    writer.close().await.unwrap();
+    assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("END")));
+    assert_eq!(reader.read().await.unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+fn test_transform_stream_new_with_raw_transformer_readable_type() {
+    let transformer = sys::Transformer::new();
+    transformer.set_readable_type(&JsValue::from("bytes"));
+    let strategy = QueuingStrategy::new(1.0);
+    let result = sys::TransformStream::new_with_transformer_and_strategies(
+        &transformer,
+        strategy.as_raw(),
+        strategy.as_raw(),
+    );
+    assert!(result.unwrap_err().is_instance_of::<js_sys::RangeError>());
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_strip_bom_bytes() {
+    let mut transform = strip_bom();
+    let mut writable = transform.take_writable().unwrap();
+    let mut readable = transform.take_readable().unwrap();
+    join(
+        async {
+            let mut writer = writable.get_writer();
+            // BOM is split across the first two chunks
+            writer
+                .write(Uint8Array::from(&[0xEF][..]).into())
+                .await
+                .unwrap();
+            writer
+                .write(Uint8Array::from(&[0xBB, 0xBF, 0x41][..]).into())
+                .await
+                .unwrap();
+            writer
+                .write(Uint8Array::from(&[0xEF, 0xBB, 0xBF][..]).into())
+                .await
+                .unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            let mut reader = readable.get_reader();
+            let chunk = reader.read().await.unwrap().unwrap();
+            assert_eq!(Uint8Array::from(chunk).to_vec(), vec![0x41]);
+            let chunk = reader.read().await.unwrap().unwrap();
+            assert_eq!(Uint8Array::from(chunk).to_vec(), vec![0xEF, 0xBB, 0xBF]);
+            assert_eq!(reader.read().await.unwrap(), None);
+        },
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_strip_bom_text() {
+    let mut transform = strip_bom();
+    let mut writable = transform.take_writable().unwrap();
+    let mut readable = transform.take_readable().unwrap();
+    join(
+        async {
+            let mut writer = writable.get_writer();
+            writer.write(JsValue::from("\u{FEFF}Hello")).await.unwrap();
+            writer.write(JsValue::from("\u{FEFF}world!")).await.unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            let mut reader = readable.get_reader();
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("Hello")));
+            assert_eq!(
+                reader.read().await.unwrap(),
+                Some(JsValue::from("\u{FEFF}world!"))
+            );
+            assert_eq!(reader.read().await.unwrap(), None);
+        },
+    )
+    .await;
+}
+
+struct Repeat {
+    started: bool,
+    count: usize,
+}
+
+impl Transformer for Repeat {
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.started = true;
+        ready(Ok(())).boxed_local()
+    }
+
+    fn transform<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        async move {
+            assert!(self.started);
+            self.count += 1;
+            controller.enqueue(&chunk)?;
+            controller.enqueue(&chunk)?;
+            Ok(())
+        }
+        .boxed_local()
+    }
+
+    fn flush<'a>(
+        &'a mut self,
+        controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(controller.enqueue(&JsValue::from(self.count as u32))).boxed_local()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_from_transformer() {
+    let mut transform = TransformStream::from_transformer(Repeat {
+        started: false,
+        count: 0,
+    });
+    let mut writable = transform.take_writable().unwrap();
+    let mut readable = transform.take_readable().unwrap();
+    join(
+        async {
+            let mut writer = writable.get_writer();
+            writer.write(JsValue::from("a")).await.unwrap();
+            writer.write(JsValue::from("b")).await.unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            let mut reader = readable.get_reader();
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("a")));
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("a")));
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("b")));
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("b")));
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from(2)));
+            assert_eq!(reader.read().await.unwrap(), None);
+        },
+    )
+    .await;
+}
+
+struct Failing;
+
+impl Transformer for Failing {
+    fn transform<'a>(
+        &'a mut self,
+        _chunk: JsValue,
+        _controller: &'a TransformStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        ready(Err(JsValue::from_str("oops"))).boxed_local()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_from_transformer_error() {
+    let mut transform = TransformStream::from_transformer(Failing);
+    let mut writable = transform.take_writable().unwrap();
+    let mut writer = writable.get_writer();
+    assert_eq!(
+        writer
+            .write(JsValue::from("a"))
+            .await
+            .unwrap_err()
+            .into_js(),
+        JsValue::from_str("oops")
+    );
+    let mut readable = transform.take_readable().unwrap();
+    let mut reader = readable.get_reader();
+    assert_eq!(
+        reader.read().await.unwrap_err(),
+        ReadError::Js(JsValue::from_str("oops"))
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_new_identity_with_strategies() {
+    let mut transform = TransformStream::new_identity_with_strategies(
+        QueuingStrategy::count(2.0),
+        QueuingStrategy::count(1.0),
+    );
+    let mut writable = transform.take_writable().unwrap();
+    let mut writer = writable.get_writer();
+    assert_eq!(writer.desired_size(), Some(2.0));
+
+    // The first chunk moves straight to the readable side's queue
+    writer.write(JsValue::from("a")).await.unwrap();
+    assert_eq!(writer.desired_size(), Some(2.0));
+
+    let mut readable = transform.take_readable().unwrap();
+    join(
+        async {
+            writer.write(JsValue::from("b")).await.unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            let mut reader = readable.get_reader();
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("a")));
+            assert_eq!(reader.read().await.unwrap(), Some(JsValue::from("b")));
+            assert_eq!(reader.read().await.unwrap(), None);
+        },
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_compression_round_trip() {
+    if !CompressionStream::is_supported() || !DecompressionStream::is_supported() {
+        return;
+    }
+    let input = b"Hello world! Hello world! Hello world!".to_vec();
+    for &format in &[
+        CompressionFormat::Gzip,
+        CompressionFormat::Deflate,
+        CompressionFormat::DeflateRaw,
+    ] {
+        let compress = CompressionStream::new(format);
+        let decompress = DecompressionStream::new(format);
+        let mut readable = ReadableStream::from_stream(futures::stream::iter(vec![Ok(
+            Uint8Array::from(&input[..]).into(),
+        )]));
+        let output = readable
+            .pipe_through(compress.as_transform_stream())
+            .pipe_through(decompress.as_transform_stream())
+            .collect_bytes()
+            .await
+            .unwrap();
+        assert_eq!(output, input);
+    }
+}
+
+async fn decode_chunks(transform: &TransformStream, chunks: &[&[u8]]) -> String {
+    let chunks = chunks
+        .iter()
+        .map(|chunk| Ok(Uint8Array::from(*chunk).into()))
+        .collect::<Vec<_>>();
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    readable
+        .pipe_through(transform)
+        .into_stream()
+        .map_ok(|chunk| chunk.as_string().unwrap())
+        .try_collect::<String>()
+        .await
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_utf8_decoder() {
+    let chunks: &[&[u8]] = &[
+        &[0xEF, 0xBB],
+        &[0xBF, 0x48, 0xC3],
+        &[0xA9, 0xE2, 0x82],
+        &[0xAC],
+    ];
+    let decoder = Utf8DecoderStream::new();
+    assert_eq!(
+        decode_chunks(decoder.as_transform_stream(), chunks).await,
+        "H\u{E9}\u{20AC}"
+    );
+    if TextDecoderStream::is_supported() {
+        let decoder = TextDecoderStream::new();
+        assert_eq!(
+            decode_chunks(decoder.as_transform_stream(), chunks).await,
+            "H\u{E9}\u{20AC}"
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_utf8_decoder_invalid() {
+    let chunks: &[&[u8]] = &[&[0x48, 0xFF, 0x49], &[0xE2, 0x82]];
+    let decoder = Utf8DecoderStream::new();
+    assert_eq!(
+        decode_chunks(decoder.as_transform_stream(), chunks).await,
+        "H\u{FFFD}I\u{FFFD}"
+    );
+    if TextDecoderStream::is_supported() {
+        let decoder = TextDecoderStream::new();
+        assert_eq!(
+            decode_chunks(decoder.as_transform_stream(), chunks).await,
+            "H\u{FFFD}I\u{FFFD}"
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_lines() {
+    let chunks = vec![Ok(JsValue::from("a\r\nb")), Ok(JsValue::from("c\n\nd"))];
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let output = readable
+        .lines()
+        .into_stream()
+        .map_ok(|chunk| chunk.as_string().unwrap())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, vec!["a", "bc", "", "d"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_readable_stream_try_lines_locked() {
+    let mut readable = ReadableStream::from_stream(futures::stream::pending());
+    let _lines = readable.lines();
+    assert!(readable.try_lines().is_err());
+    assert!(readable.try_inspect(|_| {}).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_split_by_bytes() {
+    let chunks = vec![
+        Ok(Uint8Array::from(&[1, 2, b'-'][..]).into()),
+        Ok(Uint8Array::from(&[b'-', 3, b'-', b'-'][..]).into()),
+    ];
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let output = readable
+        .pipe_through(&split_by(b"--"))
+        .into_bytes_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, vec![vec![1, 2], vec![3]]);
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_rechunk() {
+    let chunks = vec![
+        Ok(Uint8Array::from(&[1, 2][..]).into()),
+        Ok(Uint8Array::from(&[3, 4, 5, 6, 7, 8, 9][..]).into()),
+        Ok(Uint8Array::new_with_length(0).into()),
+        Ok(Uint8Array::from(&[10][..]).into()),
+    ];
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let output = readable
+        .pipe_through(&rechunk(3))
+        .into_bytes_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        output,
+        vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9], vec![10]]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_batch() {
+    let chunks = (1..=5).map(|i| Ok(JsValue::from(i))).collect::<Vec<_>>();
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let output = readable
+        .pipe_through(&batch(2, Duration::from_secs(10)))
+        .into_stream()
+        .map_ok(|batch| {
+            batch
+                .unchecked_into::<js_sys::Array>()
+                .iter()
+                .map(|x| x.as_f64().unwrap() as u32)
+                .collect::<Vec<_>>()
+        })
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(output, vec![vec![1, 2], vec![3, 4], vec![5]]);
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_batch_bytes_flushes_after_delay() {
+    let mut transform = batch_bytes(10, Duration::from_millis(20));
+    let mut writer = transform.take_writable().unwrap().into_sink();
+    let mut output = transform.take_readable().unwrap().into_bytes_stream();
+
+    join(
+        async {
+            writer
+                .send(Uint8Array::from(&[1, 2][..]).into())
+                .await
+                .unwrap();
+            writer
+                .send(Uint8Array::from(&[3][..]).into())
+                .await
+                .unwrap();
+            writer
+                .send(Uint8Array::from(&[4][..]).into())
+                .await
+                .unwrap();
+            writer.close().await.unwrap();
+        },
+        async {
+            // The batch is not full, so it is flushed once the delay has passed.
+            assert_eq!(output.next().await.unwrap().unwrap(), vec![1, 2, 3]);
+            // The remaining chunks are flushed when the stream is closed.
+            assert_eq!(output.next().await.unwrap().unwrap(), vec![4]);
+            assert!(output.next().await.is_none());
+        },
+    )
+    .await;
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_debounce_throttle_sample() {
+    async fn run(transform: TransformStream) -> Vec<JsValue> {
+        let chunks = (1..=3).map(|i| Ok(JsValue::from(i))).collect::<Vec<_>>();
+        let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+        readable
+            .pipe_through(&transform)
+            .into_stream()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+    }
+
+    // All chunks arrive at once, so only the last one passes through
+    let output = run(debounce(Duration::from_millis(50))).await;
+    assert_eq!(output, vec![JsValue::from(3)]);
+
+    // Only the first chunk passes through, the others are within the same window
+    let output = run(throttle(Duration::from_secs(10))).await;
+    assert_eq!(output, vec![JsValue::from(1)]);
+
+    // The stream ends before the first sample, so only the last chunk passes through
+    let output = run(sample(Duration::from_secs(10))).await;
+    assert_eq!(output, vec![JsValue::from(3)]);
+}
+
+/// Runs `f` in a global scope without `setTimeout()`, like a worklet.
+fn without_set_timeout<R>(f: impl FnOnce() -> R) -> R {
+    let global = js_sys::global();
+    let key = JsValue::from("setTimeout");
+    let set_timeout = js_sys::Reflect::get(&global, &key).unwrap();
+    js_sys::Reflect::set(&global, &key, &JsValue::UNDEFINED).unwrap();
+    let result = f();
+    js_sys::Reflect::set(&global, &key, &set_timeout).unwrap();
+    result
+}
+
+#[wasm_bindgen_test]
+async fn test_timers_without_set_timeout() {
+    // A time-based transform errors instead of panicking
+    let transform = without_set_timeout(|| debounce(Duration::from_millis(20)));
+    let mut readable = ReadableStream::from_stream(futures::stream::pending());
+    let mut output = readable.pipe_through(&transform);
+    let mut reader = output.get_reader();
+    assert!(matches!(reader.read().await, Err(ReadError::Js(_))));
+
+    // A time-based stream adapter cannot be created
+    let duration = Duration::from_millis(20);
+    let stream = || futures::stream::pending::<u32>();
+    assert!(without_set_timeout(|| Debounce::new(stream(), duration)).is_err());
+    assert!(without_set_timeout(|| Throttle::new(stream(), duration)).is_err());
+    assert!(without_set_timeout(|| Sample::new(stream(), duration)).is_err());
+
+    // A read with a timeout fails right away
+    let mut readable = ReadableStream::from_stream(futures::stream::pending());
+    let mut reader = readable.get_reader();
+    let result = without_set_timeout(|| {
+        reader
+            .read_timeout(Duration::from_millis(20))
+            .now_or_never()
+            .unwrap()
+    });
+    assert!(matches!(result, Err(ReadError::Js(_))));
+}
+
+#[wasm_bindgen_test]
+async fn test_debounce_stream_adapter() {
+    let (sender, receiver) = futures::channel::mpsc::unbounded::<u32>();
+    let mut debounced = Debounce::new(receiver, Duration::from_millis(20)).unwrap();
+
+    sender.unbounded_send(1).unwrap();
+    sender.unbounded_send(2).unwrap();
+    assert_eq!(debounced.next().await, Some(2));
+
+    sender.unbounded_send(3).unwrap();
+    sender.close_channel();
+    assert_eq!(debounced.next().await, Some(3));
+    assert_eq!(debounced.next().await, None);
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_rate_limit() {
+    // 1000 bytes per second, with an initial burst of 100 bytes
+    let mut options = RateLimitOptions::new(1000.0);
+    options.burst(100);
+
+    let chunks = (0..3)
+        .map(|_| Ok(Uint8Array::new_with_length(100).into()))
+        .collect::<Vec<_>>();
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let start = js_sys::Date::now();
+    let output = readable
+        .pipe_through(&rate_limit(&options))
+        .into_bytes_stream()
+        .try_concat()
+        .await
+        .unwrap();
+    let elapsed = js_sys::Date::now() - start;
+
+    assert_eq!(output.len(), 300);
+    // The first chunk passes immediately, the other two take 100ms each
+    assert!(elapsed >= 190.0, "elapsed: {}ms", elapsed);
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_length_delimited_round_trip() {
+    let mut options = length_delimited::FramingOptions::new();
+    options.length_field_length(2).little_endian();
+
+    let frames = vec![
+        Ok(Uint8Array::from(&[1, 2, 3][..]).into()),
+        Ok(Uint8Array::new_with_length(0).into()),
+        Ok(Uint8Array::from(&[4][..]).into()),
+    ];
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(frames));
+    let encoded = readable
+        .pipe_through(&length_delimited::encoder(&options))
+        .into_bytes_stream()
+        .try_concat()
+        .await
+        .unwrap();
+    assert_eq!(encoded, vec![3, 0, 1, 2, 3, 0, 0, 1, 0, 4]);
+
+    // Split the encoded bytes at arbitrary positions
+    let chunks = encoded
+        .chunks(3)
+        .map(|chunk| Ok(Uint8Array::from(chunk).into()))
+        .collect::<Vec<_>>();
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let decoded = readable
+        .pipe_through(&length_delimited::decoder(&options))
+        .into_bytes_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(decoded, vec![vec![1, 2, 3], vec![], vec![4]]);
+}
+
+#[wasm_bindgen_test]
+async fn test_transform_stream_length_delimited_max_frame_length() {
+    let mut options = length_delimited::FramingOptions::new();
+    options.max_frame_length(2);
+
+    let chunks = vec![Ok(Uint8Array::from(&[0, 0, 0, 3, 1, 2, 3][..]).into())];
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let mut output = readable
+        .pipe_through(&length_delimited::decoder(&options))
+        .into_bytes_stream();
+    let error = output.try_next().await.unwrap_err();
+    assert!(error.is_instance_of::<js_sys::RangeError>());
+}
+
+#[cfg(feature = "digest")]
+#[wasm_bindgen_test]
+async fn test_transform_stream_digest() {
+    use sha2::{Digest, Sha256};
+
+    let chunks = vec![
+        Ok(Uint8Array::from(&b"Hello, "[..]).into()),
+        Ok(Uint8Array::from(&b"world!"[..]).into()),
+    ];
+    let (transform, digest) = digest::<Sha256>();
+    assert_eq!(digest.digest(), None);
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let output = readable
+        .pipe_through(&transform)
+        .into_bytes_stream()
+        .try_concat()
+        .await
+        .unwrap();
+    assert_eq!(output, b"Hello, world!");
+    assert_eq!(digest.digest().unwrap(), Sha256::digest(b"Hello, world!"));
+}
+
+#[cfg(feature = "digest")]
+#[wasm_bindgen_test]
+async fn test_transform_stream_digest_error() {
+    let chunks = vec![
+        Ok(Uint8Array::from(&b"Hello"[..]).into()),
+        Err(JsValue::from_str("oops")),
+    ];
+    let (transform, digest) = digest::<sha2::Sha256>();
+    let mut readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let result = readable
+        .pipe_through(&transform)
+        .into_bytes_stream()
+        .try_concat()
+        .await;
+    assert_eq!(result.unwrap_err(), JsValue::from_str("oops"));
+    assert_eq!(digest.digest(), None);
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+async fn test_transform_stream_json_lines_round_trip() {
+    let points = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+    let encoded = json_lines_encoder(futures::stream::iter(
+        vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+            .into_iter()
+            .map(Ok),
+    ));
+    let decoded = json_lines_decoder::<Point>(encoded.cast())
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(decoded, points);
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+async fn test_transform_stream_json_lines_decoder_text() {
+    use futures::StreamExt;
+
+    let chunks = vec![
+        Ok(JsValue::from("{\"x\":1,\"y\":2}\r\n\n{\"x\"")),
+        Ok(JsValue::from(":3,\"y\":4}\nnot json\n")),
+    ];
+    let readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let mut decoded = json_lines_decoder::<Point>(readable);
+    assert_eq!(decoded.next().await, Some(Ok(Point { x: 1, y: 2 })));
+    assert_eq!(decoded.next().await, Some(Ok(Point { x: 3, y: 4 })));
+    assert!(decoded.next().await.unwrap().is_err());
+    assert_eq!(decoded.next().await, None);
+}
+
+#[wasm_bindgen_test]
+async fn test_sse_stream() {
+    use futures::StreamExt;
+
+    let chunks = vec![
+        Ok(Uint8Array::from(&b"id: 1\r\ndata: a\r\nda"[..]).into()),
+        Ok(Uint8Array::from(&b"ta: b\n: comment\nevent: x\nretry: 5\n\n"[..]).into()),
+        Ok(Uint8Array::from(&b"data:\xE2\x82"[..]).into()),
+        Ok(Uint8Array::from(&b"\xAC\n\ndata: incomplete\n"[..]).into()),
+    ];
+    let readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let mut events = SseStream::new(readable);
+    assert_eq!(
+        events.next().await,
+        Some(Ok(SseEvent {
+            id: Some("1".to_owned()),
+            event: "x".to_owned(),
+            data: "a\nb".to_owned(),
+            retry: Some(5),
+        }))
+    );
+    assert_eq!(
+        events.next().await,
+        Some(Ok(SseEvent {
+            id: Some("1".to_owned()),
+            event: "message".to_owned(),
+            data: "\u{20AC}".to_owned(),
+            retry: None,
+        }))
+    );
+    assert_eq!(events.next().await, None);
+}
+
+#[wasm_bindgen_test]
+async fn test_sse_stream_retry_without_data() {
+    use futures::StreamExt;
+
+    let chunks = vec![Ok(JsValue::from("retry: 1000\n\n"))];
+    let readable = ReadableStream::from_stream(futures::stream::iter(chunks));
+    let mut events = SseStream::new(readable);
+    assert_eq!(events.reconnection_time(), None);
+    // The block has no data, so no event is yielded, but the reconnection time is updated
+    assert_eq!(events.next().await, None);
+    assert_eq!(events.reconnection_time(), Some(1000));
+}
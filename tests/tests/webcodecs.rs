@@ -0,0 +1,44 @@
+use futures::stream::iter;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::webcodecs::*;
+use wasm_streams::ReadableStream;
+
+#[wasm_bindgen_test]
+async fn test_video_frame_stream_rejects_other_chunks() {
+    let readable = ReadableStream::from_stream(iter(vec![Ok(JsValue::from("not a frame"))]));
+    let mut frames: VideoFrameStream = readable.cast();
+    let mut reader = frames.get_reader();
+    assert!(reader.read().await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_track_from_video_frames() {
+    if !sys::MediaStreamTrackGenerator::is_supported() {
+        assert!(track_from_video_frames().is_err());
+        return;
+    }
+    let (track, _sink) = track_from_video_frames().unwrap();
+    assert_eq!(track.kind(), "video");
+
+    // The generated track is a video track, so it cannot be processed as audio
+    assert!(audio_data_from_track(&track).is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_track_generator_and_processor() {
+    if !sys::MediaStreamTrackGenerator::is_supported()
+        || !sys::MediaStreamTrackProcessor::is_supported()
+    {
+        return;
+    }
+    let generator = TrackGenerator::video().unwrap();
+    let track = generator.track();
+    assert_eq!(track.kind(), "video");
+    assert!(!generator.writable().is_locked());
+
+    let processor = TrackProcessor::video_with_max_buffer_size(&track, 2).unwrap();
+    assert!(!processor.readable().is_locked());
+    assert!(TrackProcessor::audio(&track).is_err());
+}
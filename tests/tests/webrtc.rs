@@ -0,0 +1,31 @@
+use js_sys::Uint8Array;
+use wasm_bindgen_test::*;
+
+use wasm_streams::webrtc::*;
+
+#[wasm_bindgen_test]
+fn test_duplex_from_data_channel_write_buffer() {
+    let connection = web_sys::RtcPeerConnection::new().unwrap();
+    let channel = connection.create_data_channel("test");
+    let _duplex = duplex_from_data_channel(&channel, DataChannelOptions::new().write_buffer(4096));
+
+    // Writes wait for the buffered amount to drop to the write buffer,
+    // so the channel must signal exactly that
+    assert_eq!(channel.buffered_amount_low_threshold(), 4096);
+}
+
+#[wasm_bindgen_test]
+async fn test_duplex_from_data_channel_closed() {
+    let connection = web_sys::RtcPeerConnection::new().unwrap();
+    let channel = connection.create_data_channel("test");
+    let duplex = duplex_from_data_channel(&channel, &DataChannelOptions::new());
+    let (mut readable, mut writable) = duplex.into_parts();
+
+    // The channel closes before it opens, so the readable stream closes
+    channel.close();
+    let mut reader = readable.get_reader();
+    assert!(reader.read().await.unwrap().is_none());
+    // and writes fail
+    let mut writer = writable.get_writer();
+    assert!(writer.write(Uint8Array::new_with_length(1)).await.is_err());
+}
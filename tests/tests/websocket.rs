@@ -0,0 +1,46 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+use wasm_streams::websocket::*;
+
+#[wasm_bindgen_test]
+fn test_websocket_stream_options() {
+    let mut options = WebSocketStreamOptions::new();
+    options.protocols(&["chat", "superchat"]);
+    let raw = options.into_raw();
+    assert_eq!(raw.protocols().length(), 2);
+    assert_eq!(raw.protocols().get(0).as_string().as_deref(), Some("chat"));
+    assert!(raw.signal().is_none());
+}
+
+#[wasm_bindgen_test]
+fn test_websocket_stream_unsupported_or_invalid_url() {
+    // Without support, this fails with a TypeError; with support, the URL is rejected.
+    assert!(WebSocketStream::new("not a url").is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_duplex_from_web_socket_connection_refused() {
+    let socket = web_sys::WebSocket::new("ws://127.0.0.1:1/").unwrap();
+    let duplex = duplex_from_web_socket(&socket, &WebSocketAdapterOptions::new());
+    let (mut readable, mut writable) = duplex.into_parts();
+
+    // The connection never opens, so the readable stream errors
+    let mut reader = readable.get_reader();
+    assert!(reader.read().await.is_err());
+    // and writes fail
+    let mut writer = writable.get_writer();
+    assert!(writer.write(JsValue::from("Hello")).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_duplex_from_web_socket_already_closing() {
+    let socket = web_sys::WebSocket::new("ws://127.0.0.1:1/").unwrap();
+    socket.close().unwrap();
+    let duplex = duplex_from_web_socket(&socket, &WebSocketAdapterOptions::new());
+    let (_readable, mut writable) = duplex.into_parts();
+
+    // The socket was closing before it was wrapped, so writes fail instead of hanging
+    let mut writer = writable.get_writer();
+    assert!(writer.write(JsValue::from("Hello")).await.is_err());
+}
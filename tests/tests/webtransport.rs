@@ -0,0 +1,19 @@
+use wasm_bindgen_test::*;
+
+use wasm_streams::webtransport::*;
+
+#[wasm_bindgen_test]
+fn test_webtransport_invalid_url() {
+    // Without support, this fails with a TypeError; with support, the URL is rejected.
+    assert!(WebTransport::new("not a url").is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_webtransport_connection_refused() {
+    if !WebTransport::is_supported() {
+        return;
+    }
+    let transport = WebTransport::new("https://127.0.0.1:1/").unwrap();
+    assert!(transport.ready().await.is_err());
+    assert!(transport.closed().await.is_err());
+}
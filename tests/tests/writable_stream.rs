@@ -1,12 +1,20 @@
+use std::cell::RefCell;
 use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
 
 use futures::channel::*;
+use futures::future::{ready, FutureExt, LocalBoxFuture};
+use futures::io::AsyncWriteExt;
 use futures::stream::iter;
 use futures::{SinkExt, StreamExt};
+use js_sys::{JsString, Uint8Array};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 
 use wasm_streams::writable::*;
+use wasm_streams::{QueuingStrategy, StreamError};
 
 use crate::js::*;
 
@@ -40,6 +48,116 @@ async fn test_writable_stream_into_sink() {
     );
 }
 
+#[wasm_bindgen_test]
+async fn test_writable_stream_drop_policy() {
+    let recording_stream = RecordingWritableStream::new();
+    let mut writable = WritableStream::from_raw(recording_stream.stream());
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer.set_drop_policy(WriterDropPolicy::AbortThenRelease);
+    drop(writer);
+
+    assert!(!writable.is_locked());
+    let writer = writable.get_writer();
+    assert!(writer.closed().await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_sink_close_on_drop() {
+    let recording_stream = RecordingWritableStream::new();
+    let mut writable = WritableStream::from_raw(recording_stream.stream());
+
+    let mut sink = writable.get_writer().into_sink().close_on_drop();
+    sink.send(JsValue::from("Hello")).await.unwrap();
+    drop(sink);
+
+    let writer = writable.get_writer();
+    writer.closed().await.unwrap();
+    assert_eq!(recording_stream.events(), vec!["write", "Hello", "close"]);
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_sink_abort_on_drop() {
+    let recording_stream = RecordingWritableStream::new();
+    let mut writable = WritableStream::from_raw(recording_stream.stream());
+
+    let mut sink = writable
+        .get_writer()
+        .into_sink()
+        .abort_on_drop(JsValue::from("dropped"));
+    sink.send(JsValue::from("Hello")).await.unwrap();
+    drop(sink);
+
+    let writer = writable.get_writer();
+    assert_eq!(
+        writer.closed().await.unwrap_err().into_js(),
+        JsValue::from("dropped")
+    );
+    assert_eq!(
+        recording_stream.events(),
+        vec!["write", "Hello", "abort", "dropped"]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_by_ref() {
+    let recording_stream = RecordingWritableStream::new();
+    let mut writable = WritableStream::from_raw(recording_stream.stream());
+
+    (&mut writable).send(JsValue::from("Hello")).await.unwrap();
+    // The lock is released once all writes have completed
+    assert!(!writable.is_locked());
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+    assert_eq!(
+        recording_stream.events(),
+        vec!["write", "Hello", "write", "world!", "close"]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_by_ref_locked() {
+    let mut writable = WritableStream::from_raw(new_noop_writable_stream());
+    let raw = writable.as_raw().clone();
+
+    // Without flushing, the writer stays locked until the stream is dropped
+    (&mut writable).feed(JsValue::from("Hello")).await.unwrap();
+    assert!(raw.is_locked());
+    let mut other = WritableStream::from_raw(raw.clone());
+    let error = (&mut other)
+        .send(JsValue::from("world!"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, StreamError::TypeError(_)));
+
+    drop(writable);
+    assert!(!raw.is_locked());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_by_ref_sink_typed() {
+    let recording_stream = RecordingWritableStream::new();
+    let mut writable = WritableStream::from_raw(recording_stream.stream()).cast::<JsString>();
+
+    writable
+        .by_ref_sink()
+        .send(JsString::from("Hello"))
+        .await
+        .unwrap();
+    assert!(!writable.is_locked());
+
+    let mut writer = writable.get_writer();
+    writer.write(JsString::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+    assert_eq!(
+        recording_stream.events(),
+        vec!["write", "Hello", "write", "world!", "close"]
+    );
+}
+
 #[wasm_bindgen_test]
 fn test_writable_stream_into_sink_impl_unpin() {
     let writable = WritableStream::from_raw(new_noop_writable_stream());
@@ -134,3 +252,484 @@ async fn test_writable_stream_multiple_writers() {
         vec!["write", "Hello", "write", "world!", "close"]
     );
 }
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_switchable() {
+    let (first_sink, first_stream) = mpsc::unbounded::<JsValue>();
+    let first_sink = first_sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let (second_sink, second_stream) = mpsc::unbounded::<JsValue>();
+    let second_sink = second_sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+
+    let switchable = SwitchableWritableStream::new(first_sink);
+    let mut writable = switchable.writable();
+    let mut writer = writable.get_writer();
+
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    let mut previous = switchable.swap(second_sink).await.unwrap();
+    writer.write(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+
+    // The previous sink is not closed by the swap
+    previous.close().await.unwrap();
+    assert_eq!(
+        first_stream.collect::<Vec<_>>().await,
+        vec![JsValue::from("Hello")]
+    );
+    assert_eq!(
+        second_stream.collect::<Vec<_>>().await,
+        vec![JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_async_write() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink(sink);
+
+    let mut async_write = writable.into_async_write();
+    async_write.write_all(&[1, 2, 3]).await.unwrap();
+    async_write.write_all(&[4, 5]).await.unwrap();
+    async_write.close().await.unwrap();
+
+    let output = stream
+        .map(|chunk| Uint8Array::from(chunk).to_vec())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(output, vec![vec![1, 2, 3], vec![4, 5]]);
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_copy_from_async_read() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink(sink);
+
+    let mut async_write = writable.into_async_write();
+    let async_read = futures::io::Cursor::new(vec![1, 2, 3, 4, 5]);
+    futures::io::copy(async_read, &mut async_write)
+        .await
+        .unwrap();
+    async_write.close().await.unwrap();
+
+    let output = stream
+        .map(|chunk| Uint8Array::from(chunk).to_vec())
+        .collect::<Vec<_>>()
+        .await
+        .concat();
+    assert_eq!(output, vec![1, 2, 3, 4, 5]);
+}
+
+#[derive(Default)]
+struct RecordingSink {
+    events: Rc<RefCell<Vec<String>>>,
+}
+
+impl UnderlyingSink for RecordingSink {
+    fn start<'a>(
+        &'a mut self,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.events.borrow_mut().push("start".into());
+        ready(Ok(())).boxed_local()
+    }
+
+    fn write<'a>(
+        &'a mut self,
+        chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.events
+            .borrow_mut()
+            .push(format!("write {}", chunk.as_string().unwrap()));
+        ready(Ok(())).boxed_local()
+    }
+
+    fn close<'a>(&'a mut self) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.events.borrow_mut().push("close".into());
+        ready(Ok(())).boxed_local()
+    }
+
+    fn abort<'a>(&'a mut self, reason: JsValue) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        self.events
+            .borrow_mut()
+            .push(format!("abort {}", reason.as_string().unwrap()));
+        ready(Ok(())).boxed_local()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_from_underlying_sink() {
+    let sink = RecordingSink::default();
+    let events = sink.events.clone();
+    let mut writable = WritableStream::from_underlying_sink(sink);
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer.write(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+
+    assert_eq!(
+        *events.borrow(),
+        vec!["start", "write Hello", "write world!", "close"]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_from_underlying_sink_abort() {
+    let sink = RecordingSink::default();
+    let events = sink.events.clone();
+    let mut writable = WritableStream::from_underlying_sink(sink);
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer
+        .abort_with_reason(&JsValue::from("oops"))
+        .await
+        .unwrap();
+
+    assert_eq!(*events.borrow(), vec!["start", "write Hello", "abort oops"]);
+}
+
+/// A sink whose writes never complete.
+struct PendingSink;
+
+impl UnderlyingSink for PendingSink {
+    fn write<'a>(
+        &'a mut self,
+        _chunk: JsValue,
+        _controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        futures::future::pending().boxed_local()
+    }
+}
+
+/// A sink whose writes complete only once the stream is aborted.
+#[derive(Default)]
+struct AbortableSink {
+    aborted: Rc<RefCell<Option<JsValue>>>,
+}
+
+impl UnderlyingSink for AbortableSink {
+    fn write<'a>(
+        &'a mut self,
+        _chunk: JsValue,
+        controller: &'a WritableStreamDefaultController,
+    ) -> LocalBoxFuture<'a, Result<(), JsValue>> {
+        let signal = controller.signal().unwrap();
+        async move {
+            let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+                signal
+                    .add_event_listener_with_callback("abort", &resolve)
+                    .unwrap();
+            });
+            wasm_bindgen_futures::JsFuture::from(promise).await?;
+            *self.aborted.borrow_mut() = Some(signal.reason());
+            Err(signal.reason())
+        }
+        .boxed_local()
+    }
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_controller_signal() {
+    let sink = AbortableSink::default();
+    let aborted = sink.aborted.clone();
+    let mut writable = WritableStream::from_underlying_sink(sink);
+
+    let mut writer = writable.get_writer();
+    let write = writer.as_raw().write(JsValue::from("Hello"));
+    // The stream waits for the pending write to stop before it is aborted
+    writer
+        .abort_with_reason(&JsValue::from("oops"))
+        .await
+        .unwrap();
+    assert_eq!(*aborted.borrow(), Some(JsValue::from("oops")));
+    assert!(wasm_bindgen_futures::JsFuture::from(write).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_write_timeout() {
+    let mut writable =
+        WritableStream::from_underlying_sink(PendingSink).write_timeout(Duration::from_millis(20));
+
+    let mut writer = writable.get_writer();
+    let error = writer.write(JsValue::from("Hello")).await.unwrap_err();
+    assert!(matches!(error, wasm_streams::StreamError::Timeout(_)));
+    // The returned stream is errored as well
+    assert!(writer.closed().await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_writer_write_with_signal() {
+    let mut writable = WritableStream::from_underlying_sink(PendingSink);
+    let mut writer = writable.get_writer();
+
+    let controller = web_sys::AbortController::new().unwrap();
+    let signal = controller.signal();
+    let mut write = writer
+        .write_with_signal(JsValue::from("Hello"), &signal)
+        .boxed_local();
+    assert!(futures::poll!(&mut write).is_pending());
+    controller.abort();
+    let error = write.await.unwrap_err();
+    assert!(error.is_aborted());
+
+    assert!(writer
+        .closed_with_signal(&signal)
+        .await
+        .unwrap_err()
+        .is_aborted());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_from_sink_with_abort_handler() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let reason = Rc::new(RefCell::new(None));
+    let mut writable = WritableStream::from_sink_with_abort_handler(sink, {
+        let reason = reason.clone();
+        move |r| *reason.borrow_mut() = Some(r)
+    });
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer
+        .abort_with_reason(&JsValue::from("oops"))
+        .await
+        .unwrap();
+
+    assert_eq!(*reason.borrow(), Some(JsValue::from("oops")));
+    // The sink was dropped without being closed
+    let output = stream.collect::<Vec<_>>().await;
+    assert_eq!(output, vec![JsValue::from("Hello")]);
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_closed_reason() {
+    let mut writable = WritableStream::from_underlying_sink(RecordingSink::default());
+    assert_eq!(writable.closed_reason().await, None);
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer
+        .abort_with_reason(&JsValue::from("oops"))
+        .await
+        .unwrap();
+    drop(writer);
+
+    // The reason is kept after the writer was dropped
+    assert!(!writable.is_locked());
+    assert_eq!(writable.closed_reason().await, Some(JsValue::from("oops")));
+    assert_eq!(writable.closed_reason().await, Some(JsValue::from("oops")));
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_closed_reason_closed() {
+    let mut writable = WritableStream::from_underlying_sink(RecordingSink::default());
+    let mut writer = writable.get_writer();
+    writer.close().await.unwrap();
+    writer.set_drop_policy(WriterDropPolicy::Leak);
+    drop(writer);
+    // The stream is still locked by the leaked writer
+    let mut locked = WritableStream::from_raw(writable.as_raw().clone());
+    assert!(locked.try_closed_reason().await.is_err());
+
+    let mut writable = WritableStream::from_underlying_sink(RecordingSink::default());
+    writable.get_writer().close().await.unwrap();
+    assert_eq!(writable.try_closed_reason().await.unwrap(), None);
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_from_sink_with_strategy() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let mut writable = WritableStream::from_sink_with_strategy(sink, QueuingStrategy::count(3.0));
+
+    let mut writer = writable.get_writer();
+    assert_eq!(writer.desired_size(), Some(3.0));
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer.write(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+
+    let output = stream.collect::<Vec<_>>().await;
+    assert_eq!(
+        output,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_sink_does_not_wait_for_writes() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink_with_strategy(sink, QueuingStrategy::count(3.0));
+
+    let mut sink = writable.into_sink();
+    // The queue has room for three chunks, so these are accepted immediately
+    for i in 0..3 {
+        assert_eq!(sink.feed(JsValue::from(i)).now_or_never(), Some(Ok(())));
+    }
+    // The fourth chunk must wait for the queue to drain
+    assert_eq!(sink.feed(JsValue::from(3)).await, Ok(()));
+    assert_eq!(sink.close().await, Ok(()));
+
+    let output = stream.collect::<Vec<_>>().await;
+    assert_eq!(output, (0..4).map(JsValue::from).collect::<Vec<_>>());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_sink_desired_size() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink_with_strategy(sink, QueuingStrategy::count(2.0));
+
+    let mut sink = writable.into_sink();
+    assert_eq!(sink.desired_size(), Some(2.0));
+    assert!(!sink.has_backpressure());
+    sink.feed(JsValue::from("Hello")).await.unwrap();
+    sink.feed(JsValue::from("world!")).await.unwrap();
+    assert_eq!(sink.desired_size(), Some(0.0));
+    assert!(sink.has_backpressure());
+
+    sink.close().await.unwrap();
+    assert_eq!(sink.desired_size(), None);
+    let output = stream.collect::<Vec<_>>().await;
+    assert_eq!(
+        output,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_sink_flush_error() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    drop(stream);
+    let sink = sink.sink_map_err(|_| JsValue::from_str("oops"));
+    let writable = WritableStream::from_sink_with_strategy(sink, QueuingStrategy::count(3.0));
+
+    let mut sink = writable.into_sink();
+    assert_eq!(sink.feed(JsValue::from("Hello")).await, Ok(()));
+    assert_eq!(sink.flush().await, Err(JsValue::from_str("oops")));
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_writer_feed() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let mut writable = WritableStream::from_sink(sink);
+
+    let mut writer = writable.get_writer();
+    writer.feed(JsValue::from("Hello")).await.unwrap();
+    writer.feed(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+
+    let output = stream.collect::<Vec<_>>().await;
+    assert_eq!(
+        output,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_writer_write_all() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let mut writable = WritableStream::from_sink(sink).cast::<Uint8Array>();
+
+    let mut writer = writable.get_writer();
+    writer.write_all(&[1, 2, 3, 4, 5], 2).await.unwrap();
+    writer.close().await.unwrap();
+
+    let output = stream
+        .map(|chunk| chunk.unchecked_into::<Uint8Array>().to_vec())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(output, vec![vec![1, 2], vec![3, 4], vec![5]]);
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_inspect_writes() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut writable = WritableStream::from_sink(sink).inspect_writes({
+        let seen = seen.clone();
+        move |chunk| seen.borrow_mut().push(chunk.clone())
+    });
+
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer.write(JsValue::from("world!")).await.unwrap();
+    writer.close().await.unwrap();
+
+    let output = stream.collect::<Vec<_>>().await;
+    let expected = vec![JsValue::from("Hello"), JsValue::from("world!")];
+    assert_eq!(output, expected);
+    assert_eq!(*seen.borrow(), expected);
+}
+
+#[wasm_bindgen_test]
+fn test_writable_stream_try_inspect_writes_locked() {
+    let mut writable = WritableStream::from_raw(new_noop_writable_stream());
+    let mut writer = writable.get_writer();
+    writer.set_drop_policy(WriterDropPolicy::Leak);
+    drop(writer);
+    let (_, writable) = writable.try_inspect_writes(|_| {}).unwrap_err();
+    assert!(writable.is_locked());
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_cast() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let mut writable = WritableStream::from_sink(sink).cast::<Uint8Array>();
+
+    let mut writer = writable.get_writer();
+    writer.write(Uint8Array::from(&[1, 2][..])).await.unwrap();
+    writer.close().await.unwrap();
+    drop(writer);
+
+    let output = stream.collect::<Vec<_>>().await;
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0].unchecked_ref::<Uint8Array>().to_vec(), vec![1, 2]);
+}
+
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_bytes_sink() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink(sink);
+
+    let mut sink = writable.into_bytes_sink();
+    sink.send(vec![1, 2, 3]).await.unwrap();
+    sink.send(&[4, 5][..]).await.unwrap();
+    SinkExt::<Vec<u8>>::close(&mut sink).await.unwrap();
+
+    let output = stream
+        .map(|chunk| chunk.unchecked_into::<Uint8Array>().to_vec())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(output, vec![vec![1, 2, 3], vec![4, 5]]);
+}
+
+#[cfg(feature = "bytes")]
+#[wasm_bindgen_test]
+async fn test_writable_stream_into_bytes_sink_bytes() {
+    let (sink, stream) = mpsc::unbounded::<JsValue>();
+    let sink = sink.sink_map_err(|_| JsValue::from_str("cannot happen"));
+    let writable = WritableStream::from_sink(sink);
+
+    let mut sink = writable.into_bytes_sink();
+    sink.send(bytes::Bytes::from_static(&[1, 2, 3]))
+        .await
+        .unwrap();
+    sink.send(bytes::BytesMut::from(&[4, 5][..])).await.unwrap();
+    SinkExt::<bytes::Bytes>::close(&mut sink).await.unwrap();
+
+    let output = stream
+        .map(|chunk| chunk.unchecked_into::<Uint8Array>().to_vec())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(output, vec![vec![1, 2, 3], vec![4, 5]]);
+}
@@ -0,0 +1,96 @@
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+
+// These tests check that streams do not depend on `window`.
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::stream::{iter, StreamExt, TryStreamExt};
+use futures::SinkExt;
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+use wasm_streams::transform::batch;
+use wasm_streams::{ReadableStream, WritableStream};
+
+#[wasm_bindgen_test]
+fn test_worker_global_scope() {
+    let global = js_sys::global();
+    assert!(global.dyn_ref::<web_sys::Window>().is_none());
+    assert!(wasm_streams::support::supports_readable_stream());
+    assert!(wasm_streams::support::supports_writable_stream());
+    assert!(wasm_streams::support::supports_transform_stream());
+}
+
+#[wasm_bindgen_test]
+async fn test_worker_readable_stream() {
+    let readable = ReadableStream::from_stream(iter(vec![
+        Ok(JsValue::from("Hello")),
+        Ok(JsValue::from("world!")),
+    ]));
+    let chunks = readable
+        .into_stream()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        chunks,
+        vec![JsValue::from("Hello"), JsValue::from("world!")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_worker_byte_stream() {
+    if !wasm_streams::support::supports_byob_reader() {
+        return;
+    }
+    let bytes = vec![1u8, 2, 3];
+    let readable = ReadableStream::from_async_read(futures::io::Cursor::new(bytes.clone()), 2);
+    let chunks = readable
+        .into_stream()
+        .map_ok(|chunk| chunk.unchecked_into::<Uint8Array>().to_vec())
+        .try_concat()
+        .await
+        .unwrap();
+    assert_eq!(chunks, bytes);
+}
+
+#[wasm_bindgen_test]
+async fn test_worker_pipe_through() {
+    let mut readable =
+        ReadableStream::from_stream(iter(vec![Ok(JsValue::from(1)), Ok(JsValue::from(2))]));
+    let output = readable.pipe_through(&batch(2, Duration::from_secs(1)));
+    let batches = output.into_stream().try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(batches.len(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn test_worker_writable_stream() {
+    let (sender, receiver) = mpsc::unbounded::<JsValue>();
+    let mut writable =
+        WritableStream::from_sink(sender.sink_map_err(|_| JsValue::from_str("receiver dropped")));
+    let mut writer = writable.get_writer();
+    writer.write(JsValue::from("Hello")).await.unwrap();
+    writer.close().await.unwrap();
+    drop(writer);
+    assert_eq!(
+        receiver.collect::<Vec<_>>().await,
+        vec![JsValue::from("Hello")]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn test_worker_interval() {
+    let ticks = ReadableStream::interval(Duration::from_millis(1))
+        .into_stream()
+        .take(2)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(ticks.len(), 2);
+}